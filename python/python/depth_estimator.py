@@ -11,6 +11,8 @@ Contract (ARCH-101, ARCH-102, AI-301):
   - Depth values are guaranteed in [0, 1] for all code paths (stub and Depth-Anything-V2).
     Out-of-range and NaN are clamped to 0 or 1 before output.
   - Progress: stderr only, e.g. "PROGRESS 25", "STAGE loading_model".
+  - Model: one "MODEL <id>" stderr line naming the model that produced the depth ("stub" in stub
+    mode), recorded in export reproducibility sidecars.
   - Exit 0 on success; non-zero + stderr message on error.
 
 Uses Depth-Anything-V2 (Hugging Face) when available; falls back to stub depth for roundtrip testing
@@ -39,6 +41,11 @@ def emit_stage(stage: str, stderr=sys.stderr) -> None:
     print(f"STAGE {stage}", file=stderr, flush=True)
 
 
+def emit_model(model_id: str, stderr=sys.stderr) -> None:
+    """Emit MODEL line to stderr naming the model that produced the depth map."""
+    print(f"MODEL {model_id}", file=stderr, flush=True)
+
+
 def clamp_depth_to_01(depth: list[float]) -> list[float]:
     """
     Enforce [0, 1] range for depth output (AI-301, ARCH-102).
@@ -211,6 +218,7 @@ def main() -> int:
 
         if use_stub:
             emit_stage("inference")
+            emit_model("stub")
             depth = run_inference_stub(width, height)
         else:
             try:
@@ -218,12 +226,14 @@ def main() -> int:
                 width, height, depth = run_inference_depth_anything_v2(
                     args.input, args.model, device, sys.stderr
                 )
+                emit_model(args.model)
             except ImportError as e:
                 print(
                     "warning: PyTorch/transformers not available, using stub depth",
                     file=sys.stderr,
                 )
                 emit_stage("inference")
+                emit_model("stub")
                 depth = run_inference_stub(width, height)
             except Exception as e:
                 if "out of memory" in str(e).lower() or "oom" in str(e).lower():
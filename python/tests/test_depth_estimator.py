@@ -193,6 +193,14 @@ def test_cli_env_stub():
     assert "depth" in data and len(data["depth"]) == 1
 
 
+@pytest.mark.skipif(not VALID_1X1.is_file(), reason="fixture valid_1x1.png not found")
+def test_cli_stub_reports_model():
+    """Stub mode names itself on a MODEL stderr line (reproducibility sidecar)."""
+    code, _, err = _run_cli([str(VALID_1X1)])
+    assert code == 0
+    assert "MODEL stub" in err.splitlines()
+
+
 # ----- --show-license (AI-502) -----
 
 
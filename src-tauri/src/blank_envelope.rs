@@ -158,35 +158,16 @@ pub fn compute_bbox(points: &[[f32; 3]]) -> Option<([f32; 3], [f32; 3])> {
     Some((min, max))
 }
 
-/// Scale and translate a point cloud to fit within the blank envelope.
-///
-/// This function:
-/// 1. Computes the bounding box of the input points
-/// 2. Calculates a uniform scale factor to fit within the interior bounds
-/// 3. Translates points to center them within the blank
-/// 4. Validates that all points are within bounds (returns outlier count)
+/// Uniform scale and translation that fit content with bounds `content_min`..`content_max`
+/// centred inside the blank interior (the transform [`fit_to_blank`] applies).
 ///
-/// # Arguments
-/// * `points` - Mutable slice of 3D points to transform in-place
-/// * `envelope` - The blank envelope defining target bounds
-///
-/// # Returns
-/// * `Ok(FitResult)` - Transformation applied successfully
-/// * `Err(String)` - Invalid envelope or empty point cloud
-pub fn fit_to_blank(
-    points: &mut [[f32; 3]],
+/// Only the bounds are needed, so callers that know them in advance (e.g. streaming
+/// generation) can compute the fit without holding the points. `envelope` must be valid.
+pub fn fit_transform(
+    content_min: [f32; 3],
+    content_max: [f32; 3],
     envelope: &BlankEnvelope,
-) -> Result<FitResult, String> {
-    // Validate envelope
-    envelope.validate()?;
-
-    if points.is_empty() {
-        return Err("Point cloud is empty".to_string());
-    }
-
-    // Compute bounding box of content
-    let (content_min, content_max) = compute_bbox(points).unwrap();
-
+) -> (f32, [f32; 3]) {
     let content_span = [
         content_max[0] - content_min[0],
         content_max[1] - content_min[1],
@@ -234,6 +215,49 @@ pub fn fit_to_blank(
         blank_center[2] - content_center[2],
     ];
 
+    (scale, translation)
+}
+
+/// Whether a fitted point lies outside the blank (counted as an outlier by [`fit_to_blank`]).
+pub fn is_outside_envelope(p: &[f32; 3], envelope: &BlankEnvelope) -> bool {
+    p[0] < 0.0
+        || p[0] > envelope.length_mm
+        || p[1] < 0.0
+        || p[1] > envelope.width_mm
+        || p[2] < 0.0
+        || p[2] > envelope.height_mm
+}
+
+/// Scale and translate a point cloud to fit within the blank envelope.
+///
+/// This function:
+/// 1. Computes the bounding box of the input points
+/// 2. Calculates a uniform scale factor to fit within the interior bounds
+/// 3. Translates points to center them within the blank
+/// 4. Validates that all points are within bounds (returns outlier count)
+///
+/// # Arguments
+/// * `points` - Mutable slice of 3D points to transform in-place
+/// * `envelope` - The blank envelope defining target bounds
+///
+/// # Returns
+/// * `Ok(FitResult)` - Transformation applied successfully
+/// * `Err(String)` - Invalid envelope or empty point cloud
+pub fn fit_to_blank(
+    points: &mut [[f32; 3]],
+    envelope: &BlankEnvelope,
+) -> Result<FitResult, String> {
+    // Validate envelope
+    envelope.validate()?;
+
+    if points.is_empty() {
+        return Err("Point cloud is empty".to_string());
+    }
+
+    // Compute bounding box of content
+    let (content_min, content_max) = compute_bbox(points).unwrap();
+    let (scale, translation) = fit_transform(content_min, content_max, envelope);
+
     // Apply transformation to all points
     for p in points.iter_mut() {
         p[0] = p[0] * scale + translation[0];
@@ -244,14 +268,7 @@ pub fn fit_to_blank(
     // Count outliers (points outside envelope bounds)
     let outliers = points
         .iter()
-        .filter(|p| {
-            p[0] < 0.0
-                || p[0] > envelope.length_mm
-                || p[1] < 0.0
-                || p[1] > envelope.width_mm
-                || p[2] < 0.0
-                || p[2] > envelope.height_mm
-        })
+        .filter(|p| is_outside_envelope(p, envelope))
         .count();
 
     Ok(FitResult {
@@ -3,9 +3,11 @@
 
 //! Depth map adjustment pipeline (BACK-401–405, BACK-1101–1104).
 //!
-//! Transforms normalized depth [0, 1] with brightness, contrast, gamma, invert, and optional curve.
-//! All operations work on f32 in [0, 1]; output is clamped to [0, 1].
-//! Order of application: invert → gamma → contrast → brightness → curve (documented for predictable behaviour).
+//! Transforms normalized depth [0, 1] with an ordered stack of operations ([`AdjustmentOp`]):
+//! brightness, contrast, gamma, invert and curves, plus the spatial and histogram stages of the
+//! sibling `depth_*` modules. All operations work on f32 in [0, 1]; output is clamped to [0, 1].
+//! Operations run in stack order; flat params from before the stack migrate to the former fixed
+//! order invert → gamma → contrast → brightness → curve (see [`FlatAdjustmentParams`]).
 //!
 //! Formulas (BACK-401):
 //! - Brightness: v' = clamp(v + b, 0, 1)
@@ -13,7 +15,16 @@
 //! - Gamma:      v' = v^g for v > 0; 0 stays 0
 //! - Invert:     v' = 1.0 - v
 //! - Curve:      v' = interpolate(v; control points) — BACK-1102, BACK-1103
+//!
+//! The curve interpolates linearly, with a monotone cubic (Fritsch–Carlson) or with a
+//! Catmull-Rom spline ([`CurveInterpolation`]). [`apply_adjustments`] samples each curve once
+//! into a [`CurveLut`] and looks each pixel up there instead of searching the control points.
 
+use crate::bas_relief::BasRelief;
+use crate::depth_filter::{DetailTransfer, SpatialFilter};
+use crate::depth_inpaint::Inpaint;
+use crate::depth_levels::{AutoLevels, Equalization};
+use crate::depth_terrace::Terracing;
 use serde::{Deserialize, Serialize};
 
 /// Clamp value to [0, 1].
@@ -114,7 +125,160 @@ pub fn apply_curve_value(v: f32, points: &[CurvePoint]) -> f32 {
     clamp01(p0.y + t * (p1.y - p0.y))
 }
 
-/// Compute histogram of depth values (BACK-1101). Returns bin counts for [0, 1] divided into `bins` buckets.
+/// Interpolation between curve control points (BACK-1103).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CurveInterpolation {
+    /// Straight segments (kinks at each point); what presets without a mode use.
+    #[default]
+    Linear,
+    /// Fritsch–Carlson monotone cubic: smooth and never overshoots, so a rising curve
+    /// stays rising (no depth inversions).
+    MonotoneCubic,
+    /// Catmull-Rom spline: smooth through every point but may overshoot between them
+    /// (output clamped to [0, 1]).
+    CatmullRom,
+}
+
+impl CurveInterpolation {
+    pub fn is_linear(&self) -> bool {
+        *self == CurveInterpolation::Linear
+    }
+}
+
+/// Evaluate the curve at `v` with `interpolation`. Points need not be sorted; fewer than two
+/// points leave `v` unchanged. Cubic modes hold the end values outside the first/last point.
+pub fn curve_value(v: f32, points: &[CurvePoint], interpolation: CurveInterpolation) -> f32 {
+    if points.len() < 2 {
+        return clamp01(v);
+    }
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x));
+    match interpolation {
+        CurveInterpolation::Linear => apply_curve_value(v, &sorted),
+        _ => hermite_value(clamp01(v), &sorted, &curve_tangents(&sorted, interpolation)),
+    }
+}
+
+/// Tangents (dy/dx) at each sorted control point for the cubic modes.
+fn curve_tangents(points: &[CurvePoint], interpolation: CurveInterpolation) -> Vec<f32> {
+    let n = points.len();
+    let secant = |k: usize| {
+        let dx = points[k + 1].x - points[k].x;
+        if dx <= 0.0 {
+            0.0
+        } else {
+            (points[k + 1].y - points[k].y) / dx
+        }
+    };
+    let d: Vec<f32> = (0..n - 1).map(secant).collect();
+    let mut m = vec![0.0; n];
+    m[0] = d[0];
+    m[n - 1] = d[n - 2];
+    match interpolation {
+        CurveInterpolation::CatmullRom => {
+            for k in 1..n - 1 {
+                let dx = points[k + 1].x - points[k - 1].x;
+                m[k] = if dx <= 0.0 {
+                    0.0
+                } else {
+                    (points[k + 1].y - points[k - 1].y) / dx
+                };
+            }
+        }
+        _ => {
+            // Fritsch–Carlson: average secants, zero at local extrema, then limit each
+            // segment's tangents to the circle of radius 3 so the segment stays monotone.
+            for k in 1..n - 1 {
+                m[k] = if d[k - 1] * d[k] <= 0.0 {
+                    0.0
+                } else {
+                    (d[k - 1] + d[k]) / 2.0
+                };
+            }
+            for k in 0..n - 1 {
+                if d[k] == 0.0 {
+                    m[k] = 0.0;
+                    m[k + 1] = 0.0;
+                    continue;
+                }
+                let (a, b) = (m[k] / d[k], m[k + 1] / d[k]);
+                let s = a * a + b * b;
+                if s > 9.0 {
+                    let t = 3.0 / s.sqrt();
+                    m[k] = t * a * d[k];
+                    m[k + 1] = t * b * d[k];
+                }
+            }
+        }
+    }
+    m
+}
+
+/// Cubic Hermite evaluation through sorted `points` with `tangents`; `v` already in [0, 1].
+fn hermite_value(v: f32, points: &[CurvePoint], tangents: &[f32]) -> f32 {
+    let last = points.len() - 1;
+    if v <= points[0].x {
+        return clamp01(points[0].y);
+    }
+    if v >= points[last].x {
+        return clamp01(points[last].y);
+    }
+    let i = points.partition_point(|p| p.x <= v).clamp(1, last) - 1;
+    let (p0, p1) = (points[i], points[i + 1]);
+    let h = p1.x - p0.x;
+    if h <= 0.0 {
+        return clamp01(p1.y);
+    }
+    let t = (v - p0.x) / h;
+    let (t2, t3) = (t * t, t * t * t);
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + t;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t2;
+    clamp01(h00 * p0.y + h10 * h * tangents[i] + h01 * p1.y + h11 * h * tangents[i + 1])
+}
+
+/// Entries in a [`CurveLut`].
+pub const CURVE_LUT_SIZE: usize = 4096;
+
+/// Curve sampled at [`CURVE_LUT_SIZE`] evenly spaced inputs over [0, 1]. Lookups interpolate
+/// linearly between neighbouring entries, so the 12-bit table adds no visible steps.
+#[derive(Debug, Clone)]
+pub struct CurveLut {
+    table: Vec<f32>,
+}
+
+impl CurveLut {
+    pub fn new(points: &[CurvePoint], interpolation: CurveInterpolation) -> Self {
+        let mut sorted = points.to_vec();
+        sorted.sort_by(|a, b| a.x.total_cmp(&b.x));
+        let tangents = (sorted.len() >= 2 && !interpolation.is_linear())
+            .then(|| curve_tangents(&sorted, interpolation));
+        let scale = (CURVE_LUT_SIZE - 1) as f32;
+        let table = (0..CURVE_LUT_SIZE)
+            .map(|i| {
+                let v = i as f32 / scale;
+                match &tangents {
+                    Some(m) => hermite_value(v, &sorted, m),
+                    None => apply_curve_value(v, &sorted),
+                }
+            })
+            .collect();
+        Self { table }
+    }
+
+    #[inline]
+    pub fn eval(&self, v: f32) -> f32 {
+        let pos = clamp01(v) * (CURVE_LUT_SIZE - 1) as f32;
+        let i = (pos as usize).min(CURVE_LUT_SIZE - 2);
+        let t = pos - i as f32;
+        self.table[i] + t * (self.table[i + 1] - self.table[i])
+    }
+}
+
+/// Compute histogram of depth values (BACK-1101). Returns bin counts for [0, 1] divided into
+/// `bins` buckets.
 pub fn compute_histogram(depth: &[f32], bins: usize) -> Vec<u32> {
     let bins = bins.max(1);
     let mut counts = vec![0u32; bins];
@@ -128,27 +292,120 @@ pub fn compute_histogram(depth: &[f32], bins: usize) -> Vec<u32> {
     counts
 }
 
-/// User-adjustable parameters for depth map display and future mesh/export (BACK-401, BACK-404, BACK-1102).
-/// Range [depth_min_mm, depth_max_mm] is stored for mesh generation; preview uses normalized 0–1.
-/// Optional curve: when Some, applied after brightness (BACK-1103).
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// One operation of the adjustment stack. Serialized with a `type` tag next to its fields,
+/// e.g. `{"type":"gamma","gamma":1.2}`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum AdjustmentKind {
+    Invert,
+    Gamma {
+        gamma: f32,
+    },
+    Contrast {
+        contrast: f32,
+    },
+    Brightness {
+        brightness: f32,
+    },
+    /// Curve through the control points; fewer than two points is the identity.
+    Curve {
+        points: Vec<CurvePoint>,
+        #[serde(default, skip_serializing_if = "CurveInterpolation::is_linear")]
+        interpolation: CurveInterpolation,
+    },
+    /// Fill holes from the surrounding depth (see [`crate::depth_inpaint`]).
+    Inpaint(Inpaint),
+    /// Denoise / sharpen filter (see [`crate::depth_filter`]).
+    Filter {
+        filter: SpatialFilter,
+    },
+    /// Gradient-domain compression (see [`crate::bas_relief`]).
+    BasRelief(BasRelief),
+    /// Percentile stretch (see [`crate::depth_levels`]).
+    AutoLevels(AutoLevels),
+    /// Global or CLAHE histogram equalisation.
+    Equalization {
+        equalization: Equalization,
+    },
+    /// Snap to N planes (see [`crate::depth_terrace`]).
+    Terracing(Terracing),
+    /// Add source image detail (needs the source image).
+    DetailTransfer(DetailTransfer),
+}
+
+impl AdjustmentKind {
+    /// Position in the canonical order: the fixed pipeline order used before the stack existed
+    /// (with hole filling ahead of it), used when migrating flat params and when a control adds
+    /// an operation.
+    pub fn canonical_rank(&self) -> u8 {
+        match self {
+            AdjustmentKind::Inpaint(_) => 0,
+            AdjustmentKind::Filter { .. } => 1,
+            AdjustmentKind::BasRelief(_) => 2,
+            AdjustmentKind::AutoLevels(_) => 3,
+            AdjustmentKind::Equalization { .. } => 4,
+            AdjustmentKind::Invert => 5,
+            AdjustmentKind::Gamma { .. } => 6,
+            AdjustmentKind::Contrast { .. } => 7,
+            AdjustmentKind::Brightness { .. } => 8,
+            AdjustmentKind::Curve { .. } => 9,
+            AdjustmentKind::Terracing(_) => 10,
+            AdjustmentKind::DetailTransfer(_) => 11,
+        }
+    }
+
+    /// Whether the operation reads the source image.
+    pub fn uses_source_image(&self) -> bool {
+        match self {
+            AdjustmentKind::Filter { filter } => filter.uses_guide(),
+            AdjustmentKind::DetailTransfer(detail) => detail.is_active(),
+            _ => false,
+        }
+    }
+}
+
+/// An operation in the stack; disabled operations stay in place but are skipped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
+pub struct AdjustmentOp {
+    #[serde(default = "enabled_by_default")]
+    pub enabled: bool,
+    #[serde(flatten)]
+    pub kind: AdjustmentKind,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl AdjustmentOp {
+    pub fn new(kind: AdjustmentKind) -> Self {
+        Self {
+            enabled: true,
+            kind,
+        }
+    }
+}
+
+/// User-adjustable parameters for depth map display and future mesh/export (BACK-401, BACK-404,
+/// BACK-1102).
+/// Range [depth_min_mm, depth_max_mm] is stored for mesh generation; preview uses normalized 0–1.
+/// `operations` run top to bottom; the stack can hold any number of each kind (e.g. two curves).
+/// Files written before the stack existed hold the flat [`FlatAdjustmentParams`] layout and are
+/// migrated when read.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", from = "ParamsRepr")]
 pub struct DepthAdjustmentParams {
-    /// Brightness offset, typically in [-0.5, 0.5].
-    pub brightness: f32,
-    /// Contrast factor; 1.0 = no change.
-    pub contrast: f32,
-    /// Gamma exponent; 1.0 = linear.
-    pub gamma: f32,
-    /// Invert depth (near ↔ far).
-    pub invert: bool,
     /// Depth range minimum in mm (e.g. 2 for laser engraving).
     pub depth_min_mm: f32,
     /// Depth range maximum in mm (e.g. 10).
     pub depth_max_mm: f32,
-    /// Optional curve control points (BACK-1102). When None or len < 2, no curve (identity).
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub curve_control_points: Option<Vec<CurvePoint>>,
+    /// Ordered adjustment operations.
+    pub operations: Vec<AdjustmentOp>,
     /// Feather radius in pixels at mask edges (BACK-1203). 0 = hard edge.
     #[serde(default)]
     pub feather_radius_px: f32,
@@ -156,40 +413,302 @@ pub struct DepthAdjustmentParams {
 
 impl Default for DepthAdjustmentParams {
     fn default() -> Self {
+        Self {
+            depth_min_mm: 2.0,
+            depth_max_mm: 10.0,
+            operations: Vec::new(),
+            feather_radius_px: 0.0,
+        }
+    }
+}
+
+/// Accepts both layouts: `operations` when present, otherwise the flat fields.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ParamsRepr {
+    #[serde(default)]
+    operations: Option<Vec<AdjustmentOp>>,
+    #[serde(flatten)]
+    flat: FlatAdjustmentParams,
+}
+
+impl From<ParamsRepr> for DepthAdjustmentParams {
+    fn from(repr: ParamsRepr) -> Self {
+        match repr.operations {
+            Some(operations) => Self {
+                operations,
+                ..repr.flat.into()
+            },
+            None => repr.flat.into(),
+        }
+    }
+}
+
+/// The flat parameter layout used before the operation stack: one value per operation in a
+/// fixed order. Read from older presets, settings and recipes and converted with
+/// [`FlatAdjustmentParams::operations`].
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FlatAdjustmentParams {
+    pub brightness: f32,
+    pub contrast: f32,
+    pub gamma: f32,
+    pub invert: bool,
+    pub depth_min_mm: f32,
+    pub depth_max_mm: f32,
+    pub curve_control_points: Option<Vec<CurvePoint>>,
+    pub curve_interpolation: CurveInterpolation,
+    pub feather_radius_px: f32,
+    pub spatial_filters: Vec<SpatialFilter>,
+    pub bas_relief: Option<BasRelief>,
+    pub auto_levels: Option<AutoLevels>,
+    pub equalization: Option<Equalization>,
+    pub terracing: Option<Terracing>,
+    pub detail_transfer: Option<DetailTransfer>,
+}
+
+impl Default for FlatAdjustmentParams {
+    fn default() -> Self {
+        let params = DepthAdjustmentParams::default();
         Self {
             brightness: 0.0,
             contrast: 1.0,
             gamma: 1.0,
             invert: false,
-            depth_min_mm: 2.0,
-            depth_max_mm: 10.0,
+            depth_min_mm: params.depth_min_mm,
+            depth_max_mm: params.depth_max_mm,
             curve_control_points: None,
-            feather_radius_px: 0.0,
+            curve_interpolation: CurveInterpolation::Linear,
+            feather_radius_px: params.feather_radius_px,
+            spatial_filters: Vec::new(),
+            bas_relief: None,
+            auto_levels: None,
+            equalization: None,
+            terracing: None,
+            detail_transfer: None,
         }
     }
 }
 
-/// Apply full pipeline in order: invert → gamma → contrast → brightness → curve (BACK-402, BACK-403, BACK-1103).
-/// Does not mutate `depth`; returns a new Vec. Single pass over the array.
-pub fn apply_adjustments(depth: &[f32], params: &DepthAdjustmentParams) -> Vec<f32> {
-    let curve_pts = params
-        .curve_control_points
-        .as_ref()
-        .filter(|p| p.len() >= 2);
-    depth
-        .iter()
-        .map(|&v| {
-            let v = if params.invert { invert(v) } else { v };
-            let v = gamma(v, params.gamma);
-            let v = contrast(v, params.contrast);
-            let v = brightness(v, params.brightness);
-            if let Some(pts) = curve_pts {
-                apply_curve_value(v, pts)
-            } else {
-                v
-            }
+impl FlatAdjustmentParams {
+    /// The equivalent stack in canonical order; identity steps (gamma 1, contrast 1,
+    /// brightness 0, no invert, no curve) are left out.
+    pub fn operations(&self) -> Vec<AdjustmentOp> {
+        let mut kinds: Vec<AdjustmentKind> = self
+            .spatial_filters
+            .iter()
+            .map(|&filter| AdjustmentKind::Filter { filter })
+            .collect();
+        kinds.extend(self.bas_relief.map(AdjustmentKind::BasRelief));
+        kinds.extend(self.auto_levels.map(AdjustmentKind::AutoLevels));
+        kinds.extend(
+            self.equalization
+                .map(|equalization| AdjustmentKind::Equalization { equalization }),
+        );
+        if self.invert {
+            kinds.push(AdjustmentKind::Invert);
+        }
+        if self.gamma != 1.0 {
+            kinds.push(AdjustmentKind::Gamma { gamma: self.gamma });
+        }
+        if self.contrast != 1.0 {
+            kinds.push(AdjustmentKind::Contrast {
+                contrast: self.contrast,
+            });
+        }
+        if self.brightness != 0.0 {
+            kinds.push(AdjustmentKind::Brightness {
+                brightness: self.brightness,
+            });
+        }
+        if let Some(points) = self.curve_control_points.as_ref().filter(|p| p.len() >= 2) {
+            kinds.push(AdjustmentKind::Curve {
+                points: points.clone(),
+                interpolation: self.curve_interpolation,
+            });
+        }
+        kinds.extend(self.terracing.map(AdjustmentKind::Terracing));
+        kinds.extend(self.detail_transfer.map(AdjustmentKind::DetailTransfer));
+        kinds.into_iter().map(AdjustmentOp::new).collect()
+    }
+}
+
+impl From<FlatAdjustmentParams> for DepthAdjustmentParams {
+    fn from(flat: FlatAdjustmentParams) -> Self {
+        Self {
+            depth_min_mm: flat.depth_min_mm,
+            depth_max_mm: flat.depth_max_mm,
+            operations: flat.operations(),
+            feather_radius_px: flat.feather_radius_px,
+        }
+    }
+}
+
+impl DepthAdjustmentParams {
+    /// Params with the default range and the given operations.
+    pub fn with_operations(kinds: impl IntoIterator<Item = AdjustmentKind>) -> Self {
+        Self {
+            operations: kinds.into_iter().map(AdjustmentOp::new).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Enabled operations in order.
+    pub fn enabled_operations(&self) -> impl Iterator<Item = &AdjustmentKind> {
+        self.operations
+            .iter()
+            .filter(|op| op.enabled)
+            .map(|op| &op.kind)
+    }
+
+    /// Whether an enabled operation reads the source image (guided filter or detail transfer).
+    pub fn uses_source_image(&self) -> bool {
+        self.enabled_operations().any(|k| k.uses_source_image())
+    }
+
+    /// Where the histogram stage sits: the first enabled auto-levels / equalisation operation,
+    /// or else where a new auto-levels operation would be inserted (canonical order).
+    pub fn levels_stage_index(&self) -> usize {
+        self.operations
+            .iter()
+            .position(|op| {
+                op.enabled
+                    && matches!(
+                        op.kind,
+                        AdjustmentKind::AutoLevels(_) | AdjustmentKind::Equalization { .. }
+                    )
+            })
+            .unwrap_or_else(|| {
+                self.canonical_insert_index(&AdjustmentKind::AutoLevels(AutoLevels::default()))
+            })
+    }
+
+    /// Index before the first operation that comes after `kind` in the canonical order.
+    pub fn canonical_insert_index(&self, kind: &AdjustmentKind) -> usize {
+        let rank = kind.canonical_rank();
+        self.operations
+            .iter()
+            .position(|op| op.kind.canonical_rank() > rank)
+            .unwrap_or(self.operations.len())
+    }
+}
+
+/// Per-pixel operation, prepared once per call.
+enum PixelOp {
+    Invert,
+    Gamma(f32),
+    Contrast(f32),
+    Brightness(f32),
+    Curve(CurveLut),
+}
+
+impl PixelOp {
+    fn from_kind(kind: &AdjustmentKind) -> Option<Self> {
+        Some(match kind {
+            AdjustmentKind::Invert => PixelOp::Invert,
+            AdjustmentKind::Gamma { gamma } => PixelOp::Gamma(*gamma),
+            AdjustmentKind::Contrast { contrast } => PixelOp::Contrast(*contrast),
+            AdjustmentKind::Brightness { brightness } => PixelOp::Brightness(*brightness),
+            AdjustmentKind::Curve {
+                points,
+                interpolation,
+            } => PixelOp::Curve(CurveLut::new(points, *interpolation)),
+            _ => return None,
         })
-        .collect()
+    }
+
+    #[inline]
+    fn apply(&self, v: f32) -> f32 {
+        match self {
+            PixelOp::Invert => invert(v),
+            PixelOp::Gamma(g) => gamma(v, *g),
+            PixelOp::Contrast(c) => contrast(v, *c),
+            PixelOp::Brightness(b) => brightness(v, *b),
+            PixelOp::Curve(lut) => lut.eval(v),
+        }
+    }
+}
+
+/// Apply the enabled operations of `params` in stack order (BACK-402, BACK-403, BACK-1103) to a
+/// `width`×`height` depth map. Does not mutate `depth`; returns a new Vec. Consecutive per-pixel
+/// operations (invert, gamma, contrast, brightness, curve) run in one pass, with curves sampled
+/// into a [`CurveLut`]; `source` is the source image at depth resolution for the guided filter
+/// and detail transfer (both are skipped or self-guided without it); `mask` is the selection
+/// mask read by mask-based inpainting (ignored when its dimensions do not match).
+pub fn apply_adjustments(
+    depth: &[f32],
+    width: u32,
+    height: u32,
+    params: &DepthAdjustmentParams,
+    source: Option<&image::RgbImage>,
+    mask: Option<&crate::mask::MaskBitmap>,
+) -> Vec<f32> {
+    let mask = mask.filter(|m| m.dimensions_match(width, height));
+    let mut out = depth.to_vec();
+    let mut chain: Vec<PixelOp> = Vec::new();
+    let flush = |out: &mut Vec<f32>, chain: &mut Vec<PixelOp>| {
+        if !chain.is_empty() {
+            for v in out.iter_mut() {
+                *v = chain.iter().fold(*v, |v, op| op.apply(v));
+            }
+            chain.clear();
+        }
+    };
+    for kind in params.enabled_operations() {
+        if let Some(op) = PixelOp::from_kind(kind) {
+            chain.push(op);
+            continue;
+        }
+        flush(&mut out, &mut chain);
+        match kind {
+            AdjustmentKind::Inpaint(inpaint) => {
+                let selected = mask.map(|m| m.to_bool_vec());
+                out = crate::depth_inpaint::apply_inpaint(
+                    &out,
+                    width,
+                    height,
+                    inpaint,
+                    selected.as_deref(),
+                );
+            }
+            AdjustmentKind::Filter { filter } => {
+                out = crate::depth_filter::apply_spatial_filters(
+                    &out,
+                    width,
+                    height,
+                    std::slice::from_ref(filter),
+                    source,
+                );
+            }
+            AdjustmentKind::BasRelief(relief) => {
+                if relief.is_active() || relief.target_ratio < 1.0 {
+                    out = crate::bas_relief::apply_bas_relief(&out, width, height, relief);
+                }
+            }
+            AdjustmentKind::AutoLevels(levels) => {
+                out = crate::depth_levels::apply_auto_levels(&out, levels);
+            }
+            AdjustmentKind::Equalization { equalization } => {
+                out = crate::depth_levels::apply_equalization(&out, width, height, equalization);
+            }
+            AdjustmentKind::Terracing(terracing) => {
+                out = crate::depth_terrace::apply_terracing(&out, terracing);
+            }
+            AdjustmentKind::DetailTransfer(detail) => {
+                if let Some(source) = source {
+                    crate::depth_filter::apply_detail_transfer(
+                        &mut out, width, height, detail, source,
+                    );
+                }
+            }
+            _ => unreachable!("per-pixel operations are chained above"),
+        }
+    }
+    flush(&mut out, &mut chain);
+    for v in out.iter_mut() {
+        *v = clamp01(*v);
+    }
+    out
 }
 
 /// Map normalized depth [0, 1] to physical range [min_mm, max_mm] (BACK-404).
@@ -203,6 +722,18 @@ pub fn depth_to_mm(v: f32, min_mm: f32, max_mm: f32) -> f32 {
 mod tests {
     use super::*;
 
+    /// Run flat (pre-stack) params on a 1-row map.
+    fn adjust(depth: &[f32], params: &FlatAdjustmentParams) -> Vec<f32> {
+        apply_adjustments(
+            depth,
+            depth.len() as u32,
+            1,
+            &params.clone().into(),
+            None,
+            None,
+        )
+    }
+
     #[test]
     fn brightness_shifts_values() {
         assert!((brightness(0.5, 0.2) - 0.7).abs() < 1e-6);
@@ -257,14 +788,14 @@ mod tests {
     #[test]
     fn pipeline_output_in_0_1() {
         let depth = vec![0.0, 0.25, 0.5, 0.75, 1.0];
-        let params = DepthAdjustmentParams {
+        let params = FlatAdjustmentParams {
             brightness: 0.1,
             contrast: 1.5,
             gamma: 0.8,
             invert: true,
             ..Default::default()
         };
-        let out = apply_adjustments(&depth, &params);
+        let out = adjust(&depth, &params);
         for &v in &out {
             assert!(
                 (0.0..=1.0).contains(&v),
@@ -277,7 +808,7 @@ mod tests {
     #[test]
     fn pipeline_default_is_identity() {
         let depth = vec![0.0, 0.25, 0.5, 0.75, 1.0];
-        let out = apply_adjustments(&depth, &DepthAdjustmentParams::default());
+        let out = adjust(&depth, &FlatAdjustmentParams::default());
         for (a, b) in depth.iter().zip(out.iter()) {
             assert!(
                 (a - b).abs() < 1e-5,
@@ -291,11 +822,11 @@ mod tests {
     #[test]
     fn pipeline_invert_only() {
         let depth = vec![0.0, 0.5, 1.0];
-        let params = DepthAdjustmentParams {
+        let params = FlatAdjustmentParams {
             invert: true,
             ..Default::default()
         };
-        let out = apply_adjustments(&depth, &params);
+        let out = adjust(&depth, &params);
         assert_eq!(out[0], 1.0);
         assert!((out[1] - 0.5).abs() < 1e-6);
         assert_eq!(out[2], 0.0);
@@ -313,14 +844,14 @@ mod tests {
     #[test]
     fn boundary_all_zeros() {
         let depth: Vec<f32> = vec![0.0; 100];
-        let params = DepthAdjustmentParams {
+        let params = FlatAdjustmentParams {
             brightness: 0.5,
             contrast: 2.0,
             gamma: 0.1,
             invert: true,
             ..Default::default()
         };
-        let out = apply_adjustments(&depth, &params);
+        let out = adjust(&depth, &params);
         for (i, &v) in out.iter().enumerate() {
             assert!(
                 (0.0..=1.0).contains(&v),
@@ -337,14 +868,14 @@ mod tests {
     #[test]
     fn boundary_all_ones() {
         let depth: Vec<f32> = vec![1.0; 100];
-        let params = DepthAdjustmentParams {
+        let params = FlatAdjustmentParams {
             brightness: -0.5,
             contrast: 0.5,
             gamma: 5.0,
             invert: false,
             ..Default::default()
         };
-        let out = apply_adjustments(&depth, &params);
+        let out = adjust(&depth, &params);
         for (i, &v) in out.iter().enumerate() {
             assert!(
                 (0.0..=1.0).contains(&v),
@@ -359,14 +890,14 @@ mod tests {
     #[test]
     fn boundary_mixed_values() {
         let depth = vec![0.0, 0.001, 0.5, 0.999, 1.0];
-        let params = DepthAdjustmentParams {
+        let params = FlatAdjustmentParams {
             brightness: 1.0,
             contrast: 0.01,
             gamma: 0.1,
             invert: true,
             ..Default::default()
         };
-        let out = apply_adjustments(&depth, &params);
+        let out = adjust(&depth, &params);
         for (i, &v) in out.iter().enumerate() {
             assert!(
                 (0.0..=1.0).contains(&v),
@@ -382,16 +913,16 @@ mod tests {
     fn boundary_extreme_brightness() {
         // Brightness +1: everything shifts to 1 (clamped). Brightness -1: to 0.
         let depth = vec![0.0, 0.5, 1.0];
-        let out_plus = apply_adjustments(
+        let out_plus = adjust(
             &depth,
-            &DepthAdjustmentParams {
+            &FlatAdjustmentParams {
                 brightness: 1.0,
                 ..Default::default()
             },
         );
-        let out_minus = apply_adjustments(
+        let out_minus = adjust(
             &depth,
-            &DepthAdjustmentParams {
+            &FlatAdjustmentParams {
                 brightness: -1.0,
                 ..Default::default()
             },
@@ -414,16 +945,16 @@ mod tests {
     fn boundary_extreme_gamma() {
         // Gamma 0.1: very steep curve (low values stay low, high values pulled). Gamma 5: flattens.
         let depth = vec![0.0, 0.25, 0.5, 0.75, 1.0];
-        let params_low = DepthAdjustmentParams {
+        let params_low = FlatAdjustmentParams {
             gamma: 0.1,
             ..Default::default()
         };
-        let params_high = DepthAdjustmentParams {
+        let params_high = FlatAdjustmentParams {
             gamma: 5.0,
             ..Default::default()
         };
-        let out_low = apply_adjustments(&depth, &params_low);
-        let out_high = apply_adjustments(&depth, &params_high);
+        let out_low = adjust(&depth, &params_low);
+        let out_high = adjust(&depth, &params_high);
         for &v in out_low.iter().chain(out_high.iter()) {
             assert!(
                 (0.0..=1.0).contains(&v),
@@ -440,9 +971,9 @@ mod tests {
     fn boundary_extreme_contrast() {
         // Contrast 0: everything becomes 0.5. Very high contrast: pushes toward 0/1.
         let depth = vec![0.25, 0.5, 0.75];
-        let out_zero = apply_adjustments(
+        let out_zero = adjust(
             &depth,
-            &DepthAdjustmentParams {
+            &FlatAdjustmentParams {
                 contrast: 0.0,
                 ..Default::default()
             },
@@ -455,9 +986,9 @@ mod tests {
             );
             assert!(!v.is_nan());
         }
-        let out_high = apply_adjustments(
+        let out_high = adjust(
             &depth,
-            &DepthAdjustmentParams {
+            &FlatAdjustmentParams {
                 contrast: 100.0,
                 ..Default::default()
             },
@@ -504,16 +1035,191 @@ mod tests {
     #[test]
     fn pipeline_with_curve_linear_unchanged() {
         let depth = vec![0.0, 0.25, 0.5, 0.75, 1.0];
-        let params = DepthAdjustmentParams {
+        let params = FlatAdjustmentParams {
             curve_control_points: Some(preset_linear()),
             ..Default::default()
         };
-        let out = apply_adjustments(&depth, &params);
+        let out = adjust(&depth, &params);
         for (a, b) in depth.iter().zip(out.iter()) {
             assert!((a - b).abs() < 1e-5, "with linear curve: {} vs {}", a, b);
         }
     }
 
+    #[test]
+    fn monotone_cubic_is_smooth_and_never_overshoots() {
+        let pts = preset_s_curve();
+        let mut prev = 0.0;
+        for i in 0..=1000 {
+            let v = i as f32 / 1000.0;
+            let y = curve_value(v, &pts, CurveInterpolation::MonotoneCubic);
+            assert!(y >= prev - 1e-6, "not monotone at {}", v);
+            prev = y;
+        }
+        for p in &pts {
+            let y = curve_value(p.x, &pts, CurveInterpolation::MonotoneCubic);
+            assert!((y - p.y).abs() < 1e-5, "misses control point {:?}", p);
+        }
+        // A flat run stays flat (Catmull-Rom overshoots on the same points).
+        let step = [
+            CurvePoint { x: 0.0, y: 0.0 },
+            CurvePoint { x: 0.4, y: 0.0 },
+            CurvePoint { x: 0.6, y: 1.0 },
+            CurvePoint { x: 1.0, y: 1.0 },
+        ];
+        assert_eq!(
+            curve_value(0.2, &step, CurveInterpolation::MonotoneCubic),
+            0.0
+        );
+        assert_eq!(
+            curve_value(0.8, &step, CurveInterpolation::MonotoneCubic),
+            1.0
+        );
+        let slope_left = curve_value(0.5001, &step, CurveInterpolation::MonotoneCubic)
+            - curve_value(0.4999, &step, CurveInterpolation::MonotoneCubic);
+        assert!(slope_left > 0.0);
+    }
+
+    #[test]
+    fn catmull_rom_passes_through_points_smoothly() {
+        let pts = preset_exponential();
+        for p in &pts {
+            let y = curve_value(p.x, &pts, CurveInterpolation::CatmullRom);
+            assert!((y - p.y).abs() < 1e-5);
+        }
+        // No kink at the middle point: left and right slopes agree.
+        let f = |v| curve_value(v, &pts, CurveInterpolation::CatmullRom);
+        let left = (f(0.5) - f(0.499)) / 0.001;
+        let right = (f(0.501) - f(0.5)) / 0.001;
+        assert!((left - right).abs() < 0.01, "{} vs {}", left, right);
+        let lin = |v| curve_value(v, &pts, CurveInterpolation::Linear);
+        assert!(((lin(0.5) - lin(0.499)) / 0.001 - (lin(0.501) - lin(0.5)) / 0.001).abs() > 0.5);
+    }
+
+    #[test]
+    fn lut_matches_direct_evaluation() {
+        let pts = vec![
+            CurvePoint { x: 0.8, y: 0.9 },
+            CurvePoint { x: 0.0, y: 0.1 },
+            CurvePoint { x: 0.3, y: 0.5 },
+        ];
+        for mode in [
+            CurveInterpolation::Linear,
+            CurveInterpolation::MonotoneCubic,
+            CurveInterpolation::CatmullRom,
+        ] {
+            let lut = CurveLut::new(&pts, mode);
+            for i in 0..=997 {
+                let v = i as f32 / 997.0;
+                let exact = curve_value(v, &pts, mode);
+                assert!((lut.eval(v) - exact).abs() < 2e-4, "{:?} at {}", mode, v);
+            }
+        }
+        let curve = AdjustmentOp::new(AdjustmentKind::Curve {
+            points: pts.clone(),
+            interpolation: CurveInterpolation::MonotoneCubic,
+        });
+        let json = serde_json::to_string(&curve).unwrap();
+        assert!(json.contains(r#""type":"curve""#));
+        assert!(json.contains(r#""interpolation":"monotoneCubic""#));
+        let linear = serde_json::to_string(&AdjustmentKind::Curve {
+            points: pts,
+            interpolation: CurveInterpolation::Linear,
+        })
+        .unwrap();
+        assert!(!linear.contains("interpolation"));
+    }
+
+    // --- Operation stack ---
+
+    #[test]
+    fn operations_run_in_stack_order_and_skip_disabled() {
+        let depth = vec![0.2, 0.6];
+        let brighten = AdjustmentKind::Brightness { brightness: 0.1 };
+        let run =
+            |params: &DepthAdjustmentParams| apply_adjustments(&depth, 2, 1, params, None, None);
+        let invert_first = run(&DepthAdjustmentParams::with_operations([
+            AdjustmentKind::Invert,
+            brighten.clone(),
+        ]));
+        let brighten_first = run(&DepthAdjustmentParams::with_operations([
+            brighten,
+            AdjustmentKind::Invert,
+        ]));
+        assert!((invert_first[0] - 0.9).abs() < 1e-6);
+        assert!((brighten_first[0] - 0.7).abs() < 1e-6);
+
+        let mut params = DepthAdjustmentParams::with_operations([AdjustmentKind::Invert]);
+        params.operations[0].enabled = false;
+        assert_eq!(run(&params), depth);
+    }
+
+    #[test]
+    fn stack_can_hold_the_same_operation_twice() {
+        let depth = vec![0.5];
+        let half = AdjustmentKind::Curve {
+            points: vec![CurvePoint { x: 0.0, y: 0.0 }, CurvePoint { x: 1.0, y: 0.5 }],
+            interpolation: CurveInterpolation::Linear,
+        };
+        let params = DepthAdjustmentParams::with_operations([half.clone(), half]);
+        let out = apply_adjustments(&depth, 1, 1, &params, None, None);
+        assert!((out[0] - 0.125).abs() < 1e-3);
+    }
+
+    #[test]
+    fn flat_params_migrate_to_canonical_stack() {
+        let json = r#"{"brightness":0.1,"contrast":1,"gamma":1.5,"invert":true,
+            "depthMinMm":1,"depthMaxMm":5,"curveControlPoints":[{"x":0,"y":0},{"x":1,"y":1}],
+            "terracing":{"levels":4},"featherRadiusPx":3}"#;
+        let params: DepthAdjustmentParams = serde_json::from_str(json).unwrap();
+        assert_eq!((params.depth_min_mm, params.depth_max_mm), (1.0, 5.0));
+        assert_eq!(params.feather_radius_px, 3.0);
+        let types: Vec<u8> = params
+            .operations
+            .iter()
+            .map(|op| op.kind.canonical_rank())
+            .collect();
+        // invert, gamma, brightness, curve, terracing; contrast 1 is the identity and dropped.
+        assert_eq!(types, vec![5, 6, 8, 9, 10]);
+        // The migrated stack reproduces the old fixed pipeline
+        // curve(brightness(contrast(gamma(invert(v))))), computed by hand.
+        let legacy = r#"{"brightness":0.1,"contrast":2,"gamma":1.5,"invert":true,
+            "curveControlPoints":[{"x":0,"y":0},{"x":0.5,"y":0.25},{"x":1,"y":1}]}"#;
+        let migrated: DepthAdjustmentParams = serde_json::from_str(legacy).unwrap();
+        let depth = [0.0, 0.25, 0.5, 0.75, 1.0];
+        let out = apply_adjustments(&depth, 5, 1, &migrated, None, None);
+        let expected = [1.0, 0.848_557, 0.153_553, 0.05, 0.05];
+        for (got, want) in out.iter().zip(expected) {
+            assert!((got - want).abs() < 1e-3, "{:?} != {:?}", out, expected);
+        }
+
+        let round_trip: DepthAdjustmentParams =
+            serde_json::from_str(&serde_json::to_string(&params).unwrap()).unwrap();
+        assert_eq!(round_trip, params);
+        assert!(
+            serde_json::from_str::<DepthAdjustmentParams>(r#"{"operations":[]}"#)
+                .unwrap()
+                .operations
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn canonical_insert_index_keeps_pipeline_order() {
+        let params = DepthAdjustmentParams::with_operations([
+            AdjustmentKind::Invert,
+            AdjustmentKind::Terracing(Terracing {
+                levels: 3,
+                spacing: Default::default(),
+                transition: 0.0,
+            }),
+        ]);
+        assert_eq!(
+            params.canonical_insert_index(&AdjustmentKind::Brightness { brightness: 0.1 }),
+            1
+        );
+        assert_eq!(params.levels_stage_index(), 0);
+    }
+
     #[test]
     fn histogram_bins() {
         let depth = vec![0.0, 0.0, 0.5, 0.5, 0.5, 1.0];
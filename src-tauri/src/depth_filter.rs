@@ -0,0 +1,529 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Spatial filters for depth maps (denoise / sharpen stage of the adjustment pipeline).
+//!
+//! AI depth maps carry noise in textured regions that turns into speckle in the crystal. The
+//! per-pixel tone operations in [`crate::depth_adjust`] cannot remove it, so `filter`
+//! operations in the adjustment stack run one of these on the depth map (canonically before
+//! the tone operations):
+//!
+//! - Gaussian blur (`sigmaPx`), separable.
+//! - Median (`radiusPx`, square window) for salt-and-pepper speckle.
+//! - Bilateral (`sigmaSpatialPx`, `sigmaRange`): smooths while keeping depth steps.
+//! - Guided (`radiusPx`, `epsilon`): edge-preserving smoothing that follows the edges of the
+//!   source photo (He et al., colour guide); without a source image the depth guides itself.
+//! - Unsharp mask (`sigmaPx`, `amount`): adds back `amount` × the detail a Gaussian removes.
+//!
+//! Sizes are clamped to the `MAX_*` constants so a bad value cannot stall the preview. Like
+//! the other adjustments, filters are blended through the mask and stored in the undo history
+//! with the rest of the params.
+//!
+//! [`DetailTransfer`] goes the other way: it adds the high-frequency luminance of the source
+//! photo (hair, fabric, lettering) to the smooth AI depth, after the tone pipeline.
+
+use serde::{Deserialize, Serialize};
+
+/// Largest Gaussian / unsharp sigma (px).
+pub const MAX_SIGMA_PX: f32 = 25.0;
+/// Largest median window radius (px); the window is (2r+1)² pixels.
+pub const MAX_MEDIAN_RADIUS_PX: u32 = 7;
+/// Largest bilateral spatial sigma (px); the window radius is 2σ.
+pub const MAX_BILATERAL_SIGMA_PX: f32 = 8.0;
+/// Largest guided filter radius (px).
+pub const MAX_GUIDED_RADIUS_PX: u32 = 32;
+
+/// One spatial filter of the adjustment pipeline.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum SpatialFilter {
+    Gaussian {
+        sigma_px: f32,
+    },
+    Median {
+        radius_px: u32,
+    },
+    Bilateral {
+        sigma_spatial_px: f32,
+        sigma_range: f32,
+    },
+    Guided {
+        radius_px: u32,
+        epsilon: f32,
+    },
+    UnsharpMask {
+        sigma_px: f32,
+        amount: f32,
+    },
+}
+
+impl SpatialFilter {
+    /// Whether the filter reads the source image (only the guided filter does).
+    pub fn uses_guide(&self) -> bool {
+        matches!(self, SpatialFilter::Guided { .. })
+    }
+}
+
+/// Run `filters` in order over a `width`×`height` depth map. `guide` is the source image at
+/// depth resolution (for [`SpatialFilter::Guided`]); a guide of another size is ignored.
+pub fn apply_spatial_filters(
+    depth: &[f32],
+    width: u32,
+    height: u32,
+    filters: &[SpatialFilter],
+    guide: Option<&image::RgbImage>,
+) -> Vec<f32> {
+    let (w, h) = (width as usize, height as usize);
+    let mut out = depth.to_vec();
+    if w * h != depth.len() || w == 0 || h == 0 {
+        return out;
+    }
+    let guide = guide.filter(|g| g.dimensions() == (width, height));
+    for filter in filters {
+        out = match *filter {
+            SpatialFilter::Gaussian { sigma_px } => gaussian_blur(&out, w, h, sigma_px),
+            SpatialFilter::Median { radius_px } => median(&out, w, h, radius_px),
+            SpatialFilter::Bilateral {
+                sigma_spatial_px,
+                sigma_range,
+            } => bilateral(&out, w, h, sigma_spatial_px, sigma_range),
+            SpatialFilter::Guided { radius_px, epsilon } => match guide {
+                Some(g) => guided_rgb(&out, w, h, g, radius_px, epsilon),
+                None => guided_self(&out, w, h, radius_px, epsilon),
+            },
+            SpatialFilter::UnsharpMask { sigma_px, amount } => {
+                let blurred = gaussian_blur(&out, w, h, sigma_px);
+                let amount = amount.max(0.0);
+                out.iter()
+                    .zip(&blurred)
+                    .map(|(&v, &b)| (v + amount * (v - b)).clamp(0.0, 1.0))
+                    .collect()
+            }
+        };
+    }
+    out
+}
+
+/// Separable Gaussian with clamp-to-edge borders; kernel radius 3σ.
+pub fn gaussian_blur(src: &[f32], w: usize, h: usize, sigma_px: f32) -> Vec<f32> {
+    let sigma = sigma_px.min(MAX_SIGMA_PX);
+    if sigma.is_nan() || sigma <= 0.0 {
+        return src.to_vec();
+    }
+    let r = (3.0 * sigma).ceil() as isize;
+    let kernel: Vec<f32> = (-r..=r)
+        .map(|i| (-((i * i) as f32) / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+    let kernel: Vec<f32> = kernel.iter().map(|k| k / sum).collect();
+    let pass = |src: &[f32], len: usize, lines: usize, at: &dyn Fn(usize, usize) -> usize| {
+        let mut out = vec![0.0; src.len()];
+        for line in 0..lines {
+            for i in 0..len {
+                let mut acc = 0.0;
+                for (k, weight) in kernel.iter().enumerate() {
+                    let j = (i as isize + k as isize - r).clamp(0, len as isize - 1) as usize;
+                    acc += weight * src[at(line, j)];
+                }
+                out[at(line, i)] = acc;
+            }
+        }
+        out
+    };
+    let horizontal = pass(src, w, h, &|y, x| y * w + x);
+    pass(&horizontal, h, w, &|x, y| y * w + x)
+}
+
+/// Median of the (2r+1)² window, shrunk at the borders.
+fn median(src: &[f32], w: usize, h: usize, radius_px: u32) -> Vec<f32> {
+    let r = radius_px.min(MAX_MEDIAN_RADIUS_PX) as usize;
+    if r == 0 {
+        return src.to_vec();
+    }
+    let mut window = Vec::with_capacity((2 * r + 1) * (2 * r + 1));
+    let mut out = vec![0.0; src.len()];
+    for y in 0..h {
+        for x in 0..w {
+            window.clear();
+            for yy in y.saturating_sub(r)..=(y + r).min(h - 1) {
+                let row = &src[yy * w..(yy + 1) * w];
+                window.extend_from_slice(&row[x.saturating_sub(r)..=(x + r).min(w - 1)]);
+            }
+            let mid = window.len() / 2;
+            let (_, m, _) = window.select_nth_unstable_by(mid, |a, b| a.total_cmp(b));
+            out[y * w + x] = *m;
+        }
+    }
+    out
+}
+
+/// Brute-force bilateral filter over a 2σ window.
+fn bilateral(src: &[f32], w: usize, h: usize, sigma_spatial_px: f32, sigma_range: f32) -> Vec<f32> {
+    let sigma_s = sigma_spatial_px.min(MAX_BILATERAL_SIGMA_PX);
+    if sigma_s.is_nan() || sigma_s <= 0.0 || sigma_range.is_nan() || sigma_range <= 0.0 {
+        return src.to_vec();
+    }
+    let r = (2.0 * sigma_s).ceil() as usize;
+    let side = 2 * r + 1;
+    let spatial: Vec<f32> = (0..side * side)
+        .map(|k| {
+            let (dy, dx) = ((k / side) as f32 - r as f32, (k % side) as f32 - r as f32);
+            (-(dx * dx + dy * dy) / (2.0 * sigma_s * sigma_s)).exp()
+        })
+        .collect();
+    let range_scale = -1.0 / (2.0 * sigma_range * sigma_range);
+    let mut out = vec![0.0; src.len()];
+    for y in 0..h {
+        for x in 0..w {
+            let center = src[y * w + x];
+            let (mut acc, mut norm) = (0.0, 0.0);
+            for yy in y.saturating_sub(r)..=(y + r).min(h - 1) {
+                for xx in x.saturating_sub(r)..=(x + r).min(w - 1) {
+                    let v = src[yy * w + xx];
+                    let k = (yy + r - y) * side + (xx + r - x);
+                    let weight = spatial[k] * ((v - center) * (v - center) * range_scale).exp();
+                    acc += weight * v;
+                    norm += weight;
+                }
+            }
+            out[y * w + x] = if norm > 0.0 { acc / norm } else { center };
+        }
+    }
+    out
+}
+
+/// Mean over the (2r+1)² window (shrunk at the borders), via running sums per axis.
+fn box_mean(src: &[f32], w: usize, h: usize, r: usize) -> Vec<f32> {
+    let pass = |src: &[f32], len: usize, lines: usize, at: &dyn Fn(usize, usize) -> usize| {
+        let mut out = vec![0.0; src.len()];
+        for line in 0..lines {
+            let mut prefix = vec![0.0f64; len + 1];
+            for i in 0..len {
+                prefix[i + 1] = prefix[i] + src[at(line, i)] as f64;
+            }
+            for i in 0..len {
+                let (lo, hi) = (i.saturating_sub(r), (i + r + 1).min(len));
+                out[at(line, i)] = ((prefix[hi] - prefix[lo]) / (hi - lo) as f64) as f32;
+            }
+        }
+        out
+    };
+    let horizontal = pass(src, w, h, &|y, x| y * w + x);
+    pass(&horizontal, h, w, &|x, y| y * w + x)
+}
+
+/// Guided filter with the depth as its own guide (edge-preserving smoothing).
+fn guided_self(p: &[f32], w: usize, h: usize, radius_px: u32, epsilon: f32) -> Vec<f32> {
+    let r = radius_px.min(MAX_GUIDED_RADIUS_PX) as usize;
+    if r == 0 {
+        return p.to_vec();
+    }
+    let eps = epsilon.max(1e-8);
+    let mean_p = box_mean(p, w, h, r);
+    let sq: Vec<f32> = p.iter().map(|v| v * v).collect();
+    let mean_pp = box_mean(&sq, w, h, r);
+    let (a, b): (Vec<f32>, Vec<f32>) = mean_p
+        .iter()
+        .zip(&mean_pp)
+        .map(|(&m, &mm)| {
+            let var = (mm - m * m).max(0.0);
+            let a = var / (var + eps);
+            (a, m - a * m)
+        })
+        .unzip();
+    let (mean_a, mean_b) = (box_mean(&a, w, h, r), box_mean(&b, w, h, r));
+    (0..p.len())
+        .map(|i| (mean_a[i] * p[i] + mean_b[i]).clamp(0.0, 1.0))
+        .collect()
+}
+
+/// Guided filter with an RGB guide (He, Sun & Tang 2013, colour variant): the output is
+/// locally a linear function of the guide colours, so depth edges snap to photo edges.
+fn guided_rgb(
+    p: &[f32],
+    w: usize,
+    h: usize,
+    guide: &image::RgbImage,
+    radius_px: u32,
+    epsilon: f32,
+) -> Vec<f32> {
+    let r = radius_px.min(MAX_GUIDED_RADIUS_PX) as usize;
+    if r == 0 {
+        return p.to_vec();
+    }
+    let eps = epsilon.max(1e-8);
+    let n = p.len();
+    let channel =
+        |c: usize| -> Vec<f32> { guide.pixels().map(|px| px[c] as f32 / 255.0).collect() };
+    let guide_ch = [channel(0), channel(1), channel(2)];
+    let mean = |v: &[f32]| box_mean(v, w, h, r);
+    let product =
+        |a: &[f32], b: &[f32]| -> Vec<f32> { a.iter().zip(b).map(|(x, y)| x * y).collect() };
+
+    let mean_i: Vec<Vec<f32>> = guide_ch.iter().map(|c| mean(c)).collect();
+    let mean_p = mean(p);
+    let mean_ip: Vec<Vec<f32>> = guide_ch.iter().map(|c| mean(&product(c, p))).collect();
+    // Upper triangle of the guide covariance: rr, rg, rb, gg, gb, bb.
+    const PAIRS: [(usize, usize); 6] = [(0, 0), (0, 1), (0, 2), (1, 1), (1, 2), (2, 2)];
+    let mean_ii: Vec<Vec<f32>> = PAIRS
+        .iter()
+        .map(|&(a, b)| mean(&product(&guide_ch[a], &guide_ch[b])))
+        .collect();
+
+    let mut coeffs = [vec![0.0; n], vec![0.0; n], vec![0.0; n], vec![0.0; n]];
+    for i in 0..n {
+        let m = [mean_i[0][i], mean_i[1][i], mean_i[2][i]];
+        let cov_ip: [f64; 3] = std::array::from_fn(|c| (mean_ip[c][i] - m[c] * mean_p[i]) as f64);
+        let s = |k: usize| {
+            let (a, b) = PAIRS[k];
+            (mean_ii[k][i] - m[a] * m[b]) as f64 + if a == b { eps as f64 } else { 0.0 }
+        };
+        let sigma = [[s(0), s(1), s(2)], [s(1), s(3), s(4)], [s(2), s(4), s(5)]];
+        let a = solve3(&sigma, &cov_ip);
+        let b = mean_p[i] as f64 - (0..3).map(|c| a[c] * m[c] as f64).sum::<f64>();
+        for c in 0..3 {
+            coeffs[c][i] = a[c] as f32;
+        }
+        coeffs[3][i] = b as f32;
+    }
+    let mean_coeffs: Vec<Vec<f32>> = coeffs.iter().map(|c| mean(c)).collect();
+    (0..n)
+        .map(|i| {
+            let q = (0..3)
+                .map(|c| mean_coeffs[c][i] * guide_ch[c][i])
+                .sum::<f32>()
+                + mean_coeffs[3][i];
+            q.clamp(0.0, 1.0)
+        })
+        .collect()
+}
+
+/// Solve the symmetric positive definite 3×3 system `m x = v` (Cramer's rule).
+fn solve3(m: &[[f64; 3]; 3], v: &[f64; 3]) -> [f64; 3] {
+    let det = |m: &[[f64; 3]; 3]| {
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    };
+    let d = det(m);
+    if d.abs() < 1e-18 {
+        return [0.0; 3];
+    }
+    std::array::from_fn(|c| {
+        let mut mc = *m;
+        for row in 0..3 {
+            mc[row][c] = v[row];
+        }
+        det(&mc) / d
+    })
+}
+
+/// Detail transfer: `depth += strength × (luma − blur(luma, cutoff_px))`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetailTransfer {
+    /// Depth change (in normalized depth, 0–1 = full range) per unit of luminance detail.
+    /// Negative values engrave bright detail instead of raising it.
+    pub strength: f32,
+    /// Gaussian sigma (px) separating detail from shading; only features finer than this
+    /// are transferred.
+    pub cutoff_px: f32,
+}
+
+impl DetailTransfer {
+    /// Whether the stage changes anything.
+    pub fn is_active(&self) -> bool {
+        self.strength != 0.0 && self.strength.is_finite() && self.cutoff_px > 0.0
+    }
+}
+
+/// Add the source image's luminance detail to `depth` (in place). `source` must be at depth
+/// resolution; otherwise the depth is left unchanged.
+pub fn apply_detail_transfer(
+    depth: &mut [f32],
+    width: u32,
+    height: u32,
+    detail: &DetailTransfer,
+    source: &image::RgbImage,
+) {
+    let (w, h) = (width as usize, height as usize);
+    if !detail.is_active() || source.dimensions() != (width, height) || depth.len() != w * h {
+        return;
+    }
+    let luma: Vec<f32> = source
+        .pixels()
+        .map(|p| crate::volumetric::luminance(p.0))
+        .collect();
+    let base = gaussian_blur(&luma, w, h, detail.cutoff_px);
+    for ((d, l), b) in depth.iter_mut().zip(&luma).zip(&base) {
+        *d = (*d + detail.strength * (l - b)).clamp(0.0, 1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Left half 0.2, right half 0.8, with a little deterministic noise.
+    fn noisy_step(w: usize, h: usize) -> Vec<f32> {
+        (0..w * h)
+            .map(|i| {
+                let base = if i % w < w / 2 { 0.2 } else { 0.8 };
+                let noise = ((i * 7919) % 13) as f32 / 13.0 - 0.5;
+                base + 0.04 * noise
+            })
+            .collect()
+    }
+
+    fn roughness(v: &[f32], w: usize) -> f32 {
+        // Mean absolute difference between vertical neighbours (no step vertically).
+        v.iter()
+            .zip(&v[w..])
+            .map(|(a, b)| (a - b).abs())
+            .sum::<f32>()
+            / (v.len() - w) as f32
+    }
+
+    #[test]
+    fn smoothing_filters_reduce_noise_and_keep_constants() {
+        let (w, h) = (16, 12);
+        let depth = noisy_step(w, h);
+        let flat = vec![0.4; w * h];
+        for filter in [
+            SpatialFilter::Gaussian { sigma_px: 1.5 },
+            SpatialFilter::Median { radius_px: 2 },
+            SpatialFilter::Bilateral {
+                sigma_spatial_px: 2.0,
+                sigma_range: 0.1,
+            },
+            SpatialFilter::Guided {
+                radius_px: 2,
+                epsilon: 0.01,
+            },
+        ] {
+            let out = apply_spatial_filters(&depth, w as u32, h as u32, &[filter], None);
+            assert!(
+                roughness(&out, w) < roughness(&depth, w) * 0.6,
+                "{:?} did not smooth",
+                filter
+            );
+            let same = apply_spatial_filters(&flat, w as u32, h as u32, &[filter], None);
+            assert!(same.iter().all(|v| (v - 0.4).abs() < 1e-5), "{:?}", filter);
+        }
+    }
+
+    #[test]
+    fn edge_preserving_filters_keep_the_step() {
+        let (w, h) = (16, 8);
+        let depth = noisy_step(w, h);
+        let step_height = |v: &[f32]| v[3 * w + w / 2] - v[3 * w + w / 2 - 1];
+        let blurred = apply_spatial_filters(
+            &depth,
+            w as u32,
+            h as u32,
+            &[SpatialFilter::Gaussian { sigma_px: 2.0 }],
+            None,
+        );
+        let bilateral = apply_spatial_filters(
+            &depth,
+            w as u32,
+            h as u32,
+            &[SpatialFilter::Bilateral {
+                sigma_spatial_px: 2.0,
+                sigma_range: 0.1,
+            }],
+            None,
+        );
+        assert!(step_height(&blurred) < 0.4);
+        assert!(step_height(&bilateral) > 0.55);
+    }
+
+    #[test]
+    fn guided_filter_follows_guide_edges() {
+        // Depth ramps smoothly; the photo has a hard edge at x = 8.
+        let (w, h) = (16u32, 6u32);
+        let depth: Vec<f32> = (0..w * h)
+            .map(|i| (i % w) as f32 / (w - 1) as f32)
+            .collect();
+        let guide = image::RgbImage::from_fn(w, h, |x, _| {
+            if x < 8 {
+                image::Rgb([20, 20, 20])
+            } else {
+                image::Rgb([230, 230, 230])
+            }
+        });
+        let filter = [SpatialFilter::Guided {
+            radius_px: 4,
+            epsilon: 1e-4,
+        }];
+        let out = apply_spatial_filters(&depth, w, h, &filter, Some(&guide));
+        let row = &out[2 * w as usize..3 * w as usize];
+        // Inside each guide region the output flattens; across the edge it jumps.
+        assert!((row[2] - row[5]).abs() < (depth[2] - depth[5]).abs() * 0.5);
+        assert!(row[8] - row[7] > depth[8] - depth[7] + 0.1);
+    }
+
+    #[test]
+    fn unsharp_mask_raises_edge_contrast_and_median_removes_speckle() {
+        let (w, h) = (9, 9);
+        let mut depth = vec![0.5; w * h];
+        depth[4 * w + 4] = 1.0;
+        let cleaned = apply_spatial_filters(
+            &depth,
+            w as u32,
+            h as u32,
+            &[SpatialFilter::Median { radius_px: 1 }],
+            None,
+        );
+        assert_eq!(cleaned[4 * w + 4], 0.5);
+
+        let step: Vec<f32> = (0..w * h)
+            .map(|i| if i % w < 4 { 0.3 } else { 0.6 })
+            .collect();
+        let sharpened = apply_spatial_filters(
+            &step,
+            w as u32,
+            h as u32,
+            &[SpatialFilter::UnsharpMask {
+                sigma_px: 1.0,
+                amount: 1.0,
+            }],
+            None,
+        );
+        assert!(sharpened[4 * w + 3] < 0.3);
+        assert!(sharpened[4 * w + 4] > 0.6);
+        let json = serde_json::to_string(&SpatialFilter::UnsharpMask {
+            sigma_px: 1.0,
+            amount: 0.5,
+        })
+        .unwrap();
+        assert_eq!(json, r#"{"type":"unsharpMask","sigmaPx":1.0,"amount":0.5}"#);
+    }
+
+    #[test]
+    fn detail_transfer_adds_fine_texture_but_not_shading() {
+        let (w, h) = (32u32, 8u32);
+        // Slow left-to-right shading plus a one-pixel bright line at x = 16.
+        let source = image::RgbImage::from_fn(w, h, |x, _| {
+            let v = (x * 4) as u8 + if x == 16 { 100 } else { 0 };
+            image::Rgb([v, v, v])
+        });
+        let mut depth = vec![0.5; (w * h) as usize];
+        let detail = DetailTransfer {
+            strength: 0.5,
+            cutoff_px: 2.0,
+        };
+        apply_detail_transfer(&mut depth, w, h, &detail, &source);
+        let row = &depth[(3 * w) as usize..(4 * w) as usize];
+        assert!(row[16] > 0.6, "line raised: {}", row[16]);
+        assert!((row[4] - 0.5).abs() < 0.01, "shading ignored: {}", row[4]);
+
+        let mut untouched = vec![0.5; 4];
+        apply_detail_transfer(&mut untouched, 2, 2, &detail, &source);
+        assert_eq!(untouched, vec![0.5; 4]);
+    }
+}
@@ -0,0 +1,360 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Histogram-driven tone stages: auto-levels, global histogram equalisation and CLAHE.
+//!
+//! Canonically they sit above the manual tone operations in the adjustment stack (`autoLevels`,
+//! then `equalization`), so brightness/contrast/gamma/curve fine-tune an already spread histogram.
+//! [`propose_levels`] suggests settings from a depth map without applying them (the
+//! `propose_depth_levels` command).
+
+use serde::{Deserialize, Serialize};
+
+/// Histogram resolution for global equalisation.
+const EQUALIZE_BINS: usize = 4096;
+/// Histogram resolution per CLAHE tile.
+const CLAHE_BINS: usize = 256;
+/// Largest CLAHE grid (tiles per axis).
+pub const MAX_CLAHE_TILES: u32 = 32;
+
+/// Stretch the depth so the `low_percentile` value maps to 0 and `high_percentile` to 1
+/// (percentiles in percent; values outside are clipped).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoLevels {
+    pub low_percentile: f32,
+    pub high_percentile: f32,
+}
+
+impl Default for AutoLevels {
+    fn default() -> Self {
+        Self {
+            low_percentile: 0.5,
+            high_percentile: 99.5,
+        }
+    }
+}
+
+/// Histogram equalisation mode.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "mode",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Equalization {
+    /// One mapping for the whole map: depth becomes its own cumulative distribution.
+    Global,
+    /// Contrast-limited adaptive equalisation on a `tiles`×`tiles` grid; `clip_limit` caps each
+    /// histogram bin at that multiple of the mean bin count (1 = no change, 2–4 typical).
+    Clahe { tiles: u32, clip_limit: f32 },
+}
+
+/// Suggested histogram settings for the current depth map (not applied).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LevelsProposal {
+    /// Depth values at the proposed percentiles.
+    pub low: f32,
+    pub high: f32,
+    pub auto_levels: AutoLevels,
+    /// Suggested when most of the map sits in a narrow band even after stretching.
+    pub equalization: Option<Equalization>,
+}
+
+/// Value at percentile `p` (0–100) of the finite values, or `None` when there are none.
+pub fn percentile(depth: &[f32], p: f32) -> Option<f32> {
+    let mut values: Vec<f32> = depth.iter().copied().filter(|v| v.is_finite()).collect();
+    if values.is_empty() {
+        return None;
+    }
+    let rank = ((p.clamp(0.0, 100.0) / 100.0) * (values.len() - 1) as f32).round() as usize;
+    let (_, v, _) = values.select_nth_unstable_by(rank, |a, b| a.total_cmp(b));
+    Some(*v)
+}
+
+/// Apply auto-levels; a flat or empty map is returned unchanged.
+pub fn apply_auto_levels(depth: &[f32], levels: &AutoLevels) -> Vec<f32> {
+    let lo_p = levels.low_percentile.min(levels.high_percentile);
+    let hi_p = levels.low_percentile.max(levels.high_percentile);
+    match (percentile(depth, lo_p), percentile(depth, hi_p)) {
+        (Some(lo), Some(hi)) if hi > lo => depth
+            .iter()
+            .map(|&v| ((v - lo) / (hi - lo)).clamp(0.0, 1.0))
+            .collect(),
+        _ => depth.to_vec(),
+    }
+}
+
+/// Apply an equalisation mode to a `width`×`height` map.
+pub fn apply_equalization(depth: &[f32], width: u32, height: u32, mode: &Equalization) -> Vec<f32> {
+    match *mode {
+        Equalization::Global => equalize_global(depth),
+        Equalization::Clahe { tiles, clip_limit } => {
+            clahe(depth, width as usize, height as usize, tiles, clip_limit)
+        }
+    }
+}
+
+fn bin_of(v: f32, bins: usize) -> (usize, f32) {
+    let pos = v.clamp(0.0, 1.0) * bins as f32;
+    let bin = (pos as usize).min(bins - 1);
+    (bin, pos - bin as f32)
+}
+
+fn equalize_global(depth: &[f32]) -> Vec<f32> {
+    let counts = crate::depth_adjust::compute_histogram(depth, EQUALIZE_BINS);
+    let total: u64 = counts.iter().map(|&c| c as u64).sum();
+    let first = counts.iter().find(|&&c| c > 0).copied().unwrap_or(0) as u64;
+    if total <= first {
+        return depth.to_vec();
+    }
+    // cdf[b] = pixels in bins before b.
+    let mut cdf = Vec::with_capacity(EQUALIZE_BINS + 1);
+    let mut acc = 0u64;
+    cdf.push(0u64);
+    for &c in &counts {
+        acc += c as u64;
+        cdf.push(acc);
+    }
+    let scale = 1.0 / (total - first) as f64;
+    depth
+        .iter()
+        .map(|&v| {
+            if !v.is_finite() {
+                return v;
+            }
+            let (bin, frac) = bin_of(v, EQUALIZE_BINS);
+            // Interpolate inside the bin so equal inputs stay equal and order is kept.
+            let below = cdf[bin] as f64 + frac as f64 * counts[bin] as f64;
+            ((below - first as f64).max(0.0) * scale).clamp(0.0, 1.0) as f32
+        })
+        .collect()
+}
+
+fn clahe(depth: &[f32], w: usize, h: usize, tiles: u32, clip_limit: f32) -> Vec<f32> {
+    if w * h != depth.len() || w == 0 || h == 0 {
+        return depth.to_vec();
+    }
+    let tiles = tiles.clamp(1, MAX_CLAHE_TILES) as usize;
+    let (tx, ty) = (tiles.min(w), tiles.min(h));
+    // Tile `i` of `count` along an axis of `size` pixels covers `i * size / count` up to
+    // `(i + 1) * size / count`, so every tile is non-empty even when `count` doesn't divide `size`.
+    let bounds = |i: usize, size: usize, count: usize| (i * size / count, (i + 1) * size / count);
+    let clip_limit = if clip_limit.is_finite() {
+        clip_limit.max(1.0)
+    } else {
+        1.0
+    };
+
+    // Per tile: mapping from bin to output value in [0, 1].
+    let maps: Vec<Vec<f32>> = (0..tx * ty)
+        .map(|t| {
+            let (x0, x1) = bounds(t % tx, w, tx);
+            let (y0, y1) = bounds(t / tx, h, ty);
+            let mut hist = vec![0f32; CLAHE_BINS];
+            let mut n = 0usize;
+            for y in y0..y1 {
+                for &v in &depth[y * w + x0..y * w + x1] {
+                    if v.is_finite() {
+                        hist[bin_of(v, CLAHE_BINS).0] += 1.0;
+                        n += 1;
+                    }
+                }
+            }
+            if n == 0 {
+                return (0..CLAHE_BINS)
+                    .map(|b| (b as f32 + 0.5) / CLAHE_BINS as f32)
+                    .collect();
+            }
+            let cap = clip_limit * n as f32 / CLAHE_BINS as f32;
+            let excess: f32 = hist.iter().map(|&c| (c - cap).max(0.0)).sum();
+            let share = excess / CLAHE_BINS as f32;
+            let mut acc = 0.0;
+            hist.iter()
+                .map(|&c| {
+                    acc += c.min(cap) + share;
+                    acc / n as f32
+                })
+                .collect()
+        })
+        .collect();
+
+    // Bilinear blend of the four nearest tile mappings, measured from tile centres.
+    let centres = |size: usize, count: usize| -> Vec<f32> {
+        (0..count)
+            .map(|i| {
+                let (a, b) = bounds(i, size, count);
+                (a + b) as f32 / 2.0
+            })
+            .collect()
+    };
+    let (cx, cy) = (centres(w, tx), centres(h, ty));
+    let axis = |p: usize, centres: &[f32]| {
+        let p = p as f32 + 0.5;
+        let i0 = centres.iter().rposition(|&c| c <= p).unwrap_or(0);
+        let i1 = (i0 + 1).min(centres.len() - 1);
+        let f = if i1 > i0 {
+            ((p - centres[i0]) / (centres[i1] - centres[i0])).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (i0, i1, f)
+    };
+    let mut out = vec![0.0; depth.len()];
+    for y in 0..h {
+        let (j0, j1, fy) = axis(y, &cy);
+        for x in 0..w {
+            let v = depth[y * w + x];
+            if !v.is_finite() {
+                out[y * w + x] = v;
+                continue;
+            }
+            let (i0, i1, fx) = axis(x, &cx);
+            let b = bin_of(v, CLAHE_BINS).0;
+            let at = |i: usize, j: usize| maps[j * tx + i][b];
+            let top = at(i0, j0) * (1.0 - fx) + at(i1, j0) * fx;
+            let bottom = at(i0, j1) * (1.0 - fx) + at(i1, j1) * fx;
+            out[y * w + x] = (top * (1.0 - fy) + bottom * fy).clamp(0.0, 1.0);
+        }
+    }
+    out
+}
+
+/// Propose auto-levels (default percentiles) and, for maps whose middle 80 % still spans less
+/// than 40 % of the range after stretching, CLAHE.
+pub fn propose_levels(depth: &[f32]) -> Option<LevelsProposal> {
+    let auto_levels = AutoLevels::default();
+    let low = percentile(depth, auto_levels.low_percentile)?;
+    let high = percentile(depth, auto_levels.high_percentile)?;
+    let span = (high - low).max(1e-6);
+    let p10 = percentile(depth, 10.0)?;
+    let p90 = percentile(depth, 90.0)?;
+    let equalization = ((p90 - p10) / span < 0.4).then_some(Equalization::Clahe {
+        tiles: 8,
+        clip_limit: 2.0,
+    });
+    Some(LevelsProposal {
+        low,
+        high,
+        auto_levels,
+        equalization,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_levels_clip_percentiles_and_stretch() {
+        // 0.3..0.5 ramp with one outlier at each end.
+        let mut depth: Vec<f32> = (0..200).map(|i| 0.3 + 0.2 * i as f32 / 199.0).collect();
+        depth[0] = 0.0;
+        depth[199] = 1.0;
+        let out = apply_auto_levels(
+            &depth,
+            &AutoLevels {
+                low_percentile: 1.0,
+                high_percentile: 99.0,
+            },
+        );
+        assert_eq!(out[0], 0.0);
+        assert_eq!(out[199], 1.0);
+        assert!(out[2] < 0.02 && out[197] > 0.98);
+        assert!((out[100] - 0.5).abs() < 0.02);
+    }
+
+    #[test]
+    fn global_equalization_flattens_histogram_and_keeps_order() {
+        // Most values crowded near 0.2, a few spread to 1.
+        let depth: Vec<f32> = (0..1000)
+            .map(|i| {
+                if i < 900 {
+                    0.2 + i as f32 * 1e-4
+                } else {
+                    0.3 + (i - 900) as f32 * 0.007
+                }
+            })
+            .collect();
+        let out = apply_equalization(&depth, 1000, 1, &Equalization::Global);
+        assert!(out.windows(2).all(|p| p[0] <= p[1]));
+        // The 50th percentile input lands near the middle of the output range.
+        assert!((out[500] - 0.5).abs() < 0.05, "{}", out[500]);
+    }
+
+    #[test]
+    fn clahe_raises_local_contrast_and_limit_one_is_near_identity() {
+        let (w, h) = (64, 64);
+        // Left half: small ripple around 0.2; right half: around 0.8.
+        let depth: Vec<f32> = (0..w * h)
+            .map(|i| {
+                let base = if i % w < w / 2 { 0.2 } else { 0.8 };
+                base + if (i / w) % 2 == 0 { 0.02 } else { -0.02 }
+            })
+            .collect();
+        let ripple = |v: &[f32]| (v[10 * w + 10] - v[11 * w + 10]).abs();
+        let out = apply_equalization(
+            &depth,
+            w as u32,
+            h as u32,
+            &Equalization::Clahe {
+                tiles: 4,
+                clip_limit: 4.0,
+            },
+        );
+        assert!(ripple(&out) > 1.2 * ripple(&depth));
+        let plain = apply_equalization(
+            &depth,
+            w as u32,
+            h as u32,
+            &Equalization::Clahe {
+                tiles: 4,
+                clip_limit: 1.0,
+            },
+        );
+        assert!(plain.iter().zip(&depth).all(|(a, b)| (a - b).abs() < 0.01));
+    }
+
+    #[test]
+    fn clahe_handles_sizes_the_tile_count_does_not_divide() {
+        for (w, h, tiles) in [(490, 300, 32), (9, 9, 8), (33, 7, 32), (1, 5, 3)] {
+            let depth: Vec<f32> = (0..w * h).map(|i| (i % 97) as f32 / 96.0).collect();
+            let out = apply_equalization(
+                &depth,
+                w as u32,
+                h as u32,
+                &Equalization::Clahe {
+                    tiles,
+                    clip_limit: 2.0,
+                },
+            );
+            assert_eq!(out.len(), depth.len());
+            assert!(out.iter().all(|v| (0.0..=1.0).contains(v)));
+        }
+    }
+
+    #[test]
+    fn proposal_matches_auto_levels_and_flags_crowded_maps() {
+        let depth: Vec<f32> = (0..500).map(|i| 0.4 + 0.1 * i as f32 / 499.0).collect();
+        let p = propose_levels(&depth).unwrap();
+        assert!(p.low > 0.4 && p.high < 0.5);
+        assert_eq!(percentile(&depth, 0.5), Some(p.low));
+        let stretched = apply_auto_levels(&depth, &p.auto_levels);
+        assert_eq!((stretched[2], stretched[497]), (0.0, 1.0));
+        assert!(p.equalization.is_none());
+
+        // 90 % of the map at one depth, a few far outliers spread across the range.
+        let crowded: Vec<f32> = (0..1000)
+            .map(|i| {
+                if i < 900 {
+                    0.5
+                } else {
+                    (i - 900) as f32 / 100.0
+                }
+            })
+            .collect();
+        assert!(propose_levels(&crowded).unwrap().equalization.is_some());
+        assert!(propose_levels(&[]).is_none());
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Depth terracing: snap the depth map to N flat planes ("layered paper" / stepped engraving).
+//!
+//! Canonically placed after the tone operations, so `Even` planes are equally spaced in the final depth range
+//! (equal layer thickness in mm). `KMeans` places the planes at the centres of the N main depth
+//! clusters (1-D k-means on the histogram), with the cuts halfway between them. A `transition`
+//! above 0 replaces each hard step by a smoothstep ramp spanning that fraction of the gap.
+
+use serde::{Deserialize, Serialize};
+
+/// Fewest / most planes.
+pub const MIN_LEVELS: u32 = 2;
+pub const MAX_LEVELS: u32 = 64;
+/// Histogram resolution for k-means.
+const KMEANS_BINS: usize = 1024;
+const KMEANS_MAX_ITERATIONS: usize = 100;
+
+/// How plane depths are chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TerraceSpacing {
+    /// Planes at `k/(N−1)`; the input range is cut into N equal bands.
+    #[default]
+    Even,
+    /// Planes at the k-means cluster centres of the depth histogram.
+    KMeans,
+}
+
+/// Terracing settings (the `terracing` adjustment operation).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Terracing {
+    /// Number of planes, clamped to [`MIN_LEVELS`, `MAX_LEVELS`].
+    pub levels: u32,
+    #[serde(default)]
+    pub spacing: TerraceSpacing,
+    /// Width of the soft step as a fraction of the gap between planes, in [0, 1]; 0 = hard.
+    #[serde(default)]
+    pub transition: f32,
+}
+
+/// Plane depths and the cut between each consecutive pair (`cuts.len() == planes.len() − 1`).
+#[derive(Debug, Clone, PartialEq)]
+struct Terraces {
+    planes: Vec<f32>,
+    cuts: Vec<f32>,
+}
+
+/// Apply terracing to a depth map with values in [0, 1].
+pub fn apply_terracing(depth: &[f32], terracing: &Terracing) -> Vec<f32> {
+    let n = terracing.levels.clamp(MIN_LEVELS, MAX_LEVELS) as usize;
+    let terraces = match terracing.spacing {
+        TerraceSpacing::Even => even_terraces(n),
+        TerraceSpacing::KMeans => kmeans_terraces(depth, n),
+    };
+    let transition = if terracing.transition.is_finite() {
+        terracing.transition.clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    depth
+        .iter()
+        .map(|&v| {
+            if v.is_finite() {
+                terraces.value(v, transition)
+            } else {
+                v
+            }
+        })
+        .collect()
+}
+
+fn even_terraces(n: usize) -> Terraces {
+    Terraces {
+        planes: (0..n).map(|k| k as f32 / (n - 1) as f32).collect(),
+        cuts: (1..n).map(|k| k as f32 / n as f32).collect(),
+    }
+}
+
+/// 1-D k-means (Lloyd) on a histogram, seeded at the quantiles; falls back to even spacing when
+/// the map has fewer distinct depths than planes.
+fn kmeans_terraces(depth: &[f32], n: usize) -> Terraces {
+    let counts = crate::depth_adjust::compute_histogram(depth, KMEANS_BINS);
+    let centre = |b: usize| (b as f32 + 0.5) / KMEANS_BINS as f32;
+    let occupied: Vec<usize> = (0..KMEANS_BINS).filter(|&b| counts[b] > 0).collect();
+    if occupied.len() < n {
+        return even_terraces(n);
+    }
+    let total: u64 = counts.iter().map(|&c| c as u64).sum();
+    let mut means: Vec<f32> = Vec::with_capacity(n);
+    let mut acc = 0u64;
+    let mut next = 0;
+    for &b in &occupied {
+        acc += counts[b] as u64;
+        // Seed k at the (k + 0.5)/n quantile.
+        while next < n && acc as f64 >= (next as f64 + 0.5) / n as f64 * total as f64 {
+            means.push(centre(b));
+            next += 1;
+        }
+    }
+    means.dedup();
+    // Duplicate seeds (one very full bin): spread the rest over the remaining occupied bins.
+    for &b in occupied.iter().rev() {
+        if means.len() >= n {
+            break;
+        }
+        if !means.contains(&centre(b)) {
+            means.push(centre(b));
+        }
+    }
+    means.sort_by(f32::total_cmp);
+
+    for _ in 0..KMEANS_MAX_ITERATIONS {
+        let cuts: Vec<f32> = means.windows(2).map(|m| (m[0] + m[1]) / 2.0).collect();
+        let mut sums = vec![(0.0f64, 0u64); n];
+        for &b in &occupied {
+            let v = centre(b);
+            let k = cuts.partition_point(|&c| c <= v);
+            sums[k].0 += v as f64 * counts[b] as f64;
+            sums[k].1 += counts[b] as u64;
+        }
+        let updated: Vec<f32> = sums
+            .iter()
+            .zip(&means)
+            .map(|(&(sum, count), &old)| {
+                if count > 0 {
+                    (sum / count as f64) as f32
+                } else {
+                    old
+                }
+            })
+            .collect();
+        let converged = updated
+            .iter()
+            .zip(&means)
+            .all(|(a, b)| (a - b).abs() < 0.25 / KMEANS_BINS as f32);
+        means = updated;
+        means.sort_by(f32::total_cmp);
+        if converged {
+            break;
+        }
+    }
+    Terraces {
+        cuts: means.windows(2).map(|m| (m[0] + m[1]) / 2.0).collect(),
+        planes: means,
+    }
+}
+
+impl Terraces {
+    fn value(&self, v: f32, transition: f32) -> f32 {
+        let k = self.cuts.partition_point(|&c| c <= v);
+        if transition > 0.0 {
+            // Nearest cut: the one at index k−1 (below) or k (above).
+            for j in [k.checked_sub(1), (k < self.cuts.len()).then_some(k)]
+                .into_iter()
+                .flatten()
+            {
+                let (lo, hi) = (self.planes[j], self.planes[j + 1]);
+                let half = self.half_width(j, transition);
+                let t = (v - (self.cuts[j] - half)) / (2.0 * half);
+                if half > 0.0 && (0.0..=1.0).contains(&t) {
+                    return lo + (hi - lo) * t * t * (3.0 - 2.0 * t);
+                }
+            }
+        }
+        self.planes[k]
+    }
+
+    /// Half-width of the ramp at cut `j`, kept inside the band between its neighbouring cuts.
+    fn half_width(&self, j: usize, transition: f32) -> f32 {
+        let gap = self.planes[j + 1] - self.planes[j];
+        let below = self.cuts[j] - j.checked_sub(1).map_or(0.0, |i| self.cuts[i]);
+        let above = self.cuts.get(j + 1).copied().unwrap_or(1.0) - self.cuts[j];
+        (transition * gap / 2.0).min(below / 2.0).min(above / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn distinct(values: &[f32]) -> Vec<f32> {
+        let mut v = values.to_vec();
+        v.sort_by(f32::total_cmp);
+        v.dedup();
+        v
+    }
+
+    #[test]
+    fn even_terraces_give_n_equally_spaced_planes() {
+        let ramp: Vec<f32> = (0..=100).map(|i| i as f32 / 100.0).collect();
+        let out = apply_terracing(
+            &ramp,
+            &Terracing {
+                levels: 4,
+                spacing: TerraceSpacing::Even,
+                transition: 0.0,
+            },
+        );
+        assert_eq!(distinct(&out), vec![0.0, 1.0 / 3.0, 2.0 / 3.0, 1.0]);
+        assert!(out.windows(2).all(|p| p[0] <= p[1]));
+        assert_eq!(out[24], 0.0);
+        assert_eq!(out[26], 1.0 / 3.0);
+    }
+
+    #[test]
+    fn kmeans_planes_sit_on_depth_clusters() {
+        // Three clusters around 0.1, 0.45 and 0.9 with some spread.
+        let depth: Vec<f32> = (0..300)
+            .map(|i| [0.1, 0.45, 0.9][i % 3] + ((i / 3) % 5) as f32 * 0.01 - 0.02)
+            .collect();
+        let out = apply_terracing(
+            &depth,
+            &Terracing {
+                levels: 3,
+                spacing: TerraceSpacing::KMeans,
+                transition: 0.0,
+            },
+        );
+        let planes = distinct(&out);
+        assert_eq!(planes.len(), 3);
+        for (plane, expected) in planes.iter().zip([0.1, 0.45, 0.9]) {
+            assert!((plane - expected).abs() < 0.01, "{:?}", planes);
+        }
+    }
+
+    #[test]
+    fn soft_transition_is_continuous_and_keeps_plateaus() {
+        let ramp: Vec<f32> = (0..=1000).map(|i| i as f32 / 1000.0).collect();
+        let out = apply_terracing(
+            &ramp,
+            &Terracing {
+                levels: 3,
+                spacing: TerraceSpacing::Even,
+                transition: 0.5,
+            },
+        );
+        assert!(out.windows(2).all(|p| p[0] <= p[1] && p[1] - p[0] < 0.02));
+        assert_eq!(out[0], 0.0);
+        assert_eq!(out[500], 0.5);
+        assert_eq!(out[1000], 1.0);
+        // Midway between plane and cut: still flat.
+        assert_eq!(out[150], 0.0);
+    }
+}
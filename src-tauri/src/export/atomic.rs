@@ -0,0 +1,311 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Crash-safe export writes.
+//!
+//! An export is written to a hidden temporary file in the destination directory, flushed
+//! and fsynced, then streamed back through the format's reader to check the point count
+//! and bounds before it is renamed over the destination. Only a running count and min/max
+//! are kept, so verifying a multi-GB export stays bounded in memory. A crash, full disk or
+//! failed check never leaves a truncated file under the export name; the temporary is
+//! removed on every error path.
+
+use anyhow::{ensure, Context, Result};
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::{ExportFormat, ExportOptions, ExportReceipt};
+use crate::blank_envelope::compute_bbox;
+use crate::import::ReadSummary;
+
+/// Absolute tolerance (mm) when comparing read-back bounds, on top of the rounding the
+/// exporter reports for its precision.
+const BOUNDS_TOLERANCE_MM: f32 = 1e-3;
+
+/// What the written file must contain.
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct ExpectedCloud {
+    pub point_count: usize,
+    /// Bounds (min, max) of the written positions; `None` for an empty cloud.
+    pub bounds: Option<([f32; 3], [f32; 3])>,
+}
+
+impl ExpectedCloud {
+    pub fn for_points(points: &[[f32; 3]]) -> Self {
+        Self {
+            point_count: points.len(),
+            bounds: compute_bbox(points),
+        }
+    }
+}
+
+/// Export file being written: a temporary next to `dest`, moved into place by [`commit`].
+///
+/// [`commit`]: AtomicExport::commit
+pub(super) struct AtomicExport {
+    dest: PathBuf,
+    temp: PathBuf,
+    format: ExportFormat,
+    options: ExportOptions,
+    writer: Option<BufWriter<File>>,
+}
+
+impl AtomicExport {
+    pub fn create(dest: &Path, format: ExportFormat, options: &ExportOptions) -> Result<Self> {
+        let temp = temp_path(dest);
+        let file = File::create(&temp).with_context(|| {
+            format!(
+                "Failed to create {} file: {:?}",
+                format.id().to_uppercase(),
+                dest
+            )
+        })?;
+        Ok(Self {
+            dest: dest.to_path_buf(),
+            temp,
+            format,
+            options: options.clone(),
+            writer: Some(BufWriter::new(file)),
+        })
+    }
+
+    pub fn writer(&mut self) -> &mut BufWriter<File> {
+        self.writer
+            .as_mut()
+            .expect("export writer already committed")
+    }
+
+    /// Flush and fsync, verify against `expected`, then rename over the destination.
+    pub fn commit(mut self, expected: &ExpectedCloud) -> Result<ExportReceipt> {
+        let mut writer = self.writer.take().expect("export writer already committed");
+        writer.flush().context("Failed to write export")?;
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all().context("Failed to sync export to disk")?;
+        drop(file);
+
+        let verified = verify(&self.temp, self.format, &self.options, expected)?;
+        let sha256 = crate::recipe::sha256_file(&self.temp)?;
+        let byte_size = std::fs::metadata(&self.temp)?.len();
+
+        std::fs::rename(&self.temp, &self.dest)
+            .with_context(|| format!("Failed to move export into place: {:?}", self.dest))?;
+        sync_parent_dir(&self.dest);
+        Ok(ExportReceipt {
+            point_count: expected.point_count,
+            sha256,
+            byte_size,
+            verified,
+            merged_points: 0,
+        })
+    }
+}
+
+impl Drop for AtomicExport {
+    fn drop(&mut self) {
+        // Still present unless the rename in `commit` succeeded.
+        if self.temp.exists() {
+            self.writer = None;
+            let _ = std::fs::remove_file(&self.temp);
+        }
+    }
+}
+
+/// Write `contents` to `dest` through a synced temporary and a rename, so `dest` is either
+/// the old file or the complete new one. Used for small side files such as export recipes.
+pub(crate) fn write_file_atomic(dest: &Path, contents: &[u8]) -> Result<()> {
+    let temp = temp_path(dest);
+    let written = File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(contents)?;
+            file.sync_all()
+        })
+        .and_then(|()| std::fs::rename(&temp, dest));
+    if let Err(e) = written {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e).with_context(|| format!("Failed to write {:?}", dest));
+    }
+    sync_parent_dir(dest);
+    Ok(())
+}
+
+/// Per-process counter that keeps concurrent exports to one destination apart.
+static NEXT_TEMP_ID: AtomicU64 = AtomicU64::new(0);
+
+/// `.<file name>.<pid>.<n>.tmp` in the destination directory, so the rename stays on one
+/// filesystem and two exports to the same destination never share a temporary.
+pub(super) fn temp_path(dest: &Path) -> PathBuf {
+    let name = dest
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let n = NEXT_TEMP_ID.fetch_add(1, Ordering::Relaxed);
+    dest.with_file_name(format!(".{}.{}.{}.tmp", name, std::process::id(), n))
+}
+
+/// Make the rename durable. Best effort: directories cannot be opened for sync on Windows.
+pub(super) fn sync_parent_dir(path: &Path) {
+    #[cfg(unix)]
+    if let Some(dir) = path.parent() {
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+}
+
+/// Re-parse the file as a stream and check point count and bounds; the points are not kept.
+/// Every point must read back finite. Returns `false` when the format has no reader; such
+/// files are only synced and checksummed.
+fn verify(
+    path: &Path,
+    format: ExportFormat,
+    options: &ExportOptions,
+    expected: &ExpectedCloud,
+) -> Result<bool> {
+    let file = File::open(path).context("Failed to read back export")?;
+    let mut read = ReadSummary::default();
+    let Some(parsed) = format
+        .exporter()
+        .read_back(&mut BufReader::new(file), options, &mut read)
+    else {
+        return Ok(false);
+    };
+    parsed.context("Export verification failed: written file does not parse")?;
+    let tolerance = BOUNDS_TOLERANCE_MM + format.exporter().read_back_rounding_mm(options);
+    ensure!(
+        read.skipped == 0,
+        "Export verification failed: {} points read back with non-finite coordinates",
+        read.skipped
+    );
+    ensure!(
+        read.count == expected.point_count,
+        "Export verification failed: wrote {} points but read back {}",
+        expected.point_count,
+        read.count
+    );
+    if let (Some((min, max)), Some((read_min, read_max))) = (expected.bounds, read.bounds) {
+        let close = |a: &[f32; 3], b: &[f32; 3]| {
+            a.iter()
+                .zip(b)
+                .all(|(x, y)| (x - y).abs() <= tolerance.max(x.abs() * 1e-6))
+        };
+        ensure!(
+            close(&min, &read_min) && close(&max, &read_max),
+            "Export verification failed: bounds {:?}–{:?} read back as {:?}–{:?}",
+            min,
+            max,
+            read_min,
+            read_max
+        );
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(id: &str) -> ExportFormat {
+        id.parse().unwrap()
+    }
+
+    fn default_options(id: &str) -> ExportOptions {
+        ExportOptions::resolve(&format(id).exporter().options_schema(), None).unwrap()
+    }
+
+    #[test]
+    fn commit_replaces_destination_and_reports_checksum() {
+        let dest = std::env::temp_dir().join("sp3d_atomic_commit_test.xyz");
+        std::fs::write(&dest, "old").unwrap();
+        let points = [[0.0, 0.0, 0.0], [10.0, 5.0, 2.5]];
+
+        let mut file = AtomicExport::create(&dest, format("xyz"), &default_options("xyz")).unwrap();
+        let temp = file.temp.clone();
+        for p in points {
+            super::super::xyz::write_xyz_row(file.writer(), p).unwrap();
+        }
+        let receipt = file.commit(&ExpectedCloud::for_points(&points)).unwrap();
+
+        let bytes = std::fs::read(&dest).unwrap();
+        let _ = std::fs::remove_file(&dest);
+        assert!(!temp.exists());
+        assert!(receipt.verified);
+        assert_eq!(receipt.point_count, 2);
+        assert_eq!(receipt.byte_size, bytes.len() as u64);
+        assert_eq!(receipt.sha256.len(), 64);
+    }
+
+    #[test]
+    fn truncated_write_is_rejected_and_destination_kept() {
+        let dest = std::env::temp_dir().join("sp3d_atomic_truncated_test.xyz");
+        std::fs::write(&dest, "previous export").unwrap();
+        let points = [[0.0, 0.0, 0.0], [10.0, 5.0, 2.5], [1.0, 1.0, 1.0]];
+
+        let mut file = AtomicExport::create(&dest, format("xyz"), &default_options("xyz")).unwrap();
+        let temp = file.temp.clone();
+        // Simulate a short write: only the first point reaches the file.
+        super::super::xyz::write_xyz_row(file.writer(), points[0]).unwrap();
+        let err = file
+            .commit(&ExpectedCloud::for_points(&points))
+            .unwrap_err();
+
+        let kept = std::fs::read_to_string(&dest).unwrap();
+        let _ = std::fs::remove_file(&dest);
+        assert!(err.to_string().contains("read back 1"));
+        assert_eq!(kept, "previous export");
+        assert!(!temp.exists());
+    }
+
+    #[test]
+    fn every_format_is_read_back() {
+        let points = [[2.0, 2.0, 2.0], [10.0, 5.0, 2.5], [7.25, 3.5, 1.125]];
+        let metadata = super::super::test_util::sample_metadata();
+        for f in ExportFormat::all() {
+            let dest = std::env::temp_dir().join(format!("sp3d_atomic_all.{}", f.extension()));
+            let receipt = super::super::export_points(&dest, &points, &metadata, f).unwrap();
+            let _ = std::fs::remove_file(&dest);
+            assert!(receipt.verified, "{} was not read back", f.id());
+        }
+    }
+
+    #[test]
+    fn non_finite_rows_fail_verification() {
+        let dest = std::env::temp_dir().join("sp3d_atomic_nan_test.xyz");
+        let points = [[0.0, 0.0, 0.0], [10.0, 5.0, 2.5]];
+        let mut file = AtomicExport::create(&dest, format("xyz"), &default_options("xyz")).unwrap();
+        super::super::xyz::write_xyz_row(file.writer(), points[0]).unwrap();
+        super::super::xyz::write_xyz_row(file.writer(), [f32::NAN, 5.0, 2.5]).unwrap();
+        let err = file
+            .commit(&ExpectedCloud::for_points(&points))
+            .unwrap_err();
+        assert!(err.to_string().contains("non-finite"), "{}", err);
+        assert!(!dest.exists());
+    }
+
+    #[test]
+    fn concurrent_exports_to_one_destination_keep_separate_temporaries() {
+        let dest = std::env::temp_dir().join("sp3d_atomic_concurrent_test.xyz");
+        let first = AtomicExport::create(&dest, format("xyz"), &default_options("xyz")).unwrap();
+        let second = AtomicExport::create(&dest, format("xyz"), &default_options("xyz")).unwrap();
+        assert_ne!(first.temp, second.temp);
+        let kept = second.temp.clone();
+        drop(first);
+        assert!(kept.exists());
+        drop(second);
+        assert!(!kept.exists());
+    }
+
+    #[test]
+    fn dropped_export_leaves_no_temporary() {
+        let dest = std::env::temp_dir().join("sp3d_atomic_dropped_test.ply");
+        let file = AtomicExport::create(&dest, format("ply"), &default_options("ply")).unwrap();
+        let temp = file.temp.clone();
+        assert!(temp.exists());
+        drop(file);
+        assert!(!temp.exists());
+        assert!(!dest.exists());
+    }
+}
@@ -0,0 +1,200 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! CSV export: `#` comment header, `x,y,z` header row, one point per row.
+//!
+//! Delimiter, decimal separator, precision, units, columns, header and line endings are
+//! configurable through the [`TextDialect`] options.
+
+use anyhow::Result;
+use std::io::{BufRead, Write};
+
+use super::dialect::TextDialect;
+use super::{
+    ExportHeader, ExportInput, ExportMetadata, ExportOptionSpec, ExportOptions, PointCloudExporter,
+    PointRowWriter,
+};
+use crate::import::ReadSink;
+use crate::volumetric::PointRecord;
+
+/// Write points to CSV format with header.
+///
+/// CSV format with header row: `x,y,z`
+/// Compatible with spreadsheet software and many import tools.
+pub fn write_csv<W: Write + ?Sized>(
+    writer: &mut W,
+    points: &[[f32; 3]],
+    metadata: &ExportMetadata,
+) -> Result<()> {
+    write_csv_dialect(writer, points, metadata, &TextDialect::csv())
+}
+
+/// Write points to CSV in `dialect`.
+pub fn write_csv_dialect<W: Write + ?Sized>(
+    writer: &mut W,
+    points: &[[f32; 3]],
+    metadata: &ExportMetadata,
+    dialect: &TextDialect,
+) -> Result<()> {
+    write_csv_header(writer, metadata, dialect)?;
+
+    // Points
+    for (i, &p) in points.iter().enumerate() {
+        dialect.write_row(writer, i as u64, p)?;
+    }
+
+    Ok(())
+}
+
+/// Comment lines and the column-name row (both omitted when the dialect has no header).
+fn write_csv_header<W: Write + ?Sized>(
+    writer: &mut W,
+    metadata: &ExportMetadata,
+    dialect: &TextDialect,
+) -> Result<()> {
+    if !dialect.header {
+        return Ok(());
+    }
+    let eol = dialect.line_ending.as_str();
+    let units = dialect.units.id();
+
+    // Optional comment header (some CSV readers ignore lines starting with #)
+    write!(writer, "# Generated by {}{}", metadata.generator, eol)?;
+
+    if let Some(ref source) = metadata.source_image {
+        write!(writer, "# Source: {}{}", source, eol)?;
+    }
+
+    if let Some(ref envelope) = metadata.blank_envelope {
+        write!(
+            writer,
+            "# Blank: {}x{}x{} mm, margin {} mm{}",
+            envelope.length_mm, envelope.width_mm, envelope.height_mm, envelope.margin_mm, eol
+        )?;
+    }
+
+    if units != "mm" {
+        write!(writer, "# Units: {}{}", units, eol)?;
+    }
+
+    // Header row
+    dialect.write_column_names(writer)
+}
+
+/// Registry entry for CSV.
+pub struct CsvExporter;
+
+impl PointCloudExporter for CsvExporter {
+    fn id(&self) -> &'static str {
+        "csv"
+    }
+
+    fn label(&self) -> &'static str {
+        "CSV"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["csv"]
+    }
+
+    fn text_dialect(&self) -> Option<TextDialect> {
+        Some(TextDialect::csv())
+    }
+
+    fn options_schema(&self) -> Vec<ExportOptionSpec> {
+        TextDialect::csv().options_schema()
+    }
+
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        input: &ExportInput,
+        options: &ExportOptions,
+    ) -> Result<()> {
+        let dialect = TextDialect::from_options(options)?;
+        write_csv_dialect(writer, input.points, input.metadata, &dialect)
+    }
+
+    fn begin_stream(
+        &self,
+        writer: &mut dyn Write,
+        header: &ExportHeader,
+        options: &ExportOptions,
+    ) -> Result<Option<PointRowWriter>> {
+        let dialect = TextDialect::from_options(options)?;
+        write_csv_header(writer, header.metadata, &dialect)?;
+        let mut index = 0u64;
+        let row: PointRowWriter = Box::new(move |w, r: &PointRecord| {
+            dialect.write_row(w, index, r.position)?;
+            index += 1;
+            Ok(())
+        });
+        Ok(Some(row))
+    }
+
+    fn read_back(
+        &self,
+        reader: &mut dyn BufRead,
+        options: &ExportOptions,
+        sink: &mut dyn ReadSink,
+    ) -> Option<Result<()>> {
+        Some(TextDialect::read_back(options, reader, sink))
+    }
+
+    fn read_back_rounding_mm(&self, options: &ExportOptions) -> f32 {
+        TextDialect::from_options(options).map_or(0.0, |d| d.rounding_mm())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::test_util::{sample_metadata, sample_points};
+    use std::io::Cursor;
+
+    #[test]
+    fn write_csv_format() {
+        let points = sample_points();
+        let metadata = sample_metadata();
+        let mut output = Cursor::new(Vec::new());
+
+        write_csv(&mut output, &points, &metadata).unwrap();
+
+        let result = String::from_utf8(output.into_inner()).unwrap();
+        assert!(result.contains("# Generated by"));
+        assert!(result.contains("# Source: test.png"));
+        assert!(result.contains("x,y,z"));
+        assert!(result.contains("0.000000,0.000000,0.000000"));
+        assert!(result.contains("10.000000,5.000000,3.000000"));
+    }
+
+    #[test]
+    fn dialect_export_is_verified_on_write() {
+        let format: crate::export::ExportFormat = "csv".parse().unwrap();
+        let raw = serde_json::json!({
+            "delimiter": "semicolon",
+            "decimalSeparator": "comma",
+            "precision": 2,
+            "units": "in",
+            "columns": "index,y,x,z",
+        });
+        let options =
+            ExportOptions::resolve(&format.exporter().options_schema(), Some(&raw)).unwrap();
+        let points = sample_points();
+        let metadata = sample_metadata();
+        let input = ExportInput {
+            points: &points,
+            attributes: &Default::default(),
+            metadata: &metadata,
+        };
+        let path = std::env::temp_dir().join("sp3d_csv_dialect_test.csv");
+
+        let receipt = crate::export::export_to_file(&path, format, &input, &options).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(receipt.verified);
+        assert!(text.contains("# Units: in\n"));
+        assert!(text.contains("index;y;x;z\n"));
+        assert!(text.contains("\n1;0,20;0,39;0,12\n"));
+    }
+}
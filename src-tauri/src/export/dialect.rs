@@ -0,0 +1,500 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Text dialects for the XYZ and CSV exporters.
+//!
+//! Engraver software disagrees on plain-text point files: field delimiter, decimal
+//! separator (European tools expect `12,5`), precision, length unit, column order (some
+//! want a running point index), a header and line endings. [`TextDialect`] is exposed as
+//! exporter options; the last-used dialect per format is kept in `AppSettings` and becomes
+//! the format's option defaults.
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+
+use super::{ExportOptionSpec, ExportOptions};
+use crate::import::{next_line, push_point, ImportedPoints, ReadSink};
+
+/// Largest number of decimal places offered.
+pub const MAX_PRECISION: u8 = 9;
+
+/// Field delimiter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Delimiter {
+    Space,
+    Tab,
+    Comma,
+    Semicolon,
+}
+
+impl Delimiter {
+    const IDS: &'static [&'static str] = &["space", "tab", "comma", "semicolon"];
+    const ALL: [Self; 4] = [Self::Space, Self::Tab, Self::Comma, Self::Semicolon];
+
+    fn as_char(self) -> char {
+        match self {
+            Self::Space => ' ',
+            Self::Tab => '\t',
+            Self::Comma => ',',
+            Self::Semicolon => ';',
+        }
+    }
+}
+
+/// Decimal separator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DecimalSeparator {
+    Point,
+    Comma,
+}
+
+impl DecimalSeparator {
+    const IDS: &'static [&'static str] = &["point", "comma"];
+    const ALL: [Self; 2] = [Self::Point, Self::Comma];
+}
+
+/// Length unit of written coordinates (points are fitted in millimetres).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LengthUnit {
+    #[serde(rename = "mm")]
+    Millimeters,
+    #[serde(rename = "um")]
+    Micrometers,
+    #[serde(rename = "cm")]
+    Centimeters,
+    #[serde(rename = "m")]
+    Meters,
+    #[serde(rename = "in")]
+    Inches,
+}
+
+impl LengthUnit {
+    const IDS: &'static [&'static str] = &["mm", "um", "cm", "m", "in"];
+    const ALL: [Self; 5] = [
+        Self::Millimeters,
+        Self::Micrometers,
+        Self::Centimeters,
+        Self::Meters,
+        Self::Inches,
+    ];
+
+    pub fn id(self) -> &'static str {
+        id_of(&Self::ALL, Self::IDS, self)
+    }
+
+    /// Units per millimetre.
+    pub fn per_mm(self) -> f64 {
+        match self {
+            Self::Millimeters => 1.0,
+            Self::Micrometers => 1000.0,
+            Self::Centimeters => 0.1,
+            Self::Meters => 0.001,
+            Self::Inches => 1.0 / 25.4,
+        }
+    }
+}
+
+/// Line ending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LineEnding {
+    Lf,
+    Crlf,
+}
+
+impl LineEnding {
+    const IDS: &'static [&'static str] = &["lf", "crlf"];
+    const ALL: [Self; 2] = [Self::Lf, Self::Crlf];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Lf => "\n",
+            Self::Crlf => "\r\n",
+        }
+    }
+}
+
+/// One output column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Column {
+    /// Zero-based point index in file order.
+    Index,
+    X,
+    Y,
+    Z,
+}
+
+impl Column {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Index => "index",
+            Self::X => "x",
+            Self::Y => "y",
+            Self::Z => "z",
+        }
+    }
+}
+
+/// Column orders offered in the options schema.
+const COLUMN_ORDERS: &[&str] = &[
+    "x,y,z",
+    "index,x,y,z",
+    "x,y,z,index",
+    "y,x,z",
+    "index,y,x,z",
+];
+
+/// Everything that varies between plain-text point file dialects.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextDialect {
+    pub delimiter: Delimiter,
+    pub decimal_separator: DecimalSeparator,
+    /// Decimal places, 0–[`MAX_PRECISION`].
+    pub precision: u8,
+    pub units: LengthUnit,
+    /// Each of x, y, z exactly once; index at most once.
+    pub columns: Vec<Column>,
+    /// XYZ: a column-name row. CSV: `#` comment lines and the column-name row.
+    pub header: bool,
+    pub line_ending: LineEnding,
+}
+
+impl TextDialect {
+    /// Historic XYZ output: `x y z`, 6 decimals, mm, no header.
+    pub fn xyz() -> Self {
+        Self {
+            delimiter: Delimiter::Space,
+            decimal_separator: DecimalSeparator::Point,
+            precision: 6,
+            units: LengthUnit::Millimeters,
+            columns: vec![Column::X, Column::Y, Column::Z],
+            header: false,
+            line_ending: LineEnding::Lf,
+        }
+    }
+
+    /// Historic CSV output: comma-separated with comments and an `x,y,z` header row.
+    pub fn csv() -> Self {
+        Self {
+            delimiter: Delimiter::Comma,
+            header: true,
+            ..Self::xyz()
+        }
+    }
+
+    /// Options schema with this dialect as the defaults.
+    pub fn options_schema(&self) -> Vec<ExportOptionSpec> {
+        let choice = |key, label, choices: &'static [&'static str], default: &str| {
+            let mut spec = ExportOptionSpec::choice(key, label, choices, choices[0]);
+            spec.default = serde_json::Value::String(default.to_string());
+            spec
+        };
+        // A hand-edited settings file may hold an order the schema does not offer.
+        let columns = Some(self.columns_id())
+            .filter(|c| COLUMN_ORDERS.contains(&c.as_str()))
+            .unwrap_or_else(|| COLUMN_ORDERS[0].to_string());
+        vec![
+            choice(
+                "delimiter",
+                "Delimiter",
+                Delimiter::IDS,
+                id_of(&Delimiter::ALL, Delimiter::IDS, self.delimiter),
+            ),
+            choice(
+                "decimalSeparator",
+                "Decimal separator",
+                DecimalSeparator::IDS,
+                id_of(
+                    &DecimalSeparator::ALL,
+                    DecimalSeparator::IDS,
+                    self.decimal_separator,
+                ),
+            ),
+            ExportOptionSpec::number("precision", "Decimal places", self.precision as f64),
+            choice("units", "Units", LengthUnit::IDS, self.units.id()),
+            choice("columns", "Columns", COLUMN_ORDERS, &columns),
+            ExportOptionSpec::bool("header", "Header", self.header),
+            choice(
+                "lineEnding",
+                "Line endings",
+                LineEnding::IDS,
+                id_of(&LineEnding::ALL, LineEnding::IDS, self.line_ending),
+            ),
+        ]
+    }
+
+    /// Dialect from options resolved against [`TextDialect::options_schema`].
+    pub fn from_options(options: &ExportOptions) -> Result<Self> {
+        let precision = options.number("precision");
+        ensure!(
+            precision.fract() == 0.0 && (0.0..=MAX_PRECISION as f64).contains(&precision),
+            "Decimal places must be a whole number from 0 to {}",
+            MAX_PRECISION
+        );
+        let dialect = Self {
+            delimiter: from_id(&Delimiter::ALL, Delimiter::IDS, options.string("delimiter"))?,
+            decimal_separator: from_id(
+                &DecimalSeparator::ALL,
+                DecimalSeparator::IDS,
+                options.string("decimalSeparator"),
+            )?,
+            precision: precision as u8,
+            units: from_id(&LengthUnit::ALL, LengthUnit::IDS, options.string("units"))?,
+            columns: parse_columns(options.string("columns"))?,
+            header: options.bool("header"),
+            line_ending: from_id(
+                &LineEnding::ALL,
+                LineEnding::IDS,
+                options.string("lineEnding"),
+            )?,
+        };
+        dialect.validate()?;
+        Ok(dialect)
+    }
+
+    /// Reject dialects that cannot be read back unambiguously.
+    pub fn validate(&self) -> Result<()> {
+        ensure!(
+            self.precision <= MAX_PRECISION,
+            "Decimal places must be at most {}",
+            MAX_PRECISION
+        );
+        ensure!(
+            !(self.decimal_separator == DecimalSeparator::Comma
+                && self.delimiter == Delimiter::Comma),
+            "A decimal comma needs a delimiter other than comma"
+        );
+        parse_columns(&self.columns_id())?;
+        Ok(())
+    }
+
+    fn columns_id(&self) -> String {
+        self.columns
+            .iter()
+            .map(|c| c.name())
+            .collect::<Vec<_>>()
+            .join(",")
+    }
+
+    /// Write the column-name row (no-op when the header is off).
+    pub fn write_column_names<W: Write + ?Sized>(&self, writer: &mut W) -> Result<()> {
+        if self.header {
+            let names: Vec<&str> = self.columns.iter().map(|c| c.name()).collect();
+            let delimiter = self.delimiter.as_char().to_string();
+            write!(
+                writer,
+                "{}{}",
+                names.join(&delimiter),
+                self.line_ending.as_str()
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Write one point (`p` in mm) as a row; `index` fills the index column.
+    pub fn write_row<W: Write + ?Sized>(
+        &self,
+        writer: &mut W,
+        index: u64,
+        p: [f32; 3],
+    ) -> Result<()> {
+        let mut line = String::with_capacity(48);
+        for (i, column) in self.columns.iter().enumerate() {
+            if i > 0 {
+                line.push(self.delimiter.as_char());
+            }
+            let value = match column {
+                Column::Index => {
+                    line.push_str(&index.to_string());
+                    continue;
+                }
+                Column::X => p[0],
+                Column::Y => p[1],
+                Column::Z => p[2],
+            };
+            let number = format!(
+                "{:.*}",
+                self.precision as usize,
+                value as f64 * self.units.per_mm()
+            );
+            match self.decimal_separator {
+                DecimalSeparator::Point => line.push_str(&number),
+                DecimalSeparator::Comma => line.push_str(&number.replace('.', ",")),
+            }
+        }
+        line.push_str(self.line_ending.as_str());
+        writer.write_all(line.as_bytes())?;
+        Ok(())
+    }
+
+    /// Half the last written digit, in mm: how far a read-back coordinate may be off.
+    pub fn rounding_mm(&self) -> f32 {
+        (0.5 * 10f64.powi(-(self.precision as i32)) / self.units.per_mm()) as f32
+    }
+
+    /// Stream a file written with `options` into `sink` (see [`TextDialect::scan_points`]).
+    pub fn read_back(
+        options: &ExportOptions,
+        reader: &mut dyn BufRead,
+        sink: &mut dyn ReadSink,
+    ) -> Result<()> {
+        Self::from_options(options)?.scan_points(reader, sink)
+    }
+
+    /// Parse text written in this dialect back to positions in mm.
+    pub fn read_points(&self, text: &str) -> Result<ImportedPoints> {
+        let mut out = ImportedPoints::default();
+        self.scan_points(&mut text.as_bytes(), &mut out)?;
+        Ok(out)
+    }
+
+    /// Stream text written in this dialect into `sink` as positions in mm.
+    ///
+    /// Blank lines and `#` comments are skipped, as is the first row when the header is on.
+    pub fn scan_points(&self, reader: &mut dyn BufRead, sink: &mut dyn ReadSink) -> Result<()> {
+        let position = |c: Column| self.columns.iter().position(|&col| col == c);
+        let (Some(xi), Some(yi), Some(zi)) = (
+            position(Column::X),
+            position(Column::Y),
+            position(Column::Z),
+        ) else {
+            bail!("Dialect columns must include x, y and z");
+        };
+        let mut header_pending = self.header;
+        let mut line = String::new();
+        let mut line_no = 0;
+        while next_line(reader, &mut line)? {
+            line_no += 1;
+            let row = line.trim_end_matches(['\r', '\n']);
+            if row.trim().is_empty() || row.starts_with('#') {
+                continue;
+            }
+            if header_pending {
+                header_pending = false;
+                continue;
+            }
+            let fields: Vec<&str> = match self.delimiter {
+                Delimiter::Space => row.split_whitespace().collect(),
+                d => row.split(d.as_char()).map(str::trim).collect(),
+            };
+            let get = |c: usize| -> Result<f64> {
+                let field = fields
+                    .get(c)
+                    .with_context(|| format!("line {}: missing column {}", line_no, c + 1))?;
+                let field = match self.decimal_separator {
+                    DecimalSeparator::Point => field.to_string(),
+                    DecimalSeparator::Comma => field.replace(',', "."),
+                };
+                let value: f64 = field
+                    .parse()
+                    .with_context(|| format!("line {}: invalid number '{}'", line_no, field))?;
+                Ok(value / self.units.per_mm())
+            };
+            push_point(sink, [get(xi)?, get(yi)?, get(zi)?])?;
+        }
+        Ok(())
+    }
+}
+
+fn id_of<T: PartialEq + Copy>(all: &[T], ids: &'static [&'static str], value: T) -> &'static str {
+    all.iter()
+        .position(|&v| v == value)
+        .map_or(ids[0], |i| ids[i])
+}
+
+fn from_id<T: Copy>(all: &[T], ids: &[&str], id: &str) -> Result<T> {
+    ids.iter()
+        .position(|&v| v == id)
+        .map(|i| all[i])
+        .with_context(|| format!("Unknown dialect value '{}'", id))
+}
+
+/// Parse `x,y,z`-style column lists: x, y and z exactly once, index at most once.
+fn parse_columns(spec: &str) -> Result<Vec<Column>> {
+    let columns = spec
+        .split(',')
+        .map(|name| match name.trim() {
+            "index" => Ok(Column::Index),
+            "x" => Ok(Column::X),
+            "y" => Ok(Column::Y),
+            "z" => Ok(Column::Z),
+            other => bail!("Unknown column '{}'", other),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let count = |column| columns.iter().filter(|&&c| c == column).count();
+    ensure!(
+        [Column::X, Column::Y, Column::Z]
+            .iter()
+            .all(|&c| count(c) == 1)
+            && count(Column::Index) <= 1,
+        "Columns must contain x, y and z exactly once (and index at most once)"
+    );
+    Ok(columns)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(dialect: &TextDialect, raw: serde_json::Value) -> ExportOptions {
+        ExportOptions::resolve(&dialect.options_schema(), Some(&raw)).unwrap()
+    }
+
+    #[test]
+    fn default_options_roundtrip_to_dialect() {
+        for dialect in [TextDialect::xyz(), TextDialect::csv()] {
+            let resolved = options(&dialect, serde_json::json!({}));
+            assert_eq!(TextDialect::from_options(&resolved).unwrap(), dialect);
+        }
+    }
+
+    #[test]
+    fn european_dialect_writes_and_reads_back() {
+        let raw = serde_json::json!({
+            "delimiter": "semicolon",
+            "decimalSeparator": "comma",
+            "precision": 3,
+            "units": "in",
+            "columns": "index,x,y,z",
+            "header": true,
+            "lineEnding": "crlf",
+        });
+        let dialect = TextDialect::from_options(&options(&TextDialect::csv(), raw)).unwrap();
+        let mut out = Vec::new();
+        dialect.write_column_names(&mut out).unwrap();
+        dialect.write_row(&mut out, 0, [25.4, 12.7, 0.0]).unwrap();
+        dialect.write_row(&mut out, 1, [50.8, 0.0, 2.54]).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert_eq!(
+            text,
+            "index;x;y;z\r\n0;1,000;0,500;0,000\r\n1;2,000;0,000;0,100\r\n"
+        );
+
+        let back = dialect.read_points(&text).unwrap();
+        assert_eq!(back.points.len(), 2);
+        assert!((back.points[1][2] - 2.54).abs() < dialect.rounding_mm());
+    }
+
+    #[test]
+    fn ambiguous_or_malformed_dialects_are_rejected() {
+        let comma_comma = serde_json::json!({ "decimalSeparator": "comma" });
+        let resolved = options(&TextDialect::csv(), comma_comma);
+        assert!(TextDialect::from_options(&resolved).is_err());
+
+        let fractional = serde_json::json!({ "precision": 2.5 });
+        let resolved = options(&TextDialect::xyz(), fractional);
+        assert!(TextDialect::from_options(&resolved).is_err());
+
+        assert!(parse_columns("x,y").is_err());
+        assert!(parse_columns("x,y,z,x").is_err());
+        assert_eq!(
+            parse_columns("y,x,z").unwrap(),
+            vec![Column::Y, Column::X, Column::Z]
+        );
+    }
+}
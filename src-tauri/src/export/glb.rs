@@ -0,0 +1,372 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! glTF 2.0 binary (GLB) export for customer-facing web previews.
+
+use anyhow::{ensure, Context, Result};
+use std::io::{BufRead, Read, Write};
+
+use super::{ExportInput, ExportMetadata, ExportOptions, PointCloudExporter};
+use crate::blank_envelope::{compute_bbox, BlankEnvelope};
+use crate::import::{push_point, ReadSink};
+
+/// glTF primitive modes used by the GLB exporter.
+const GLTF_MODE_POINTS: u32 = 0;
+const GLTF_MODE_LINES: u32 = 1;
+const GLTF_MODE_LINE_LOOP: u32 = 2;
+
+/// glTF component types and buffer view targets.
+const GLTF_FLOAT: u32 = 5126;
+const GLTF_UNSIGNED_SHORT: u32 = 5123;
+const GLTF_ARRAY_BUFFER: u32 = 34962;
+const GLTF_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+/// GLB chunk type tags ("JSON" and "BIN\0", little-endian).
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+
+/// Blank envelope wireframe: bottom loop, top loop, then the four vertical edges.
+const ENVELOPE_INDICES: [u16; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 0, 4, 1, 5, 2, 6, 3, 7];
+
+/// The eight corners of the blank, ordered to match [`ENVELOPE_INDICES`].
+fn envelope_corners(envelope: &BlankEnvelope) -> [[f32; 3]; 8] {
+    let (l, w, h) = (envelope.length_mm, envelope.width_mm, envelope.height_mm);
+    [
+        [0.0, 0.0, 0.0],
+        [l, 0.0, 0.0],
+        [l, w, 0.0],
+        [0.0, w, 0.0],
+        [0.0, 0.0, h],
+        [l, 0.0, h],
+        [l, w, h],
+        [0.0, w, h],
+    ]
+}
+
+/// Write points to glTF 2.0 binary (GLB) for browser previews.
+///
+/// The cloud is a single POINTS primitive. When `metadata` has a blank envelope, the
+/// blank is added as a wireframe mesh (two LINE_LOOP rectangles plus vertical LINES).
+/// Buffers hold mm; the root node scales by 0.001 so viewers see metres (glTF units).
+/// [`ExportMetadata`] is stored in `asset.extras`.
+pub fn write_glb<W: Write + ?Sized>(
+    writer: &mut W,
+    points: &[[f32; 3]],
+    metadata: &ExportMetadata,
+) -> Result<()> {
+    let (min, max) = compute_bbox(points).ok_or_else(|| anyhow::anyhow!("Point cloud is empty"))?;
+
+    let mut bin: Vec<u8> = Vec::with_capacity(points.len() * 12 + 128);
+    for p in points {
+        for v in p {
+            bin.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    let points_len = bin.len();
+
+    let mut buffer_views = vec![serde_json::json!({
+        "buffer": 0, "byteOffset": 0, "byteLength": points_len, "target": GLTF_ARRAY_BUFFER
+    })];
+    let mut accessors = vec![serde_json::json!({
+        "bufferView": 0, "componentType": GLTF_FLOAT, "count": points.len(),
+        "type": "VEC3", "min": min, "max": max
+    })];
+    let mut meshes = vec![serde_json::json!({
+        "name": "point_cloud",
+        "primitives": [{ "attributes": { "POSITION": 0 }, "mode": GLTF_MODE_POINTS, "material": 0 }]
+    })];
+    let mut children = vec![1];
+    let mut nodes = vec![
+        serde_json::json!({}),
+        serde_json::json!({ "name": "point_cloud", "mesh": 0 }),
+    ];
+
+    let mut extras = serde_json::json!({ "generator": metadata.generator });
+    if let Some(ref source) = metadata.source_image {
+        extras["sourceImage"] = serde_json::json!(source);
+    }
+
+    if let Some(ref envelope) = metadata.blank_envelope {
+        extras["blankEnvelope"] = serde_json::to_value(envelope)?;
+
+        let corners = envelope_corners(envelope);
+        let corners_offset = bin.len();
+        for c in &corners {
+            for v in c {
+                bin.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        let indices_offset = bin.len();
+        for i in ENVELOPE_INDICES {
+            bin.extend_from_slice(&i.to_le_bytes());
+        }
+
+        buffer_views.push(serde_json::json!({
+            "buffer": 0, "byteOffset": corners_offset, "byteLength": indices_offset - corners_offset,
+            "target": GLTF_ARRAY_BUFFER
+        }));
+        buffer_views.push(serde_json::json!({
+            "buffer": 0, "byteOffset": indices_offset, "byteLength": ENVELOPE_INDICES.len() * 2,
+            "target": GLTF_ELEMENT_ARRAY_BUFFER
+        }));
+        accessors.push(serde_json::json!({
+            "bufferView": 1, "componentType": GLTF_FLOAT, "count": 8, "type": "VEC3",
+            "min": corners[0], "max": corners[6]
+        }));
+        for (offset, count) in [(0usize, 4usize), (8, 4), (16, 8)] {
+            accessors.push(serde_json::json!({
+                "bufferView": 2, "byteOffset": offset, "componentType": GLTF_UNSIGNED_SHORT,
+                "count": count, "type": "SCALAR"
+            }));
+        }
+        let primitives: Vec<serde_json::Value> = [
+            (2, GLTF_MODE_LINE_LOOP),
+            (3, GLTF_MODE_LINE_LOOP),
+            (4, GLTF_MODE_LINES),
+        ]
+        .iter()
+        .map(|&(indices, mode)| {
+            serde_json::json!({
+                "attributes": { "POSITION": 1 }, "indices": indices, "mode": mode, "material": 1
+            })
+        })
+        .collect();
+        meshes.push(serde_json::json!({ "name": "blank_envelope", "primitives": primitives }));
+        nodes.push(serde_json::json!({ "name": "blank_envelope", "mesh": 1 }));
+        children.push(2);
+    }
+
+    // BIN chunk must be 4-byte aligned; the buffer length excludes padding.
+    let buffer_len = bin.len();
+    bin.resize(bin.len().div_ceil(4) * 4, 0);
+
+    nodes[0] = serde_json::json!({
+        "name": "SimplePicture3D", "children": children, "scale": [0.001, 0.001, 0.001]
+    });
+
+    let gltf = serde_json::json!({
+        "asset": { "version": "2.0", "generator": metadata.generator, "extras": extras },
+        "scene": 0,
+        "scenes": [{ "nodes": [0] }],
+        "nodes": nodes,
+        "meshes": meshes,
+        "materials": [
+            {
+                "name": "points",
+                "pbrMetallicRoughness": { "baseColorFactor": [0.85, 0.92, 1.0, 1.0], "metallicFactor": 0.0 }
+            },
+            {
+                "name": "blank",
+                "pbrMetallicRoughness": { "baseColorFactor": [0.5, 0.5, 0.5, 1.0], "metallicFactor": 0.0 }
+            }
+        ],
+        "accessors": accessors,
+        "bufferViews": buffer_views,
+        "buffers": [{ "byteLength": buffer_len }]
+    });
+
+    let mut json = serde_json::to_vec(&gltf)?;
+    json.resize(json.len().div_ceil(4) * 4, b' ');
+
+    let total_len = 12 + 8 + json.len() + 8 + bin.len();
+    let total_len =
+        u32::try_from(total_len).map_err(|_| anyhow::anyhow!("GLB exceeds 4 GiB limit"))?;
+
+    writer.write_all(b"glTF")?;
+    writer.write_all(&2u32.to_le_bytes())?;
+    writer.write_all(&total_len.to_le_bytes())?;
+    writer.write_all(&(json.len() as u32).to_le_bytes())?;
+    writer.write_all(&GLB_CHUNK_JSON.to_le_bytes())?;
+    writer.write_all(&json)?;
+    writer.write_all(&(bin.len() as u32).to_le_bytes())?;
+    writer.write_all(&GLB_CHUNK_BIN.to_le_bytes())?;
+    writer.write_all(&bin)?;
+
+    Ok(())
+}
+
+/// Stream the point cloud positions of a GLB written by [`write_glb`] into `sink`.
+///
+/// Reads the POSITION accessor of the first mesh from the JSON chunk, then its vertices from
+/// the BIN chunk; errors when they run past the chunk or disagree with the accessor min/max.
+fn read_glb(reader: &mut dyn BufRead, sink: &mut dyn ReadSink) -> Result<()> {
+    let mut head = [0u8; 20];
+    reader
+        .read_exact(&mut head)
+        .context("GLB header is truncated")?;
+    let u32_at = |buf: &[u8], at: usize| u32::from_le_bytes(buf[at..at + 4].try_into().unwrap());
+    ensure!(
+        &head[0..4] == b"glTF" && u32_at(&head, 4) == 2,
+        "not a glTF 2.0 binary"
+    );
+    ensure!(
+        u32_at(&head, 16) == GLB_CHUNK_JSON,
+        "GLB does not start with a JSON chunk"
+    );
+    let mut json = vec![0u8; u32_at(&head, 12) as usize];
+    reader
+        .read_exact(&mut json)
+        .context("GLB JSON chunk is truncated")?;
+    let gltf: serde_json::Value = serde_json::from_slice(&json).context("Invalid GLB JSON")?;
+
+    let accessor = gltf["meshes"][0]["primitives"][0]["attributes"]["POSITION"]
+        .as_u64()
+        .map(|i| &gltf["accessors"][i as usize])
+        .context("GLB has no point cloud POSITION accessor")?;
+    let count = accessor["count"]
+        .as_u64()
+        .context("GLB position accessor has no count")? as usize;
+    let view = accessor["bufferView"]
+        .as_u64()
+        .map(|i| &gltf["bufferViews"][i as usize])
+        .context("GLB position accessor has no buffer view")?;
+    let start =
+        view["byteOffset"].as_u64().unwrap_or(0) + accessor["byteOffset"].as_u64().unwrap_or(0);
+    let vec3 = |key: &str| -> Result<[f32; 3]> {
+        serde_json::from_value(accessor[key].clone())
+            .with_context(|| format!("GLB position accessor has no {}", key))
+    };
+    let (accessor_min, accessor_max) = (vec3("min")?, vec3("max")?);
+
+    let mut chunk = [0u8; 8];
+    reader
+        .read_exact(&mut chunk)
+        .context("GLB has no BIN chunk")?;
+    ensure!(
+        u32_at(&chunk, 4) == GLB_CHUNK_BIN,
+        "GLB second chunk is not BIN"
+    );
+    ensure!(
+        start + count as u64 * 12 <= u32_at(&chunk, 0) as u64,
+        "GLB positions run past the BIN chunk"
+    );
+    sink.expect(count)?;
+    std::io::copy(&mut (&mut *reader).take(start), &mut std::io::sink())?;
+    let mut vertex = [0u8; 12];
+    let mut bounds: Option<([f32; 3], [f32; 3])> = None;
+    for _ in 0..count {
+        reader
+            .read_exact(&mut vertex)
+            .context("GLB BIN chunk is truncated")?;
+        let p: [f32; 3] = std::array::from_fn(|a| {
+            f32::from_le_bytes(vertex[4 * a..4 * a + 4].try_into().unwrap())
+        });
+        let (min, max) = bounds.get_or_insert((p, p));
+        for a in 0..3 {
+            min[a] = min[a].min(p[a]);
+            max[a] = max[a].max(p[a]);
+        }
+        push_point(sink, p.map(f64::from))?;
+    }
+    ensure!(
+        bounds == Some((accessor_min, accessor_max)),
+        "GLB accessor bounds {:?}–{:?} disagree with its positions {:?}",
+        accessor_min,
+        accessor_max,
+        bounds
+    );
+    Ok(())
+}
+
+/// Registry entry for GLB (web preview).
+pub struct GlbExporter;
+
+impl PointCloudExporter for GlbExporter {
+    fn id(&self) -> &'static str {
+        "glb"
+    }
+
+    fn label(&self) -> &'static str {
+        "GLB (web preview)"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["glb"]
+    }
+
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        input: &ExportInput,
+        _options: &ExportOptions,
+    ) -> Result<()> {
+        write_glb(writer, input.points, input.metadata)
+    }
+
+    fn read_back(
+        &self,
+        reader: &mut dyn BufRead,
+        _options: &ExportOptions,
+        sink: &mut dyn ReadSink,
+    ) -> Option<Result<()>> {
+        Some(read_glb(reader, sink))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::test_util::{sample_metadata, sample_points};
+    use std::io::Cursor;
+
+    fn read_u32(buf: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(buf[at..at + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn write_glb_container_and_json() {
+        let points = sample_points();
+        let metadata = sample_metadata();
+        let mut output = Cursor::new(Vec::new());
+
+        write_glb(&mut output, &points, &metadata).unwrap();
+
+        let buf = output.into_inner();
+        assert_eq!(&buf[0..4], b"glTF");
+        assert_eq!(read_u32(&buf, 4), 2);
+        assert_eq!(read_u32(&buf, 8) as usize, buf.len());
+
+        let json_len = read_u32(&buf, 12) as usize;
+        assert_eq!(read_u32(&buf, 16), GLB_CHUNK_JSON);
+        assert_eq!(json_len % 4, 0);
+        let gltf: serde_json::Value = serde_json::from_slice(&buf[20..20 + json_len]).unwrap();
+
+        let bin_at = 20 + json_len;
+        let bin_len = read_u32(&buf, bin_at) as usize;
+        assert_eq!(read_u32(&buf, bin_at + 4), GLB_CHUNK_BIN);
+        assert_eq!(bin_len % 4, 0);
+        assert!(gltf["buffers"][0]["byteLength"].as_u64().unwrap() as usize <= bin_len);
+
+        assert_eq!(gltf["asset"]["version"], "2.0");
+        assert_eq!(gltf["asset"]["extras"]["generator"], "SimplePicture3D-Test");
+        assert_eq!(gltf["asset"]["extras"]["sourceImage"], "test.png");
+        assert_eq!(gltf["meshes"][0]["primitives"][0]["mode"], GLTF_MODE_POINTS);
+        assert_eq!(gltf["accessors"][0]["count"], 3);
+        assert_eq!(gltf["accessors"][0]["max"][0], 10.0);
+        assert_eq!(
+            gltf["meshes"][1]["primitives"][0]["mode"],
+            GLTF_MODE_LINE_LOOP
+        );
+    }
+
+    #[test]
+    fn write_glb_without_envelope_has_only_points() {
+        let points = sample_points();
+        let metadata = ExportMetadata::new();
+        let mut output = Cursor::new(Vec::new());
+
+        write_glb(&mut output, &points, &metadata).unwrap();
+
+        let buf = output.into_inner();
+        let json_len = read_u32(&buf, 12) as usize;
+        let gltf: serde_json::Value = serde_json::from_slice(&buf[20..20 + json_len]).unwrap();
+        assert_eq!(gltf["meshes"].as_array().unwrap().len(), 1);
+        assert_eq!(gltf["buffers"][0]["byteLength"], 36);
+    }
+
+    #[test]
+    fn write_glb_rejects_empty_points() {
+        let mut output = Cursor::new(Vec::new());
+        assert!(write_glb(&mut output, &[], &ExportMetadata::new()).is_err());
+    }
+}
@@ -0,0 +1,434 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! LAS 1.4 export (point data record format 6, micrometre quantisation).
+
+use anyhow::{ensure, Context, Result};
+use std::io::{BufRead, Read, Write};
+
+use super::{
+    ExportHeader, ExportInput, ExportMetadata, ExportOptions, PointCloudExporter, PointRowWriter,
+};
+use crate::blank_envelope::BlankEnvelope;
+use crate::import::{push_point, ReadSink};
+use crate::volumetric::{PointAttributes, PointRecord};
+
+/// LAS 1.4 public header block size in bytes.
+const LAS_HEADER_SIZE: u16 = 375;
+
+/// Size of a LAS variable length record header in bytes.
+const LAS_VLR_HEADER_SIZE: usize = 54;
+
+/// Point data record format 6: the base LAS 1.4 point record (no colour, no waveform).
+const LAS_POINT_FORMAT: u8 = 6;
+
+/// Record length for point data record format 6 in bytes.
+const LAS_POINT_RECORD_LEN: u16 = 30;
+
+/// Coordinate resolution in mm. Points are stored in mm, so 0.001 is one micrometre.
+pub const LAS_SCALE_MM: f64 = 0.001;
+
+/// Scale and offset used to quantise coordinates into LAS `i32` records.
+///
+/// Stored value = `round((coord - offset) / scale)`; readers recover `coord = value * scale + offset`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LasQuantization {
+    pub scale: [f64; 3],
+    pub offset: [f64; 3],
+}
+
+impl LasQuantization {
+    /// Choose micrometre scale with the offset at the centre of the extents (rounded to whole mm).
+    ///
+    /// Errors if the extents are too large to be represented at micrometre resolution in `i32`.
+    pub fn from_extents(min: [f64; 3], max: [f64; 3]) -> Result<Self> {
+        let mut offset = [0.0f64; 3];
+        for i in 0..3 {
+            offset[i] = ((min[i] + max[i]) / 2.0).round();
+            let reach = (max[i] - offset[i]).abs().max((min[i] - offset[i]).abs());
+            if !reach.is_finite() || reach / LAS_SCALE_MM > i32::MAX as f64 {
+                anyhow::bail!("Extents too large for micrometre LAS quantisation");
+            }
+        }
+        Ok(Self {
+            scale: [LAS_SCALE_MM; 3],
+            offset,
+        })
+    }
+
+    /// Quantisation for a blank: extents are the full blank `[0, length] × [0, width] × [0, height]`.
+    pub fn for_envelope(envelope: &BlankEnvelope) -> Result<Self> {
+        Self::from_extents(
+            [0.0, 0.0, 0.0],
+            [
+                envelope.length_mm as f64,
+                envelope.width_mm as f64,
+                envelope.height_mm as f64,
+            ],
+        )
+    }
+
+    /// Quantise one coordinate on axis `i`.
+    fn quantize(&self, i: usize, v: f32) -> Result<i32> {
+        let q = ((v as f64 - self.offset[i]) / self.scale[i]).round();
+        if !q.is_finite() || q < i32::MIN as f64 || q > i32::MAX as f64 {
+            anyhow::bail!("Point coordinate {} outside LAS quantisation range", v);
+        }
+        Ok(q as i32)
+    }
+}
+
+/// Write a fixed-width, NUL-padded ASCII field (truncated to `len`).
+fn write_fixed_str<W: Write + ?Sized>(writer: &mut W, s: &str, len: usize) -> Result<()> {
+    let mut buf = vec![0u8; len];
+    let bytes = s.as_bytes();
+    let n = bytes.len().min(len);
+    buf[..n].copy_from_slice(&bytes[..n]);
+    writer.write_all(&buf)?;
+    Ok(())
+}
+
+/// Current (day of year, year) in UTC for the LAS header creation date.
+fn las_creation_date() -> (u16, u16) {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let mut days = (secs / 86_400) as i64;
+    let mut year = 1970i64;
+    loop {
+        let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+        let len = if leap { 366 } else { 365 };
+        if days < len {
+            break;
+        }
+        days -= len;
+        year += 1;
+    }
+    ((days + 1) as u16, year as u16)
+}
+
+/// Text of the "Text Area Description" VLR: source image and blank dimensions.
+fn las_description_text(metadata: &ExportMetadata) -> String {
+    let mut text = format!("Generated by {}\n", metadata.generator);
+    if let Some(ref source) = metadata.source_image {
+        text.push_str(&format!("Source: {}\n", source));
+    }
+    if let Some(ref envelope) = metadata.blank_envelope {
+        text.push_str(&format!(
+            "Blank: {}x{}x{} mm, margin {} mm\n",
+            envelope.length_mm, envelope.width_mm, envelope.height_mm, envelope.margin_mm
+        ));
+    }
+    text
+}
+
+/// Write points to LAS 1.4 (point data record format 6).
+///
+/// Coordinates are in mm and quantised at micrometre resolution. Scale/offset come from
+/// the blank envelope in `metadata` (falls back to the point bounds when absent); header
+/// bounds are the point bounds. The generator goes in the System Identifier and
+/// Generating Software fields; source and blank go in a `LASF_Spec` text VLR (record 3).
+///
+/// # Format
+/// ```text
+/// public header (375 bytes)
+/// VLR: LASF_Spec / 3 "Text Area Description"
+/// N × 30-byte point records: X Y Z (i32), intensity, returns, flags, class, ...
+/// ```
+pub fn write_las<W: Write + ?Sized>(
+    writer: &mut W,
+    points: &[[f32; 3]],
+    metadata: &ExportMetadata,
+) -> Result<()> {
+    let header = ExportHeader::for_points(points, &PointAttributes::default(), metadata)?;
+    let quant = write_las_header(writer, &header)?;
+
+    for &p in points {
+        write_las_row(writer, &quant, p)?;
+    }
+
+    Ok(())
+}
+
+/// Write the public header and description VLR; returns the quantisation for the records.
+fn write_las_header<W: Write + ?Sized>(
+    writer: &mut W,
+    header: &ExportHeader,
+) -> Result<LasQuantization> {
+    let metadata = header.metadata;
+    let bbox = header.bounds;
+    let quant = match (&metadata.blank_envelope, bbox) {
+        (Some(envelope), _) => LasQuantization::for_envelope(envelope)?,
+        (None, Some((min, max))) => LasQuantization::from_extents(
+            [min[0] as f64, min[1] as f64, min[2] as f64],
+            [max[0] as f64, max[1] as f64, max[2] as f64],
+        )?,
+        (None, None) => LasQuantization::from_extents([0.0; 3], [0.0; 3])?,
+    };
+    let (min, max) = bbox.unwrap_or(([0.0; 3], [0.0; 3]));
+    let point_count = header.point_count as u64;
+
+    let description = las_description_text(metadata);
+    let vlr_len = u16::try_from(description.len())
+        .map_err(|_| anyhow::anyhow!("LAS description VLR too long"))?;
+    let offset_to_points = LAS_HEADER_SIZE as u32 + (LAS_VLR_HEADER_SIZE as u32) + vlr_len as u32;
+    let (day, year) = las_creation_date();
+
+    // Public header block
+    writer.write_all(b"LASF")?;
+    writer.write_all(&0u16.to_le_bytes())?; // File Source ID
+    writer.write_all(&(1u16 << 4).to_le_bytes())?; // Global Encoding: WKT (required for PDRF 6+)
+    writer.write_all(&[0u8; 16])?; // Project ID (GUID)
+    writer.write_all(&[1u8, 4u8])?; // Version 1.4
+    write_fixed_str(writer, &metadata.generator, 32)?; // System Identifier
+    write_fixed_str(writer, &metadata.generator, 32)?; // Generating Software
+    writer.write_all(&day.to_le_bytes())?;
+    writer.write_all(&year.to_le_bytes())?;
+    writer.write_all(&LAS_HEADER_SIZE.to_le_bytes())?;
+    writer.write_all(&offset_to_points.to_le_bytes())?;
+    writer.write_all(&1u32.to_le_bytes())?; // Number of VLRs
+    writer.write_all(&[LAS_POINT_FORMAT])?;
+    writer.write_all(&LAS_POINT_RECORD_LEN.to_le_bytes())?;
+    writer.write_all(&0u32.to_le_bytes())?; // Legacy point count (0 for PDRF 6+)
+    writer.write_all(&[0u8; 20])?; // Legacy points by return
+    for s in quant.scale {
+        writer.write_all(&s.to_le_bytes())?;
+    }
+    for o in quant.offset {
+        writer.write_all(&o.to_le_bytes())?;
+    }
+    for i in 0..3 {
+        writer.write_all(&(max[i] as f64).to_le_bytes())?;
+        writer.write_all(&(min[i] as f64).to_le_bytes())?;
+    }
+    writer.write_all(&0u64.to_le_bytes())?; // Start of waveform data
+    writer.write_all(&0u64.to_le_bytes())?; // Start of first EVLR
+    writer.write_all(&0u32.to_le_bytes())?; // Number of EVLRs
+    writer.write_all(&point_count.to_le_bytes())?;
+    // Points by return: every point is a single first return.
+    writer.write_all(&point_count.to_le_bytes())?;
+    writer.write_all(&[0u8; 14 * 8])?;
+
+    // VLR: Text Area Description
+    writer.write_all(&0u16.to_le_bytes())?; // Reserved
+    write_fixed_str(writer, "LASF_Spec", 16)?;
+    writer.write_all(&3u16.to_le_bytes())?;
+    writer.write_all(&vlr_len.to_le_bytes())?;
+    write_fixed_str(writer, "Text Area Description", 32)?;
+    writer.write_all(description.as_bytes())?;
+
+    Ok(quant)
+}
+
+/// Write one point data record (format 6).
+fn write_las_row<W: Write + ?Sized>(
+    writer: &mut W,
+    quant: &LasQuantization,
+    p: [f32; 3],
+) -> Result<()> {
+    for (i, &v) in p.iter().enumerate() {
+        writer.write_all(&quant.quantize(i, v)?.to_le_bytes())?;
+    }
+    writer.write_all(&0u16.to_le_bytes())?; // Intensity
+    writer.write_all(&[0x11])?; // Return 1 of 1
+    writer.write_all(&[0u8])?; // Classification flags, channel, scan direction, edge
+    writer.write_all(&[0u8])?; // Classification: created, never classified
+    writer.write_all(&[0u8])?; // User data
+    writer.write_all(&0i16.to_le_bytes())?; // Scan angle
+    writer.write_all(&0u16.to_le_bytes())?; // Point source ID
+    writer.write_all(&0f64.to_le_bytes())?; // GPS time
+    Ok(())
+}
+
+/// Stream a LAS 1.4 file written by [`write_las`] into `sink`: every point record, dequantised.
+///
+/// Errors when the records end early or run past the header's point count, or when their
+/// bounds disagree with the header bounds by more than one quantisation step.
+fn read_las(reader: &mut dyn BufRead, sink: &mut dyn ReadSink) -> Result<()> {
+    let mut header = [0u8; LAS_HEADER_SIZE as usize];
+    reader
+        .read_exact(&mut header)
+        .context("LAS header is truncated")?;
+    ensure!(
+        &header[0..4] == b"LASF",
+        "not a LAS file (missing 'LASF' signature)"
+    );
+    let u32_at = |at: usize| u32::from_le_bytes(header[at..at + 4].try_into().unwrap());
+    let f64_at = |at: usize| f64::from_le_bytes(header[at..at + 8].try_into().unwrap());
+    let offset_to_points = u32_at(96) as u64;
+    let record_len = u16::from_le_bytes([header[105], header[106]]) as usize;
+    ensure!(
+        record_len >= 12 && offset_to_points >= LAS_HEADER_SIZE as u64,
+        "LAS header has an invalid record length or point offset"
+    );
+    let scale = [f64_at(131), f64_at(139), f64_at(147)];
+    let offset = [f64_at(155), f64_at(163), f64_at(171)];
+    let header_max = [f64_at(179), f64_at(195), f64_at(211)];
+    let header_min = [f64_at(187), f64_at(203), f64_at(219)];
+    let point_count = usize::try_from(u64::from_le_bytes(header[247..255].try_into().unwrap()))
+        .context("LAS point count does not fit in memory")?;
+    sink.expect(point_count)?;
+
+    let gap = offset_to_points - LAS_HEADER_SIZE as u64;
+    let skipped = std::io::copy(&mut (&mut *reader).take(gap), &mut std::io::sink())?;
+    ensure!(skipped == gap, "LAS file ends before its point records");
+    let mut record = vec![0u8; record_len];
+    let mut bounds: Option<([f64; 3], [f64; 3])> = None;
+    for i in 0..point_count {
+        reader
+            .read_exact(&mut record)
+            .with_context(|| format!("LAS has {} of {} point records", i, point_count))?;
+        let p: [f64; 3] = std::array::from_fn(|a| {
+            let raw = i32::from_le_bytes(record[4 * a..4 * a + 4].try_into().unwrap());
+            raw as f64 * scale[a] + offset[a]
+        });
+        let (min, max) = bounds.get_or_insert((p, p));
+        for a in 0..3 {
+            min[a] = min[a].min(p[a]);
+            max[a] = max[a].max(p[a]);
+        }
+        push_point(sink, p)?;
+    }
+    ensure!(
+        reader.fill_buf()?.is_empty(),
+        "LAS has data after its {} point records",
+        point_count
+    );
+    if let Some((min, max)) = bounds {
+        let close = |a: &[f64; 3], b: &[f64; 3]| (0..3).all(|i| (a[i] - b[i]).abs() <= scale[i]);
+        ensure!(
+            close(&min, &header_min) && close(&max, &header_max),
+            "LAS header bounds {:?}–{:?} disagree with the point records {:?}–{:?}",
+            header_min,
+            header_max,
+            min,
+            max
+        );
+    }
+    Ok(())
+}
+
+/// Registry entry for LAS.
+pub struct LasExporter;
+
+impl PointCloudExporter for LasExporter {
+    fn id(&self) -> &'static str {
+        "las"
+    }
+
+    fn label(&self) -> &'static str {
+        "LAS"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["las"]
+    }
+
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        input: &ExportInput,
+        _options: &ExportOptions,
+    ) -> Result<()> {
+        write_las(writer, input.points, input.metadata)
+    }
+
+    fn begin_stream(
+        &self,
+        writer: &mut dyn Write,
+        header: &ExportHeader,
+        _options: &ExportOptions,
+    ) -> Result<Option<PointRowWriter>> {
+        let quant = write_las_header(writer, header)?;
+        let row: PointRowWriter =
+            Box::new(move |w, r: &PointRecord| write_las_row(w, &quant, r.position));
+        Ok(Some(row))
+    }
+
+    fn read_back(
+        &self,
+        reader: &mut dyn BufRead,
+        _options: &ExportOptions,
+        sink: &mut dyn ReadSink,
+    ) -> Option<Result<()>> {
+        Some(read_las(reader, sink))
+    }
+
+    fn read_back_rounding_mm(&self, _options: &ExportOptions) -> f32 {
+        LAS_SCALE_MM as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::test_util::{sample_metadata, sample_points};
+    use std::io::Cursor;
+
+    fn read_f64(buf: &[u8], at: usize) -> f64 {
+        f64::from_le_bytes(buf[at..at + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn write_las_header_and_records() {
+        let points = sample_points();
+        let metadata = sample_metadata();
+        let mut output = Cursor::new(Vec::new());
+
+        write_las(&mut output, &points, &metadata).unwrap();
+
+        let buf = output.into_inner();
+        assert_eq!(&buf[0..4], b"LASF");
+        assert_eq!((buf[24], buf[25]), (1, 4));
+        assert!(String::from_utf8_lossy(&buf[26..58]).starts_with("SimplePicture3D-Test"));
+        assert_eq!(u16::from_le_bytes([buf[94], buf[95]]), LAS_HEADER_SIZE);
+        let offset = u32::from_le_bytes(buf[96..100].try_into().unwrap()) as usize;
+        assert_eq!(buf[104], LAS_POINT_FORMAT);
+        assert_eq!(
+            u16::from_le_bytes([buf[105], buf[106]]),
+            LAS_POINT_RECORD_LEN
+        );
+        assert_eq!(u64::from_le_bytes(buf[247..255].try_into().unwrap()), 3);
+        assert_eq!(buf.len(), offset + 3 * LAS_POINT_RECORD_LEN as usize);
+
+        // Micrometre scale; bounds are the point bounds (max before min per axis).
+        assert_eq!(read_f64(&buf, 131), LAS_SCALE_MM);
+        assert_eq!(read_f64(&buf, 179), 10.0);
+        assert_eq!(read_f64(&buf, 187), 0.0);
+        assert_eq!(read_f64(&buf, 211), 3.0);
+
+        // VLR text carries the source name.
+        let vlr = String::from_utf8_lossy(&buf[LAS_HEADER_SIZE as usize..offset]);
+        assert!(vlr.contains("LASF_Spec"));
+        assert!(vlr.contains("Source: test.png"));
+    }
+
+    #[test]
+    fn write_las_roundtrips_coordinates_at_micrometre() {
+        let points = vec![[12.3456, 7.0011, 25.5]];
+        let metadata = sample_metadata();
+        let mut output = Cursor::new(Vec::new());
+
+        write_las(&mut output, &points, &metadata).unwrap();
+
+        let buf = output.into_inner();
+        let offset = u32::from_le_bytes(buf[96..100].try_into().unwrap()) as usize;
+        for (i, &expected) in points[0].iter().enumerate() {
+            let scale = read_f64(&buf, 131 + i * 8);
+            let off = read_f64(&buf, 155 + i * 8);
+            let at = offset + i * 4;
+            let q = i32::from_le_bytes(buf[at..at + 4].try_into().unwrap());
+            let decoded = q as f64 * scale + off;
+            assert!((decoded - expected as f64).abs() <= 0.0005 + 1e-6);
+        }
+    }
+
+    #[test]
+    fn las_quantization_uses_blank_centre() {
+        let q = LasQuantization::for_envelope(&BlankEnvelope::default()).unwrap();
+        assert_eq!(q.scale, [LAS_SCALE_MM; 3]);
+        assert_eq!(q.offset, [40.0, 25.0, 25.0]);
+        assert!(LasQuantization::from_extents([0.0; 3], [1.0e7, 1.0, 1.0]).is_err());
+    }
+}
@@ -0,0 +1,957 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Point cloud export formats (ADR-011).
+//!
+//! Provides PLY, XYZ, CSV, OBJ, PTS, LAS, and GLB exporters for volumetric point clouds.
+//! PLY/XYZ/CSV are the primary export formats for laser engraver compatibility;
+//! vertex-only OBJ and Leica-style PTS cover older engraver front-ends;
+//! LAS 1.4 is used for archiving jobs alongside scanned verification data;
+//! GLB (glTF 2.0 binary) is the customer-facing web preview.
+//!
+//! Each format lives in its own submodule and implements [`PointCloudExporter`]. The
+//! [`EXPORTERS`] registry drives [`ExportFormat`] parsing, export path extension checks,
+//! the options schema shown by the UI and the generic `export_point_cloud` command.
+//! Adding a format means adding a submodule and one registry entry.
+//!
+//! Exporters whose header only needs the point count and bounds also implement
+//! [`PointCloudExporter::begin_stream`], so [`export_stream_to_file`] can write a cloud
+//! straight from generation (a [`PointSink`]) without materialising it. Formats that
+//! need every point up front (GLB) are collected and written at the end.
+//!
+//! Every format also accepts the `gridMm` option: positions are snapped to the engraver's
+//! machine grid and points that collapse onto one node are merged (see [`quantize`]). A
+//! streamed export with a grid generates the cloud twice: once to count the distinct nodes
+//! the header announces, then to write them.
+//!
+//! Files are written atomically (see [`atomic`]): temporary file, fsync, read-back check,
+//! rename. Each export returns an [`ExportReceipt`] with the file's SHA-256 and size.
+//!
+//! Layer-based engravers that take a stack of 1-bit bitmaps instead of a point list are served
+//! by [`slices`], and per-layer 1:1 SVGs by [`svg_layers`]. These write a folder of layer files
+//! rather than one point file; they implement [`LayerFolderExporter`] and are listed in
+//! [`LAYER_FOLDER_EXPORTERS`], which drives the generic `export_layer_folder` command. Adding a
+//! folder format likewise means a submodule and one registry entry.
+
+use anyhow::{bail, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::blank_envelope::{compute_bbox, BlankEnvelope, FitResult};
+use crate::import::{scan_points, ImportFormat, ReadSink};
+use crate::volumetric::{
+    PointAttributeOptions, PointAttributes, PointCollector, PointRecord, PointSink, StreamPlan,
+};
+use dialect::TextDialect;
+
+mod atomic;
+mod csv;
+pub mod dialect;
+mod glb;
+mod las;
+mod obj;
+mod ply;
+mod pts;
+pub mod quantize;
+pub mod slices;
+pub mod svg_layers;
+mod xyz;
+
+pub(crate) use atomic::write_file_atomic;
+pub use csv::{write_csv, write_csv_dialect};
+pub use glb::write_glb;
+pub use las::{write_las, LasQuantization, LAS_SCALE_MM};
+pub use obj::write_obj;
+pub use ply::{write_ply_ascii, write_ply_binary};
+pub use pts::write_pts;
+pub use xyz::{write_xyz, write_xyz_dialect};
+
+/// Metadata to include in export file headers.
+#[derive(Debug, Clone, Default)]
+pub struct ExportMetadata {
+    /// Source image filename (if available).
+    pub source_image: Option<String>,
+    /// Blank envelope dimensions.
+    pub blank_envelope: Option<BlankEnvelope>,
+    /// Generator name and version.
+    pub generator: String,
+}
+
+impl ExportMetadata {
+    pub fn new() -> Self {
+        Self {
+            source_image: None,
+            blank_envelope: None,
+            generator: "SimplePicture3D".to_string(),
+        }
+    }
+}
+
+// ============================================================================
+// Exporter trait and options
+// ============================================================================
+
+/// Everything an exporter may write: the fitted points, their optional channels and metadata.
+pub struct ExportInput<'a> {
+    pub points: &'a [[f32; 3]],
+    pub attributes: &'a PointAttributes,
+    pub metadata: &'a ExportMetadata,
+}
+
+/// What a format header may need, known before the first point is written.
+pub struct ExportHeader<'a> {
+    pub point_count: usize,
+    /// Bounds (min, max) of the points; `None` when there are none.
+    pub bounds: Option<([f32; 3], [f32; 3])>,
+    /// Optional channels present on every [`PointRecord`].
+    pub channels: PointAttributeOptions,
+    pub metadata: &'a ExportMetadata,
+}
+
+impl<'a> ExportHeader<'a> {
+    /// Header for an in-memory cloud; errors if `attributes` don't match `points`.
+    pub fn for_points(
+        points: &[[f32; 3]],
+        attributes: &PointAttributes,
+        metadata: &'a ExportMetadata,
+    ) -> Result<Self> {
+        attributes
+            .validate_len(points.len())
+            .map_err(anyhow::Error::msg)?;
+        Ok(Self {
+            point_count: points.len(),
+            bounds: compute_bbox(points),
+            channels: attributes.channels(),
+            metadata,
+        })
+    }
+
+    /// Header for a streamed cloud.
+    pub fn for_plan(plan: &StreamPlan, metadata: &'a ExportMetadata) -> Self {
+        Self {
+            point_count: plan.point_count,
+            bounds: Some(plan.bounds),
+            channels: plan.channels.clone(),
+            metadata,
+        }
+    }
+}
+
+/// Writes one point of a streamed export (returned by [`PointCloudExporter::begin_stream`]).
+pub type PointRowWriter = Box<dyn FnMut(&mut dyn Write, &PointRecord) -> Result<()>>;
+
+/// A point cloud file format.
+pub trait PointCloudExporter: Send + Sync {
+    /// Stable lowercase id (settings, IPC); e.g. `"ply"`.
+    fn id(&self) -> &'static str;
+
+    /// Human-readable name for the format picker.
+    fn label(&self) -> &'static str;
+
+    /// Accepted file extensions without the dot; the first is the default.
+    fn extensions(&self) -> &'static [&'static str];
+
+    /// Options this format accepts (none by default).
+    fn options_schema(&self) -> Vec<ExportOptionSpec> {
+        Vec::new()
+    }
+
+    /// Default dialect for plain-text formats whose options are a [`TextDialect`]; such
+    /// formats get the last-used dialect from settings as their option defaults.
+    fn text_dialect(&self) -> Option<TextDialect> {
+        None
+    }
+
+    /// Write the cloud. `options` has been resolved against [`Self::options_schema`].
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        input: &ExportInput,
+        options: &ExportOptions,
+    ) -> Result<()>;
+
+    /// Write the header for a streamed export and return the per-point writer, or `None`
+    /// if the format needs the whole cloud in memory (the default).
+    fn begin_stream(
+        &self,
+        _writer: &mut dyn Write,
+        _header: &ExportHeader,
+        _options: &ExportOptions,
+    ) -> Result<Option<PointRowWriter>> {
+        Ok(None)
+    }
+
+    /// Stream a file written with `options` back into `sink` as positions in mm, to verify
+    /// an atomic write. Defaults to the importer of the same id; `None` means no reader.
+    fn read_back(
+        &self,
+        reader: &mut dyn BufRead,
+        _options: &ExportOptions,
+        sink: &mut dyn ReadSink,
+    ) -> Option<Result<()>> {
+        let format = self.id().parse::<ImportFormat>().ok()?;
+        Some(scan_points(reader, format, sink))
+    }
+
+    /// Rounding (mm) the written precision adds to read-back coordinates.
+    fn read_back_rounding_mm(&self, _options: &ExportOptions) -> f32 {
+        0.0
+    }
+}
+
+/// Value type of an exporter option.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportOptionType {
+    Bool,
+    Number,
+    String,
+}
+
+/// One entry of an exporter's options schema.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOptionSpec {
+    pub key: &'static str,
+    pub label: &'static str,
+    #[serde(rename = "type")]
+    pub kind: ExportOptionType,
+    pub default: serde_json::Value,
+    /// Allowed values for string options (empty = any string).
+    #[serde(skip_serializing_if = "<[_]>::is_empty")]
+    pub choices: &'static [&'static str],
+}
+
+impl ExportOptionSpec {
+    pub fn bool(key: &'static str, label: &'static str, default: bool) -> Self {
+        Self {
+            key,
+            label,
+            kind: ExportOptionType::Bool,
+            default: serde_json::Value::Bool(default),
+            choices: &[],
+        }
+    }
+
+    pub fn number(key: &'static str, label: &'static str, default: f64) -> Self {
+        Self {
+            key,
+            label,
+            kind: ExportOptionType::Number,
+            default: serde_json::json!(default),
+            choices: &[],
+        }
+    }
+
+    pub fn choice(
+        key: &'static str,
+        label: &'static str,
+        choices: &'static [&'static str],
+        default: &'static str,
+    ) -> Self {
+        Self {
+            key,
+            label,
+            kind: ExportOptionType::String,
+            default: serde_json::Value::String(default.to_string()),
+            choices,
+        }
+    }
+}
+
+/// Exporter options resolved against a schema: every key present with a value of the right type.
+#[derive(Debug, Clone, Default)]
+pub struct ExportOptions(serde_json::Map<String, serde_json::Value>);
+
+impl ExportOptions {
+    /// Validate `raw` (a JSON object or null) against `schema` and fill in defaults.
+    pub fn resolve(schema: &[ExportOptionSpec], raw: Option<&serde_json::Value>) -> Result<Self> {
+        let empty = serde_json::Map::new();
+        let raw = match raw {
+            None | Some(serde_json::Value::Null) => &empty,
+            Some(serde_json::Value::Object(map)) => map,
+            Some(_) => bail!("Export options must be an object"),
+        };
+        if let Some(key) = raw
+            .keys()
+            .find(|k| !schema.iter().any(|s| s.key == k.as_str()))
+        {
+            bail!("Unknown export option '{}'", key);
+        }
+        let mut resolved = serde_json::Map::new();
+        for spec in schema {
+            let value = raw.get(spec.key).unwrap_or(&spec.default);
+            let ok = match spec.kind {
+                ExportOptionType::Bool => value.is_boolean(),
+                ExportOptionType::Number => value.as_f64().is_some_and(f64::is_finite),
+                ExportOptionType::String => value
+                    .as_str()
+                    .is_some_and(|v| spec.choices.is_empty() || spec.choices.contains(&v)),
+            };
+            if !ok {
+                bail!("Invalid value for export option '{}': {}", spec.key, value);
+            }
+            resolved.insert(spec.key.to_string(), value.clone());
+        }
+        Ok(Self(resolved))
+    }
+
+    /// Boolean option (false when absent).
+    pub fn bool(&self, key: &str) -> bool {
+        self.0.get(key).and_then(|v| v.as_bool()).unwrap_or(false)
+    }
+
+    /// Numeric option (0 when absent).
+    pub fn number(&self, key: &str) -> f64 {
+        self.0.get(key).and_then(|v| v.as_f64()).unwrap_or(0.0)
+    }
+
+    /// String option (empty when absent).
+    pub fn string(&self, key: &str) -> &str {
+        self.0.get(key).and_then(|v| v.as_str()).unwrap_or("")
+    }
+
+    /// The resolved options as a JSON object (e.g. for export recipes).
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::Value::Object(self.0.clone())
+    }
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+/// All exporters, in the order the format picker lists them.
+pub static EXPORTERS: [&dyn PointCloudExporter; 7] = [
+    &ply::PlyExporter,
+    &xyz::XyzExporter,
+    &csv::CsvExporter,
+    &las::LasExporter,
+    &obj::ObjExporter,
+    &pts::PtsExporter,
+    &glb::GlbExporter,
+];
+
+/// A format written as a folder of per-layer files plus `manifest.json` for layer-based
+/// engravers, instead of one point file.
+pub trait LayerFolderExporter: Send + Sync {
+    /// Stable lowercase id (IPC); e.g. `"slices"`.
+    fn id(&self) -> &'static str;
+
+    /// Human-readable name.
+    fn label(&self) -> &'static str;
+
+    /// Check `options` (the format's options object; omitted keys take their defaults)
+    /// before a job is started.
+    fn validate(&self, options: &serde_json::Value) -> Result<()>;
+
+    /// Write the fitted `points` into the folder `dir`, replacing an earlier stack there.
+    /// `progress(done, total)` is called per layer; an error from it aborts the export and
+    /// leaves `dir` as it was. Returns the manifest.
+    fn write(
+        &self,
+        dir: &Path,
+        points: &[[f32; 3]],
+        envelope: &BlankEnvelope,
+        options: &serde_json::Value,
+        progress: &mut dyn FnMut(usize, usize) -> Result<()>,
+    ) -> Result<serde_json::Value>;
+}
+
+/// All folder exporters.
+pub static LAYER_FOLDER_EXPORTERS: [&dyn LayerFolderExporter; 2] =
+    [&slices::SliceStackExporter, &svg_layers::SvgLayerExporter];
+
+/// The folder exporter with id `id`.
+pub fn layer_folder_exporter(id: &str) -> Option<&'static dyn LayerFolderExporter> {
+    let id = id.trim().to_lowercase();
+    LAYER_FOLDER_EXPORTERS
+        .iter()
+        .copied()
+        .find(|e| e.id() == id)
+}
+
+/// Comma-separated ids of all folder exporters, for error messages.
+pub fn layer_folder_formats() -> String {
+    LAYER_FOLDER_EXPORTERS
+        .iter()
+        .map(|e| e.id())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Deserialize a folder exporter's options object.
+fn layer_folder_options<T: DeserializeOwned>(
+    exporter: &dyn LayerFolderExporter,
+    options: &serde_json::Value,
+) -> Result<T> {
+    serde_json::from_value(options.clone())
+        .map_err(|e| anyhow::anyhow!("Invalid {} options: {}", exporter.label(), e))
+}
+
+/// A registered export format (cheap handle to its [`PointCloudExporter`]).
+#[derive(Clone, Copy)]
+pub struct ExportFormat(&'static dyn PointCloudExporter);
+
+impl ExportFormat {
+    /// All registered formats.
+    pub fn all() -> impl Iterator<Item = ExportFormat> {
+        EXPORTERS.iter().map(|&e| ExportFormat(e))
+    }
+
+    /// Format id (e.g. `"ply"`).
+    pub fn id(&self) -> &'static str {
+        self.0.id()
+    }
+
+    /// Get the default file extension for this format.
+    pub fn extension(&self) -> &'static str {
+        self.0.extensions()[0]
+    }
+
+    /// The exporter implementing this format.
+    pub fn exporter(&self) -> &'static dyn PointCloudExporter {
+        self.0
+    }
+
+    /// Full options schema: the exporter's options plus those every format accepts.
+    pub fn options_schema(&self) -> Vec<ExportOptionSpec> {
+        with_common_options(self.0.options_schema())
+    }
+
+    /// Comma-separated ids of all formats, for error messages.
+    pub fn available() -> String {
+        Self::all().map(|f| f.id()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl std::fmt::Debug for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("ExportFormat").field(&self.id()).finish()
+    }
+}
+
+impl PartialEq for ExportFormat {
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Eq for ExportFormat {}
+
+impl FromStr for ExportFormat {
+    type Err = ();
+
+    /// Parse a format id or any of its extensions (case-insensitive).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        Self::all()
+            .find(|f| f.id() == s || f.exporter().extensions().contains(&s.as_str()))
+            .ok_or(())
+    }
+}
+
+/// Format description for the UI (`list_export_formats`).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportFormatInfo {
+    pub id: &'static str,
+    pub label: &'static str,
+    pub extensions: &'static [&'static str],
+    pub options: Vec<ExportOptionSpec>,
+}
+
+fn with_common_options(mut schema: Vec<ExportOptionSpec>) -> Vec<ExportOptionSpec> {
+    schema.push(quantize::grid_option());
+    schema
+}
+
+/// Describe every registered format. Text formats take their option defaults from
+/// `saved_dialects` (last used, by format id) when present and valid.
+pub fn format_infos(saved_dialects: &BTreeMap<String, TextDialect>) -> Vec<ExportFormatInfo> {
+    ExportFormat::all()
+        .map(|f| {
+            let exporter = f.exporter();
+            let saved = saved_dialects
+                .get(f.id())
+                .filter(|d| exporter.text_dialect().is_some() && d.validate().is_ok());
+            ExportFormatInfo {
+                id: f.id(),
+                label: exporter.label(),
+                extensions: exporter.extensions(),
+                options: saved.map_or_else(
+                    || f.options_schema(),
+                    |d| with_common_options(d.options_schema()),
+                ),
+            }
+        })
+        .collect()
+}
+
+// ============================================================================
+// Generic Export
+// ============================================================================
+
+/// A completed export: what ended up at the destination.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReceipt {
+    pub point_count: usize,
+    /// Lowercase hex SHA-256 of the written file.
+    pub sha256: String,
+    pub byte_size: u64,
+    /// The file was read back and its point count and bounds matched (formats with a reader).
+    pub verified: bool,
+    /// Points dropped because they snapped onto an already used machine grid node.
+    pub merged_points: usize,
+}
+
+/// Write `input` to `path` in `format` with resolved `options`.
+pub fn export_to_file(
+    path: &Path,
+    format: ExportFormat,
+    input: &ExportInput,
+    options: &ExportOptions,
+) -> Result<ExportReceipt> {
+    let mut file = atomic::AtomicExport::create(path, format, options)?;
+
+    let grid = quantize::MachineGrid::for_export(options, input.metadata)?;
+    let (merged_points, expected) = match grid {
+        Some(grid) => {
+            let mut points = input.points.to_vec();
+            let mut attributes = input.attributes.clone();
+            let merged = quantize::quantize_dedup(&mut points, &mut attributes, &grid);
+            let quantized = ExportInput {
+                points: &points,
+                attributes: &attributes,
+                metadata: input.metadata,
+            };
+            format
+                .exporter()
+                .write(file.writer(), &quantized, options)?;
+            (merged, atomic::ExpectedCloud::for_points(&points))
+        }
+        None => {
+            format.exporter().write(file.writer(), input, options)?;
+            (0, atomic::ExpectedCloud::for_points(input.points))
+        }
+    };
+
+    let mut receipt = file.commit(&expected)?;
+    receipt.merged_points = merged_points;
+    Ok(receipt)
+}
+
+/// Write the cloud that `generate` streams into its sink straight to `path`.
+///
+/// Streaming formats write each point as it arrives, so memory stays bounded regardless
+/// of the point count; other formats collect the cloud first. With a machine grid,
+/// `generate` is called twice (deduplication changes the point count a header announces,
+/// so the first pass counts the nodes) and must stream the same cloud both times. Returns
+/// `generate`'s fit.
+pub fn export_stream_to_file(
+    path: &Path,
+    format: ExportFormat,
+    metadata: &ExportMetadata,
+    options: &ExportOptions,
+    generate: impl FnMut(&mut dyn PointSink) -> Result<FitResult, String>,
+) -> Result<(FitResult, ExportReceipt)> {
+    let mut file = atomic::AtomicExport::create(path, format, options)?;
+
+    let (fit_result, expected, merged_points) =
+        stream_into(file.writer(), format, metadata, options, generate)?;
+
+    let mut receipt = file.commit(&expected)?;
+    receipt.merged_points = merged_points;
+    Ok((fit_result, receipt))
+}
+
+/// [`export_stream_to_file`] into any writer.
+pub fn stream_export(
+    writer: &mut dyn Write,
+    format: ExportFormat,
+    metadata: &ExportMetadata,
+    options: &ExportOptions,
+    generate: impl FnMut(&mut dyn PointSink) -> Result<FitResult, String>,
+) -> Result<FitResult> {
+    stream_into(writer, format, metadata, options, generate).map(|(fit, _, _)| fit)
+}
+
+fn stream_into(
+    writer: &mut dyn Write,
+    format: ExportFormat,
+    metadata: &ExportMetadata,
+    options: &ExportOptions,
+    mut generate: impl FnMut(&mut dyn PointSink) -> Result<FitResult, String>,
+) -> Result<(FitResult, atomic::ExpectedCloud, usize)> {
+    let grid = quantize::MachineGrid::for_export(options, metadata)?;
+    let planned = match &grid {
+        Some(grid) => {
+            let mut counter = quantize::GridDedup::new(grid.clone());
+            generate(&mut counter).map_err(anyhow::Error::msg)?;
+            Some(atomic::ExpectedCloud {
+                point_count: counter.kept(),
+                bounds: counter.bounds(),
+            })
+        }
+        None => None,
+    };
+    let mut sink = ExportSink {
+        writer,
+        exporter: format.exporter(),
+        metadata,
+        options,
+        row: None,
+        collector: None,
+        dedup: grid.map(quantize::GridDedup::new),
+        planned,
+        expected: atomic::ExpectedCloud::default(),
+    };
+    let fit_result = generate(&mut sink).map_err(anyhow::Error::msg)?;
+    if planned.is_some_and(|p| p.point_count != sink.expected.point_count) {
+        bail!("The point cloud changed between the counting and writing passes");
+    }
+    let merged_points = sink.dedup.as_ref().map_or(0, |d| d.merged());
+    let expected = sink.expected;
+    if let Some(collector) = sink.collector {
+        let input = ExportInput {
+            points: &collector.points,
+            attributes: &collector.attributes,
+            metadata,
+        };
+        sink.exporter.write(sink.writer, &input, options)?;
+    }
+    Ok((fit_result, expected, merged_points))
+}
+
+/// [`PointSink`] writing through an exporter: row by row when it streams, else collected.
+struct ExportSink<'a> {
+    writer: &'a mut dyn Write,
+    exporter: &'static dyn PointCloudExporter,
+    metadata: &'a ExportMetadata,
+    options: &'a ExportOptions,
+    row: Option<PointRowWriter>,
+    collector: Option<PointCollector>,
+    /// Machine grid deduplication applied to pushed points.
+    dedup: Option<quantize::GridDedup>,
+    /// Point count and bounds after deduplication, from a counting pass; they replace the
+    /// generator's plan in the header.
+    planned: Option<atomic::ExpectedCloud>,
+    /// Announced bounds and the number of points actually written.
+    expected: atomic::ExpectedCloud,
+}
+
+impl PointSink for ExportSink<'_> {
+    fn begin(&mut self, plan: &StreamPlan) -> Result<(), String> {
+        if let Some(dedup) = &mut self.dedup {
+            dedup.begin(plan)?;
+        }
+        let plan = match self.planned {
+            Some(planned) => StreamPlan {
+                point_count: planned.point_count,
+                bounds: planned.bounds.unwrap_or(plan.bounds),
+                ..plan.clone()
+            },
+            None => plan.clone(),
+        };
+        let header = ExportHeader::for_plan(&plan, self.metadata);
+        self.expected.bounds = (plan.point_count > 0).then_some(plan.bounds);
+        self.row = self
+            .exporter
+            .begin_stream(self.writer, &header, self.options)
+            .map_err(|e| e.to_string())?;
+        if self.row.is_none() {
+            let mut collector = PointCollector::default();
+            collector.begin(&plan)?;
+            self.collector = Some(collector);
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, record: &PointRecord) -> Result<(), String> {
+        let mut snapped = *record;
+        if let Some(dedup) = &mut self.dedup {
+            if !dedup.snap(&mut snapped) {
+                return Ok(());
+            }
+        }
+        let record = &snapped;
+        self.expected.point_count += 1;
+        match (&mut self.row, &mut self.collector) {
+            (Some(row), _) => row(self.writer, record).map_err(|e| e.to_string()),
+            (None, Some(collector)) => collector.push(record),
+            (None, None) => Err("Point pushed before begin".to_string()),
+        }
+    }
+}
+
+/// Export points to file with the format's default options and no per-point attributes.
+pub fn export_points(
+    path: &Path,
+    points: &[[f32; 3]],
+    metadata: &ExportMetadata,
+    format: ExportFormat,
+) -> Result<ExportReceipt> {
+    let options = ExportOptions::resolve(&format.options_schema(), None)?;
+    let input = ExportInput {
+        points,
+        attributes: &PointAttributes::default(),
+        metadata,
+    };
+    export_to_file(path, format, &input, &options)
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    use super::*;
+
+    pub fn sample_points() -> Vec<[f32; 3]> {
+        vec![[0.0, 0.0, 0.0], [10.0, 5.0, 3.0], [5.5, 2.5, 1.5]]
+    }
+
+    pub fn sample_metadata() -> ExportMetadata {
+        ExportMetadata {
+            source_image: Some("test.png".to_string()),
+            blank_envelope: Some(BlankEnvelope::default()),
+            generator: "SimplePicture3D-Test".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_util::{sample_metadata, sample_points};
+    use super::*;
+
+    fn format(s: &str) -> ExportFormat {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn export_format_extension() {
+        for ext in ["ply", "xyz", "csv", "las", "obj", "pts", "glb"] {
+            assert_eq!(format(ext).extension(), ext);
+        }
+    }
+
+    #[test]
+    fn export_format_from_str() {
+        assert_eq!(format("PLY"), format("ply"));
+        assert_eq!(format("LAS").id(), "las");
+        assert_ne!(format("obj"), format("pts"));
+        assert_eq!(ExportFormat::from_str("stl"), Err(()));
+    }
+
+    #[test]
+    fn registry_ids_and_extensions_are_unique() {
+        let infos = format_infos(&BTreeMap::new());
+        assert_eq!(infos.len(), EXPORTERS.len());
+        let mut seen = std::collections::HashSet::new();
+        for info in &infos {
+            assert!(seen.insert(info.id));
+            for ext in info.extensions {
+                assert!(*ext == info.id || seen.insert(ext));
+            }
+        }
+    }
+
+    #[test]
+    fn layer_folder_registry_lookup_and_validation() {
+        assert_eq!(layer_folder_exporter(" SVG ").unwrap().id(), "svg");
+        assert!(layer_folder_exporter("ply").is_none());
+        assert_eq!(layer_folder_formats(), "slices, svg");
+        let slices = layer_folder_exporter("slices").unwrap();
+        assert!(slices.validate(&serde_json::json!({})).is_ok());
+        assert!(slices.validate(&serde_json::json!({ "dpi": 1 })).is_err());
+        let err = slices
+            .validate(&serde_json::json!({ "dpi": "high" }))
+            .unwrap_err();
+        assert!(err.to_string().contains("Z-slice bitmap stack"));
+    }
+
+    #[test]
+    fn saved_dialect_becomes_text_format_defaults() {
+        let mut european = TextDialect::csv();
+        european.delimiter = dialect::Delimiter::Semicolon;
+        european.decimal_separator = dialect::DecimalSeparator::Comma;
+        let saved = BTreeMap::from([
+            ("csv".to_string(), european.clone()),
+            ("ply".to_string(), european.clone()),
+        ]);
+        let infos = format_infos(&saved);
+        let schema = |id: &str| infos.iter().find(|i| i.id == id).unwrap().options.clone();
+
+        let csv = ExportOptions::resolve(&schema("csv"), None).unwrap();
+        assert_eq!(TextDialect::from_options(&csv).unwrap(), european);
+        let xyz = ExportOptions::resolve(&schema("xyz"), None).unwrap();
+        assert_eq!(TextDialect::from_options(&xyz).unwrap(), TextDialect::xyz());
+        // Non-text formats ignore saved dialects (binary + machine grid).
+        assert_eq!(schema("ply").len(), 2);
+    }
+
+    #[test]
+    fn options_resolve_defaults_and_reject_bad_values() {
+        let schema = vec![
+            ExportOptionSpec::bool("binary", "Binary", false),
+            ExportOptionSpec::choice("sep", "Separator", &["comma", "tab"], "comma"),
+        ];
+        let opts = ExportOptions::resolve(&schema, None).unwrap();
+        assert!(!opts.bool("binary"));
+        assert_eq!(opts.string("sep"), "comma");
+
+        let raw = serde_json::json!({ "binary": true, "sep": "tab" });
+        let opts = ExportOptions::resolve(&schema, Some(&raw)).unwrap();
+        assert!(opts.bool("binary"));
+        assert_eq!(opts.string("sep"), "tab");
+
+        for bad in [
+            serde_json::json!({ "binary": "yes" }),
+            serde_json::json!({ "sep": "semicolon" }),
+            serde_json::json!({ "unknown": 1 }),
+            serde_json::json!([1, 2]),
+        ] {
+            assert!(ExportOptions::resolve(&schema, Some(&bad)).is_err());
+        }
+    }
+
+    #[test]
+    fn export_to_file_uses_exporter_options() {
+        let path = std::env::temp_dir().join("sp3d_export_registry_test.ply");
+        let points = sample_points();
+        let metadata = sample_metadata();
+        let input = ExportInput {
+            points: &points,
+            attributes: &PointAttributes::default(),
+            metadata: &metadata,
+        };
+        let ply = format("ply");
+        let raw = serde_json::json!({ "binary": true });
+        let options = ExportOptions::resolve(&ply.exporter().options_schema(), Some(&raw)).unwrap();
+
+        export_to_file(&path, ply, &input, &options).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert!(String::from_utf8_lossy(&bytes).contains("format binary_little_endian 1.0"));
+    }
+
+    #[test]
+    fn streamed_export_matches_in_memory_export() {
+        use crate::volumetric::{self, VolumetricParams};
+
+        let (w, h) = (16u32, 12u32);
+        let depth: Vec<f32> = (0..w * h).map(|i| (i % 23) as f32 / 22.0).collect();
+        let params = VolumetricParams::default();
+        let envelope = BlankEnvelope::default();
+        let attr_options = PointAttributeOptions {
+            depth: true,
+            source_pixel: true,
+            layer_index: true,
+            ..Default::default()
+        };
+        let cloud = volumetric::generate_volumetric_points_with_attributes(
+            &depth,
+            w,
+            h,
+            &params,
+            &envelope,
+            &attr_options,
+            None,
+            None,
+        )
+        .unwrap();
+        let metadata = sample_metadata();
+
+        for f in ExportFormat::all() {
+            let options = ExportOptions::resolve(&f.options_schema(), None).unwrap();
+            // LAS, OBJ etc. ignore attributes; only PLY writes them.
+            let attributes = if f.id() == "ply" {
+                cloud.attributes.clone()
+            } else {
+                PointAttributes::default()
+            };
+            let channels = attributes.channels();
+            let input = ExportInput {
+                points: &cloud.points,
+                attributes: &attributes,
+                metadata: &metadata,
+            };
+            let mut expected = Vec::new();
+            f.exporter().write(&mut expected, &input, &options).unwrap();
+
+            let mut streamed = Vec::new();
+            let fit = stream_export(&mut streamed, f, &metadata, &options, |sink| {
+                volumetric::stream_volumetric_points(
+                    &depth,
+                    w,
+                    h,
+                    &params,
+                    &envelope,
+                    &PointAttributeOptions {
+                        layer_thickness_mm: attr_options.layer_thickness_mm,
+                        ..channels
+                    },
+                    None,
+                    None,
+                    sink,
+                )
+            })
+            .unwrap();
+            assert_eq!(fit.point_count, cloud.point_count);
+            assert!(streamed == expected, "{} stream differs", f.id());
+        }
+    }
+
+    #[test]
+    fn machine_grid_merges_points_in_every_format() {
+        let points = vec![
+            [0.0, 0.0, 0.0],
+            [0.002, 0.001, 0.0],
+            [10.0, 5.0, 3.0],
+            [10.001, 5.0, 3.002],
+        ];
+        let metadata = sample_metadata();
+        let input = ExportInput {
+            points: &points,
+            attributes: &PointAttributes::default(),
+            metadata: &metadata,
+        };
+        let raw = serde_json::json!({ quantize::GRID_OPTION: 0.005 });
+
+        for f in ExportFormat::all() {
+            let options = ExportOptions::resolve(&f.options_schema(), Some(&raw)).unwrap();
+            let path = std::env::temp_dir().join(format!("sp3d_grid_test.{}", f.extension()));
+            let receipt = export_to_file(&path, f, &input, &options).unwrap();
+            let (_, streamed) = export_stream_to_file(&path, f, &metadata, &options, |sink| {
+                let plan = StreamPlan {
+                    point_count: points.len(),
+                    scale: 1.0,
+                    translation: [0.0; 3],
+                    bounds: compute_bbox(&points).unwrap(),
+                    channels: PointAttributeOptions::default(),
+                };
+                sink.begin(&plan)?;
+                for &position in &points {
+                    sink.push(&PointRecord {
+                        position,
+                        ..Default::default()
+                    })?;
+                }
+                Ok(FitResult {
+                    scale: 1.0,
+                    translation: [0.0; 3],
+                    point_count: points.len(),
+                    outliers: 0,
+                })
+            })
+            .unwrap();
+            let _ = std::fs::remove_file(&path);
+            assert_eq!(receipt.point_count, 2, "{}", f.id());
+            assert_eq!(receipt.merged_points, 2, "{}", f.id());
+            assert_eq!(streamed, receipt, "{} stream differs", f.id());
+        }
+    }
+}
@@ -0,0 +1,160 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Vertex-only Wavefront OBJ export (`v x y z` lines, no faces).
+
+use anyhow::{anyhow, Result};
+use std::io::{BufRead, Write};
+
+use super::{
+    ExportHeader, ExportInput, ExportMetadata, ExportOptions, PointCloudExporter, PointRowWriter,
+};
+use crate::import::{next_line, push_point, ReadSink};
+use crate::volumetric::PointRecord;
+
+/// Write points to Wavefront OBJ as vertices only.
+///
+/// One `v x y z` line per point and no faces; `#` comment lines carry the metadata.
+/// Accepted by older engraver front-ends that import only OBJ.
+pub fn write_obj<W: Write + ?Sized>(
+    writer: &mut W,
+    points: &[[f32; 3]],
+    metadata: &ExportMetadata,
+) -> Result<()> {
+    write_obj_header(writer, points.len(), metadata)?;
+
+    for &p in points {
+        write_obj_row(writer, p)?;
+    }
+    Ok(())
+}
+
+/// `#` comment lines: generator, source, blank and vertex count.
+fn write_obj_header<W: Write + ?Sized>(
+    writer: &mut W,
+    point_count: usize,
+    metadata: &ExportMetadata,
+) -> Result<()> {
+    writeln!(writer, "# Generated by {}", metadata.generator)?;
+
+    if let Some(ref source) = metadata.source_image {
+        writeln!(writer, "# Source: {}", source)?;
+    }
+
+    if let Some(ref envelope) = metadata.blank_envelope {
+        writeln!(
+            writer,
+            "# Blank: {}x{}x{} mm, margin {} mm",
+            envelope.length_mm, envelope.width_mm, envelope.height_mm, envelope.margin_mm
+        )?;
+    }
+
+    writeln!(writer, "# Vertices: {}", point_count)?;
+    Ok(())
+}
+
+fn write_obj_row<W: Write + ?Sized>(writer: &mut W, p: [f32; 3]) -> Result<()> {
+    writeln!(writer, "v {:.6} {:.6} {:.6}", p[0], p[1], p[2])?;
+    Ok(())
+}
+
+/// Stream the `v` lines of an OBJ into `sink`; comments and other statements are skipped.
+fn read_obj(reader: &mut dyn BufRead, sink: &mut dyn ReadSink) -> Result<()> {
+    let mut line = String::new();
+    let mut line_no = 0;
+    while next_line(reader, &mut line)? {
+        line_no += 1;
+        let Some(coords) = line.trim().strip_prefix("v ") else {
+            continue;
+        };
+        let mut values = coords.split_whitespace().map(str::parse::<f64>);
+        let mut next = || {
+            values
+                .next()
+                .and_then(|v| v.ok())
+                .ok_or_else(|| anyhow!("line {}: malformed vertex", line_no))
+        };
+        push_point(sink, [next()?, next()?, next()?])?;
+    }
+    Ok(())
+}
+
+/// Registry entry for OBJ (vertices).
+pub struct ObjExporter;
+
+impl PointCloudExporter for ObjExporter {
+    fn id(&self) -> &'static str {
+        "obj"
+    }
+
+    fn label(&self) -> &'static str {
+        "OBJ (vertices)"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["obj"]
+    }
+
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        input: &ExportInput,
+        _options: &ExportOptions,
+    ) -> Result<()> {
+        write_obj(writer, input.points, input.metadata)
+    }
+
+    fn begin_stream(
+        &self,
+        writer: &mut dyn Write,
+        header: &ExportHeader,
+        _options: &ExportOptions,
+    ) -> Result<Option<PointRowWriter>> {
+        write_obj_header(writer, header.point_count, header.metadata)?;
+        let row: PointRowWriter = Box::new(|w, r: &PointRecord| write_obj_row(w, r.position));
+        Ok(Some(row))
+    }
+
+    fn read_back(
+        &self,
+        reader: &mut dyn BufRead,
+        _options: &ExportOptions,
+        sink: &mut dyn ReadSink,
+    ) -> Option<Result<()>> {
+        Some(read_obj(reader, sink))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::test_util::{sample_metadata, sample_points};
+    use std::io::Cursor;
+
+    #[test]
+    fn write_obj_format() {
+        let points = sample_points();
+        let metadata = sample_metadata();
+        let mut output = Cursor::new(Vec::new());
+
+        write_obj(&mut output, &points, &metadata).unwrap();
+
+        let result = String::from_utf8(output.into_inner()).unwrap();
+        assert!(result.starts_with("# Generated by SimplePicture3D-Test"));
+        assert!(result.contains("# Source: test.png"));
+        let vertices: Vec<&str> = result.lines().filter(|l| l.starts_with("v ")).collect();
+        assert_eq!(vertices.len(), 3);
+        assert_eq!(vertices[1], "v 10.000000 5.000000 3.000000");
+        assert!(!result.lines().any(|l| l.starts_with("f ")));
+    }
+
+    #[test]
+    fn read_obj_returns_vertices() {
+        let mut output = Vec::new();
+        write_obj(&mut output, &sample_points(), &sample_metadata()).unwrap();
+        let mut imported = crate::import::ImportedPoints::default();
+        read_obj(&mut &output[..], &mut imported).unwrap();
+        assert_eq!(imported.points, sample_points());
+        assert!(read_obj(&mut &b"v 1 2\n"[..], &mut imported).is_err());
+    }
+}
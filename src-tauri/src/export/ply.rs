@@ -0,0 +1,387 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! PLY export (ASCII or binary little-endian), with optional per-point attributes.
+
+use anyhow::Result;
+use std::io::Write;
+
+use super::{
+    ExportHeader, ExportInput, ExportMetadata, ExportOptionSpec, ExportOptions, PointCloudExporter,
+    PointRowWriter,
+};
+use crate::volumetric::{PointAttributes, PointRecord};
+
+/// Write the shared PLY header (everything except the `format` line).
+///
+/// Optional vertex properties follow x/y/z in a fixed order: depth, raw_depth, u/v,
+/// red/green/blue, intensity, layer. Only channels enabled in `header.channels` are declared.
+fn write_ply_header<W: Write + ?Sized>(
+    writer: &mut W,
+    format: &str,
+    header: &ExportHeader,
+) -> Result<()> {
+    let metadata = header.metadata;
+    let channels = &header.channels;
+
+    writeln!(writer, "ply")?;
+    writeln!(writer, "format {} 1.0", format)?;
+    writeln!(writer, "comment Generated by {}", metadata.generator)?;
+
+    if let Some(ref source) = metadata.source_image {
+        writeln!(writer, "comment Source: {}", source)?;
+    }
+
+    if let Some(ref envelope) = metadata.blank_envelope {
+        writeln!(
+            writer,
+            "comment Blank: {}x{}x{} mm, margin {} mm",
+            envelope.length_mm, envelope.width_mm, envelope.height_mm, envelope.margin_mm
+        )?;
+    }
+
+    writeln!(writer, "element vertex {}", header.point_count)?;
+    writeln!(writer, "property float x")?;
+    writeln!(writer, "property float y")?;
+    writeln!(writer, "property float z")?;
+    if channels.depth {
+        writeln!(writer, "property float depth")?;
+    }
+    if channels.raw_depth {
+        writeln!(writer, "property float raw_depth")?;
+    }
+    if channels.source_pixel {
+        writeln!(writer, "property uint u")?;
+        writeln!(writer, "property uint v")?;
+    }
+    if channels.rgb {
+        writeln!(writer, "property uchar red")?;
+        writeln!(writer, "property uchar green")?;
+        writeln!(writer, "property uchar blue")?;
+    }
+    if channels.intensity {
+        writeln!(writer, "property float intensity")?;
+    }
+    if channels.layer_index {
+        writeln!(writer, "property uint layer")?;
+    }
+    writeln!(writer, "end_header")?;
+    Ok(())
+}
+
+/// Write one ASCII PLY vertex line.
+fn write_ply_ascii_row<W: Write + ?Sized>(writer: &mut W, record: &PointRecord) -> Result<()> {
+    let p = record.position;
+    write!(writer, "{:.6} {:.6} {:.6}", p[0], p[1], p[2])?;
+    if let Some(depth) = record.depth {
+        write!(writer, " {:.6}", depth)?;
+    }
+    if let Some(raw) = record.raw_depth {
+        write!(writer, " {:.6}", raw)?;
+    }
+    if let Some([u, v]) = record.source_pixel {
+        write!(writer, " {} {}", u, v)?;
+    }
+    if let Some([r, g, b]) = record.rgb {
+        write!(writer, " {} {} {}", r, g, b)?;
+    }
+    if let Some(intensity) = record.intensity {
+        write!(writer, " {:.6}", intensity)?;
+    }
+    if let Some(layer) = record.layer_index {
+        write!(writer, " {}", layer)?;
+    }
+    writeln!(writer)?;
+    Ok(())
+}
+
+/// Write one binary little-endian PLY vertex.
+fn write_ply_binary_row<W: Write + ?Sized>(writer: &mut W, record: &PointRecord) -> Result<()> {
+    for v in record.position {
+        writer.write_all(&v.to_le_bytes())?;
+    }
+    if let Some(depth) = record.depth {
+        writer.write_all(&depth.to_le_bytes())?;
+    }
+    if let Some(raw) = record.raw_depth {
+        writer.write_all(&raw.to_le_bytes())?;
+    }
+    if let Some([u, v]) = record.source_pixel {
+        writer.write_all(&u.to_le_bytes())?;
+        writer.write_all(&v.to_le_bytes())?;
+    }
+    if let Some(rgb) = record.rgb {
+        writer.write_all(&rgb)?;
+    }
+    if let Some(intensity) = record.intensity {
+        writer.write_all(&intensity.to_le_bytes())?;
+    }
+    if let Some(layer) = record.layer_index {
+        writer.write_all(&layer.to_le_bytes())?;
+    }
+    Ok(())
+}
+
+/// Write points to PLY format (ASCII).
+///
+/// PLY (Polygon File Format) is widely supported by 3D software and some
+/// laser engraving tools. Optional per-point attributes are appended as
+/// extra vertex properties (see [`PointAttributes`]).
+///
+/// # Format
+/// ```text
+/// ply
+/// format ascii 1.0
+/// comment Generated by SimplePicture3D
+/// element vertex N
+/// property float x
+/// property float y
+/// property float z
+/// end_header
+/// x1 y1 z1
+/// x2 y2 z2
+/// ...
+/// ```
+pub fn write_ply_ascii<W: Write + ?Sized>(
+    writer: &mut W,
+    points: &[[f32; 3]],
+    attributes: &PointAttributes,
+    metadata: &ExportMetadata,
+) -> Result<()> {
+    let header = ExportHeader::for_points(points, attributes, metadata)?;
+    write_ply_header(writer, "ascii", &header)?;
+
+    for (i, &p) in points.iter().enumerate() {
+        write_ply_ascii_row(writer, &attributes.record(i, p))?;
+    }
+
+    Ok(())
+}
+
+/// Write points to PLY format (binary little-endian).
+///
+/// Binary PLY is more compact and faster to parse than ASCII.
+pub fn write_ply_binary<W: Write + ?Sized>(
+    writer: &mut W,
+    points: &[[f32; 3]],
+    attributes: &PointAttributes,
+    metadata: &ExportMetadata,
+) -> Result<()> {
+    let header = ExportHeader::for_points(points, attributes, metadata)?;
+    write_ply_header(writer, "binary_little_endian", &header)?;
+
+    for (i, &p) in points.iter().enumerate() {
+        write_ply_binary_row(writer, &attributes.record(i, p))?;
+    }
+
+    Ok(())
+}
+
+/// Registry entry for PLY (`binary` option selects binary little-endian).
+pub struct PlyExporter;
+
+impl PointCloudExporter for PlyExporter {
+    fn id(&self) -> &'static str {
+        "ply"
+    }
+
+    fn label(&self) -> &'static str {
+        "PLY"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["ply"]
+    }
+
+    fn options_schema(&self) -> Vec<ExportOptionSpec> {
+        vec![ExportOptionSpec::bool(
+            "binary",
+            "Binary (little-endian)",
+            false,
+        )]
+    }
+
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        input: &ExportInput,
+        options: &ExportOptions,
+    ) -> Result<()> {
+        if options.bool("binary") {
+            write_ply_binary(writer, input.points, input.attributes, input.metadata)
+        } else {
+            write_ply_ascii(writer, input.points, input.attributes, input.metadata)
+        }
+    }
+
+    fn begin_stream(
+        &self,
+        writer: &mut dyn Write,
+        header: &ExportHeader,
+        options: &ExportOptions,
+    ) -> Result<Option<PointRowWriter>> {
+        let row: PointRowWriter = if options.bool("binary") {
+            write_ply_header(writer, "binary_little_endian", header)?;
+            Box::new(|w, r| write_ply_binary_row(w, r))
+        } else {
+            write_ply_header(writer, "ascii", header)?;
+            Box::new(|w, r| write_ply_ascii_row(w, r))
+        };
+        Ok(Some(row))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::test_util::{sample_metadata, sample_points};
+    use std::io::Cursor;
+
+    fn sample_attributes() -> PointAttributes {
+        PointAttributes {
+            depth: Some(vec![0.0, 1.0, 0.5]),
+            raw_depth: Some(vec![0.25, 0.75, 0.5]),
+            source_pixel: Some(vec![[0, 0], [4, 2], [2, 1]]),
+            rgb: Some(vec![[0, 0, 0], [255, 128, 0], [10, 20, 30]]),
+            intensity: Some(vec![0.0, 0.6, 0.07]),
+            layer_index: Some(vec![0, 30, 15]),
+            layer_thickness_mm: Some(0.1),
+        }
+    }
+
+    #[test]
+    fn write_ply_ascii_format() {
+        let points = sample_points();
+        let metadata = sample_metadata();
+        let mut output = Cursor::new(Vec::new());
+
+        write_ply_ascii(&mut output, &points, &PointAttributes::default(), &metadata).unwrap();
+
+        let result = String::from_utf8(output.into_inner()).unwrap();
+        assert!(result.starts_with("ply\n"));
+        assert!(result.contains("format ascii 1.0"));
+        assert!(result.contains("element vertex 3"));
+        assert!(result.contains("property float x"));
+        assert!(result.contains("end_header"));
+        assert!(result.contains("0.000000 0.000000 0.000000"));
+        assert!(result.contains("10.000000 5.000000 3.000000"));
+    }
+
+    #[test]
+    fn write_ply_binary_format() {
+        let points = sample_points();
+        let metadata = sample_metadata();
+        let mut output = Cursor::new(Vec::new());
+
+        write_ply_binary(&mut output, &points, &PointAttributes::default(), &metadata).unwrap();
+
+        let result = output.into_inner();
+        // Header should be ASCII
+        let header_end = result
+            .windows(11)
+            .position(|w| w == b"end_header\n")
+            .unwrap();
+        let header = String::from_utf8_lossy(&result[..header_end + 11]);
+        assert!(header.contains("format binary_little_endian 1.0"));
+        assert!(header.contains("element vertex 3"));
+
+        // Binary data follows header: 3 points * 3 floats * 4 bytes = 36 bytes
+        let expected_binary_size = 3 * 3 * 4;
+        let actual_binary_size = result.len() - (header_end + 11);
+        assert_eq!(actual_binary_size, expected_binary_size);
+    }
+
+    #[test]
+    fn write_ply_ascii_with_attributes() {
+        let points = sample_points();
+        let mut output = Cursor::new(Vec::new());
+
+        write_ply_ascii(
+            &mut output,
+            &points,
+            &sample_attributes(),
+            &sample_metadata(),
+        )
+        .unwrap();
+
+        let result = String::from_utf8(output.into_inner()).unwrap();
+        let props: Vec<&str> = result
+            .lines()
+            .filter_map(|l| l.strip_prefix("property "))
+            .collect();
+        assert_eq!(
+            props,
+            vec![
+                "float x",
+                "float y",
+                "float z",
+                "float depth",
+                "float raw_depth",
+                "uint u",
+                "uint v",
+                "uchar red",
+                "uchar green",
+                "uchar blue",
+                "float intensity",
+                "uint layer",
+            ]
+        );
+        assert!(result
+            .contains("10.000000 5.000000 3.000000 1.000000 0.750000 4 2 255 128 0 0.600000 30\n"));
+    }
+
+    #[test]
+    fn write_ply_binary_with_attributes() {
+        let points = sample_points();
+        let mut output = Cursor::new(Vec::new());
+
+        write_ply_binary(
+            &mut output,
+            &points,
+            &sample_attributes(),
+            &sample_metadata(),
+        )
+        .unwrap();
+
+        let result = output.into_inner();
+        let header_end = result
+            .windows(11)
+            .position(|w| w == b"end_header\n")
+            .unwrap()
+            + 11;
+        // xyz 12 + depth 4 + raw depth 4 + uv 8 + rgb 3 + intensity 4 + layer 4 = 39 bytes
+        assert_eq!(result.len() - header_end, 3 * 39);
+        let second = &result[header_end + 39..header_end + 78];
+        assert_eq!(f32::from_le_bytes(second[16..20].try_into().unwrap()), 0.75);
+        assert_eq!(&second[28..31], &[255, 128, 0]);
+        assert_eq!(u32::from_le_bytes(second[35..39].try_into().unwrap()), 30);
+    }
+
+    #[test]
+    fn write_ply_rejects_misaligned_attributes() {
+        let attributes = PointAttributes {
+            depth: Some(vec![0.5]),
+            ..Default::default()
+        };
+        let mut output = Cursor::new(Vec::new());
+        let result = write_ply_ascii(
+            &mut output,
+            &sample_points(),
+            &attributes,
+            &sample_metadata(),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn export_empty_points() {
+        let points: Vec<[f32; 3]> = vec![];
+        let metadata = ExportMetadata::new();
+        let mut output = Cursor::new(Vec::new());
+
+        // Should succeed even with empty points
+        write_ply_ascii(&mut output, &points, &PointAttributes::default(), &metadata).unwrap();
+        let result = String::from_utf8(output.into_inner()).unwrap();
+        assert!(result.contains("element vertex 0"));
+    }
+}
@@ -0,0 +1,81 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Leica-style PTS export: point count line, then `x y z` lines.
+
+use anyhow::Result;
+use std::io::Write;
+
+use super::xyz::write_xyz_row;
+use super::{ExportHeader, ExportInput, ExportOptions, PointCloudExporter, PointRowWriter};
+use crate::volumetric::PointRecord;
+
+/// Write points to Leica-style PTS format (ASCII).
+///
+/// The first line is the point count, followed by one `x y z` line per point.
+/// PTS has no comment syntax, so no metadata header is written.
+pub fn write_pts<W: Write + ?Sized>(writer: &mut W, points: &[[f32; 3]]) -> Result<()> {
+    writeln!(writer, "{}", points.len())?;
+    for &p in points {
+        write_xyz_row(writer, p)?;
+    }
+    Ok(())
+}
+
+/// Registry entry for PTS.
+pub struct PtsExporter;
+
+impl PointCloudExporter for PtsExporter {
+    fn id(&self) -> &'static str {
+        "pts"
+    }
+
+    fn label(&self) -> &'static str {
+        "PTS"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["pts"]
+    }
+
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        input: &ExportInput,
+        _options: &ExportOptions,
+    ) -> Result<()> {
+        write_pts(writer, input.points)
+    }
+
+    fn begin_stream(
+        &self,
+        writer: &mut dyn Write,
+        header: &ExportHeader,
+        _options: &ExportOptions,
+    ) -> Result<Option<PointRowWriter>> {
+        writeln!(writer, "{}", header.point_count)?;
+        let row: PointRowWriter = Box::new(|w, r: &PointRecord| write_xyz_row(w, r.position));
+        Ok(Some(row))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::test_util::sample_points;
+    use std::io::Cursor;
+
+    #[test]
+    fn write_pts_format() {
+        let points = sample_points();
+        let mut output = Cursor::new(Vec::new());
+
+        write_pts(&mut output, &points).unwrap();
+
+        let result = String::from_utf8(output.into_inner()).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "3");
+        assert_eq!(lines[2], "10.000000 5.000000 3.000000");
+    }
+}
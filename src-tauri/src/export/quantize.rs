@@ -0,0 +1,351 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Export-stage quantisation to the engraver's machine grid.
+//!
+//! Controllers round coordinates to their step resolution (e.g. 0.005 mm), so points that
+//! are distinct in `f32` can land on the same machine position and fire twice. With a grid
+//! set (the `gridMm` option every format accepts; 0 = off) each position is snapped to the
+//! nearest grid node inside the blank interior and later points on an already used node
+//! are dropped together with their attributes; Z-layer indices are recomputed from the
+//! snapped height. It runs on the cloud before any exporter sees it, so every format
+//! writes the same points. Streamed exports deduplicate as points arrive ([`GridDedup`]),
+//! keeping only the set of used nodes; because deduplication changes the point count that
+//! headers announce, the cloud is generated twice, first to count the nodes.
+
+use anyhow::{bail, ensure, Context, Result};
+use std::collections::HashSet;
+
+use super::{ExportMetadata, ExportOptionSpec, ExportOptions};
+use crate::blank_envelope::BlankEnvelope;
+use crate::volumetric::{layer_of, PointAttributes, PointRecord, PointSink, StreamPlan};
+
+/// Option key of the grid step in mm.
+pub const GRID_OPTION: &str = "gridMm";
+
+/// Accepted grid steps (mm) when enabled.
+pub const MIN_GRID_MM: f64 = 0.0001;
+pub const MAX_GRID_MM: f64 = 10.0;
+
+/// The grid option appended to every format's schema.
+pub fn grid_option() -> ExportOptionSpec {
+    ExportOptionSpec::number(GRID_OPTION, "Machine grid (mm, 0 = off)", 0.0)
+}
+
+/// Grid step from resolved options; `None` when quantisation is off.
+pub fn grid_from_options(options: &ExportOptions) -> Result<Option<f64>> {
+    let grid = options.number(GRID_OPTION);
+    if grid == 0.0 {
+        return Ok(None);
+    }
+    ensure!(
+        (MIN_GRID_MM..=MAX_GRID_MM).contains(&grid),
+        "Machine grid must be 0 (off) or between {} and {} mm",
+        MIN_GRID_MM,
+        MAX_GRID_MM
+    );
+    Ok(Some(grid))
+}
+
+/// Machine grid of an export, limited to the nodes inside the blank interior.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MachineGrid {
+    step_mm: f64,
+    /// Lowest and highest node index inside the interior, per axis.
+    min_node: [i64; 3],
+    max_node: [i64; 3],
+    margin_mm: f32,
+}
+
+impl MachineGrid {
+    /// Grid of `step_mm` within `envelope`'s interior; errors when an axis has no node inside.
+    pub fn new(step_mm: f64, envelope: &BlankEnvelope) -> Result<Self> {
+        let margin = envelope.margin_mm as f64;
+        let size = [envelope.length_mm, envelope.width_mm, envelope.height_mm];
+        let mut min_node = [0; 3];
+        let mut max_node = [0; 3];
+        for axis in 0..3 {
+            min_node[axis] = (margin / step_mm).ceil() as i64;
+            max_node[axis] = ((size[axis] as f64 - margin) / step_mm).floor() as i64;
+            if min_node[axis] > max_node[axis] {
+                bail!(
+                    "Machine grid of {} mm has no node inside the blank interior",
+                    step_mm
+                );
+            }
+        }
+        Ok(Self {
+            step_mm,
+            min_node,
+            max_node,
+            margin_mm: envelope.margin_mm,
+        })
+    }
+
+    /// The grid for an export: `None` when the `gridMm` option is off. Needs the blank.
+    pub fn for_export(options: &ExportOptions, metadata: &ExportMetadata) -> Result<Option<Self>> {
+        let Some(step_mm) = grid_from_options(options)? else {
+            return Ok(None);
+        };
+        let envelope = metadata
+            .blank_envelope
+            .as_ref()
+            .context("Machine grid needs the blank envelope")?;
+        Self::new(step_mm, envelope).map(Some)
+    }
+
+    /// Nearest node to `p`, clamped to the interior.
+    fn node(&self, p: &[f32; 3]) -> [i64; 3] {
+        std::array::from_fn(|axis| {
+            ((p[axis] as f64 / self.step_mm).round() as i64)
+                .clamp(self.min_node[axis], self.max_node[axis])
+        })
+    }
+
+    /// Position of `node`.
+    fn position(&self, node: [i64; 3]) -> [f32; 3] {
+        node.map(|n| (n as f64 * self.step_mm) as f32)
+    }
+}
+
+/// Snap `points` to `grid` and drop points on an already used node (the first one wins),
+/// keeping `attributes` aligned and their layer indices matching the snapped Z. Returns
+/// the number of points merged away.
+pub fn quantize_dedup(
+    points: &mut Vec<[f32; 3]>,
+    attributes: &mut PointAttributes,
+    grid: &MachineGrid,
+) -> usize {
+    let mut seen = HashSet::with_capacity(points.len());
+    let keep: Vec<bool> = points
+        .iter_mut()
+        .map(|p| {
+            let node = grid.node(p);
+            *p = grid.position(node);
+            seen.insert(node)
+        })
+        .collect();
+    if let (Some(layers), Some(thickness)) = (
+        attributes.layer_index.as_mut(),
+        attributes.layer_thickness_mm,
+    ) {
+        for (layer, p) in layers.iter_mut().zip(points.iter()) {
+            *layer = layer_of(p[2], grid.margin_mm, thickness);
+        }
+    }
+    let merged = keep.iter().filter(|&&k| !k).count();
+    if merged > 0 {
+        let mut flags = keep.iter();
+        points.retain(|_| *flags.next().unwrap_or(&true));
+        attributes.retain(&keep);
+    }
+    merged
+}
+
+/// [`quantize_dedup`] for a streamed cloud: records are snapped one at a time and those on an
+/// already used node are dropped (the first one wins). Only the used nodes are kept. As a
+/// [`PointSink`] it just counts, so a first pass can size a header.
+#[derive(Debug)]
+pub struct GridDedup {
+    grid: MachineGrid,
+    seen: HashSet<[i64; 3]>,
+    layer_thickness_mm: Option<f32>,
+    merged: usize,
+    bounds: Option<([f32; 3], [f32; 3])>,
+}
+
+impl GridDedup {
+    pub fn new(grid: MachineGrid) -> Self {
+        Self {
+            grid,
+            seen: HashSet::new(),
+            layer_thickness_mm: None,
+            merged: 0,
+            bounds: None,
+        }
+    }
+
+    /// Snap `record` in place, recomputing its layer index; `false` when its node was
+    /// already used and the record should be dropped.
+    pub fn snap(&mut self, record: &mut PointRecord) -> bool {
+        let node = self.grid.node(&record.position);
+        if !self.seen.insert(node) {
+            self.merged += 1;
+            return false;
+        }
+        let p = self.grid.position(node);
+        record.position = p;
+        if let (Some(layer), Some(thickness)) =
+            (record.layer_index.as_mut(), self.layer_thickness_mm)
+        {
+            *layer = layer_of(p[2], self.grid.margin_mm, thickness);
+        }
+        let (min, max) = self.bounds.get_or_insert((p, p));
+        for axis in 0..3 {
+            min[axis] = min[axis].min(p[axis]);
+            max[axis] = max[axis].max(p[axis]);
+        }
+        true
+    }
+
+    /// Records kept so far.
+    pub fn kept(&self) -> usize {
+        self.seen.len()
+    }
+
+    /// Records dropped so far.
+    pub fn merged(&self) -> usize {
+        self.merged
+    }
+
+    /// Bounds (min, max) of the kept positions; `None` before the first one.
+    pub fn bounds(&self) -> Option<([f32; 3], [f32; 3])> {
+        self.bounds
+    }
+}
+
+impl PointSink for GridDedup {
+    fn begin(&mut self, plan: &StreamPlan) -> Result<(), String> {
+        self.layer_thickness_mm = plan
+            .channels
+            .layer_index
+            .then_some(plan.channels.layer_thickness_mm);
+        Ok(())
+    }
+
+    fn push(&mut self, record: &PointRecord) -> Result<(), String> {
+        self.snap(&mut { *record });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> BlankEnvelope {
+        BlankEnvelope {
+            length_mm: 10.0,
+            width_mm: 10.0,
+            height_mm: 10.0,
+            margin_mm: 0.0,
+        }
+    }
+
+    #[test]
+    fn snaps_and_merges_with_attributes() {
+        let mut points = vec![
+            [0.001, 0.0, 0.0],
+            [0.004, 0.0, 0.0],
+            [0.0012, 0.0001, 0.0],
+            [1.0, 2.0, 3.0],
+        ];
+        let mut attributes = PointAttributes {
+            intensity: Some(vec![0.1, 0.2, 0.3, 0.4]),
+            ..Default::default()
+        };
+        let grid = MachineGrid::new(0.005, &envelope()).unwrap();
+        let merged = quantize_dedup(&mut points, &mut attributes, &grid);
+        assert_eq!(merged, 1);
+        assert_eq!(points.len(), 3);
+        assert_eq!(points[0], [0.0, 0.0, 0.0]);
+        assert!((points[1][0] - 0.005).abs() < 1e-7);
+        assert_eq!(attributes.intensity, Some(vec![0.1, 0.2, 0.4]));
+        assert!(attributes.validate_len(points.len()).is_ok());
+    }
+
+    #[test]
+    fn snapped_nodes_stay_inside_the_interior_and_relayer() {
+        let envelope = BlankEnvelope {
+            margin_mm: 1.5,
+            ..envelope()
+        };
+        // Interior 1.5..8.5: a 2 mm grid would round 0.9 to 0 and 9.2 to 10 without clamping.
+        let grid = MachineGrid::new(2.0, &envelope).unwrap();
+        let mut points = vec![[0.9, 5.0, 0.9], [9.2, 5.0, 9.2], [5.0, 5.0, 4.9]];
+        let mut attributes = PointAttributes {
+            layer_index: Some(vec![0, 69, 33]),
+            layer_thickness_mm: Some(0.1),
+            ..Default::default()
+        };
+        assert_eq!(quantize_dedup(&mut points, &mut attributes, &grid), 0);
+        assert_eq!(
+            points,
+            vec![[2.0, 6.0, 2.0], [8.0, 6.0, 8.0], [6.0, 6.0, 4.0]]
+        );
+        assert_eq!(attributes.layer_index, Some(vec![5, 65, 25]));
+
+        let too_coarse = BlankEnvelope {
+            margin_mm: 4.5,
+            ..envelope
+        };
+        assert!(MachineGrid::new(2.0, &too_coarse).is_err());
+    }
+
+    #[test]
+    fn streamed_dedup_matches_in_memory_dedup() {
+        let envelope = BlankEnvelope {
+            margin_mm: 1.5,
+            ..envelope()
+        };
+        let grid = MachineGrid::new(2.0, &envelope).unwrap();
+        let positions = [
+            [0.9, 5.0, 0.9],
+            [9.2, 5.0, 9.2],
+            [5.0, 5.0, 4.9],
+            [5.2, 5.1, 4.8],
+        ];
+        let mut points = positions.to_vec();
+        let mut attributes = PointAttributes {
+            layer_index: Some(vec![0; 4]),
+            layer_thickness_mm: Some(0.1),
+            ..Default::default()
+        };
+        quantize_dedup(&mut points, &mut attributes, &grid);
+
+        let mut dedup = GridDedup::new(grid);
+        dedup
+            .begin(&StreamPlan {
+                point_count: positions.len(),
+                scale: 1.0,
+                translation: [0.0; 3],
+                bounds: ([0.0; 3], [10.0; 3]),
+                channels: crate::volumetric::PointAttributeOptions {
+                    layer_index: true,
+                    layer_thickness_mm: 0.1,
+                    ..Default::default()
+                },
+            })
+            .unwrap();
+        let mut kept = Vec::new();
+        let mut layers = Vec::new();
+        for position in positions {
+            let mut record = PointRecord {
+                position,
+                layer_index: Some(0),
+                ..Default::default()
+            };
+            if dedup.snap(&mut record) {
+                kept.push(record.position);
+                layers.push(record.layer_index.unwrap());
+            }
+        }
+        assert_eq!(kept, points);
+        assert_eq!(Some(layers), attributes.layer_index);
+        assert_eq!((dedup.kept(), dedup.merged()), (3, 1));
+        assert_eq!(dedup.bounds(), Some(([2.0, 6.0, 2.0], [8.0, 6.0, 8.0])));
+    }
+
+    #[test]
+    fn grid_option_bounds() {
+        let schema = [grid_option()];
+        let resolve = |v: f64| {
+            let raw = serde_json::json!({ GRID_OPTION: v });
+            grid_from_options(&ExportOptions::resolve(&schema, Some(&raw)).unwrap())
+        };
+        assert_eq!(resolve(0.0).unwrap(), None);
+        assert_eq!(resolve(0.005).unwrap(), Some(0.005));
+        assert!(resolve(-0.005).is_err());
+        assert!(resolve(50.0).is_err());
+    }
+}
@@ -0,0 +1,625 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Z-slice bitmap stacks for layer-based sub-surface engravers.
+//!
+//! Some machines take one 1-bit bitmap per Z layer instead of a point list. The fitted cloud
+//! is cut into layers `layerThicknessMm` thick, counted from the blank floor (z = 0), and each
+//! layer is rasterised over the whole blank footprint at `dpi`: pixel (col, row) covers
+//! x = col·pitch, y = row·pitch, so row 0 is the blank's y = 0 edge (the top of the source
+//! image). Pixels holding at least one point are black, the rest white. Layers from the lowest
+//! to the highest occupied one are written as `layer_NNNNN.png|bmp` next to a `manifest.json`
+//! with each layer's Z centre and the pixel pitch in mm.
+//!
+//! Like single-file exports, the stack is built in a hidden temporary folder and renamed into
+//! place when complete, so a failed or cancelled export leaves the destination untouched.
+
+use anyhow::{bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use crate::blank_envelope::{is_outside_envelope, BlankEnvelope};
+
+/// Name of the manifest written into the stack folder.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Accepted layer thickness (mm).
+pub const MIN_LAYER_THICKNESS_MM: f32 = 0.01;
+pub const MAX_LAYER_THICKNESS_MM: f32 = 10.0;
+
+/// Accepted raster resolution (dots per inch).
+pub const MIN_DPI: f32 = 25.0;
+pub const MAX_DPI: f32 = 4800.0;
+
+/// Largest stack accepted (layers, pixels per layer); guards against runaway settings.
+const MAX_LAYERS: usize = 100_000;
+const MAX_LAYER_PIXELS: u64 = 200_000_000;
+
+const MM_PER_INCH: f32 = 25.4;
+
+/// Bitmap file format of the layers (both 1 bit per pixel).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SliceImageFormat {
+    #[default]
+    Png,
+    Bmp,
+}
+
+impl SliceImageFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            SliceImageFormat::Png => "png",
+            SliceImageFormat::Bmp => "bmp",
+        }
+    }
+}
+
+/// Slicing and rasterisation settings; omitted keys take their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SliceStackOptions {
+    pub layer_thickness_mm: f32,
+    pub dpi: f32,
+    pub image_format: SliceImageFormat,
+}
+
+impl Default for SliceStackOptions {
+    fn default() -> Self {
+        Self {
+            layer_thickness_mm: 0.1,
+            dpi: 600.0,
+            image_format: SliceImageFormat::Png,
+        }
+    }
+}
+
+impl SliceStackOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_LAYER_THICKNESS_MM..=MAX_LAYER_THICKNESS_MM).contains(&self.layer_thickness_mm) {
+            return Err(format!(
+                "Layer thickness must be between {} and {} mm",
+                MIN_LAYER_THICKNESS_MM, MAX_LAYER_THICKNESS_MM
+            ));
+        }
+        if !(MIN_DPI..=MAX_DPI).contains(&self.dpi) {
+            return Err(format!("DPI must be between {} and {}", MIN_DPI, MAX_DPI));
+        }
+        Ok(())
+    }
+
+    /// Size of one pixel in mm.
+    pub fn pixel_pitch_mm(&self) -> f32 {
+        MM_PER_INCH / self.dpi
+    }
+}
+
+/// One written layer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SliceLayer {
+    pub index: usize,
+    /// Z of the layer centre, mm above the blank floor.
+    pub z_mm: f32,
+    /// Image file name inside the stack folder.
+    pub file: String,
+    pub point_count: usize,
+}
+
+/// Contents of `manifest.json`; also the result of the slice export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SliceManifest {
+    pub image_format: SliceImageFormat,
+    pub layer_thickness_mm: f32,
+    pub dpi: f32,
+    /// Pixel size in mm (same on both axes).
+    pub pixel_pitch_mm: f32,
+    pub width_px: u32,
+    pub height_px: u32,
+    /// Blank footprint covered by every image (X length, Y width), mm.
+    pub blank_length_mm: f32,
+    pub blank_width_mm: f32,
+    /// Points rasterised into the layers.
+    pub point_count: usize,
+    /// Points outside the blank, left out of the stack.
+    pub clipped_points: usize,
+    pub layers: Vec<SliceLayer>,
+}
+
+/// Registry entry for bitmap slice stacks (see [`super::LAYER_FOLDER_EXPORTERS`]).
+pub struct SliceStackExporter;
+
+impl super::LayerFolderExporter for SliceStackExporter {
+    fn id(&self) -> &'static str {
+        "slices"
+    }
+
+    fn label(&self) -> &'static str {
+        "Z-slice bitmap stack"
+    }
+
+    fn validate(&self, options: &serde_json::Value) -> Result<()> {
+        let options: SliceStackOptions = super::layer_folder_options(self, options)?;
+        options.validate().map_err(anyhow::Error::msg)
+    }
+
+    fn write(
+        &self,
+        dir: &Path,
+        points: &[[f32; 3]],
+        envelope: &BlankEnvelope,
+        options: &serde_json::Value,
+        progress: &mut dyn FnMut(usize, usize) -> Result<()>,
+    ) -> Result<serde_json::Value> {
+        let options: SliceStackOptions = super::layer_folder_options(self, options)?;
+        let manifest = write_slice_stack(dir, points, envelope, &options, progress)?;
+        Ok(serde_json::to_value(manifest)?)
+    }
+}
+
+/// Slice `points` (fitted to `envelope`) and write the stack into `dir`, replacing an earlier
+/// stack there. `progress(done, total)` is called per layer; an error from it aborts the export
+/// and leaves `dir` as it was.
+pub fn write_slice_stack(
+    dir: &Path,
+    points: &[[f32; 3]],
+    envelope: &BlankEnvelope,
+    options: &SliceStackOptions,
+    mut progress: impl FnMut(usize, usize) -> Result<()>,
+) -> Result<SliceManifest> {
+    options.validate().map_err(anyhow::Error::msg)?;
+    let pitch = options.pixel_pitch_mm();
+    let width_px = (envelope.length_mm / pitch).ceil().max(1.0) as u32;
+    let height_px = (envelope.width_mm / pitch).ceil().max(1.0) as u32;
+    ensure!(
+        width_px as u64 * height_px as u64 <= MAX_LAYER_PIXELS,
+        "Slice images would be {}×{} px; lower the DPI",
+        width_px,
+        height_px
+    );
+
+    let slabs = Slabs::new(points, envelope, options.layer_thickness_mm)?;
+    let stack = StackDir::create(dir)?;
+    let row_bytes = (width_px as usize).div_ceil(8);
+    let mut layers = Vec::with_capacity(slabs.count);
+    for (index, (layer, in_layer)) in slabs.layers().enumerate() {
+        progress(index, slabs.count)?;
+        let mut bits = vec![0xFF_u8; row_bytes * height_px as usize];
+        for &(_, i) in in_layer {
+            let p = points[i];
+            let col = ((p[0] / pitch) as usize).min(width_px as usize - 1);
+            let row = ((p[1] / pitch) as usize).min(height_px as usize - 1);
+            bits[row * row_bytes + col / 8] &= !(0x80 >> (col % 8));
+        }
+        let file = format!("layer_{:05}.{}", index, options.image_format.extension());
+        let writer = &mut BufWriter::new(
+            File::create(stack.temp.join(&file))
+                .with_context(|| format!("Failed to create slice image {}", file))?,
+        );
+        match options.image_format {
+            SliceImageFormat::Png => write_png(writer, &bits, width_px, height_px, options.dpi)?,
+            SliceImageFormat::Bmp => write_bmp(writer, &bits, width_px, height_px, options.dpi)?,
+        }
+        writer.flush()?;
+        layers.push(SliceLayer {
+            index,
+            z_mm: slabs.z_mm(layer),
+            file,
+            point_count: in_layer.len(),
+        });
+    }
+    progress(slabs.count, slabs.count)?;
+
+    let manifest = SliceManifest {
+        image_format: options.image_format,
+        layer_thickness_mm: options.layer_thickness_mm,
+        dpi: options.dpi,
+        pixel_pitch_mm: pitch,
+        width_px,
+        height_px,
+        blank_length_mm: envelope.length_mm,
+        blank_width_mm: envelope.width_mm,
+        point_count: slabs.point_count(),
+        clipped_points: slabs.clipped,
+        layers,
+    };
+    stack.commit(&manifest)?;
+    Ok(manifest)
+}
+
+/// Points of a fitted cloud grouped into Z layers, from the lowest to the highest occupied
+/// layer (empty layers in between included). Points outside the blank are left out.
+pub(super) struct Slabs {
+    /// Layer number (counted from the blank floor) of the lowest occupied layer.
+    first: usize,
+    /// Layers from the lowest to the highest occupied one.
+    pub count: usize,
+    thickness: f32,
+    /// (layer, point index), sorted, so each layer keeps the cloud's point order.
+    entries: Vec<(usize, usize)>,
+    pub clipped: usize,
+}
+
+impl Slabs {
+    pub fn new(points: &[[f32; 3]], envelope: &BlankEnvelope, thickness: f32) -> Result<Self> {
+        let mut entries = Vec::with_capacity(points.len());
+        let mut clipped = 0;
+        for (i, p) in points.iter().enumerate() {
+            let layer = (p[2] / thickness).floor();
+            if is_outside_envelope(p, envelope) || !layer.is_finite() {
+                clipped += 1;
+                continue;
+            }
+            entries.push((layer as usize, i));
+        }
+        ensure!(!entries.is_empty(), "No points inside the blank to slice");
+        entries.sort_unstable();
+        let first = entries[0].0;
+        let count = entries[entries.len() - 1].0 - first + 1;
+        ensure!(
+            count <= MAX_LAYERS,
+            "Stack would have {} layers; increase the layer thickness",
+            count
+        );
+        Ok(Self {
+            first,
+            count,
+            thickness,
+            entries,
+            clipped,
+        })
+    }
+
+    /// Points placed in layers.
+    pub fn point_count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Z of a layer's centre, mm above the blank floor.
+    pub fn z_mm(&self, layer: usize) -> f32 {
+        (layer as f32 + 0.5) * self.thickness
+    }
+
+    /// `(layer, entries)` for every layer; entries are `(layer, point index)` pairs.
+    pub fn layers(&self) -> impl Iterator<Item = (usize, &[(usize, usize)])> + '_ {
+        let mut rest = self.entries.as_slice();
+        (self.first..self.first + self.count).map(move |layer| {
+            let n = rest.iter().take_while(|&&(l, _)| l == layer).count();
+            let (in_layer, tail) = rest.split_at(n);
+            rest = tail;
+            (layer, in_layer)
+        })
+    }
+}
+
+/// 1-bit greyscale PNG; `bits` holds `ceil(width / 8)` bytes per row, MSB first, 0 = black.
+fn write_png(w: &mut impl Write, bits: &[u8], width: u32, height: u32, dpi: f32) -> Result<()> {
+    let mut encoder = png::Encoder::new(w, width, height);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::One);
+    let per_metre = (dpi / MM_PER_INCH * 1000.0).round() as u32;
+    encoder.set_pixel_dims(Some(png::PixelDimensions {
+        xppu: per_metre,
+        yppu: per_metre,
+        unit: png::Unit::Meter,
+    }));
+    let mut writer = encoder
+        .write_header()
+        .context("Failed to write slice PNG")?;
+    writer
+        .write_image_data(bits)
+        .context("Failed to write slice PNG")?;
+    writer.finish().context("Failed to write slice PNG")?;
+    Ok(())
+}
+
+/// 1-bit BMP with a black/white palette (same bit layout as [`write_png`], rows stored bottom-up
+/// and padded to 4 bytes).
+fn write_bmp(w: &mut impl Write, bits: &[u8], width: u32, height: u32, dpi: f32) -> Result<()> {
+    const HEADER_BYTES: u32 = 14 + 40 + 2 * 4;
+    let row_bytes = (width as usize).div_ceil(8);
+    let stride = row_bytes.div_ceil(4) * 4;
+    let image_bytes = (stride * height as usize) as u32;
+    let per_metre = (dpi / MM_PER_INCH * 1000.0).round() as u32;
+
+    w.write_all(b"BM")?;
+    w.write_all(&(HEADER_BYTES + image_bytes).to_le_bytes())?;
+    w.write_all(&0u32.to_le_bytes())?;
+    w.write_all(&HEADER_BYTES.to_le_bytes())?;
+    // BITMAPINFOHEADER
+    w.write_all(&40u32.to_le_bytes())?;
+    w.write_all(&(width as i32).to_le_bytes())?;
+    w.write_all(&(height as i32).to_le_bytes())?;
+    w.write_all(&1u16.to_le_bytes())?;
+    w.write_all(&1u16.to_le_bytes())?;
+    w.write_all(&0u32.to_le_bytes())?;
+    w.write_all(&image_bytes.to_le_bytes())?;
+    w.write_all(&per_metre.to_le_bytes())?;
+    w.write_all(&per_metre.to_le_bytes())?;
+    w.write_all(&2u32.to_le_bytes())?;
+    w.write_all(&0u32.to_le_bytes())?;
+    // Palette (BGRA): index 0 black, 1 white.
+    w.write_all(&[0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0])?;
+    let padding = vec![0u8; stride - row_bytes];
+    for row in bits.chunks(row_bytes).rev() {
+        w.write_all(row)?;
+        w.write_all(&padding)?;
+    }
+    Ok(())
+}
+
+/// Stack folder being written: a hidden temporary next to `dest` that this export created,
+/// moved into place by [`commit`](StackDir::commit) and removed on drop otherwise.
+pub(super) struct StackDir {
+    dest: PathBuf,
+    /// Where the layer files are written until the commit.
+    pub temp: PathBuf,
+}
+
+impl StackDir {
+    pub fn create(dest: &Path) -> Result<Self> {
+        if dest.exists() {
+            ensure_replaceable(dest)?;
+        }
+        // A unique name per export; an existing folder (e.g. left by a crashed run) is
+        // skipped, never removed, since it may belong to another job still writing.
+        let temp = loop {
+            let temp = super::atomic::temp_path(dest);
+            match std::fs::create_dir(&temp) {
+                Ok(()) => break temp,
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("Failed to create slice folder: {:?}", dest))
+                }
+            }
+        };
+        Ok(Self {
+            dest: dest.to_path_buf(),
+            temp,
+        })
+    }
+
+    /// Write `manifest` as [`MANIFEST_FILE`] and move the folder into place. An earlier stack
+    /// is renamed aside first and only deleted once the new one is in place; if the move fails
+    /// it is put back, so `dest` never ends up empty or half-deleted.
+    pub fn commit(self, manifest: &impl Serialize) -> Result<()> {
+        let file =
+            File::create(self.temp.join(MANIFEST_FILE)).context("Failed to create manifest")?;
+        let mut writer = BufWriter::new(file);
+        serde_json::to_writer_pretty(&mut writer, manifest)?;
+        writer.flush()?;
+        let previous = if self.dest.exists() {
+            ensure_replaceable(&self.dest)?;
+            let previous = super::atomic::temp_path(&self.dest);
+            std::fs::rename(&self.dest, &previous)
+                .with_context(|| format!("Failed to replace slice folder: {:?}", self.dest))?;
+            Some(previous)
+        } else {
+            None
+        };
+        if let Err(e) = std::fs::rename(&self.temp, &self.dest) {
+            if let Some(previous) = &previous {
+                let _ = std::fs::rename(previous, &self.dest);
+            }
+            return Err(e).with_context(|| {
+                format!("Failed to move slice folder into place: {:?}", self.dest)
+            });
+        }
+        super::atomic::sync_parent_dir(&self.dest);
+        if let Some(previous) = previous {
+            // The new stack is in place; a leftover old one is only clutter.
+            let _ = std::fs::remove_dir_all(previous);
+        }
+        Ok(())
+    }
+}
+
+impl Drop for StackDir {
+    fn drop(&mut self) {
+        if self.temp.exists() {
+            let _ = std::fs::remove_dir_all(&self.temp);
+        }
+    }
+}
+
+/// An existing destination is only replaced when it is empty or holds nothing but an earlier
+/// stack (manifest and layer files), so user files are never deleted.
+fn ensure_replaceable(dir: &Path) -> Result<()> {
+    ensure!(dir.is_dir(), "Slice destination is not a folder: {:?}", dir);
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_file() && (name == MANIFEST_FILE || is_layer_file(&name)) {
+            continue;
+        }
+        bail!(
+            "Slice folder {:?} contains other files ({}); choose an empty folder",
+            dir,
+            name
+        );
+    }
+    Ok(())
+}
+
+fn is_layer_file(name: &str) -> bool {
+    let Some((stem, ext)) = name.rsplit_once('.') else {
+        return false;
+    };
+    let Some(number) = stem.strip_prefix("layer_") else {
+        return false;
+    };
+    !number.is_empty()
+        && number.bytes().all(|b| b.is_ascii_digit())
+        && ["png", "bmp", "svg"].contains(&ext)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn envelope() -> BlankEnvelope {
+        BlankEnvelope::new(10.0, 5.0, 5.0, 0.5)
+    }
+
+    fn options(image_format: SliceImageFormat) -> SliceStackOptions {
+        SliceStackOptions {
+            layer_thickness_mm: 0.5,
+            dpi: 25.4,
+            image_format,
+        }
+    }
+
+    fn is_black(dir: &Path, file: &str, x: u32, y: u32) -> bool {
+        let img = image::open(dir.join(file)).unwrap().to_luma8();
+        img.get_pixel(x, y)[0] == 0
+    }
+
+    #[test]
+    fn concurrent_stacks_to_one_folder_keep_separate_temporaries() {
+        let dir = std::env::temp_dir().join("sp3d_slices_concurrent_test");
+        let first = StackDir::create(&dir).unwrap();
+        let second = StackDir::create(&dir).unwrap();
+        assert_ne!(first.temp, second.temp);
+        let kept = second.temp.clone();
+        drop(first);
+        assert!(kept.is_dir());
+        drop(second);
+        assert!(!kept.exists());
+    }
+
+    #[test]
+    fn slices_layers_into_png_stack_with_manifest() {
+        let dir = std::env::temp_dir().join("sp3d_slices_png_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let points = [
+            [1.5, 2.5, 1.2],
+            [3.5, 0.5, 1.3],
+            [3.6, 0.4, 1.4],
+            [9.5, 4.5, 2.1],
+            [20.0, 1.0, 1.0],
+        ];
+        let mut calls = Vec::new();
+        let manifest = write_slice_stack(
+            &dir,
+            &points,
+            &envelope(),
+            &options(SliceImageFormat::Png),
+            |done, total| {
+                calls.push((done, total));
+                Ok(())
+            },
+        )
+        .unwrap();
+
+        assert_eq!((manifest.width_px, manifest.height_px), (10, 5));
+        assert_eq!(manifest.pixel_pitch_mm, 1.0);
+        assert_eq!(manifest.clipped_points, 1);
+        let summary: Vec<(f32, usize)> = manifest
+            .layers
+            .iter()
+            .map(|l| (l.z_mm, l.point_count))
+            .collect();
+        assert_eq!(summary, vec![(1.25, 3), (1.75, 0), (2.25, 1)]);
+        assert_eq!(calls.last(), Some(&(3, 3)));
+
+        let first = &manifest.layers[0].file;
+        assert_eq!(first, "layer_00000.png");
+        assert!(is_black(&dir, first, 1, 2));
+        assert!(is_black(&dir, first, 3, 0));
+        assert!(!is_black(&dir, first, 9, 4));
+        assert!(is_black(&dir, &manifest.layers[2].file, 9, 4));
+        let saved: SliceManifest =
+            serde_json::from_slice(&std::fs::read(dir.join(MANIFEST_FILE)).unwrap()).unwrap();
+        assert_eq!(saved, manifest);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn bmp_stack_replaces_previous_stack_only() {
+        let dir = std::env::temp_dir().join("sp3d_slices_bmp_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let points = [[0.2, 0.2, 0.2], [9.9, 4.9, 0.3], [5.0, 2.0, 1.8]];
+        write_slice_stack(
+            &dir,
+            &points,
+            &envelope(),
+            &options(SliceImageFormat::Png),
+            |_, _| Ok(()),
+        )
+        .unwrap();
+        let manifest = write_slice_stack(
+            &dir,
+            &points[..2],
+            &envelope(),
+            &options(SliceImageFormat::Bmp),
+            |_, _| Ok(()),
+        )
+        .unwrap();
+        let mut files: Vec<String> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["layer_00000.bmp", MANIFEST_FILE]);
+        // The replaced stack was moved aside and removed, not left next to the new one.
+        let leftovers = std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter(|e| {
+                let name = e
+                    .as_ref()
+                    .unwrap()
+                    .file_name()
+                    .to_string_lossy()
+                    .to_string();
+                name.starts_with(".sp3d_slices_bmp_test.")
+            })
+            .count();
+        assert_eq!(leftovers, 0);
+        assert!(is_black(&dir, &manifest.layers[0].file, 0, 0));
+        assert!(is_black(&dir, &manifest.layers[0].file, 9, 4));
+        assert!(!is_black(&dir, &manifest.layers[0].file, 5, 2));
+
+        // A cancelled run and a folder with foreign files leave everything in place.
+        let cancelled = write_slice_stack(
+            &dir,
+            &points,
+            &envelope(),
+            &options(SliceImageFormat::Png),
+            |done, _| {
+                ensure!(done == 0, "cancelled");
+                Ok(())
+            },
+        );
+        assert!(cancelled.is_err());
+        std::fs::write(dir.join("notes.txt"), "mine").unwrap();
+        let refused = write_slice_stack(
+            &dir,
+            &points,
+            &envelope(),
+            &options(SliceImageFormat::Png),
+            |_, _| Ok(()),
+        );
+        assert!(refused.unwrap_err().to_string().contains("other files"));
+        assert!(dir.join("layer_00000.bmp").exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn options_are_range_checked() {
+        assert!(SliceStackOptions::default().validate().is_ok());
+        let thin = SliceStackOptions {
+            layer_thickness_mm: 0.0,
+            ..Default::default()
+        };
+        assert!(thin.validate().is_err());
+        let coarse = SliceStackOptions {
+            dpi: 5.0,
+            ..Default::default()
+        };
+        assert!(coarse.validate().is_err());
+    }
+}
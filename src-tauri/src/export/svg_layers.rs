@@ -0,0 +1,477 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Per-layer SVG export for inspection and vector-driven machines.
+//!
+//! The fitted cloud is cut into Z slabs like a bitmap slice stack (see [`super::slices`]) and
+//! each slab is written as `layer_NNNNN.svg` at 1:1 scale: the document is the blank footprint
+//! in millimetres (`width="80mm"`, `viewBox="0 0 80 50"`), with y = 0 at the top as in the
+//! source image. Every file has a "Guide" layer with the blank outline and the dashed margin,
+//! and a layer with the slab's points, either as circles or as polylines through the points in
+//! the chosen travel order. A polyline is broken where the next point is farther than
+//! `joinDistanceMm`, so separate features are not tied together. A `manifest.json` lists each
+//! file with its Z.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use super::slices::{Slabs, SliceLayer, StackDir, MAX_LAYER_THICKNESS_MM, MIN_LAYER_THICKNESS_MM};
+use crate::blank_envelope::BlankEnvelope;
+
+/// Accepted dot diameter / line width (mm).
+pub const MIN_DOT_DIAMETER_MM: f32 = 0.001;
+pub const MAX_DOT_DIAMETER_MM: f32 = 5.0;
+
+/// How a slab's points are drawn.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SvgLayerStyle {
+    /// One filled circle of `dotDiameterMm` per point.
+    #[default]
+    Circles,
+    /// Points joined in travel order, stroked `dotDiameterMm` wide.
+    Polylines,
+}
+
+/// Order in which a slab's points are visited (document order, and the polyline path).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TravelOrder {
+    /// The cloud's own order (row by row for depth-map clouds).
+    #[default]
+    AsGenerated,
+    /// Rows of `dotDiameterMm` height, alternating left-to-right and right-to-left.
+    Serpentine,
+    /// Greedy nearest neighbour from the first point.
+    NearestNeighbour,
+}
+
+/// Per-layer SVG settings; omitted keys take their defaults.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SvgLayerOptions {
+    pub layer_thickness_mm: f32,
+    pub style: SvgLayerStyle,
+    pub travel_order: TravelOrder,
+    /// Circle diameter, or stroke width for polylines (mm).
+    pub dot_diameter_mm: f32,
+    /// Longest segment a polyline may have before it is broken (mm).
+    pub join_distance_mm: f32,
+}
+
+impl Default for SvgLayerOptions {
+    fn default() -> Self {
+        Self {
+            layer_thickness_mm: 0.5,
+            style: SvgLayerStyle::Circles,
+            travel_order: TravelOrder::AsGenerated,
+            dot_diameter_mm: 0.05,
+            join_distance_mm: 1.0,
+        }
+    }
+}
+
+impl SvgLayerOptions {
+    pub fn validate(&self) -> Result<(), String> {
+        if !(MIN_LAYER_THICKNESS_MM..=MAX_LAYER_THICKNESS_MM).contains(&self.layer_thickness_mm) {
+            return Err(format!(
+                "Layer thickness must be between {} and {} mm",
+                MIN_LAYER_THICKNESS_MM, MAX_LAYER_THICKNESS_MM
+            ));
+        }
+        if !(MIN_DOT_DIAMETER_MM..=MAX_DOT_DIAMETER_MM).contains(&self.dot_diameter_mm) {
+            return Err(format!(
+                "Dot diameter must be between {} and {} mm",
+                MIN_DOT_DIAMETER_MM, MAX_DOT_DIAMETER_MM
+            ));
+        }
+        if !(self.join_distance_mm > 0.0 && self.join_distance_mm.is_finite()) {
+            return Err("Join distance must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Contents of `manifest.json`; also the result of the SVG layer export.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SvgLayerManifest {
+    pub style: SvgLayerStyle,
+    pub travel_order: TravelOrder,
+    pub layer_thickness_mm: f32,
+    pub dot_diameter_mm: f32,
+    /// Document size (blank footprint) and guide margin, mm.
+    pub blank_length_mm: f32,
+    pub blank_width_mm: f32,
+    pub margin_mm: f32,
+    /// Points drawn over all layers.
+    pub point_count: usize,
+    /// Points outside the blank, left out.
+    pub clipped_points: usize,
+    pub layers: Vec<SliceLayer>,
+}
+
+/// Registry entry for per-layer SVGs (see [`super::LAYER_FOLDER_EXPORTERS`]).
+pub struct SvgLayerExporter;
+
+impl super::LayerFolderExporter for SvgLayerExporter {
+    fn id(&self) -> &'static str {
+        "svg"
+    }
+
+    fn label(&self) -> &'static str {
+        "SVG layers"
+    }
+
+    fn validate(&self, options: &serde_json::Value) -> Result<()> {
+        let options: SvgLayerOptions = super::layer_folder_options(self, options)?;
+        options.validate().map_err(anyhow::Error::msg)
+    }
+
+    fn write(
+        &self,
+        dir: &Path,
+        points: &[[f32; 3]],
+        envelope: &BlankEnvelope,
+        options: &serde_json::Value,
+        progress: &mut dyn FnMut(usize, usize) -> Result<()>,
+    ) -> Result<serde_json::Value> {
+        let options: SvgLayerOptions = super::layer_folder_options(self, options)?;
+        let manifest = write_svg_layers(dir, points, envelope, &options, progress)?;
+        Ok(serde_json::to_value(manifest)?)
+    }
+}
+
+/// Slice `points` (fitted to `envelope`) and write one SVG per slab into `dir`, replacing an
+/// earlier stack there. `progress(done, total)` is called per layer; an error from it aborts the
+/// export and leaves `dir` as it was.
+pub fn write_svg_layers(
+    dir: &Path,
+    points: &[[f32; 3]],
+    envelope: &BlankEnvelope,
+    options: &SvgLayerOptions,
+    mut progress: impl FnMut(usize, usize) -> Result<()>,
+) -> Result<SvgLayerManifest> {
+    options.validate().map_err(anyhow::Error::msg)?;
+    let slabs = Slabs::new(points, envelope, options.layer_thickness_mm)?;
+    let stack = StackDir::create(dir)?;
+    let mut layers = Vec::with_capacity(slabs.count);
+    for (index, (layer, in_layer)) in slabs.layers().enumerate() {
+        progress(index, slabs.count)?;
+        let xy: Vec<[f32; 2]> = in_layer
+            .iter()
+            .map(|&(_, i)| [points[i][0], points[i][1]])
+            .collect();
+        let order = travel_order(&xy, options.travel_order, options.dot_diameter_mm);
+        let z_mm = slabs.z_mm(layer);
+        let file = format!("layer_{:05}.svg", index);
+        let mut writer = BufWriter::new(
+            File::create(stack.temp.join(&file))
+                .with_context(|| format!("Failed to create layer SVG {}", file))?,
+        );
+        write_layer_svg(&mut writer, &xy, &order, index, z_mm, envelope, options)
+            .with_context(|| format!("Failed to write layer SVG {}", file))?;
+        writer.flush()?;
+        layers.push(SliceLayer {
+            index,
+            z_mm,
+            file,
+            point_count: xy.len(),
+        });
+    }
+    progress(slabs.count, slabs.count)?;
+
+    let manifest = SvgLayerManifest {
+        style: options.style,
+        travel_order: options.travel_order,
+        layer_thickness_mm: options.layer_thickness_mm,
+        dot_diameter_mm: options.dot_diameter_mm,
+        blank_length_mm: envelope.length_mm,
+        blank_width_mm: envelope.width_mm,
+        margin_mm: envelope.margin_mm,
+        point_count: slabs.point_count(),
+        clipped_points: slabs.clipped,
+        layers,
+    };
+    stack.commit(&manifest)?;
+    Ok(manifest)
+}
+
+fn write_layer_svg(
+    w: &mut impl Write,
+    xy: &[[f32; 2]],
+    order: &[usize],
+    index: usize,
+    z_mm: f32,
+    envelope: &BlankEnvelope,
+    options: &SvgLayerOptions,
+) -> std::io::Result<()> {
+    let (length, width, margin) = (envelope.length_mm, envelope.width_mm, envelope.margin_mm);
+    let label = format!("Layer {} (z = {:.3} mm)", index, z_mm);
+    writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        w,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" width="{l}mm" height="{wd}mm" viewBox="0 0 {l} {wd}">"#,
+        l = length,
+        wd = width
+    )?;
+    writeln!(w, "  <title>{}</title>", label)?;
+    writeln!(
+        w,
+        r##"  <g id="guide" inkscape:groupmode="layer" inkscape:label="Guide" fill="none" stroke="#2563eb" stroke-width="0.1">"##
+    )?;
+    writeln!(
+        w,
+        r#"    <rect x="0" y="0" width="{}" height="{}"/>"#,
+        length, width
+    )?;
+    writeln!(
+        w,
+        r#"    <rect x="{m}" y="{m}" width="{}" height="{}" stroke-dasharray="1 0.5"/>"#,
+        envelope.interior_length(),
+        envelope.interior_width(),
+        m = margin
+    )?;
+    writeln!(w, "  </g>")?;
+
+    let radius = options.dot_diameter_mm / 2.0;
+    match options.style {
+        SvgLayerStyle::Circles => {
+            writeln!(
+                w,
+                r##"  <g id="points" inkscape:groupmode="layer" inkscape:label="{}" fill="#000000">"##,
+                label
+            )?;
+            for &i in order {
+                write_circle(w, xy[i], radius)?;
+            }
+        }
+        SvgLayerStyle::Polylines => {
+            writeln!(
+                w,
+                r##"  <g id="points" inkscape:groupmode="layer" inkscape:label="{}" fill="none" stroke="#000000" stroke-width="{}" stroke-linecap="round" stroke-linejoin="round">"##,
+                label, options.dot_diameter_mm
+            )?;
+            for run in polyline_runs(xy, order, options.join_distance_mm) {
+                if let [single] = run {
+                    // A one-point polyline draws nothing; mark the point instead.
+                    writeln!(w, r##"    <g fill="#000000" stroke="none">"##)?;
+                    write_circle(w, xy[*single], radius)?;
+                    writeln!(w, "    </g>")?;
+                    continue;
+                }
+                write!(w, r#"    <polyline points=""#)?;
+                for (k, &i) in run.iter().enumerate() {
+                    let sep = if k == 0 { "" } else { " " };
+                    write!(w, "{}{:.3},{:.3}", sep, xy[i][0], xy[i][1])?;
+                }
+                writeln!(w, r#""/>"#)?;
+            }
+        }
+    }
+    writeln!(w, "  </g>")?;
+    writeln!(w, "</svg>")
+}
+
+fn write_circle(w: &mut impl Write, p: [f32; 2], radius: f32) -> std::io::Result<()> {
+    writeln!(
+        w,
+        r#"    <circle cx="{:.3}" cy="{:.3}" r="{:.4}"/>"#,
+        p[0], p[1], radius
+    )
+}
+
+/// Split the visiting order into runs whose consecutive points are at most `join_mm` apart.
+fn polyline_runs<'a>(xy: &[[f32; 2]], order: &'a [usize], join_mm: f32) -> Vec<&'a [usize]> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for k in 1..=order.len() {
+        if k == order.len() || distance(xy[order[k - 1]], xy[order[k]]) > join_mm {
+            runs.push(&order[start..k]);
+            start = k;
+        }
+    }
+    runs
+}
+
+fn distance(a: [f32; 2], b: [f32; 2]) -> f32 {
+    ((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2)).sqrt()
+}
+
+/// Visiting order of `xy` (indices into it). `row_mm` is the serpentine row height.
+pub fn travel_order(xy: &[[f32; 2]], order: TravelOrder, row_mm: f32) -> Vec<usize> {
+    match order {
+        TravelOrder::AsGenerated => (0..xy.len()).collect(),
+        TravelOrder::Serpentine => {
+            let row = |i: usize| (xy[i][1] / row_mm).round() as i64;
+            let mut indices: Vec<usize> = (0..xy.len()).collect();
+            indices.sort_by(|&a, &b| row(a).cmp(&row(b)).then(xy[a][0].total_cmp(&xy[b][0])));
+            for (n, run) in indices.chunk_by_mut(|&a, &b| row(a) == row(b)).enumerate() {
+                if !n.is_multiple_of(2) {
+                    run.reverse();
+                }
+            }
+            indices
+        }
+        TravelOrder::NearestNeighbour => nearest_neighbour_order(xy),
+    }
+}
+
+/// Greedy nearest-neighbour tour starting at the first point, using a uniform grid so each
+/// step only searches the rings of cells around the current point.
+fn nearest_neighbour_order(xy: &[[f32; 2]]) -> Vec<usize> {
+    let n = xy.len();
+    if n == 0 {
+        return Vec::new();
+    }
+    let mut min = xy[0];
+    let mut max = xy[0];
+    for p in xy {
+        for a in 0..2 {
+            min[a] = min[a].min(p[a]);
+            max[a] = max[a].max(p[a]);
+        }
+    }
+    let span = [max[0] - min[0], max[1] - min[1]];
+    let cell = ((span[0].max(1e-3) * span[1].max(1e-3)) / n as f32)
+        .sqrt()
+        .max(1e-4);
+    let cols = (span[0] / cell) as usize + 1;
+    let rows = (span[1] / cell) as usize + 1;
+    let cell_of = |p: [f32; 2]| {
+        let c = (((p[0] - min[0]) / cell) as usize).min(cols - 1);
+        let r = (((p[1] - min[1]) / cell) as usize).min(rows - 1);
+        (c, r)
+    };
+    let mut grid: Vec<Vec<usize>> = vec![Vec::new(); cols * rows];
+    for (i, &p) in xy.iter().enumerate() {
+        let (c, r) = cell_of(p);
+        grid[r * cols + c].push(i);
+    }
+
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    let mut current = 0;
+    visited[0] = true;
+    order.push(0);
+    let max_ring = cols.max(rows);
+    while order.len() < n {
+        let (cc, cr) = cell_of(xy[current]);
+        let mut best: Option<(f32, usize)> = None;
+        for ring in 0..=max_ring {
+            let (c0, c1) = (cc.saturating_sub(ring), (cc + ring).min(cols - 1));
+            let (r0, r1) = (cr.saturating_sub(ring), (cr + ring).min(rows - 1));
+            for r in r0..=r1 {
+                for c in c0..=c1 {
+                    // Only the ring's border cells; the inside was searched already.
+                    if c.abs_diff(cc) != ring && r.abs_diff(cr) != ring {
+                        continue;
+                    }
+                    let bucket = &mut grid[r * cols + c];
+                    bucket.retain(|&j| !visited[j]);
+                    for &j in bucket.iter() {
+                        let d = distance(xy[current], xy[j]);
+                        if best.is_none_or(|(bd, bj)| d < bd || (d == bd && j < bj)) {
+                            best = Some((d, j));
+                        }
+                    }
+                }
+            }
+            // Points in later rings are at least `ring * cell` away.
+            if matches!(best, Some((d, _)) if d < ring as f32 * cell) {
+                break;
+            }
+        }
+        let Some((_, next)) = best else {
+            break;
+        };
+        visited[next] = true;
+        order.push(next);
+        current = next;
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn travel_orders_visit_every_point_once() {
+        let xy = [
+            [0.0, 0.0],
+            [2.0, 0.0],
+            [1.0, 0.0],
+            [0.0, 1.0],
+            [2.0, 1.0],
+            [1.0, 1.0],
+        ];
+        assert_eq!(
+            travel_order(&xy, TravelOrder::Serpentine, 0.5),
+            vec![0, 2, 1, 4, 5, 3]
+        );
+        assert_eq!(
+            travel_order(&xy, TravelOrder::NearestNeighbour, 0.5),
+            vec![0, 2, 1, 4, 5, 3]
+        );
+        let far = [
+            [0.0, 0.0],
+            [10.0, 10.0],
+            [0.5, 0.0],
+            [9.5, 10.0],
+            [0.0, 9.0],
+        ];
+        let mut order = travel_order(&far, TravelOrder::NearestNeighbour, 0.5);
+        assert_eq!(order, vec![0, 2, 4, 3, 1]);
+        order.sort_unstable();
+        assert_eq!(order, vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn polylines_break_at_gaps() {
+        let xy = [[0.0, 0.0], [0.5, 0.0], [5.0, 0.0], [5.5, 0.0], [9.0, 0.0]];
+        let order: Vec<usize> = (0..xy.len()).collect();
+        let runs = polyline_runs(&xy, &order, 1.0);
+        assert_eq!(runs, vec![&[0, 1][..], &[2, 3][..], &[4][..]]);
+    }
+
+    #[test]
+    fn writes_one_svg_per_layer_at_one_to_one_scale() {
+        let dir = std::env::temp_dir().join("sp3d_svg_layers_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let envelope = BlankEnvelope::new(20.0, 10.0, 10.0, 1.0);
+        let points = [
+            [2.0, 3.0, 1.1],
+            [2.5, 3.0, 1.2],
+            [8.0, 3.0, 1.3],
+            [4.0, 4.0, 2.6],
+            [30.0, 1.0, 1.0],
+        ];
+        let options = SvgLayerOptions {
+            layer_thickness_mm: 1.0,
+            style: SvgLayerStyle::Polylines,
+            ..Default::default()
+        };
+        let manifest = write_svg_layers(&dir, &points, &envelope, &options, |_, _| Ok(())).unwrap();
+
+        assert_eq!(manifest.clipped_points, 1);
+        let summary: Vec<(f32, usize)> = manifest
+            .layers
+            .iter()
+            .map(|l| (l.z_mm, l.point_count))
+            .collect();
+        assert_eq!(summary, vec![(1.5, 3), (2.5, 1)]);
+        let svg = std::fs::read_to_string(dir.join(&manifest.layers[0].file)).unwrap();
+        assert!(svg.contains(r#"width="20mm" height="10mm" viewBox="0 0 20 10""#));
+        assert!(svg.contains(r#"inkscape:label="Guide""#));
+        assert!(svg.contains(r#"<rect x="1" y="1" width="18" height="8""#));
+        assert!(svg.contains(r#"<polyline points="2.000,3.000 2.500,3.000"/>"#));
+        // The isolated point is drawn as a circle.
+        assert!(svg.contains(r#"<circle cx="8.000" cy="3.000" r="0.0250"/>"#));
+        assert!(dir.join(super::super::slices::MANIFEST_FILE).exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
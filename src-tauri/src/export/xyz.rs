@@ -0,0 +1,132 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! XYZ export: one `x y z` line per point, no header by default.
+//!
+//! Delimiter, decimal separator, precision, units, columns, header and line endings are
+//! configurable through the [`TextDialect`] options.
+
+use anyhow::Result;
+use std::io::{BufRead, Write};
+
+use super::dialect::TextDialect;
+use super::{
+    ExportHeader, ExportInput, ExportOptionSpec, ExportOptions, PointCloudExporter, PointRowWriter,
+};
+use crate::import::ReadSink;
+use crate::volumetric::PointRecord;
+
+/// Write points to XYZ format (ASCII).
+///
+/// XYZ is a simple format with one point per line: `x y z`
+/// Widely supported by point cloud processing tools and some laser engravers.
+pub fn write_xyz<W: Write + ?Sized>(writer: &mut W, points: &[[f32; 3]]) -> Result<()> {
+    for &p in points {
+        write_xyz_row(writer, p)?;
+    }
+    Ok(())
+}
+
+/// Write points in `dialect` (column-name row first when its header is on).
+pub fn write_xyz_dialect<W: Write + ?Sized>(
+    writer: &mut W,
+    points: &[[f32; 3]],
+    dialect: &TextDialect,
+) -> Result<()> {
+    dialect.write_column_names(writer)?;
+    for (i, &p) in points.iter().enumerate() {
+        dialect.write_row(writer, i as u64, p)?;
+    }
+    Ok(())
+}
+
+/// Write one `x y z` line in the default dialect (shared with PTS).
+pub(super) fn write_xyz_row<W: Write + ?Sized>(writer: &mut W, p: [f32; 3]) -> Result<()> {
+    writeln!(writer, "{:.6} {:.6} {:.6}", p[0], p[1], p[2])?;
+    Ok(())
+}
+
+/// Registry entry for XYZ.
+pub struct XyzExporter;
+
+impl PointCloudExporter for XyzExporter {
+    fn id(&self) -> &'static str {
+        "xyz"
+    }
+
+    fn label(&self) -> &'static str {
+        "XYZ"
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        &["xyz"]
+    }
+
+    fn text_dialect(&self) -> Option<TextDialect> {
+        Some(TextDialect::xyz())
+    }
+
+    fn options_schema(&self) -> Vec<ExportOptionSpec> {
+        TextDialect::xyz().options_schema()
+    }
+
+    fn write(
+        &self,
+        writer: &mut dyn Write,
+        input: &ExportInput,
+        options: &ExportOptions,
+    ) -> Result<()> {
+        write_xyz_dialect(writer, input.points, &TextDialect::from_options(options)?)
+    }
+
+    fn begin_stream(
+        &self,
+        writer: &mut dyn Write,
+        _header: &ExportHeader,
+        options: &ExportOptions,
+    ) -> Result<Option<PointRowWriter>> {
+        let dialect = TextDialect::from_options(options)?;
+        dialect.write_column_names(writer)?;
+        let mut index = 0u64;
+        let row: PointRowWriter = Box::new(move |w, r: &PointRecord| {
+            dialect.write_row(w, index, r.position)?;
+            index += 1;
+            Ok(())
+        });
+        Ok(Some(row))
+    }
+
+    fn read_back(
+        &self,
+        reader: &mut dyn BufRead,
+        options: &ExportOptions,
+        sink: &mut dyn ReadSink,
+    ) -> Option<Result<()>> {
+        Some(TextDialect::read_back(options, reader, sink))
+    }
+
+    fn read_back_rounding_mm(&self, options: &ExportOptions) -> f32 {
+        TextDialect::from_options(options).map_or(0.0, |d| d.rounding_mm())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::test_util::sample_points;
+    use std::io::Cursor;
+
+    #[test]
+    fn write_xyz_format() {
+        let points = sample_points();
+        let mut output = Cursor::new(Vec::new());
+
+        write_xyz(&mut output, &points).unwrap();
+
+        let result = String::from_utf8(output.into_inner()).unwrap();
+        let lines: Vec<&str> = result.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].contains("0.000000 0.000000 0.000000"));
+        assert!(lines[1].contains("10.000000 5.000000 3.000000"));
+    }
+}
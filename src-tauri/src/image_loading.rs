@@ -57,7 +57,7 @@ fn validate_path(path: &str) -> anyhow::Result<PathBuf> {
 }
 
 /// Returns true if the canonical path is under a blocklisted system directory (threat model §2.3).
-fn is_blocklisted(canonical: &Path) -> bool {
+pub(crate) fn is_blocklisted(canonical: &Path) -> bool {
     #[cfg(windows)]
     {
         let s = match canonical.to_str() {
@@ -177,6 +177,21 @@ pub fn read_image_bytes_for_depth(path: &str) -> anyhow::Result<Vec<u8>> {
     Ok(bytes)
 }
 
+/// Loads the source image as RGB on the depth map grid (per-point colour attributes).
+/// Same validation as load_image; resampled when the depth map dimensions differ from the image.
+pub fn load_rgb_for_depth(path: &str, width: u32, height: u32) -> anyhow::Result<image::RgbImage> {
+    let canonical = validate_path(path)?;
+    let bytes = fs::read(&canonical).context("read image file")?;
+    let format = validate_magic_bytes(&bytes)?;
+    let img = decode_image(&bytes, format)?;
+    let img = if img.dimensions() == (width, height) {
+        img
+    } else {
+        img.resize_exact(width, height, FilterType::Triangle)
+    };
+    Ok(to_rgb8(img))
+}
+
 /// Full load_image implementation: validate path, read, magic-check, decode, downsample, RGB, response.
 pub fn load_image_impl(path: String) -> anyhow::Result<LoadImageOut> {
     let start = std::time::Instant::now();
@@ -282,6 +297,22 @@ mod tests {
         assert!(r.file_size_bytes > 0);
     }
 
+    /// Per-point colour attributes: source RGB is resampled onto the depth grid.
+    #[test]
+    fn load_rgb_for_depth_resamples_to_depth_dimensions() {
+        let temp = std::env::temp_dir().join("sp3d_rgb_for_depth.png");
+        let img = image::ImageBuffer::from_fn(8, 4, |_x, _y| image::Rgb([10u8, 20, 30]));
+        img.save(&temp).expect("write test PNG");
+        let path = temp.to_string_lossy().to_string();
+        let same = load_rgb_for_depth(&path, 8, 4);
+        let half = load_rgb_for_depth(&path, 4, 2);
+        let _ = std::fs::remove_file(&temp);
+        let same = same.expect("same-size load");
+        assert_eq!(same.dimensions(), (8, 4));
+        assert_eq!(same.get_pixel(3, 2).0, [10, 20, 30]);
+        assert_eq!(half.expect("resampled load").dimensions(), (4, 2));
+    }
+
     /// Error path: nonexistent path returns error (BACK-101).
     #[test]
     fn load_image_impl_invalid_path_returns_error() {
@@ -0,0 +1,986 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Point cloud import (re-fitting and re-export of external clouds).
+//!
+//! Reads the formats the export module writes — PLY (ASCII and binary), XYZ, CSV and
+//! PTS — plus PCD (ASCII and binary) from photogrammetry tools. Only x/y/z are kept;
+//! other per-vertex properties are skipped. Points with non-finite coordinates
+//! (e.g. NaN placeholders in organised PCD clouds) are dropped and counted.
+//!
+//! The imported cloud is fitted into the blank with `fit_to_blank` by the caller, so
+//! input units and placement do not matter.
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use std::fs::File;
+use std::io::{BufRead, BufReader, ErrorKind};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Maximum number of points accepted from a single file (guards header-declared counts).
+pub const MAX_IMPORT_POINTS: usize = 50_000_000;
+
+/// Importable point cloud formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    /// PLY (ASCII, binary little- or big-endian); `vertex` element x/y/z.
+    Ply,
+    /// XYZ (whitespace-, `;`- or comma-separated, first three columns; decimal commas accepted).
+    Xyz,
+    /// CSV (optional `#` comments, optional header row naming x/y/z columns; `,`, `;` or tab).
+    Csv,
+    /// Leica-style PTS (optional leading point count line).
+    Pts,
+    /// Point Cloud Library PCD (DATA ascii or binary).
+    Pcd,
+}
+
+impl FromStr for ImportFormat {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "ply" => Ok(ImportFormat::Ply),
+            "xyz" => Ok(ImportFormat::Xyz),
+            "csv" => Ok(ImportFormat::Csv),
+            "pts" => Ok(ImportFormat::Pts),
+            "pcd" => Ok(ImportFormat::Pcd),
+            _ => Err(()),
+        }
+    }
+}
+
+impl ImportFormat {
+    /// Detect the format from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|e| e.to_str())
+            .and_then(|e| e.parse().ok())
+    }
+}
+
+/// Points read from an external file.
+#[derive(Debug, Clone, Default)]
+pub struct ImportedPoints {
+    /// Finite points in file order (file units, not yet fitted).
+    pub points: Vec<[f32; 3]>,
+    /// Points dropped because a coordinate was NaN or infinite.
+    pub skipped: usize,
+}
+
+/// Receives points one by one while a file is parsed.
+pub trait ReadSink {
+    /// Called with a header-declared point count (PLY, PCD) before the body is read.
+    fn expect(&mut self, _count: usize) -> Result<()> {
+        Ok(())
+    }
+    /// One point with finite coordinates, in file order.
+    fn push(&mut self, p: [f32; 3]) -> Result<()>;
+    /// One point dropped because a coordinate was NaN or infinite.
+    fn skip(&mut self);
+}
+
+impl ReadSink for ImportedPoints {
+    fn expect(&mut self, count: usize) -> Result<()> {
+        ensure!(
+            count <= MAX_IMPORT_POINTS,
+            "point cloud exceeds {} points",
+            MAX_IMPORT_POINTS
+        );
+        Ok(())
+    }
+
+    fn push(&mut self, p: [f32; 3]) -> Result<()> {
+        ensure!(
+            self.points.len() < MAX_IMPORT_POINTS,
+            "point cloud exceeds {} points",
+            MAX_IMPORT_POINTS
+        );
+        self.points.push(p);
+        Ok(())
+    }
+
+    fn skip(&mut self) {
+        self.skipped += 1;
+    }
+}
+
+/// Point count and bounds of a parsed file, without keeping the points (export read-back).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ReadSummary {
+    /// Finite points read.
+    pub count: usize,
+    /// Points dropped because a coordinate was NaN or infinite.
+    pub skipped: usize,
+    /// Bounds (min, max) of the finite points; `None` when there are none.
+    pub bounds: Option<([f32; 3], [f32; 3])>,
+}
+
+impl ReadSink for ReadSummary {
+    fn push(&mut self, p: [f32; 3]) -> Result<()> {
+        self.count += 1;
+        let (min, max) = self.bounds.get_or_insert((p, p));
+        for i in 0..3 {
+            min[i] = min[i].min(p[i]);
+            max[i] = max[i].max(p[i]);
+        }
+        Ok(())
+    }
+
+    fn skip(&mut self) {
+        self.skipped += 1;
+    }
+}
+
+/// Narrow to `f32` and hand the point to `sink`, or count it as skipped if not finite.
+pub(crate) fn push_point(sink: &mut dyn ReadSink, p: [f64; 3]) -> Result<()> {
+    let p = [p[0] as f32, p[1] as f32, p[2] as f32];
+    if p.iter().all(|v| v.is_finite()) {
+        sink.push(p)
+    } else {
+        sink.skip();
+        Ok(())
+    }
+}
+
+/// Validate an input file path (canonical, existing file, not a system directory).
+///
+/// Shared by point cloud and mesh import; callers check the extension.
+pub fn validate_input_file(path: &str) -> Result<PathBuf> {
+    let p = Path::new(path.trim());
+    ensure!(!p.as_os_str().is_empty(), "import path must be non-empty");
+    let canonical = p
+        .canonicalize()
+        .context("path could not be resolved (missing or inaccessible)")?;
+    ensure!(canonical.is_file(), "path is not a file");
+    ensure!(
+        !crate::image_loading::is_blocklisted(&canonical),
+        "access to system directories is not allowed"
+    );
+    Ok(canonical)
+}
+
+/// Validate a point cloud import path ([`validate_input_file`] plus a supported extension).
+pub fn validate_import_path(path: &str) -> Result<PathBuf> {
+    let canonical = validate_input_file(path)?;
+    ensure!(
+        ImportFormat::from_path(&canonical).is_some(),
+        "unsupported point cloud format (expected ply, xyz, csv, pts or pcd)"
+    );
+    Ok(canonical)
+}
+
+/// Read a point cloud file, detecting the format from its extension.
+pub fn import_points(path: &Path) -> Result<ImportedPoints> {
+    let format = ImportFormat::from_path(path).ok_or_else(|| {
+        anyhow!("unsupported point cloud format (expected ply, xyz, csv, pts or pcd)")
+    })?;
+    let file = File::open(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let mut imported = ImportedPoints::default();
+    scan_points(&mut BufReader::new(file), format, &mut imported)?;
+    ensure!(
+        !imported.points.is_empty(),
+        "point cloud contains no valid points"
+    );
+    Ok(imported)
+}
+
+/// Parse point cloud bytes in the given format.
+pub fn read_points(bytes: &[u8], format: ImportFormat) -> Result<ImportedPoints> {
+    let mut out = ImportedPoints::default();
+    scan_points(&mut &bytes[..], format, &mut out)?;
+    Ok(out)
+}
+
+/// Stream a point cloud in the given format from `reader` into `sink`.
+pub fn scan_points(
+    reader: &mut dyn BufRead,
+    format: ImportFormat,
+    sink: &mut dyn ReadSink,
+) -> Result<()> {
+    match format {
+        ImportFormat::Ply => scan_ply(reader, sink),
+        ImportFormat::Pcd => scan_pcd(reader, sink),
+        ImportFormat::Xyz | ImportFormat::Pts => scan_xyz(reader, sink),
+        ImportFormat::Csv => scan_csv(reader, sink),
+    }
+}
+
+/// Read the next line into `line` (cleared first); `false` at end of input.
+pub(crate) fn next_line(reader: &mut dyn BufRead, line: &mut String) -> Result<bool> {
+    line.clear();
+    match reader.read_line(line) {
+        Ok(n) => Ok(n > 0),
+        Err(e) if e.kind() == ErrorKind::InvalidData => {
+            Err(anyhow!("file is not valid UTF-8 text"))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn parse_f64(token: &str, line_no: usize) -> Result<f64> {
+    token
+        .trim()
+        .parse::<f64>()
+        .with_context(|| format!("line {}: invalid number '{}'", line_no, token.trim()))
+}
+
+// ============================================================================
+// Scalar types shared by PLY and PCD
+// ============================================================================
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+impl ScalarType {
+    fn from_ply(name: &str) -> Result<Self> {
+        Ok(match name {
+            "char" | "int8" => ScalarType::I8,
+            "uchar" | "uint8" => ScalarType::U8,
+            "short" | "int16" => ScalarType::I16,
+            "ushort" | "uint16" => ScalarType::U16,
+            "int" | "int32" => ScalarType::I32,
+            "uint" | "uint32" => ScalarType::U32,
+            "float" | "float32" => ScalarType::F32,
+            "double" | "float64" => ScalarType::F64,
+            _ => bail!("unknown PLY property type '{}'", name),
+        })
+    }
+
+    fn from_pcd(ty: &str, size: usize) -> Result<Self> {
+        Ok(match (ty, size) {
+            ("I", 1) => ScalarType::I8,
+            ("U", 1) => ScalarType::U8,
+            ("I", 2) => ScalarType::I16,
+            ("U", 2) => ScalarType::U16,
+            ("I", 4) => ScalarType::I32,
+            ("U", 4) => ScalarType::U32,
+            ("F", 4) => ScalarType::F32,
+            ("F", 8) => ScalarType::F64,
+            _ => bail!("unsupported PCD field type {} with size {}", ty, size),
+        })
+    }
+
+    fn size(self) -> usize {
+        match self {
+            ScalarType::I8 | ScalarType::U8 => 1,
+            ScalarType::I16 | ScalarType::U16 => 2,
+            ScalarType::I32 | ScalarType::U32 | ScalarType::F32 => 4,
+            ScalarType::F64 => 8,
+        }
+    }
+
+    /// Decode one value from the start of `b` (at least `size()` bytes).
+    fn decode(self, b: &[u8], big_endian: bool) -> f64 {
+        macro_rules! num {
+            ($t:ty, $n:expr) => {{
+                let arr: [u8; $n] = b[..$n].try_into().unwrap();
+                if big_endian {
+                    <$t>::from_be_bytes(arr) as f64
+                } else {
+                    <$t>::from_le_bytes(arr) as f64
+                }
+            }};
+        }
+        match self {
+            ScalarType::I8 => b[0] as i8 as f64,
+            ScalarType::U8 => b[0] as f64,
+            ScalarType::I16 => num!(i16, 2),
+            ScalarType::U16 => num!(u16, 2),
+            ScalarType::I32 => num!(i32, 4),
+            ScalarType::U32 => num!(u32, 4),
+            ScalarType::F32 => num!(f32, 4),
+            ScalarType::F64 => num!(f64, 8),
+        }
+    }
+}
+
+/// Sequential reader over a binary body.
+struct ByteCursor<'r> {
+    reader: &'r mut dyn BufRead,
+    buf: [u8; 8],
+    big_endian: bool,
+}
+
+impl ByteCursor<'_> {
+    fn read(&mut self, ty: ScalarType) -> Result<f64> {
+        let b = &mut self.buf[..ty.size()];
+        self.reader.read_exact(b).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => anyhow!("unexpected end of binary data"),
+            _ => e.into(),
+        })?;
+        Ok(ty.decode(b, self.big_endian))
+    }
+}
+
+/// Whitespace-separated values of an ASCII body, which may wrap across lines.
+struct AsciiTokens<'r> {
+    reader: &'r mut dyn BufRead,
+    line: String,
+    pos: usize,
+}
+
+impl AsciiTokens<'_> {
+    fn next(&mut self, what: &str) -> Result<f64> {
+        loop {
+            let rest = &self.line[self.pos..];
+            if let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+                let tail = &rest[start..];
+                let token = &tail[..tail.find(char::is_whitespace).unwrap_or(tail.len())];
+                self.pos += start + token.len();
+                return token
+                    .parse::<f64>()
+                    .with_context(|| format!("invalid {} value '{}'", what, token));
+            }
+            self.pos = 0;
+            if !next_line(self.reader, &mut self.line)? {
+                bail!("unexpected end of {} data", what);
+            }
+        }
+    }
+}
+
+/// Longest PLY/PCD header accepted, so a file without the end marker is not read whole.
+const MAX_HEADER_BYTES: usize = 64 * 1024;
+
+/// Read header lines up to and including the first line starting with `marker`.
+fn read_header(reader: &mut dyn BufRead, marker: &str) -> Result<String> {
+    let mut header = String::new();
+    let mut line = String::new();
+    loop {
+        if !next_line(reader, &mut line).context("header is not valid text")?
+            || header.len() > MAX_HEADER_BYTES
+        {
+            bail!("missing '{}' line in header", marker);
+        }
+        header.push_str(&line);
+        if line.split_whitespace().next() == Some(marker) {
+            return Ok(header);
+        }
+    }
+}
+
+// ============================================================================
+// PLY
+// ============================================================================
+
+#[derive(Debug)]
+enum PlyProperty {
+    Scalar(String, ScalarType),
+    List(ScalarType, ScalarType),
+}
+
+#[derive(Debug)]
+struct PlyElement {
+    name: String,
+    count: usize,
+    properties: Vec<PlyProperty>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlyEncoding {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+/// Read the `vertex` element x/y/z of a PLY file (ASCII or binary).
+pub fn read_ply(bytes: &[u8]) -> Result<ImportedPoints> {
+    let mut out = ImportedPoints::default();
+    scan_ply(&mut &bytes[..], &mut out)?;
+    Ok(out)
+}
+
+/// Stream the `vertex` element x/y/z of a PLY file into `sink`.
+pub fn scan_ply(reader: &mut dyn BufRead, sink: &mut dyn ReadSink) -> Result<()> {
+    let mut magic = [0u8; 3];
+    ensure!(
+        reader.read_exact(&mut magic).is_ok() && &magic == b"ply",
+        "not a PLY file (missing 'ply' magic)"
+    );
+    let header = read_header(reader, "end_header")?;
+
+    let mut encoding = None;
+    let mut elements: Vec<PlyElement> = Vec::new();
+    for line in header.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["format", fmt, _version] => {
+                encoding = Some(match *fmt {
+                    "ascii" => PlyEncoding::Ascii,
+                    "binary_little_endian" => PlyEncoding::BinaryLittleEndian,
+                    "binary_big_endian" => PlyEncoding::BinaryBigEndian,
+                    _ => bail!("unknown PLY format '{}'", fmt),
+                })
+            }
+            ["element", name, count] => elements.push(PlyElement {
+                name: name.to_string(),
+                count: count
+                    .parse()
+                    .with_context(|| format!("invalid PLY element count '{}'", count))?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count_ty, item_ty, _name] => elements
+                .last_mut()
+                .ok_or_else(|| anyhow!("PLY property before any element"))?
+                .properties
+                .push(PlyProperty::List(
+                    ScalarType::from_ply(count_ty)?,
+                    ScalarType::from_ply(item_ty)?,
+                )),
+            ["property", ty, name] => elements
+                .last_mut()
+                .ok_or_else(|| anyhow!("PLY property before any element"))?
+                .properties
+                .push(PlyProperty::Scalar(
+                    name.to_string(),
+                    ScalarType::from_ply(ty)?,
+                )),
+            _ => {}
+        }
+    }
+    let encoding = encoding.ok_or_else(|| anyhow!("PLY header has no format line"))?;
+
+    let vertex_idx = elements
+        .iter()
+        .position(|e| e.name == "vertex")
+        .ok_or_else(|| anyhow!("PLY file has no vertex element"))?;
+    let axis = |axis: &str| {
+        elements[vertex_idx]
+            .properties
+            .iter()
+            .position(|p| matches!(p, PlyProperty::Scalar(n, _) if n == axis))
+            .ok_or_else(|| anyhow!("PLY vertex element has no '{}' property", axis))
+    };
+    let xyz_idx = [axis("x")?, axis("y")?, axis("z")?];
+    sink.expect(elements[vertex_idx].count)?;
+
+    let mut row = vec![0.0f64; elements[vertex_idx].properties.len()];
+
+    if encoding == PlyEncoding::Ascii {
+        let mut tokens = AsciiTokens {
+            reader,
+            line: String::new(),
+            pos: 0,
+        };
+        for element in &elements[..=vertex_idx] {
+            let is_vertex = element.name == "vertex";
+            for _ in 0..element.count {
+                for (i, prop) in element.properties.iter().enumerate() {
+                    match prop {
+                        PlyProperty::Scalar(..) => {
+                            let v = tokens.next("PLY")?;
+                            if is_vertex {
+                                row[i] = v;
+                            }
+                        }
+                        PlyProperty::List(..) => {
+                            for _ in 0..tokens.next("PLY")? as usize {
+                                tokens.next("PLY")?;
+                            }
+                        }
+                    }
+                }
+                if is_vertex {
+                    push_point(sink, [row[xyz_idx[0]], row[xyz_idx[1]], row[xyz_idx[2]]])?;
+                }
+            }
+        }
+    } else {
+        let mut cursor = ByteCursor {
+            reader,
+            buf: [0; 8],
+            big_endian: encoding == PlyEncoding::BinaryBigEndian,
+        };
+        for element in &elements[..=vertex_idx] {
+            let is_vertex = element.name == "vertex";
+            for _ in 0..element.count {
+                for (i, prop) in element.properties.iter().enumerate() {
+                    match prop {
+                        PlyProperty::Scalar(_, ty) => {
+                            let v = cursor.read(*ty)?;
+                            if is_vertex {
+                                row[i] = v;
+                            }
+                        }
+                        PlyProperty::List(count_ty, item_ty) => {
+                            for _ in 0..cursor.read(*count_ty)? as usize {
+                                cursor.read(*item_ty)?;
+                            }
+                        }
+                    }
+                }
+                if is_vertex {
+                    push_point(sink, [row[xyz_idx[0]], row[xyz_idx[1]], row[xyz_idx[2]]])?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// XYZ / PTS
+// ============================================================================
+
+/// Read XYZ or PTS text: first three columns per line, split on whitespace, `;` or commas.
+///
+/// Blank lines and `#` comments are skipped, as is a PTS leading point-count line. Rows
+/// without a `.` whose commas don't already split them into three numbers treat commas as
+/// decimal commas, so files written with the decimal-comma export dialect (`1,5 2,5 3,0`)
+/// read back while `1.5, 2.5, 3.0` and `1,2,3` keep splitting on commas.
+pub fn read_xyz(text: &str) -> Result<ImportedPoints> {
+    let mut out = ImportedPoints::default();
+    scan_xyz(&mut text.as_bytes(), &mut out)?;
+    Ok(out)
+}
+
+/// Stream XYZ or PTS text into `sink` (see [`read_xyz`]).
+pub fn scan_xyz(reader: &mut dyn BufRead, sink: &mut dyn ReadSink) -> Result<()> {
+    let mut line = String::new();
+    let mut first_row = true;
+    let mut line_no = 0;
+    while next_line(reader, &mut line)? {
+        line_no += 1;
+        let row = line.trim();
+        if row.is_empty() || row.starts_with('#') {
+            continue;
+        }
+        let cols = xyz_columns(row);
+        if std::mem::take(&mut first_row) && cols.len() == 1 && cols[0].parse::<u64>().is_ok() {
+            continue;
+        }
+        ensure!(
+            cols.len() >= 3,
+            "line {}: expected at least 3 columns, found {}",
+            line_no,
+            cols.len()
+        );
+        push_point(
+            sink,
+            [
+                parse_f64(&cols[0], line_no)?,
+                parse_f64(&cols[1], line_no)?,
+                parse_f64(&cols[2], line_no)?,
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Columns of an XYZ/PTS row. Whitespace and `;` always separate. Commas are decimal
+/// commas only when the row has no `.` and splitting on commas alone doesn't give three
+/// numeric columns; otherwise they separate too.
+fn xyz_columns(row: &str) -> Vec<String> {
+    let split = |seps: &[char]| -> Vec<&str> {
+        row.split(|c: char| c.is_whitespace() || seps.contains(&c))
+            .filter(|t| !t.is_empty())
+            .collect()
+    };
+    let comma_columns = row
+        .split(',')
+        .take_while(|t| t.trim().parse::<f64>().is_ok())
+        .count();
+    if row.contains(',') && !row.contains('.') && comma_columns < 3 {
+        split(&[';']).iter().map(|c| c.replace(',', ".")).collect()
+    } else {
+        split(&[';', ',']).into_iter().map(String::from).collect()
+    }
+}
+
+// ============================================================================
+// CSV
+// ============================================================================
+
+/// Read CSV: `#` comment lines skipped; a non-numeric first row is a header whose
+/// `x`, `y`, `z` columns (case-insensitive) are used, otherwise the first three columns.
+///
+/// The delimiter is taken from the first row: `;` or tab when present (then commas in
+/// fields without a `.` are decimal commas, as the European export dialect writes),
+/// otherwise `,`.
+pub fn read_csv(text: &str) -> Result<ImportedPoints> {
+    let mut out = ImportedPoints::default();
+    scan_csv(&mut text.as_bytes(), &mut out)?;
+    Ok(out)
+}
+
+/// Stream CSV text into `sink` (see [`read_csv`]).
+pub fn scan_csv(reader: &mut dyn BufRead, sink: &mut dyn ReadSink) -> Result<()> {
+    let mut columns: Option<[usize; 3]> = None;
+    let mut delimiter: Option<char> = None;
+    let mut line = String::new();
+    let mut line_no = 0;
+    while next_line(reader, &mut line)? {
+        line_no += 1;
+        let row = line.trim();
+        if row.is_empty() || row.starts_with('#') {
+            continue;
+        }
+        let delimiter = *delimiter.get_or_insert_with(|| {
+            [';', '\t']
+                .into_iter()
+                .find(|&d| row.contains(d))
+                .unwrap_or(',')
+        });
+        let cols: Vec<String> = row
+            .split(delimiter)
+            .map(|c| {
+                let c = c.trim().trim_matches('"');
+                if delimiter == ',' || c.contains('.') {
+                    c.to_string()
+                } else {
+                    c.replace(',', ".")
+                }
+            })
+            .collect();
+        let idx = match columns {
+            Some(idx) => idx,
+            None if cols[0].parse::<f64>().is_err() => {
+                let find = |name: &str| {
+                    cols.iter()
+                        .position(|c| c.eq_ignore_ascii_case(name))
+                        .ok_or_else(|| anyhow!("CSV header has no '{}' column", name))
+                };
+                columns = Some([find("x")?, find("y")?, find("z")?]);
+                continue;
+            }
+            None => *columns.insert([0, 1, 2]),
+        };
+        let get = |c: usize| -> Result<f64> {
+            let token = cols
+                .get(c)
+                .ok_or_else(|| anyhow!("line {}: missing column {}", line_no, c + 1))?;
+            parse_f64(token, line_no)
+        };
+        push_point(sink, [get(idx[0])?, get(idx[1])?, get(idx[2])?])?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// PCD
+// ============================================================================
+
+/// Read a PCD v0.7 file (`DATA ascii` or `DATA binary`); `binary_compressed` is rejected.
+pub fn read_pcd(bytes: &[u8]) -> Result<ImportedPoints> {
+    let mut out = ImportedPoints::default();
+    scan_pcd(&mut &bytes[..], &mut out)?;
+    Ok(out)
+}
+
+/// Stream a PCD v0.7 file into `sink` (see [`read_pcd`]).
+pub fn scan_pcd(reader: &mut dyn BufRead, sink: &mut dyn ReadSink) -> Result<()> {
+    let header = read_header(reader, "DATA")?;
+
+    let mut fields: Vec<String> = Vec::new();
+    let mut sizes: Vec<usize> = Vec::new();
+    let mut types: Vec<String> = Vec::new();
+    let mut counts: Vec<usize> = Vec::new();
+    let mut points: Option<usize> = None;
+    let mut width_height = (None::<usize>, None::<usize>);
+    let mut data = "";
+
+    for line in header.lines() {
+        let mut tokens = line.split_whitespace();
+        let Some(key) = tokens.next() else { continue };
+        let rest: Vec<&str> = tokens.collect();
+        let nums = |v: &[&str]| -> Result<Vec<usize>> {
+            v.iter()
+                .map(|t| {
+                    t.parse()
+                        .with_context(|| format!("invalid PCD {} value '{}'", key, t))
+                })
+                .collect()
+        };
+        match key {
+            "FIELDS" => fields = rest.iter().map(|s| s.to_string()).collect(),
+            "SIZE" => sizes = nums(&rest)?,
+            "TYPE" => types = rest.iter().map(|s| s.to_string()).collect(),
+            "COUNT" => counts = nums(&rest)?,
+            "WIDTH" => width_height.0 = nums(&rest)?.first().copied(),
+            "HEIGHT" => width_height.1 = nums(&rest)?.first().copied(),
+            "POINTS" => points = nums(&rest)?.first().copied(),
+            "DATA" => data = rest.first().copied().unwrap_or(""),
+            _ => {}
+        }
+    }
+
+    ensure!(!fields.is_empty(), "PCD header has no FIELDS line");
+    if counts.is_empty() {
+        counts = vec![1; fields.len()];
+    }
+    ensure!(
+        sizes.len() == fields.len() && types.len() == fields.len() && counts.len() == fields.len(),
+        "PCD FIELDS/SIZE/TYPE/COUNT lengths differ"
+    );
+    let n = match (points, width_height) {
+        (Some(n), _) => n,
+        (None, (Some(w), Some(h))) => w * h,
+        _ => bail!("PCD header has no POINTS or WIDTH/HEIGHT"),
+    };
+    sink.expect(n)?;
+
+    // Column offset of each field's first value (fields may have COUNT > 1).
+    let mut first_col = Vec::with_capacity(fields.len());
+    let mut total_cols = 0;
+    for c in &counts {
+        first_col.push(total_cols);
+        total_cols += c;
+    }
+    let field_idx = |axis: &str| {
+        fields
+            .iter()
+            .position(|f| f == axis)
+            .ok_or_else(|| anyhow!("PCD file has no '{}' field", axis))
+    };
+    let xyz_fields = [field_idx("x")?, field_idx("y")?, field_idx("z")?];
+
+    match data {
+        "ascii" => {
+            let mut line = String::new();
+            let mut i = 0;
+            while i < n {
+                ensure!(
+                    next_line(reader, &mut line)?,
+                    "PCD data ends after {} of {} points",
+                    i,
+                    n
+                );
+                let row = line.trim();
+                if row.is_empty() {
+                    continue;
+                }
+                let cols: Vec<&str> = row.split_whitespace().collect();
+                ensure!(
+                    cols.len() >= total_cols,
+                    "PCD point {}: expected {} values",
+                    i,
+                    total_cols
+                );
+                // PCL writes "nan" for invalid points in organised clouds; f64 parses it.
+                let v = |f: usize| -> Result<f64> { parse_f64(cols[first_col[f]], i + 1) };
+                push_point(
+                    sink,
+                    [v(xyz_fields[0])?, v(xyz_fields[1])?, v(xyz_fields[2])?],
+                )?;
+                i += 1;
+            }
+        }
+        "binary" => {
+            let scalar: Vec<ScalarType> = types
+                .iter()
+                .zip(&sizes)
+                .map(|(t, &s)| ScalarType::from_pcd(t, s))
+                .collect::<Result<_>>()?;
+            let mut offsets = Vec::with_capacity(fields.len());
+            let mut stride = 0;
+            for (ty, c) in scalar.iter().zip(&counts) {
+                offsets.push(stride);
+                stride += ty.size() * c;
+            }
+            let mut row = vec![0u8; stride];
+            for i in 0..n {
+                reader.read_exact(&mut row).map_err(|e| match e.kind() {
+                    ErrorKind::UnexpectedEof => {
+                        anyhow!("PCD binary data is truncated after {} of {} points", i, n)
+                    }
+                    _ => e.into(),
+                })?;
+                let v = |f: usize| scalar[f].decode(&row[offsets[f]..], false);
+                push_point(sink, [v(xyz_fields[0]), v(xyz_fields[1]), v(xyz_fields[2])])?;
+            }
+        }
+        "binary_compressed" => bail!("PCD binary_compressed data is not supported"),
+        other => bail!("unknown PCD DATA type '{}'", other),
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::{self, ExportMetadata};
+    use crate::volumetric::PointAttributes;
+
+    fn sample_points() -> Vec<[f32; 3]> {
+        vec![[0.0, 0.0, 0.0], [10.0, 5.0, 3.0], [5.5, 2.5, 1.5]]
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(
+            ImportFormat::from_path(Path::new("scan.PLY")),
+            Some(ImportFormat::Ply)
+        );
+        assert_eq!(
+            ImportFormat::from_path(Path::new("a/b.pcd")),
+            Some(ImportFormat::Pcd)
+        );
+        assert_eq!(ImportFormat::from_path(Path::new("model.stl")), None);
+    }
+
+    #[test]
+    fn ply_ascii_and_binary_roundtrip_exporter() {
+        let points = sample_points();
+        let attributes = PointAttributes {
+            rgb: Some(vec![[1, 2, 3]; 3]),
+            ..Default::default()
+        };
+        let metadata = ExportMetadata::new();
+
+        let mut ascii = Vec::new();
+        export::write_ply_ascii(&mut ascii, &points, &attributes, &metadata).unwrap();
+        assert_eq!(read_ply(&ascii).unwrap().points, points);
+
+        let mut binary = Vec::new();
+        export::write_ply_binary(&mut binary, &points, &attributes, &metadata).unwrap();
+        assert_eq!(read_ply(&binary).unwrap().points, points);
+    }
+
+    #[test]
+    fn ply_big_endian_with_faces_and_doubles() {
+        let mut bytes = b"ply\nformat binary_big_endian 1.0\nelement vertex 2\n\
+property double x\nproperty double y\nproperty double z\n\
+element face 1\nproperty list uchar int vertex_indices\nend_header\n"
+            .to_vec();
+        for v in [1.0f64, 2.0, 3.0, -4.0, 5.5, 6.0] {
+            bytes.extend_from_slice(&v.to_be_bytes());
+        }
+        bytes.push(3);
+        for i in [0i32, 1, 0] {
+            bytes.extend_from_slice(&i.to_be_bytes());
+        }
+        let imported = read_ply(&bytes).unwrap();
+        assert_eq!(imported.points, vec![[1.0, 2.0, 3.0], [-4.0, 5.5, 6.0]]);
+    }
+
+    #[test]
+    fn ply_rejects_truncated_binary() {
+        let bytes = b"ply\nformat binary_little_endian 1.0\nelement vertex 2\n\
+property float x\nproperty float y\nproperty float z\nend_header\n\0\0\0\0";
+        assert!(read_ply(bytes).is_err());
+    }
+
+    #[test]
+    fn xyz_csv_pts_roundtrip_exporter() {
+        let points = sample_points();
+        let metadata = ExportMetadata::new();
+
+        let mut xyz = Vec::new();
+        export::write_xyz(&mut xyz, &points).unwrap();
+        assert_eq!(read_points(&xyz, ImportFormat::Xyz).unwrap().points, points);
+
+        let mut csv = Vec::new();
+        export::write_csv(&mut csv, &points, &metadata).unwrap();
+        assert_eq!(read_points(&csv, ImportFormat::Csv).unwrap().points, points);
+
+        let mut pts = Vec::new();
+        export::write_pts(&mut pts, &points).unwrap();
+        assert_eq!(read_points(&pts, ImportFormat::Pts).unwrap().points, points);
+    }
+
+    #[test]
+    fn summary_reads_count_and_bounds_from_a_stream() {
+        let mut summary = ReadSummary::default();
+        let text = "# comment\n1 5 -2\nnan 0 0\n-3 2 4\n";
+        scan_points(&mut text.as_bytes(), ImportFormat::Xyz, &mut summary).unwrap();
+        assert_eq!(summary.count, 2);
+        assert_eq!(summary.skipped, 1);
+        assert_eq!(summary.bounds, Some(([-3.0, 2.0, -2.0], [1.0, 5.0, 4.0])));
+    }
+
+    #[test]
+    fn decimal_comma_dialect_exports_import_again() {
+        use crate::export::dialect::{DecimalSeparator, Delimiter, TextDialect};
+        let points = sample_points();
+        for (mut dialect, format) in [
+            (TextDialect::xyz(), ImportFormat::Xyz),
+            (TextDialect::xyz(), ImportFormat::Pts),
+            (TextDialect::csv(), ImportFormat::Csv),
+        ] {
+            dialect.decimal_separator = DecimalSeparator::Comma;
+            if dialect.delimiter == Delimiter::Comma {
+                dialect.delimiter = Delimiter::Semicolon;
+            }
+            let mut out = Vec::new();
+            if dialect.header {
+                dialect.write_column_names(&mut out).unwrap();
+            }
+            for (i, p) in points.iter().enumerate() {
+                dialect.write_row(&mut out, i as u64, *p).unwrap();
+            }
+            let text = String::from_utf8(out).unwrap();
+            assert!(text.contains("5,5"), "{}", text);
+            let imported = read_points(text.as_bytes(), format).unwrap();
+            assert_eq!(imported.points, points, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn comma_space_separated_rows_still_import() {
+        let expected = vec![[1.5, 2.5, 3.0], [-4.0, 5.25, 6.0]];
+        let xyz = read_xyz("1.5, 2.5, 3.0\n-4, 5.25, 6\n").unwrap();
+        assert_eq!(xyz.points, expected);
+        let csv = read_csv("x, y, z\n1.5, 2.5, 3.0\n-4, 5.25, 6\n").unwrap();
+        assert_eq!(csv.points, expected);
+        assert_eq!(
+            read_xyz("1,2,3\n1,5 2,5 3,0\n").unwrap().points,
+            vec![[1.0, 2.0, 3.0], [1.5, 2.5, 3.0]]
+        );
+    }
+
+    #[test]
+    fn csv_header_selects_named_columns() {
+        let csv = "id,Z,Y,X\n1,3,2,1\n2,6,5,4\n";
+        let imported = read_csv(csv).unwrap();
+        assert_eq!(imported.points, vec![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    }
+
+    #[test]
+    fn xyz_reports_bad_line() {
+        let err = read_xyz("1 2 3\n4 five 6\n").unwrap_err().to_string();
+        assert!(err.contains("line 2"), "{}", err);
+    }
+
+    #[test]
+    fn pcd_ascii_skips_nan_points() {
+        let pcd = "# .PCD v0.7\nVERSION 0.7\nFIELDS x y z rgb\nSIZE 4 4 4 4\nTYPE F F F U\n\
+COUNT 1 1 1 1\nWIDTH 3\nHEIGHT 1\nVIEWPOINT 0 0 0 1 0 0 0\nPOINTS 3\nDATA ascii\n\
+1 2 3 0\nnan nan nan 0\n4 5 6 0\n";
+        let imported = read_pcd(pcd.as_bytes()).unwrap();
+        assert_eq!(imported.points, vec![[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        assert_eq!(imported.skipped, 1);
+    }
+
+    #[test]
+    fn pcd_binary_with_extra_fields() {
+        let mut bytes = b"VERSION 0.7\nFIELDS intensity x y z\nSIZE 2 4 4 4\nTYPE U F F F\n\
+COUNT 1 1 1 1\nWIDTH 2\nHEIGHT 1\nPOINTS 2\nDATA binary\n"
+            .to_vec();
+        for (i, p) in [[1.0f32, 2.0, 3.0], [7.0, 8.0, 9.0]].iter().enumerate() {
+            bytes.extend_from_slice(&(i as u16).to_le_bytes());
+            for v in p {
+                bytes.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        let imported = read_pcd(&bytes).unwrap();
+        assert_eq!(imported.points, vec![[1.0, 2.0, 3.0], [7.0, 8.0, 9.0]]);
+    }
+
+    #[test]
+    fn pcd_rejects_compressed() {
+        let pcd = "FIELDS x y z\nSIZE 4 4 4\nTYPE F F F\nPOINTS 1\nDATA binary_compressed\n";
+        assert!(read_pcd(pcd.as_bytes()).is_err());
+    }
+}
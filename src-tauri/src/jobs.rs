@@ -0,0 +1,389 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Background jobs for long point cloud operations (generation, export, slicing).
+//!
+//! A command registers a job with the [`JobRegistry`] in `AppState`, returns its id at once
+//! and runs the work on a worker thread. The worker reports through a [`JobContext`]:
+//! progress goes to a reporter (the `job-progress` Tauri event in the app), and a
+//! cancellation requested through `cancel_job` is observed at the next check. Progress is
+//! reported per [`JobStage`]; workers check for cancellation between stages.
+//! [`ProgressSink`] does both for streamed clouds, from the planning pass over the depth map
+//! to the last point, so cancelling an export stops the writer mid-stream; the atomic export
+//! then removes its partial temporary file.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use crate::volumetric::{PointRecord, PointSink, StreamPlan};
+
+/// Identifier returned to the frontend; unique for the app session.
+pub type JobId = u64;
+
+/// Error message of a job that stopped because it was cancelled.
+pub const JOB_CANCELLED: &str = "Job cancelled";
+
+/// Points pushed between cancellation checks in [`ProgressSink`].
+const CANCEL_CHECK_INTERVAL: usize = 4096;
+
+/// What a job does (echoed in its events).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobKind {
+    GeneratePointCloud,
+    Export,
+    /// Layer folder export (slice stack or SVG layers).
+    LayerFolder,
+    /// Regenerating a cloud from an export recipe.
+    ReplayRecipe,
+}
+
+/// Which part of a job a progress report covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStage {
+    /// Applying the depth adjustments and scanning the depth map; units are rows.
+    Preparing,
+    /// Generating or streaming points; units are points.
+    Points,
+    /// Writing a collected cloud or layer files; units are points or layers.
+    Writing,
+}
+
+/// Payload for the "job-progress" Tauri event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobProgress {
+    pub job_id: JobId,
+    pub kind: JobKind,
+    pub stage: JobStage,
+    /// Progress of `stage`.
+    pub percent: u8,
+    /// Units of `stage` processed so far out of `total`.
+    pub processed: usize,
+    pub total: usize,
+}
+
+/// How a job ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum JobStatus {
+    Completed,
+    Cancelled,
+    Failed,
+}
+
+/// Payload for the "job-finished" Tauri event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JobFinished {
+    pub job_id: JobId,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    /// Command result on success (same shape the synchronous command returned).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl JobFinished {
+    /// Classify a job's outcome; an error after cancellation counts as cancelled.
+    pub fn from_outcome<T: Serialize>(job: &JobContext, outcome: Result<T, String>) -> Self {
+        let (status, result, error) = match outcome {
+            Ok(value) => match serde_json::to_value(value) {
+                Ok(value) => (JobStatus::Completed, Some(value), None),
+                Err(e) => (JobStatus::Failed, None, Some(e.to_string())),
+            },
+            Err(_) if job.is_cancelled() => {
+                (JobStatus::Cancelled, None, Some(JOB_CANCELLED.to_string()))
+            }
+            Err(e) => (JobStatus::Failed, None, Some(e)),
+        };
+        Self {
+            job_id: job.id,
+            kind: job.kind,
+            status,
+            result,
+            error,
+        }
+    }
+}
+
+/// Receives a job's progress reports.
+pub type ProgressReporter = Arc<dyn Fn(&JobProgress) + Send + Sync>;
+
+/// Running jobs and their cancellation flags.
+#[derive(Default)]
+pub struct JobRegistry {
+    next_id: AtomicU64,
+    running: Mutex<HashMap<JobId, Arc<AtomicBool>>>,
+}
+
+impl JobRegistry {
+    /// Register a new job; progress reports go to `reporter`.
+    pub fn start(&self, kind: JobKind, reporter: ProgressReporter) -> JobContext {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if let Ok(mut running) = self.running.lock() {
+            running.insert(id, cancelled.clone());
+        }
+        JobContext {
+            id,
+            kind,
+            cancelled,
+            reporter,
+        }
+    }
+
+    /// Request cancellation. Returns `false` if the job is unknown or already finished.
+    pub fn cancel(&self, id: JobId) -> bool {
+        let Ok(running) = self.running.lock() else {
+            return false;
+        };
+        match running.get(&id) {
+            Some(flag) => {
+                flag.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a finished job.
+    pub fn finish(&self, id: JobId) {
+        if let Ok(mut running) = self.running.lock() {
+            running.remove(&id);
+        }
+    }
+
+    /// Ids of jobs that have not finished yet.
+    pub fn running(&self) -> Vec<JobId> {
+        let mut ids: Vec<JobId> = self
+            .running
+            .lock()
+            .map(|r| r.keys().copied().collect())
+            .unwrap_or_default();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+/// A job's handle on its worker thread.
+pub struct JobContext {
+    pub id: JobId,
+    pub kind: JobKind,
+    cancelled: Arc<AtomicBool>,
+    reporter: ProgressReporter,
+}
+
+impl JobContext {
+    /// A context not tracked by any registry (synchronous callers, tests).
+    pub fn detached(kind: JobKind) -> Self {
+        Self {
+            id: 0,
+            kind,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            reporter: Arc::new(|_| {}),
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// `Err(JOB_CANCELLED)` once cancellation was requested.
+    pub fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(JOB_CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn report(&self, stage: JobStage, processed: usize, total: usize) {
+        let percent = (processed.min(total) * 100)
+            .checked_div(total)
+            .unwrap_or(100) as u8;
+        (self.reporter)(&JobProgress {
+            job_id: self.id,
+            kind: self.kind,
+            stage,
+            percent,
+            processed,
+            total,
+        });
+    }
+}
+
+/// [`PointSink`] adapter that reports progress per whole percent and stops on cancellation:
+/// per row while the stream is planned, per point while it runs, and once all points are in
+/// (before a collecting sink writes them).
+pub struct ProgressSink<'a> {
+    inner: &'a mut dyn PointSink,
+    job: &'a JobContext,
+    total: usize,
+    pushed: usize,
+    last_percent: usize,
+}
+
+impl<'a> ProgressSink<'a> {
+    pub fn new(inner: &'a mut dyn PointSink, job: &'a JobContext) -> Self {
+        Self {
+            inner,
+            job,
+            total: 0,
+            pushed: 0,
+            last_percent: 0,
+        }
+    }
+}
+
+impl PointSink for ProgressSink<'_> {
+    fn scan_progress(&mut self, done: usize, total: usize) -> Result<(), String> {
+        self.job.check_cancelled()?;
+        let percent = done * 100 / total.max(1);
+        if done == 0 || percent > self.last_percent {
+            self.last_percent = percent;
+            self.job.report(JobStage::Preparing, done, total);
+        }
+        self.inner.scan_progress(done, total)
+    }
+
+    fn begin(&mut self, plan: &StreamPlan) -> Result<(), String> {
+        self.job.check_cancelled()?;
+        self.total = plan.point_count;
+        self.last_percent = 0;
+        self.job.report(JobStage::Points, 0, self.total);
+        self.inner.begin(plan)
+    }
+
+    fn push(&mut self, record: &PointRecord) -> Result<(), String> {
+        self.inner.push(record)?;
+        self.pushed += 1;
+        if self.pushed.is_multiple_of(CANCEL_CHECK_INTERVAL) || self.pushed == self.total {
+            self.job.check_cancelled()?;
+            let percent = self.pushed * 100 / self.total.max(1);
+            if percent > self.last_percent {
+                self.last_percent = percent;
+                self.job.report(JobStage::Points, self.pushed, self.total);
+            }
+        }
+        Ok(())
+    }
+
+    fn end(&mut self) -> Result<(), String> {
+        self.job.check_cancelled()?;
+        self.job.report(JobStage::Writing, 0, self.total);
+        self.inner.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blank_envelope::BlankEnvelope;
+    use crate::volumetric::{
+        stream_volumetric_points, PointAttributeOptions, PointCollector, VolumetricParams,
+    };
+
+    fn plan(point_count: usize) -> StreamPlan {
+        StreamPlan {
+            point_count,
+            scale: 1.0,
+            translation: [0.0; 3],
+            bounds: ([0.0; 3], [1.0; 3]),
+            channels: PointAttributeOptions::default(),
+        }
+    }
+
+    fn record() -> PointRecord {
+        PointRecord {
+            position: [0.5; 3],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn progress_sink_reports_whole_percents() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink_reports = reports.clone();
+        let registry = JobRegistry::default();
+        let job = registry.start(
+            JobKind::GeneratePointCloud,
+            Arc::new(move |p: &JobProgress| sink_reports.lock().unwrap().push(p.percent)),
+        );
+        let mut collector = PointCollector::default();
+        let mut sink = ProgressSink::new(&mut collector, &job);
+        let total = CANCEL_CHECK_INTERVAL * 4;
+        sink.begin(&plan(total)).unwrap();
+        for _ in 0..total {
+            sink.push(&record()).unwrap();
+        }
+        sink.end().unwrap();
+        assert_eq!(collector.points.len(), total);
+        assert_eq!(*reports.lock().unwrap(), vec![0, 25, 50, 75, 100, 0]);
+        assert_eq!(registry.running(), vec![job.id]);
+        registry.finish(job.id);
+        assert!(registry.running().is_empty());
+    }
+
+    #[test]
+    fn planning_pass_reports_progress_and_observes_cancellation() {
+        let stages = Arc::new(Mutex::new(Vec::new()));
+        let sink_stages = stages.clone();
+        let registry = JobRegistry::default();
+        let job = registry.start(
+            JobKind::Export,
+            Arc::new(move |p: &JobProgress| sink_stages.lock().unwrap().push(p.stage)),
+        );
+        let depth = vec![0.5f32; 64 * 64];
+        let generate = |collector: &mut PointCollector| {
+            stream_volumetric_points(
+                &depth,
+                64,
+                64,
+                &VolumetricParams::default(),
+                &BlankEnvelope::default(),
+                &PointAttributeOptions::default(),
+                None,
+                None,
+                &mut ProgressSink::new(collector, &job),
+            )
+        };
+        generate(&mut PointCollector::default()).unwrap();
+        let stages = stages.lock().unwrap().clone();
+        assert_eq!(stages.first(), Some(&JobStage::Preparing));
+        assert!(stages.contains(&JobStage::Points));
+        assert_eq!(stages.last(), Some(&JobStage::Writing));
+
+        registry.cancel(job.id);
+        let mut collector = PointCollector::default();
+        assert_eq!(generate(&mut collector).unwrap_err(), JOB_CANCELLED);
+        assert!(collector.points.is_empty());
+    }
+
+    #[test]
+    fn cancelled_job_stops_sink_and_is_classified() {
+        let registry = JobRegistry::default();
+        let job = registry.start(JobKind::Export, Arc::new(|_| {}));
+        let mut collector = PointCollector::default();
+        let mut sink = ProgressSink::new(&mut collector, &job);
+        let total = CANCEL_CHECK_INTERVAL * 3;
+        sink.begin(&plan(total)).unwrap();
+        assert!(registry.cancel(job.id));
+        let pushed = (0..total)
+            .take_while(|_| sink.push(&record()).is_ok())
+            .count();
+        assert_eq!(pushed, CANCEL_CHECK_INTERVAL - 1);
+        assert!(sink.scan_progress(0, 10).is_err());
+        assert!(sink.end().is_err());
+
+        let finished = JobFinished::from_outcome::<()>(&job, Err(JOB_CANCELLED.to_string()));
+        assert_eq!(finished.status, JobStatus::Cancelled);
+        registry.finish(job.id);
+        assert!(!registry.cancel(job.id));
+    }
+}
@@ -6,46 +6,66 @@
 //! Public API surface: Tauri commands registered in `run()` (load_image, generate_depth_map,
 //! get_depth_map, set_depth_adjustment_params, etc.). The 2.5D mesh / STL / OBJ surface has been
 //! retired (Sprint A). ADR-012 point cloud commands are registered: `set_blank_envelope`,
-//! `set_volumetric_params`, `generate_point_cloud`, `export_ply`, `export_xyz`, `export_csv`, etc.
+//! `set_volumetric_params`, `generate_point_cloud`, `import_point_cloud`, `import_mesh`,
+//! `list_export_formats`, `export_point_cloud` (every format in the export registry, plus a
+//! reproducibility sidecar), `replay_recipe`, etc. Point cloud generation and export run as
+//! background jobs (`job-progress` / `job-finished` events, `cancel_job`).
 //!
 //! See `docs/developer-guide.md` and `cargo doc` for command contracts and types.
 
+pub mod bas_relief;
 pub mod blank_envelope;
 pub mod depth_adjust;
+pub mod depth_filter;
+pub mod depth_inpaint;
+pub mod depth_levels;
+pub mod depth_terrace;
 pub mod export;
 mod file_io;
 mod image_loading;
+pub mod import;
+pub mod jobs;
 pub mod mask;
+pub mod mesh_import;
 pub mod preset;
 mod python_bridge;
+pub mod recipe;
+pub mod sculpt;
 pub mod settings;
 pub mod undo;
 pub mod volumetric;
 
 use std::path::Path;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use tauri::Emitter;
+use tauri::Manager;
 use tauri::State;
 
 use blank_envelope::BlankEnvelope;
 use depth_adjust::{apply_adjustments, compute_histogram, DepthAdjustmentParams};
-use export::ExportMetadata;
+use export::{ExportFormat, ExportMetadata, ExportOptions};
+use mesh_import::MeshSampling;
 use preset::{get_builtin_preset, sanitize_preset_name, Preset};
-use undo::{SetDepthParamsCommand, SetMaskCommand, UndoRedoHistory, UndoableCommand};
-use volumetric::{validate_volumetric_params, VolumetricParams, VolumetricResult};
+use undo::{SetDepthParamsCommand, SetMaskCommand, UndoRedoHistory, UndoTarget, UndoableCommand};
+use volumetric::{
+    validate_volumetric_params, PointAttributeOptions, VolumetricParams, VolumetricResult,
+};
 
 /// BACK-1202, BACK-1203: Apply depth adjustments with optional mask and feathering.
 /// When mask is None or dimensions don't match, returns full apply_adjustments(original, params).
 /// When mask is Some, adjusted depth is blended: weight = soft_mask (feather at edges), out = weight*adjusted + (1-weight)*original.
+/// `source` is the source image at depth resolution, read by the guided filter and detail
+/// transfer. The whole operation stack is masked and feathered together.
 pub(crate) fn apply_adjustments_with_mask(
     original: &[f32],
     width: u32,
     height: u32,
     params: &DepthAdjustmentParams,
     mask: Option<&mask::MaskBitmap>,
+    source: Option<&image::RgbImage>,
 ) -> Vec<f32> {
-    let adjusted = apply_adjustments(original, params);
+    let adjusted = apply_adjustments(original, width, height, params, source, mask);
     let mask = match mask {
         Some(m) if m.dimensions_match(width, height) => m,
         _ => return adjusted,
@@ -65,10 +85,11 @@ pub(crate) fn apply_adjustments_with_mask(
 // anyhow::Error via .map_err(|e| e.to_string()) at the boundary.
 
 /// SEC-401/SEC-402: Validate export path (canonicalize, extension, block system dirs, writable).
+/// `extensions` lists the accepted extensions (from the export registry for point clouds).
 /// Returns (canonical PathBuf, path as String) for use in export commands.
 fn validate_export_path(
     path: &str,
-    extension: &str,
+    extensions: &[&str],
 ) -> Result<(std::path::PathBuf, String), String> {
     if path.trim().is_empty() {
         return Err("Export path must be non-empty".to_string());
@@ -85,10 +106,24 @@ fn validate_export_path(
     let canonical_path = canonical.join(file_name);
 
     match canonical_path.extension().and_then(|e| e.to_str()) {
-        Some(ext) if ext.eq_ignore_ascii_case(extension) => {}
-        _ => return Err(format!("Export file must have .{} extension", extension)),
+        Some(ext) if extensions.iter().any(|e| ext.eq_ignore_ascii_case(e)) => {}
+        _ => {
+            return Err(format!(
+                "Export file must have .{} extension",
+                extensions.join(" or .")
+            ))
+        }
     }
 
+    validate_export_dir(&canonical)?;
+
+    let canonical_path_str = canonical_path.to_string_lossy().to_string();
+    Ok((canonical_path, canonical_path_str))
+}
+
+/// SEC-401/SEC-402: Reject system directories and directories that are not writable.
+/// `canonical` is the already canonicalized export directory.
+fn validate_export_dir(canonical: &Path) -> Result<(), String> {
     let canonical_str = canonical.to_string_lossy();
     #[cfg(target_os = "windows")]
     {
@@ -125,22 +160,38 @@ fn validate_export_path(
         }
     }
 
-    let parent = canonical_path.parent().unwrap_or(&canonical);
-    let test_file = parent.join(".sp3d_write_test");
+    let test_file = canonical.join(".sp3d_write_test");
     match std::fs::File::create(&test_file) {
         Ok(_) => {
             let _ = std::fs::remove_file(&test_file);
         }
         Err(_) => return Err("Export directory is not writable".to_string()),
     }
+    Ok(())
+}
 
-    let canonical_path_str = canonical_path.to_string_lossy().to_string();
-    Ok((canonical_path, canonical_path_str))
+/// Validate a folder export destination (Z-slice stacks): the parent must exist and pass
+/// [`validate_export_dir`]; the folder itself is created by the export.
+fn validate_export_folder_path(path: &str) -> Result<std::path::PathBuf, String> {
+    if path.trim().is_empty() {
+        return Err("Export path must be non-empty".to_string());
+    }
+    let path = Path::new(path);
+    let parent = std::fs::canonicalize(
+        path.parent()
+            .ok_or_else(|| "Invalid export path: no parent directory".to_string())?,
+    )
+    .map_err(|_| "Export directory does not exist or is not accessible".to_string())?;
+    let name = path
+        .file_name()
+        .ok_or_else(|| "Invalid export path: no folder name".to_string())?;
+    validate_export_dir(&parent)?;
+    Ok(parent.join(name))
 }
 
 /// Validates path for saving a preset JSON file (export). Same security as validate_export_path but for .json.
 fn validate_preset_export_path(path: &str) -> Result<(std::path::PathBuf, String), String> {
-    validate_export_path(path, "json")
+    validate_export_path(path, &["json"])
 }
 
 /// App-managed depth map state (BACK-302, BACK-405). Original depth from generate_depth_map;
@@ -152,6 +203,9 @@ struct AppState {
     adjustment_params: Mutex<DepthAdjustmentParams>,
     /// Mask for regional depth adjustments (BACK-1201, ARCH-502). Cleared when depth is replaced.
     mask: Mutex<Option<mask::MaskBitmap>>,
+    /// Sculpt offset layer added to the original depth before the adjustments. Cleared when
+    /// depth is replaced.
+    sculpt: Mutex<Option<sculpt::OffsetLayer>>,
     /// Path to the source image (for export metadata and PROGRESS context). Written by
     /// `generate_depth_map`; consumed by point cloud export commands.
     source_image_path: Mutex<Option<String>>,
@@ -160,7 +214,16 @@ struct AppState {
     /// Undo/redo history for depth and mask (BACK-1402, ARCH-502).
     undo_redo: Mutex<UndoRedoHistory>,
     /// Last successful [`generate_point_cloud`] result — used by export commands (ADR-012).
-    last_point_cloud: Mutex<Option<VolumetricResult>>,
+    /// Shared so export jobs can hold on to it without keeping the lock.
+    last_point_cloud: Mutex<Option<Arc<VolumetricResult>>>,
+    /// Path of the file loaded by [`import_point_cloud`] / [`import_mesh`]; `None` when
+    /// `last_point_cloud` came from the depth map. Imported clouds survive depth/sampling
+    /// changes and are re-fitted when the blank envelope changes.
+    point_cloud_source: Mutex<Option<String>>,
+    /// Depth model that produced `depth` (from the estimator's `MODEL` line), for export recipes.
+    depth_model_id: Mutex<Option<String>>,
+    /// Background generation/export jobs and their cancellation flags.
+    jobs: jobs::JobRegistry,
 }
 
 /// Payload for "depth-progress" Tauri event (BACK-205-STREAM, ARCH-501).
@@ -224,17 +287,86 @@ fn current_adjusted_depth(state: &AppState) -> Result<Option<(Vec<f32>, u32, u32
         .lock()
         .map_err(|e| e.to_string())?
         .clone();
+    let source = adjustment_source_from_state(state, &params, original.width, original.height)?;
+    let sculpted = sculpted_depth(state, &original)?;
     let mask_guard = state.mask.lock().map_err(|e| e.to_string())?;
     let adjusted = apply_adjustments_with_mask(
-        &original.depth,
+        &sculpted,
         original.width,
         original.height,
         &params,
         mask_guard.as_ref(),
+        source.as_ref(),
     );
     Ok(Some((adjusted, original.width, original.height)))
 }
 
+/// Original depth with the sculpt offset layer added (the input of the adjustment stack).
+fn sculpted_depth(
+    state: &AppState,
+    original: &python_bridge::DepthMapOutput,
+) -> Result<Vec<f32>, String> {
+    let guard = state.sculpt.lock().map_err(|e| e.to_string())?;
+    Ok(composite_sculpt(guard.as_ref(), original))
+}
+
+/// `original` with `layer` added (unchanged when there is no layer of its size).
+fn composite_sculpt(
+    layer: Option<&sculpt::OffsetLayer>,
+    original: &python_bridge::DepthMapOutput,
+) -> Vec<f32> {
+    match layer {
+        Some(layer) if layer.dimensions_match(original.width, original.height) => {
+            layer.composite(&original.depth)
+        }
+        _ => original.depth.clone(),
+    }
+}
+
+/// Source image at depth resolution when an adjustment stage reads it. A missing or unreadable
+/// source is not an error: the guided filter then uses the depth as its own guide and detail
+/// transfer is skipped.
+fn adjustment_source_from_state(
+    state: &AppState,
+    params: &DepthAdjustmentParams,
+    width: u32,
+    height: u32,
+) -> Result<Option<image::RgbImage>, String> {
+    if !params.uses_source_image() {
+        return Ok(None);
+    }
+    let path = state
+        .source_image_path
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    Ok(load_adjustment_source(
+        path.as_deref(),
+        params,
+        width,
+        height,
+    ))
+}
+
+/// [`adjustment_source_from_state`] for an explicit source image path.
+fn load_adjustment_source(
+    path: Option<&str>,
+    params: &DepthAdjustmentParams,
+    width: u32,
+    height: u32,
+) -> Option<image::RgbImage> {
+    if !params.uses_source_image() {
+        return None;
+    }
+    match image_loading::load_rgb_for_depth(path?, width, height) {
+        Ok(rgb) => Some(rgb),
+        Err(e) => {
+            log::warn!("Source image unavailable for depth adjustments: {:#}", e);
+            None
+        }
+    }
+}
+
 fn resolved_blank_envelope(state: &AppState) -> Result<BlankEnvelope, String> {
     let guard = state.app_settings.lock().map_err(|e| e.to_string())?;
     let env = guard.blank_envelope.clone().unwrap_or_default();
@@ -249,27 +381,63 @@ fn resolved_volumetric_params(state: &AppState) -> Result<VolumetricParams, Stri
 }
 
 fn invalidate_point_cloud_cache(state: &AppState) -> Result<(), String> {
+    if state
+        .point_cloud_source
+        .lock()
+        .map_err(|e| e.to_string())?
+        .is_some()
+    {
+        return Ok(());
+    }
     *state.last_point_cloud.lock().map_err(|e| e.to_string())? = None;
     Ok(())
 }
 
-fn build_export_metadata(state: &AppState) -> Result<ExportMetadata, String> {
-    let mut metadata = ExportMetadata::new();
-    let settings = state.app_settings.lock().map_err(|e| e.to_string())?;
-    metadata.blank_envelope = Some(
-        settings
-            .blank_envelope
-            .clone()
-            .unwrap_or_else(BlankEnvelope::default),
-    );
-    drop(settings);
-    let path_guard = state.source_image_path.lock().map_err(|e| e.to_string())?;
-    if let Some(ref p) = *path_guard {
-        if let Some(name) = Path::new(p).file_name() {
-            metadata.source_image = Some(name.to_string_lossy().to_string());
-        }
+/// Re-fit an imported cloud into `envelope` (no-op for depth-derived clouds).
+fn refit_imported_point_cloud(state: &AppState, envelope: &BlankEnvelope) -> Result<(), String> {
+    if state
+        .point_cloud_source
+        .lock()
+        .map_err(|e| e.to_string())?
+        .is_none()
+    {
+        return Ok(());
+    }
+    let mut guard = state.last_point_cloud.lock().map_err(|e| e.to_string())?;
+    if let Some(cached) = guard.as_mut() {
+        let cached = Arc::make_mut(cached);
+        // Compose with the previous fit so scale/translation stay relative to the file units.
+        let refit = blank_envelope::fit_to_blank(&mut cached.points, envelope)?;
+        let prev = &cached.fit_result;
+        cached.fit_result = blank_envelope::FitResult {
+            scale: prev.scale * refit.scale,
+            translation: std::array::from_fn(|i| {
+                prev.translation[i] * refit.scale + refit.translation[i]
+            }),
+            ..refit
+        };
     }
-    Ok(metadata)
+    Ok(())
+}
+
+/// Keep the dialect of a plain-text export as that format's defaults (saved with the
+/// export directory).
+fn remember_text_dialect(
+    state: &AppState,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Result<(), String> {
+    if format.exporter().text_dialect().is_none() {
+        return Ok(());
+    }
+    let dialect = export::dialect::TextDialect::from_options(options).map_err(|e| e.to_string())?;
+    state
+        .app_settings
+        .lock()
+        .map_err(|e| e.to_string())?
+        .text_dialects
+        .insert(format.id().to_string(), dialect);
+    Ok(())
 }
 
 fn persist_last_export_dir(state: &AppState, export_path: &str) -> Result<(), String> {
@@ -282,13 +450,226 @@ fn persist_last_export_dir(state: &AppState, export_path: &str) -> Result<(), St
     settings.save().map_err(|e| e.to_string())
 }
 
-fn generate_point_cloud_from_state(state: &AppState) -> Result<VolumetricResult, String> {
-    let Some((depth_vec, width, height)) = current_adjusted_depth(state)? else {
-        return Err("No depth map loaded".to_string());
-    };
-    let envelope = resolved_blank_envelope(state)?;
-    let params = resolved_volumetric_params(state)?;
-    volumetric::generate_volumetric_points(&depth_vec, width, height, &params, &envelope)
+fn resolved_point_attributes(state: &AppState) -> Result<PointAttributeOptions, String> {
+    let guard = state.app_settings.lock().map_err(|e| e.to_string())?;
+    Ok(guard.point_attributes.clone().unwrap_or_default())
+}
+
+/// Everything surface-map generation reads (adjusted depth, settings, source colours and,
+/// when recorded, the raw estimator depth).
+struct SurfaceInputs {
+    depth: Vec<f32>,
+    width: u32,
+    height: u32,
+    params: VolumetricParams,
+    envelope: BlankEnvelope,
+    options: PointAttributeOptions,
+    source_rgb: Option<image::RgbImage>,
+    raw_depth: Option<Vec<f32>>,
+}
+
+/// Depth-map inputs of cloud generation, copied out of state in one go so a job (and the
+/// recipe it writes) sees one consistent set even while the user keeps editing.
+struct DepthSnapshot {
+    original: python_bridge::DepthMapOutput,
+    source_image_path: Option<String>,
+    depth_model_id: Option<String>,
+    adjustment_params: DepthAdjustmentParams,
+    mask: Option<mask::MaskBitmap>,
+    sculpt: Option<sculpt::OffsetLayer>,
+    volumetric_params: VolumetricParams,
+    point_attributes: PointAttributeOptions,
+}
+
+impl DepthSnapshot {
+    /// `None` when no depth map is loaded.
+    fn from_state(state: &AppState) -> Result<Option<Self>, String> {
+        let Some(original) = state.depth.lock().map_err(|e| e.to_string())?.clone() else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            original,
+            source_image_path: state
+                .source_image_path
+                .lock()
+                .map_err(|e| e.to_string())?
+                .clone(),
+            depth_model_id: state
+                .depth_model_id
+                .lock()
+                .map_err(|e| e.to_string())?
+                .clone(),
+            adjustment_params: state
+                .adjustment_params
+                .lock()
+                .map_err(|e| e.to_string())?
+                .clone(),
+            mask: state.mask.lock().map_err(|e| e.to_string())?.clone(),
+            sculpt: state.sculpt.lock().map_err(|e| e.to_string())?.clone(),
+            volumetric_params: resolved_volumetric_params(state)?,
+            point_attributes: resolved_point_attributes(state)?,
+        }))
+    }
+
+    /// Everything surface-map generation reads, with the sculpted and adjusted depth. This is
+    /// the job's first stage; cancellation is checked before and after it.
+    fn surface_inputs(
+        &self,
+        envelope: BlankEnvelope,
+        job: &jobs::JobContext,
+    ) -> Result<SurfaceInputs, String> {
+        let (width, height) = (self.original.width, self.original.height);
+        job.check_cancelled()?;
+        job.report(jobs::JobStage::Preparing, 0, height as usize);
+        let source = load_adjustment_source(
+            self.source_image_path.as_deref(),
+            &self.adjustment_params,
+            width,
+            height,
+        );
+        let depth = apply_adjustments_with_mask(
+            &composite_sculpt(self.sculpt.as_ref(), &self.original),
+            width,
+            height,
+            &self.adjustment_params,
+            self.mask.as_ref(),
+            source.as_ref(),
+        );
+        let options = self.point_attributes.clone();
+        let source_rgb = if options.needs_source_image() {
+            let path = self
+                .source_image_path
+                .as_deref()
+                .ok_or("Source image is required for RGB/intensity attributes")?;
+            Some(
+                image_loading::load_rgb_for_depth(path, width, height)
+                    .map_err(|e| e.to_string())?,
+            )
+        } else {
+            None
+        };
+        let raw_depth = options.raw_depth.then(|| self.original.depth.clone());
+        job.check_cancelled()?;
+        Ok(SurfaceInputs {
+            depth,
+            width,
+            height,
+            params: self.volumetric_params.clone(),
+            envelope,
+            options,
+            source_rgb,
+            raw_depth,
+        })
+    }
+}
+
+/// Where an export's points come from.
+enum ExportSource {
+    /// The cached imported cloud and the file it was read from.
+    Imported {
+        cloud: Arc<VolumetricResult>,
+        path: String,
+        mesh_sampling: Option<MeshSampling>,
+    },
+    /// Streamed from the depth map.
+    DepthMap(Box<DepthSnapshot>),
+}
+
+/// Inputs of one export, taken when it is requested; the written file and its recipe are
+/// both built from this, never from live state.
+struct ExportSnapshot {
+    envelope: BlankEnvelope,
+    source: ExportSource,
+}
+
+impl ExportSnapshot {
+    fn from_state(state: &AppState) -> Result<Self, String> {
+        let envelope = resolved_blank_envelope(state)?;
+        let imported = state
+            .point_cloud_source
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone();
+        let source = if let Some(path) = imported {
+            let cloud = cached_point_cloud(state)?.ok_or("No point cloud imported.")?;
+            let mesh_sampling = if mesh_import::MeshFormat::from_path(Path::new(&path)).is_some() {
+                Some(
+                    state
+                        .app_settings
+                        .lock()
+                        .map_err(|e| e.to_string())?
+                        .mesh_sampling
+                        .clone()
+                        .unwrap_or_default(),
+                )
+            } else {
+                None
+            };
+            ExportSource::Imported {
+                cloud,
+                path,
+                mesh_sampling,
+            }
+        } else {
+            let depth = DepthSnapshot::from_state(state)?.ok_or("No depth map loaded")?;
+            ExportSource::DepthMap(Box::new(depth))
+        };
+        Ok(Self { envelope, source })
+    }
+
+    /// File metadata: the blank and the name of the source image or imported file.
+    fn metadata(&self) -> ExportMetadata {
+        let mut metadata = ExportMetadata::new();
+        metadata.blank_envelope = Some(self.envelope.clone());
+        let source = match &self.source {
+            ExportSource::Imported { path, .. } => Some(path.as_str()),
+            ExportSource::DepthMap(depth) => depth.source_image_path.as_deref(),
+        };
+        metadata.source_image = source
+            .and_then(|p| Path::new(p).file_name())
+            .map(|n| n.to_string_lossy().to_string());
+        metadata
+    }
+}
+
+/// The cached cloud (generated or imported); the lock is released before returning.
+fn cached_point_cloud(state: &AppState) -> Result<Option<Arc<VolumetricResult>>, String> {
+    Ok(state
+        .last_point_cloud
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone())
+}
+
+/// Generate the cloud from the current depth map, reporting progress to `job`.
+fn generate_point_cloud_from_state(
+    state: &AppState,
+    job: &jobs::JobContext,
+) -> Result<VolumetricResult, String> {
+    let depth = DepthSnapshot::from_state(state)?.ok_or("No depth map loaded")?;
+    generate_point_cloud_from_snapshot(&depth, resolved_blank_envelope(state)?, job)
+}
+
+/// Generate the cloud from `depth` fitted to `envelope`, reporting progress to `job`.
+fn generate_point_cloud_from_snapshot(
+    depth: &DepthSnapshot,
+    envelope: BlankEnvelope,
+    job: &jobs::JobContext,
+) -> Result<VolumetricResult, String> {
+    let i = depth.surface_inputs(envelope, job)?;
+    let mut collector = volumetric::PointCollector::default();
+    let fit_result = volumetric::stream_volumetric_points(
+        &i.depth,
+        i.width,
+        i.height,
+        &i.params,
+        &i.envelope,
+        &i.options,
+        i.source_rgb.as_ref(),
+        i.raw_depth.as_deref(),
+        &mut jobs::ProgressSink::new(&mut collector, job),
+    )?;
+    Ok(VolumetricResult::from_collector(collector, fit_result))
 }
 
 fn estimate_point_cloud_count_from_state(state: &AppState) -> Result<Option<usize>, String> {
@@ -307,10 +688,11 @@ fn set_blank_envelope(envelope: BlankEnvelope, state: State<AppState>) -> Result
     envelope.validate()?;
     {
         let mut settings = state.app_settings.lock().map_err(|e| e.to_string())?;
-        settings.blank_envelope = Some(envelope);
+        settings.blank_envelope = Some(envelope.clone());
         settings.save().map_err(|e| e.to_string())?;
     }
     invalidate_point_cloud_cache(&state)?;
+    refit_imported_point_cloud(&state, &envelope)?;
     Ok(())
 }
 
@@ -328,16 +710,30 @@ fn set_volumetric_params(params: VolumetricParams, state: State<AppState>) -> Re
 
 #[tauri::command]
 fn set_point_cloud_format(format: String, state: State<AppState>) -> Result<(), String> {
-    let f = format.trim().to_lowercase();
-    match f.as_str() {
-        "ply" | "xyz" | "csv" => {
-            let mut settings = state.app_settings.lock().map_err(|e| e.to_string())?;
-            settings.point_cloud_format = Some(f);
-            settings.save().map_err(|e| e.to_string())?;
-            Ok(())
-        }
-        _ => Err("Format must be ply, xyz, or csv".to_string()),
+    let Ok(f) = format.parse::<ExportFormat>() else {
+        return Err(format!(
+            "Format must be one of {}",
+            ExportFormat::available()
+        ));
+    };
+    let mut settings = state.app_settings.lock().map_err(|e| e.to_string())?;
+    settings.point_cloud_format = Some(f.id().to_string());
+    settings.save().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn set_point_attributes(
+    options: PointAttributeOptions,
+    state: State<AppState>,
+) -> Result<(), String> {
+    options.validate()?;
+    {
+        let mut settings = state.app_settings.lock().map_err(|e| e.to_string())?;
+        settings.point_attributes = Some(options);
+        settings.save().map_err(|e| e.to_string())?;
     }
+    invalidate_point_cloud_cache(&state)?;
+    Ok(())
 }
 
 #[tauri::command]
@@ -345,61 +741,638 @@ fn estimate_point_cloud_count(state: State<AppState>) -> Result<Option<usize>, S
     estimate_point_cloud_count_from_state(&state)
 }
 
+/// Generate the point cloud as a background job (see [`spawn_job`]); returns the job id.
+/// The "job-finished" result is the [`VolumetricResult`], which is also cached for export.
 #[tauri::command]
-fn generate_point_cloud(state: State<AppState>) -> Result<VolumetricResult, String> {
-    let result = generate_point_cloud_from_state(&state)?;
-    *state.last_point_cloud.lock().map_err(|e| e.to_string())? = Some(result.clone());
-    Ok(result)
+fn generate_point_cloud(
+    app_handle: tauri::AppHandle,
+    state: State<AppState>,
+) -> Result<jobs::JobId, String> {
+    spawn_job(
+        app_handle,
+        &state,
+        jobs::JobKind::GeneratePointCloud,
+        |state, job| {
+            let result = generate_point_cloud_from_state(state, job)?;
+            *state.point_cloud_source.lock().map_err(|e| e.to_string())? = None;
+            *state.last_point_cloud.lock().map_err(|e| e.to_string())? =
+                Some(Arc::new(result.clone()));
+            Ok(result)
+        },
+    )
 }
 
+/// Request cancellation of a running job. Returns `false` if it already finished.
+/// A cancelled job finishes with status `cancelled`; a cancelled export leaves no file behind.
 #[tauri::command]
-fn export_ply(path: String, binary: bool, state: State<AppState>) -> Result<(), String> {
-    let (canonical_path, canonical_str) = validate_export_path(&path, "ply")?;
-    let points = {
-        let guard = state.last_point_cloud.lock().map_err(|e| e.to_string())?;
-        let Some(ref cached) = *guard else {
-            return Err("No point cloud generated; run generate_point_cloud first.".to_string());
-        };
-        cached.points.clone()
-    };
-    let metadata = build_export_metadata(&state)?;
-    export::export_ply(canonical_path.as_path(), &points, &metadata, binary)
-        .map_err(|e| e.to_string())?;
-    persist_last_export_dir(&state, &canonical_str)?;
+fn cancel_job(job_id: jobs::JobId, state: State<AppState>) -> bool {
+    state.jobs.cancel(job_id)
+}
+
+/// Run `work` on a worker thread as a tracked job and return its id at once.
+///
+/// Progress is emitted as "job-progress" events; the end as a "job-finished" event carrying
+/// the serialized result or the error.
+fn spawn_job<T, F>(
+    app_handle: tauri::AppHandle,
+    state: &AppState,
+    kind: jobs::JobKind,
+    work: F,
+) -> Result<jobs::JobId, String>
+where
+    T: serde::Serialize,
+    F: FnOnce(&AppState, &jobs::JobContext) -> Result<T, String> + Send + 'static,
+{
+    let emitter = app_handle.clone();
+    let job = state.jobs.start(
+        kind,
+        Arc::new(move |progress: &jobs::JobProgress| {
+            if let Err(e) = emitter.emit("job-progress", progress) {
+                log::warn!("job-progress emit failed: {}", e);
+            }
+        }),
+    );
+    let id = job.id;
+    std::thread::Builder::new()
+        .name(format!("job-{}", id))
+        .spawn(move || {
+            let state = app_handle.state::<AppState>();
+            let outcome = work(&state, &job);
+            state.jobs.finish(job.id);
+            let finished = jobs::JobFinished::from_outcome(&job, outcome);
+            if let Err(e) = app_handle.emit("job-finished", &finished) {
+                log::warn!("job-finished emit failed: {}", e);
+            }
+        })
+        .map_err(|e| {
+            state.jobs.finish(id);
+            format!("Failed to start job: {}", e)
+        })?;
+    Ok(id)
+}
+
+/// Load an external PLY/XYZ/CSV/PTS/PCD cloud, fit it to the current blank and cache it
+/// for the export commands (replaces any generated cloud).
+#[tauri::command]
+fn import_point_cloud(path: String, state: State<AppState>) -> Result<VolumetricResult, String> {
+    import_point_cloud_into_state(&state, &path)
+}
+
+/// Load an STL/OBJ mesh, fit it to the current blank and sample it (surface density or
+/// interior voxel pitch); cached like [`import_point_cloud`]. The sampling is persisted.
+#[tauri::command]
+fn import_mesh(
+    path: String,
+    sampling: MeshSampling,
+    state: State<AppState>,
+) -> Result<VolumetricResult, String> {
+    sampling.validate()?;
+    {
+        let mut settings = state.app_settings.lock().map_err(|e| e.to_string())?;
+        settings.mesh_sampling = Some(sampling.clone());
+        settings.save().map_err(|e| e.to_string())?;
+    }
+    import_mesh_into_state(&state, &path, &sampling)
+}
+
+fn import_mesh_into_state(
+    state: &AppState,
+    path: &str,
+    sampling: &MeshSampling,
+) -> Result<VolumetricResult, String> {
+    let envelope = resolved_blank_envelope(state)?;
+    let (canonical, result) = read_mesh_cloud(path, &envelope, sampling)?;
+    store_imported_point_cloud(state, &canonical, &result)?;
+    Ok(result)
+}
+
+/// Read and sample a mesh fitted to `envelope`; returns its canonical path and the cloud.
+fn read_mesh_cloud(
+    path: &str,
+    envelope: &BlankEnvelope,
+    sampling: &MeshSampling,
+) -> Result<(std::path::PathBuf, VolumetricResult), String> {
+    let canonical = mesh_import::validate_mesh_path(path).map_err(|e| e.to_string())?;
+    let mesh = mesh_import::read_mesh_file(&canonical).map_err(|e| e.to_string())?;
+    let (points, fit_result) = mesh_import::sample_mesh(&mesh, envelope, sampling)?;
+    Ok((
+        canonical,
+        VolumetricResult::from_fitted_points(points, fit_result),
+    ))
+}
+
+fn store_imported_point_cloud(
+    state: &AppState,
+    canonical: &Path,
+    result: &VolumetricResult,
+) -> Result<(), String> {
+    *state.point_cloud_source.lock().map_err(|e| e.to_string())? =
+        Some(canonical.to_string_lossy().to_string());
+    *state.last_point_cloud.lock().map_err(|e| e.to_string())? = Some(Arc::new(result.clone()));
     Ok(())
 }
 
+/// Drop an imported cloud so exports go back to the depth map.
 #[tauri::command]
-fn export_xyz(path: String, state: State<AppState>) -> Result<(), String> {
-    let (canonical_path, canonical_str) = validate_export_path(&path, "xyz")?;
-    let points = {
-        let guard = state.last_point_cloud.lock().map_err(|e| e.to_string())?;
-        let Some(ref cached) = *guard else {
-            return Err("No point cloud generated; run generate_point_cloud first.".to_string());
-        };
-        cached.points.clone()
-    };
-    export::export_xyz(canonical_path.as_path(), &points).map_err(|e| e.to_string())?;
-    persist_last_export_dir(&state, &canonical_str)?;
+fn clear_imported_point_cloud(state: State<AppState>) -> Result<(), String> {
+    *state.point_cloud_source.lock().map_err(|e| e.to_string())? = None;
+    *state.last_point_cloud.lock().map_err(|e| e.to_string())? = None;
     Ok(())
 }
 
+fn import_point_cloud_into_state(state: &AppState, path: &str) -> Result<VolumetricResult, String> {
+    let envelope = resolved_blank_envelope(state)?;
+    let (canonical, result) = read_point_cloud(path, &envelope)?;
+    store_imported_point_cloud(state, &canonical, &result)?;
+    Ok(result)
+}
+
+/// Read a point cloud file fitted to `envelope`; returns its canonical path and the cloud.
+fn read_point_cloud(
+    path: &str,
+    envelope: &BlankEnvelope,
+) -> Result<(std::path::PathBuf, VolumetricResult), String> {
+    let canonical = import::validate_import_path(path).map_err(|e| e.to_string())?;
+    let imported = import::import_points(&canonical).map_err(|e| e.to_string())?;
+    if imported.skipped > 0 {
+        log::warn!(
+            "import_point_cloud: skipped {} non-finite points",
+            imported.skipped
+        );
+    }
+    let mut points = imported.points;
+    let fit_result = blank_envelope::fit_to_blank(&mut points, envelope)?;
+    Ok((
+        canonical,
+        VolumetricResult::from_fitted_points(points, fit_result),
+    ))
+}
+
+/// Registered export formats with their extensions and options schema (format picker).
 #[tauri::command]
-fn export_csv(path: String, state: State<AppState>) -> Result<(), String> {
-    let (canonical_path, canonical_str) = validate_export_path(&path, "csv")?;
-    let points = {
-        let guard = state.last_point_cloud.lock().map_err(|e| e.to_string())?;
-        let Some(ref cached) = *guard else {
-            return Err("No point cloud generated; run generate_point_cloud first.".to_string());
-        };
-        cached.points.clone()
+fn list_export_formats(state: State<AppState>) -> Result<Vec<export::ExportFormatInfo>, String> {
+    let settings = state.app_settings.lock().map_err(|e| e.to_string())?;
+    Ok(export::format_infos(&settings.text_dialects))
+}
+
+/// Write the point cloud in any registered format (see [`export::EXPORTERS`]).
+/// `options` is checked against the format's schema; omitted keys take their defaults.
+/// Depth-map clouds are streamed from the current depth map; imported clouds come from the cache.
+/// The inputs are snapshotted when the command is called, so later edits do not leak into the
+/// running export or its recipe.
+/// A reproducibility recipe is written next to the file (`<file>.recipe.json`).
+/// Runs as a background job (see [`spawn_job`]); the file is written atomically and verified,
+/// and the "job-finished" result is its [`export::ExportReceipt`] (SHA-256 and size).
+#[tauri::command]
+fn export_point_cloud(
+    format: String,
+    options: Option<serde_json::Value>,
+    path: String,
+    app_handle: tauri::AppHandle,
+    state: State<AppState>,
+) -> Result<jobs::JobId, String> {
+    let format: ExportFormat = format.parse().map_err(|_| {
+        format!(
+            "Unknown export format '{}'; expected one of {}",
+            format,
+            ExportFormat::available()
+        )
+    })?;
+    let (canonical_path, canonical_str) =
+        validate_export_path(&path, format.exporter().extensions())?;
+    let options = ExportOptions::resolve(&format.options_schema(), options.as_ref())
+        .map_err(|e| e.to_string())?;
+    let snapshot = ExportSnapshot::from_state(&state)?;
+    spawn_job(
+        app_handle,
+        &state,
+        jobs::JobKind::Export,
+        move |state, job| {
+            // Hash the source before the export replaces anything, so a missing or unreadable
+            // source fails the job without leaving an export that has no sidecar.
+            let source = recipe_source(&snapshot)?;
+            let (fit_result, receipt) =
+                export_snapshot_to_file(&snapshot, format, &options, &canonical_path, job)?;
+            let recipe = build_export_recipe(&snapshot, source, fit_result, format, &options);
+            recipe::write_sidecar(&canonical_path, &recipe).map_err(|e| e.to_string())?;
+            remember_text_dialect(state, format, &options)?;
+            persist_last_export_dir(state, &canonical_str)?;
+            Ok(receipt)
+        },
+    )
+}
+
+/// Export without materialising depth-map clouds: points flow from generation (or the cached
+/// imported cloud) into the writer. Returns the fit of the exported cloud and the export
+/// receipt. Progress and cancellation go through `job`; a cancelled export removes its partial
+/// file.
+fn export_snapshot_to_file(
+    snapshot: &ExportSnapshot,
+    format: ExportFormat,
+    options: &ExportOptions,
+    path: &Path,
+    job: &jobs::JobContext,
+) -> Result<(blank_envelope::FitResult, export::ExportReceipt), String> {
+    let metadata = snapshot.metadata();
+    match &snapshot.source {
+        ExportSource::Imported { cloud, .. } => {
+            export::export_stream_to_file(path, format, &metadata, options, |sink| {
+                cloud.stream_into(&mut jobs::ProgressSink::new(sink, job))
+            })
+        }
+        ExportSource::DepthMap(depth) => {
+            let i = depth.surface_inputs(snapshot.envelope.clone(), job)?;
+            export::export_stream_to_file(path, format, &metadata, options, |sink| {
+                volumetric::stream_volumetric_points(
+                    &i.depth,
+                    i.width,
+                    i.height,
+                    &i.params,
+                    &i.envelope,
+                    &i.options,
+                    i.source_rgb.as_ref(),
+                    i.raw_depth.as_deref(),
+                    &mut jobs::ProgressSink::new(sink, job),
+                )
+            })
+        }
+    }
+    .map_err(|e| e.to_string())
+}
+
+/// Write the point cloud (imported, or generated from the depth map) as a folder of layer files
+/// plus `manifest.json` with any registered folder exporter (see
+/// [`export::LAYER_FOLDER_EXPORTERS`]): `"slices"` for 1-bit bitmap stacks
+/// ([`export::slices`]), `"svg"` for 1:1 millimetre SVGs ([`export::svg_layers`]). `options` is
+/// the format's options object; omitted keys take their defaults. `path` is the folder to
+/// write; an earlier stack there is replaced, other contents are refused. The inputs are taken
+/// when the command is called, so edits made while the job waits or runs don't leak into it.
+/// Runs as a background job; the "job-finished" result is the format's manifest.
+#[tauri::command]
+fn export_layer_folder(
+    format: String,
+    options: Option<serde_json::Value>,
+    path: String,
+    app_handle: tauri::AppHandle,
+    state: State<AppState>,
+) -> Result<jobs::JobId, String> {
+    let exporter = export::layer_folder_exporter(&format).ok_or_else(|| {
+        format!(
+            "Unknown layer folder format '{}'; expected one of {}",
+            format,
+            export::layer_folder_formats()
+        )
+    })?;
+    let options = options.unwrap_or_else(|| serde_json::json!({}));
+    exporter.validate(&options).map_err(|e| e.to_string())?;
+    let dir = validate_export_folder_path(&path)?;
+    let snapshot = ExportSnapshot::from_state(&state)?;
+    spawn_job(
+        app_handle,
+        &state,
+        jobs::JobKind::LayerFolder,
+        move |state, job| {
+            let manifest = with_snapshot_cloud(&snapshot, job, |points, envelope, progress| {
+                exporter.write(&dir, points, envelope, &options, progress)
+            })?;
+            persist_last_export_dir(state, &dir.to_string_lossy())?;
+            Ok(manifest)
+        },
+    )
+}
+
+/// Run a layer exporter over the snapshot's cloud (the imported cloud, or one generated from
+/// the snapshot's depth map) and blank, reporting its per-layer progress to `job` and stopping
+/// when the job is cancelled.
+fn with_snapshot_cloud<T>(
+    snapshot: &ExportSnapshot,
+    job: &jobs::JobContext,
+    export: impl FnOnce(
+        &[[f32; 3]],
+        &BlankEnvelope,
+        &mut dyn FnMut(usize, usize) -> anyhow::Result<()>,
+    ) -> anyhow::Result<T>,
+) -> Result<T, String> {
+    let cloud = match &snapshot.source {
+        ExportSource::Imported { cloud, .. } => cloud.clone(),
+        ExportSource::DepthMap(depth) => Arc::new(generate_point_cloud_from_snapshot(
+            depth,
+            snapshot.envelope.clone(),
+            job,
+        )?),
     };
-    let metadata = build_export_metadata(&state)?;
-    export::export_csv(canonical_path.as_path(), &points, &metadata).map_err(|e| e.to_string())?;
-    persist_last_export_dir(&state, &canonical_str)?;
+    job.check_cancelled()?;
+    export(&cloud.points, &snapshot.envelope, &mut |done, total| {
+        job.check_cancelled().map_err(anyhow::Error::msg)?;
+        job.report(jobs::JobStage::Writing, done, total);
+        Ok(())
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Where the exported points come from, with the source files hashed (see [`recipe`]).
+/// Errors when the source can't be recorded, e.g. the image behind a depth map is gone.
+fn recipe_source(snapshot: &ExportSnapshot) -> Result<recipe::RecipeSource, String> {
+    Ok(match &snapshot.source {
+        ExportSource::Imported {
+            path,
+            mesh_sampling,
+            ..
+        } => recipe::RecipeSource::Imported {
+            file: recipe::FileRecord::from_path(Path::new(path)).map_err(|e| e.to_string())?,
+            mesh_sampling: mesh_sampling.clone(),
+        },
+        ExportSource::DepthMap(depth) => {
+            let image_path = depth
+                .source_image_path
+                .as_deref()
+                .ok_or("No source image recorded for this depth map")?;
+            recipe::RecipeSource::DepthMap {
+                image: recipe::FileRecord::from_path(Path::new(image_path))
+                    .map_err(|e| e.to_string())?,
+                depth_model_id: depth.depth_model_id.clone(),
+                depth_width: depth.original.width,
+                depth_height: depth.original.height,
+                depth_sha256: recipe::sha256_depth(&depth.original.depth),
+                adjustment_params: Box::new(depth.adjustment_params.clone()),
+                mask: depth.mask.as_ref().map(recipe::MaskRle::from_mask),
+                sculpt: depth.sculpt.clone().filter(|layer| !layer.is_empty()),
+                volumetric_params: depth.volumetric_params.clone(),
+                point_attributes: depth.point_attributes.clone(),
+            }
+        }
+    })
+}
+
+/// Everything needed to regenerate the exported cloud (see [`recipe`]).
+fn build_export_recipe(
+    snapshot: &ExportSnapshot,
+    source: recipe::RecipeSource,
+    fit_result: blank_envelope::FitResult,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> recipe::ExportRecipe {
+    recipe::ExportRecipe {
+        recipe_version: recipe::RECIPE_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        generator: ExportMetadata::new().generator,
+        source,
+        envelope: snapshot.envelope.clone(),
+        fit_result,
+        format: format.id().to_string(),
+        format_options: options.to_json(),
+    }
+}
+
+/// Outcome of [`replay_recipe`]: the regenerated cloud and how closely it matches the recipe.
+#[derive(Debug, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReplayResult {
+    point_count: usize,
+    fit_result: blank_envelope::FitResult,
+    /// Same point count, scale and translation as recorded.
+    fit_matches: bool,
+    /// Re-estimated depth map is bit-identical to the recorded one (`None` for imported clouds).
+    depth_matches: Option<bool>,
+    /// Model that produced the replayed depth map (`None` for imported clouds or if unknown).
+    depth_model_id: Option<String>,
+    /// How the replay differs from the recorded export (model, depth map, dimensions, and a
+    /// mask or sculpt layer left out because it no longer fits). Empty unless the replay was
+    /// run with `accept_mismatch`.
+    mismatches: Vec<String>,
+}
+
+/// Regenerate the cloud described by an export recipe and cache it for export.
+///
+/// Depth-map recipes re-run depth estimation on the recorded image, then restore the
+/// adjustments, mask, sculpt layer, sampling, channels and blank; imported recipes re-import
+/// the recorded file. `source_path` overrides the recorded path (e.g. the image moved); its
+/// SHA-256 must match the recipe. A different depth model, a re-estimated depth map that
+/// differs from the recorded one, or a mask or sculpt layer that no longer fits fails the
+/// replay unless `accept_mismatch` is set; the differences are then listed in the result.
+/// Restored settings are persisted. Runs as a background job (see [`spawn_job`]); the
+/// "job-finished" result is the [`ReplayResult`]. State is only changed once the source is
+/// verified and the cloud regenerated, so a failed or cancelled replay leaves the current
+/// session as it was.
+#[tauri::command]
+fn replay_recipe(
+    recipe_path: String,
+    source_path: Option<String>,
+    accept_mismatch: Option<bool>,
+    app_handle: tauri::AppHandle,
+    state: State<AppState>,
+) -> Result<jobs::JobId, String> {
+    let recipe = recipe::read_recipe(Path::new(recipe_path.trim())).map_err(|e| e.to_string())?;
+    validate_recipe(&recipe)?;
+    let depth_progress = app_handle.clone();
+    spawn_job(
+        app_handle,
+        &state,
+        jobs::JobKind::ReplayRecipe,
+        move |state, job| {
+            let depth = match &recipe.source {
+                recipe::RecipeSource::DepthMap { image, .. } => {
+                    let path = source_path.clone().unwrap_or_else(|| image.path.clone());
+                    image.verify(Path::new(&path)).map_err(|e| e.to_string())?;
+                    job.check_cancelled()?;
+                    let depth = estimate_depth_with_progress(&path, depth_progress)?;
+                    Some((depth, path))
+                }
+                recipe::RecipeSource::Imported { .. } => None,
+            };
+            job.check_cancelled()?;
+            let result = replay_recipe_into_state(
+                state,
+                &recipe,
+                source_path.as_deref(),
+                depth,
+                accept_mismatch.unwrap_or(false),
+                job,
+            )?;
+            state
+                .app_settings
+                .lock()
+                .map_err(|e| e.to_string())?
+                .save()
+                .map_err(|e| e.to_string())?;
+            Ok(result)
+        },
+    )
+}
+
+/// Check everything in `recipe` that does not need its source file.
+fn validate_recipe(recipe: &recipe::ExportRecipe) -> Result<(), String> {
+    recipe.envelope.validate()?;
+    match &recipe.source {
+        recipe::RecipeSource::DepthMap {
+            volumetric_params,
+            point_attributes,
+            mask,
+            ..
+        } => {
+            validate_volumetric_params(volumetric_params)?;
+            point_attributes.validate()?;
+            if let Some(rle) = mask {
+                rle.to_mask()?;
+            }
+        }
+        recipe::RecipeSource::Imported { mesh_sampling, .. } => {
+            if let Some(sampling) = mesh_sampling {
+                sampling.validate()?;
+            }
+        }
+    }
     Ok(())
 }
 
+/// Apply `recipe` to state and regenerate. For depth-map recipes `depth` is the freshly
+/// estimated depth map with the estimator's stderr and the image path it came from.
+/// The recipe is validated, the source verified, the replay checked against the recorded
+/// model and depth map (see [`replay_recipe`] for `accept_mismatch`) and the cloud built
+/// before anything in `state` changes.
+fn replay_recipe_into_state(
+    state: &AppState,
+    recipe: &recipe::ExportRecipe,
+    source_path: Option<&str>,
+    depth: Option<((python_bridge::DepthMapOutput, Vec<String>), String)>,
+    accept_mismatch: bool,
+    job: &jobs::JobContext,
+) -> Result<ReplayResult, String> {
+    validate_recipe(recipe)?;
+    let envelope = recipe.envelope.clone();
+    let mut mismatches = Vec::new();
+
+    let (result, depth_matches, depth_model_id) = match &recipe.source {
+        recipe::RecipeSource::DepthMap {
+            depth_model_id: recorded_model,
+            depth_width,
+            depth_height,
+            depth_sha256,
+            adjustment_params,
+            mask,
+            sculpt,
+            volumetric_params,
+            point_attributes,
+            ..
+        } => {
+            let ((depth, stderr_lines), image_path) =
+                depth.ok_or("Depth map required to replay a depth-map recipe")?;
+            let depth_model_id = python_bridge::model_from_stderr(&stderr_lines);
+            if let Some(recorded) = recorded_model {
+                if depth_model_id.as_ref() != Some(recorded) {
+                    mismatches.push(format!(
+                        "depth model is {} but the export used {}",
+                        depth_model_id.as_deref().unwrap_or("unknown"),
+                        recorded
+                    ));
+                }
+            }
+            let depth_matches = recipe::sha256_depth(&depth.depth) == *depth_sha256;
+            if (depth.width, depth.height) != (*depth_width, *depth_height) {
+                mismatches.push(format!(
+                    "depth map is {}x{} but the export used {}x{}",
+                    depth.width, depth.height, depth_width, depth_height
+                ));
+            } else if !depth_matches {
+                mismatches.push("re-estimated depth map differs from the recorded one".into());
+            }
+            let mask = mask.as_ref().map(|rle| rle.to_mask()).transpose()?;
+            let mask = match mask {
+                Some(m) if !m.dimensions_match(depth.width, depth.height) => {
+                    mismatches.push("mask does not fit the depth map and is left out".into());
+                    None
+                }
+                mask => mask,
+            };
+            let sculpt = match sculpt.clone() {
+                Some(layer) if !layer.dimensions_match(depth.width, depth.height) => {
+                    mismatches
+                        .push("sculpt layer does not fit the depth map and is left out".into());
+                    None
+                }
+                sculpt => sculpt,
+            };
+            if !mismatches.is_empty() && !accept_mismatch {
+                return Err(format!(
+                    "Replay would not reproduce the export: {}. Replay with mismatches accepted to continue anyway.",
+                    mismatches.join("; ")
+                ));
+            }
+            let snapshot = DepthSnapshot {
+                original: depth,
+                source_image_path: Some(image_path.clone()),
+                depth_model_id,
+                adjustment_params: (**adjustment_params).clone(),
+                mask,
+                sculpt,
+                volumetric_params: volumetric_params.clone(),
+                point_attributes: point_attributes.clone(),
+            };
+            let result = generate_point_cloud_from_snapshot(&snapshot, envelope.clone(), job)?;
+
+            let DepthSnapshot {
+                original,
+                depth_model_id,
+                adjustment_params,
+                mask,
+                sculpt,
+                volumetric_params,
+                point_attributes,
+                ..
+            } = snapshot;
+            *state.point_cloud_source.lock().map_err(|e| e.to_string())? = None;
+            store_depth_map(state, &original, &image_path, &stderr_lines)?;
+            *state.mask.lock().map_err(|e| e.to_string())? = mask;
+            *state.sculpt.lock().map_err(|e| e.to_string())? = sculpt;
+            *state.adjustment_params.lock().map_err(|e| e.to_string())? = adjustment_params;
+            {
+                let mut settings = state.app_settings.lock().map_err(|e| e.to_string())?;
+                settings.blank_envelope = Some(envelope);
+                settings.volumetric_params = Some(volumetric_params);
+                settings.point_attributes = Some(point_attributes);
+            }
+            *state.last_point_cloud.lock().map_err(|e| e.to_string())? =
+                Some(Arc::new(result.clone()));
+            (result, Some(depth_matches), depth_model_id)
+        }
+        recipe::RecipeSource::Imported {
+            file,
+            mesh_sampling,
+        } => {
+            let path = source_path.unwrap_or(&file.path);
+            file.verify(Path::new(path)).map_err(|e| e.to_string())?;
+            job.check_cancelled()?;
+            let (canonical, result) = match mesh_sampling {
+                Some(sampling) => read_mesh_cloud(path, &envelope, sampling)?,
+                None => read_point_cloud(path, &envelope)?,
+            };
+            job.check_cancelled()?;
+            {
+                let mut settings = state.app_settings.lock().map_err(|e| e.to_string())?;
+                settings.blank_envelope = Some(envelope);
+                if let Some(sampling) = mesh_sampling {
+                    settings.mesh_sampling = Some(sampling.clone());
+                }
+            }
+            store_imported_point_cloud(state, &canonical, &result)?;
+            (result, None, None)
+        }
+    };
+
+    let recorded = &recipe.fit_result;
+    let fit_matches = result.point_count == recorded.point_count
+        && result.fit_result.scale == recorded.scale
+        && result.fit_result.translation == recorded.translation;
+    Ok(ReplayResult {
+        point_count: result.point_count,
+        fit_result: result.fit_result,
+        fit_matches,
+        depth_matches,
+        depth_model_id,
+        mismatches,
+    })
+}
+
 // --- Sprint 2.3: Presets (BACK-1302) ---
 
 /// Save current depth/mesh settings as a preset (BACK-1302).
@@ -416,19 +1389,13 @@ fn save_preset(name: String, path: Option<String>, state: State<AppState>) -> Re
     let target_height_mm = settings_guard.target_height_mm;
     drop(settings_guard);
 
-    let preset = Preset::from_depth_and_mesh(
-        params.brightness,
-        params.contrast,
-        params.gamma,
-        params.invert,
-        params.depth_min_mm,
-        params.depth_max_mm,
-        params.curve_control_points.clone(),
-        1,
-        1,
-        target_width_mm,
-        target_height_mm,
-    );
+    let mut preset = Preset::from_depth_params(&params, 1, 1, target_width_mm, target_height_mm);
+    preset.sculpt = state
+        .sculpt
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .filter(|layer| !layer.is_empty());
 
     let json = serde_json::to_string_pretty(&preset).map_err(|e| e.to_string())?;
 
@@ -476,18 +1443,21 @@ fn load_preset(name_or_path: String, state: State<AppState>) -> Result<UndoRedoS
         .lock()
         .map_err(|e| e.to_string())?
         .clone();
-    let cmd = SetDepthParamsCommand {
+    let mut cmd = UndoableCommand::Depth(Box::new(SetDepthParamsCommand {
         previous: previous.clone(),
         new: new_params.clone(),
-    };
+    }));
+    if let Some(patch) = load_preset_sculpt(&state, preset.sculpt.as_ref())? {
+        cmd = UndoableCommand::Group(vec![cmd, UndoableCommand::Sculpt(patch)]);
+    }
     *state.adjustment_params.lock().map_err(|e| e.to_string())? = new_params.clone();
     {
         let mut hist = state.undo_redo.lock().map_err(|e| e.to_string())?;
-        hist.push(UndoableCommand::Depth(cmd));
+        hist.push(cmd);
     }
     {
         let mut app_settings = state.app_settings.lock().map_err(|e| e.to_string())?;
-        app_settings.curve_control_points = preset.curve_control_points.clone();
+        app_settings.set_adjustment_operations(&preset.operations);
         app_settings.target_width_mm = preset.target_width_mm;
         app_settings.target_height_mm = preset.target_height_mm;
         if let Err(e) = app_settings.save() {
@@ -497,6 +1467,35 @@ fn load_preset(name_or_path: String, state: State<AppState>) -> Result<UndoRedoS
     get_undo_redo_state(state)
 }
 
+/// Replace the sculpt layer with a preset's layer when it matches the loaded depth map; a
+/// layer of other dimensions is skipped. Returns the undo patch, or None if nothing changed.
+fn load_preset_sculpt(
+    state: &AppState,
+    layer: Option<&sculpt::OffsetLayer>,
+) -> Result<Option<sculpt::SculptPatch>, String> {
+    let Some(layer) = layer else {
+        return Ok(None);
+    };
+    let (width, height) = layer.dimensions();
+    let matches = state
+        .depth
+        .lock()
+        .map_err(|e| e.to_string())?
+        .as_ref()
+        .is_some_and(|d| d.width == width && d.height == height);
+    if !matches {
+        log::warn!(
+            "Preset sculpt layer is {}x{} and does not match the depth map; skipped",
+            width,
+            height
+        );
+        return Ok(None);
+    }
+    let mut current = state.sculpt.lock().map_err(|e| e.to_string())?;
+    let previous = current.replace(layer.clone());
+    Ok(Some(sculpt::SculptPatch::Swap(previous)))
+}
+
 /// List user preset names (filenames without .json) in ~/.simplepicture3d/presets/ (BACK-1302, UI-1301).
 #[tauri::command]
 fn list_presets() -> Result<Vec<String>, String> {
@@ -723,17 +1722,12 @@ fn generate_depth_map_impl(
     Ok((result.depth, result.stderr_lines))
 }
 
-/// Generates a depth map from an image file (BACK-301, BACK-303, BACK-304, BACK-205-STREAM).
-/// Accepts image path (same as load_image); validates path and format, runs Python bridge;
-/// stores result in app state (BACK-302), returns depth + progress 100 + stages.
-/// Emits "depth-progress" Tauri events during estimation for real-time UI progress (Sprint 2.4).
-#[tauri::command]
-fn generate_depth_map(
-    path: String,
+/// Run the depth estimator on `path`, emitting "depth-progress" events.
+fn estimate_depth_with_progress(
+    path: &str,
     app_handle: tauri::AppHandle,
-    state: State<AppState>,
-) -> Result<GenerateDepthMapResponse, String> {
-    let bytes = image_loading::read_image_bytes_for_depth(&path).map_err(|e| e.to_string())?;
+) -> Result<(python_bridge::DepthMapOutput, Vec<String>), String> {
+    let bytes = image_loading::read_image_bytes_for_depth(path).map_err(|e| e.to_string())?;
     let progress_cb: python_bridge::ProgressCb = Box::new(move |percent, stage| {
         let payload = DepthProgressPayload { percent, stage };
         if let Err(e) = app_handle.emit("depth-progress", &payload) {
@@ -747,20 +1741,58 @@ fn generate_depth_map(
         Some(progress_cb),
     )
     .map_err(|e| e.to_string())?;
-    let depth = result.depth;
-    let stderr_lines = result.stderr_lines;
-    log_depth_stats(&depth.depth);
+    log_depth_stats(&result.depth.depth);
+    Ok((result.depth, result.stderr_lines))
+}
+
+/// Make `depth` the current depth map: records its source and model, clears mask, history and
+/// the generated cloud.
+fn store_depth_map(
+    state: &AppState,
+    depth: &python_bridge::DepthMapOutput,
+    path: &str,
+    stderr_lines: &[String],
+) -> Result<(), String> {
     *state.depth.lock().map_err(|e| e.to_string())? = Some(depth.clone());
     // Store source image path for filename generation (BACK-705).
-    *state.source_image_path.lock().map_err(|e| e.to_string())? = Some(path.clone());
-    // Clear mask when depth map is replaced (ARCH-502).
+    *state.source_image_path.lock().map_err(|e| e.to_string())? = Some(path.to_string());
+    *state.depth_model_id.lock().map_err(|e| e.to_string())? =
+        python_bridge::model_from_stderr(stderr_lines);
+    // Clear mask and sculpt layer when depth map is replaced (ARCH-502).
     *state.mask.lock().map_err(|e| e.to_string())? = None;
+    *state.sculpt.lock().map_err(|e| e.to_string())? = None;
     // Clear undo/redo history on new depth map (PRD F2.4).
     state.undo_redo.lock().map_err(|e| e.to_string())?.clear();
-    invalidate_point_cloud_cache(&state)?;
+    invalidate_point_cloud_cache(state)
+}
+
+/// Generates a depth map from an image file (BACK-301, BACK-303, BACK-304, BACK-205-STREAM).
+/// Accepts image path (same as load_image); validates path and format, runs Python bridge;
+/// stores result in app state (BACK-302), returns depth + progress 100 + stages.
+/// Emits "depth-progress" Tauri events during estimation for real-time UI progress (Sprint 2.4).
+#[tauri::command]
+fn generate_depth_map(
+    path: String,
+    app_handle: tauri::AppHandle,
+    state: State<AppState>,
+) -> Result<GenerateDepthMapResponse, String> {
+    let (depth, stderr_lines) = estimate_depth_with_progress(&path, app_handle)?;
+    store_depth_map(&state, &depth, &path, &stderr_lines)?;
     // Leave adjustment_params unchanged (user may have presets); reset is explicit (BACK-405).
-    let params = state.adjustment_params.lock().map_err(|e| e.to_string())?;
-    let adjusted = apply_adjustments(&depth.depth, &params);
+    let params = state
+        .adjustment_params
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone();
+    let source = adjustment_source_from_state(&state, &params, depth.width, depth.height)?;
+    let adjusted = apply_adjustments(
+        &depth.depth,
+        depth.width,
+        depth.height,
+        &params,
+        source.as_ref(),
+        None,
+    );
     let stages = python_bridge::stages_from_stderr(&stderr_lines);
     Ok(GenerateDepthMapResponse {
         width: depth.width,
@@ -776,6 +1808,17 @@ fn generate_depth_map(
 #[tauri::command]
 fn get_depth_histogram(state: State<AppState>) -> Result<Option<Vec<u32>>, String> {
     const BINS: usize = 256;
+    Ok(current_adjusted_depth(&state)?.map(|(adjusted, _, _)| compute_histogram(&adjusted, BINS)))
+}
+
+/// Suggests auto-levels / equalisation for the current depth map without applying them.
+/// Computed on the input of the levels stage: the output of the operations above the first
+/// enabled auto-levels / equalisation operation (or above where one would be added).
+/// Returns None if no depth map loaded.
+#[tauri::command]
+fn propose_depth_levels(
+    state: State<AppState>,
+) -> Result<Option<depth_levels::LevelsProposal>, String> {
     let guard = state.depth.lock().map_err(|e| e.to_string())?;
     let original = match guard.as_ref() {
         Some(d) => d.clone(),
@@ -787,15 +1830,22 @@ fn get_depth_histogram(state: State<AppState>) -> Result<Option<Vec<u32>>, Strin
         .lock()
         .map_err(|e| e.to_string())?
         .clone();
+    let upstream = DepthAdjustmentParams {
+        operations: params.operations[..params.levels_stage_index()].to_vec(),
+        ..params
+    };
+    let source = adjustment_source_from_state(&state, &upstream, original.width, original.height)?;
+    let sculpted = sculpted_depth(&state, &original)?;
     let mask_guard = state.mask.lock().map_err(|e| e.to_string())?;
-    let adjusted = apply_adjustments_with_mask(
-        &original.depth,
+    let shaped = apply_adjustments(
+        &sculpted,
         original.width,
         original.height,
-        &params,
+        &upstream,
+        source.as_ref(),
         mask_guard.as_ref(),
     );
-    Ok(Some(compute_histogram(&adjusted, BINS)))
+    Ok(depth_levels::propose_levels(&shaped))
 }
 
 /// Response for undo/redo/clear_history and get_undo_redo_state (BACK-1404).
@@ -811,34 +1861,19 @@ struct UndoRedoState {
 /// When a mask is present, only masked regions (and feathered blend) are adjusted (BACK-1202, BACK-1203).
 #[tauri::command]
 fn get_depth_map(state: State<AppState>) -> Result<Option<python_bridge::DepthMapOutput>, String> {
-    let guard = state.depth.lock().map_err(|e| e.to_string())?;
-    let original = match guard.as_ref() {
-        Some(d) => d.clone(),
-        None => return Ok(None),
-    };
-    drop(guard);
-    let params = state
-        .adjustment_params
-        .lock()
-        .map_err(|e| e.to_string())?
-        .clone();
-    let mask_guard = state.mask.lock().map_err(|e| e.to_string())?;
-    let adjusted = apply_adjustments_with_mask(
-        &original.depth,
-        original.width,
-        original.height,
-        &params,
-        mask_guard.as_ref(),
-    );
-    Ok(Some(python_bridge::DepthMapOutput {
-        width: original.width,
-        height: original.height,
-        depth: adjusted,
-    }))
+    Ok(
+        current_adjusted_depth(&state)?.map(|(depth, width, height)| {
+            python_bridge::DepthMapOutput {
+                width,
+                height,
+                depth,
+            }
+        }),
+    )
 }
 
 /// Sets depth adjustment parameters (BACK-402, BACK-1403). Wrapped in command for undo/redo.
-/// CURVE-001: Persist curve_control_points to AppSettings so curve survives restart.
+/// CURVE-001: Persist the operation stack to AppSettings so it survives restart.
 #[tauri::command]
 fn set_depth_adjustment_params(
     params: DepthAdjustmentParams,
@@ -852,17 +1887,17 @@ fn set_depth_adjustment_params(
     *state.adjustment_params.lock().map_err(|e| e.to_string())? = params.clone();
     {
         let mut hist = state.undo_redo.lock().map_err(|e| e.to_string())?;
-        hist.push(UndoableCommand::Depth(SetDepthParamsCommand {
+        hist.push(UndoableCommand::Depth(Box::new(SetDepthParamsCommand {
             previous: previous.clone(),
             new: params.clone(),
-        }));
+        })));
     }
     {
         let mut settings = state.app_settings.lock().map_err(|e| e.to_string())?;
-        settings.curve_control_points = params.curve_control_points.clone();
+        settings.set_adjustment_operations(&params.operations);
         if let Err(e) = settings.save() {
             log::warn!(
-                "Failed to save settings (curve) after set_depth_adjustment_params: {}",
+                "Failed to save settings (operations) after set_depth_adjustment_params: {}",
                 e
             );
         }
@@ -901,21 +1936,27 @@ fn get_undo_redo_state(state: State<AppState>) -> Result<UndoRedoState, String>
     })
 }
 
-/// Undo last action (depth or mask) (BACK-1404, ARCH-502). Restores previous state; returns new state for UI.
-/// When nothing to undo, returns current state with can_undo: false so UI can disable button.
+/// Undo last action (depth, mask or sculpt) (BACK-1404, ARCH-502). Restores previous state;
+/// returns new state for UI. When nothing to undo, returns current state with can_undo: false
+/// so UI can disable button.
 #[tauri::command]
 fn undo(state: State<AppState>) -> Result<UndoRedoState, String> {
     let cmd = {
         let mut hist = state.undo_redo.lock().map_err(|e| e.to_string())?;
         hist.pop_undo()
     };
-    let Some(cmd) = cmd else {
+    let Some(mut cmd) = cmd else {
         return get_undo_redo_state(state);
     };
     {
         let mut params = state.adjustment_params.lock().map_err(|e| e.to_string())?;
         let mut mask = state.mask.lock().map_err(|e| e.to_string())?;
-        cmd.apply_previous(&mut params, &mut mask);
+        let mut sculpt = state.sculpt.lock().map_err(|e| e.to_string())?;
+        cmd.apply_previous(&mut UndoTarget {
+            params: &mut params,
+            mask: &mut mask,
+            sculpt: &mut sculpt,
+        });
     }
     {
         let mut hist = state.undo_redo.lock().map_err(|e| e.to_string())?;
@@ -924,7 +1965,7 @@ fn undo(state: State<AppState>) -> Result<UndoRedoState, String> {
     get_undo_redo_state(state)
 }
 
-/// Redo last undone action (depth or mask) (BACK-1404, ARCH-502). Returns new state for UI.
+/// Redo last undone action (depth, mask or sculpt) (BACK-1404, ARCH-502). Returns new state for UI.
 /// When nothing to redo, returns current state with can_redo: false so UI can disable button.
 #[tauri::command]
 fn redo(state: State<AppState>) -> Result<UndoRedoState, String> {
@@ -932,13 +1973,18 @@ fn redo(state: State<AppState>) -> Result<UndoRedoState, String> {
         let mut hist = state.undo_redo.lock().map_err(|e| e.to_string())?;
         hist.pop_redo()
     };
-    let Some(cmd) = cmd else {
+    let Some(mut cmd) = cmd else {
         return get_undo_redo_state(state);
     };
     {
         let mut params = state.adjustment_params.lock().map_err(|e| e.to_string())?;
         let mut mask = state.mask.lock().map_err(|e| e.to_string())?;
-        cmd.apply_new(&mut params, &mut mask);
+        let mut sculpt = state.sculpt.lock().map_err(|e| e.to_string())?;
+        cmd.apply_new(&mut UndoTarget {
+            params: &mut params,
+            mask: &mut mask,
+            sculpt: &mut sculpt,
+        });
     }
     {
         let mut hist = state.undo_redo.lock().map_err(|e| e.to_string())?;
@@ -1065,7 +2111,7 @@ fn reset_depth_adjustments(state: State<AppState>) -> Result<UndoRedoState, Stri
     *state.adjustment_params.lock().map_err(|e| e.to_string())? = new_params.clone();
     {
         let mut hist = state.undo_redo.lock().map_err(|e| e.to_string())?;
-        hist.push(UndoableCommand::Depth(cmd));
+        hist.push(UndoableCommand::Depth(Box::new(cmd)));
     }
     Ok(UndoRedoState {
         can_undo: state
@@ -1196,18 +2242,66 @@ fn load_mask_from_path(path: String, state: State<AppState>) -> Result<UndoRedoS
     get_undo_redo_state(state)
 }
 
+/// Apply one sculpt brush stroke to the offset layer, creating it if needed. One undo step
+/// per stroke; a stroke that misses the depth map changes nothing.
+#[tauri::command]
+fn apply_sculpt_stroke(
+    stroke: sculpt::SculptStroke,
+    state: State<AppState>,
+) -> Result<UndoRedoState, String> {
+    apply_sculpt_stroke_to_state(&state, &stroke)?;
+    get_undo_redo_state(state)
+}
+
+fn apply_sculpt_stroke_to_state(
+    state: &AppState,
+    stroke: &sculpt::SculptStroke,
+) -> Result<(), String> {
+    stroke.validate()?;
+    let guard = state.depth.lock().map_err(|e| e.to_string())?;
+    let depth = guard
+        .as_ref()
+        .ok_or_else(|| "No depth map loaded. Generate a depth map first.".to_string())?;
+    let mut layer = state.sculpt.lock().map_err(|e| e.to_string())?;
+    let layer = match layer.as_mut() {
+        Some(l) if l.dimensions_match(depth.width, depth.height) => l,
+        _ => layer.insert(sculpt::OffsetLayer::zeros(depth.width, depth.height)),
+    };
+    if let Some(patch) = layer.apply_stroke(&depth.depth, stroke) {
+        let mut hist = state.undo_redo.lock().map_err(|e| e.to_string())?;
+        hist.push(UndoableCommand::Sculpt(patch));
+    }
+    Ok(())
+}
+
+/// Remove all sculpting (offsets to zero). Pushes to undo stack; no-op without a layer.
+#[tauri::command]
+fn clear_sculpt(state: State<AppState>) -> Result<UndoRedoState, String> {
+    {
+        let mut layer = state.sculpt.lock().map_err(|e| e.to_string())?;
+        if let Some((width, height)) = layer
+            .as_ref()
+            .filter(|l| !l.is_empty())
+            .map(|l| l.dimensions())
+        {
+            let previous = layer.replace(sculpt::OffsetLayer::zeros(width, height));
+            let mut hist = state.undo_redo.lock().map_err(|e| e.to_string())?;
+            hist.push(UndoableCommand::Sculpt(sculpt::SculptPatch::Swap(previous)));
+        }
+    }
+    get_undo_redo_state(state)
+}
+
 // Sprint A retired 2.5D mesh preview (`get_mesh_data`); superseded by `generate_point_cloud` (ADR-012).
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let _ = env_logger::try_init();
     let app_settings = settings::AppSettings::load();
-    let mut adjustment_params = DepthAdjustmentParams::default();
-    if let Some(ref curve) = app_settings.curve_control_points {
-        if curve.len() >= 2 {
-            adjustment_params.curve_control_points = Some(curve.clone());
-        }
-    }
+    let adjustment_params = DepthAdjustmentParams {
+        operations: app_settings.restored_adjustment_operations(),
+        ..Default::default()
+    };
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_shell::init())
@@ -1215,16 +2309,21 @@ pub fn run() {
             depth: Mutex::new(None),
             adjustment_params: Mutex::new(adjustment_params),
             mask: Mutex::new(None),
+            sculpt: Mutex::new(None),
             source_image_path: Mutex::new(None),
             app_settings: Mutex::new(app_settings),
             undo_redo: Mutex::new(UndoRedoHistory::new()),
             last_point_cloud: Mutex::new(None),
+            point_cloud_source: Mutex::new(None),
+            depth_model_id: Mutex::new(None),
+            jobs: jobs::JobRegistry::default(),
         })
         .invoke_handler(tauri::generate_handler![
             load_image,
             generate_depth_map,
             get_depth_map,
             get_depth_histogram,
+            propose_depth_levels,
             set_depth_adjustment_params,
             get_depth_adjustment_params,
             get_undo_redo_state,
@@ -1235,6 +2334,8 @@ pub fn run() {
             set_mask_region,
             set_mask,
             clear_mask,
+            apply_sculpt_stroke,
+            clear_sculpt,
             save_mask_to_path,
             load_mask_from_path,
             reset_depth_adjustments,
@@ -1243,11 +2344,17 @@ pub fn run() {
             set_blank_envelope,
             set_volumetric_params,
             set_point_cloud_format,
+            set_point_attributes,
             estimate_point_cloud_count,
             generate_point_cloud,
-            export_ply,
-            export_xyz,
-            export_csv,
+            import_point_cloud,
+            import_mesh,
+            clear_imported_point_cloud,
+            cancel_job,
+            replay_recipe,
+            list_export_formats,
+            export_point_cloud,
+            export_layer_folder,
             save_preset,
             load_preset,
             list_presets,
@@ -1274,10 +2381,14 @@ mod tests {
             depth: Mutex::new(None),
             adjustment_params: Mutex::new(DepthAdjustmentParams::default()),
             mask: Mutex::new(None),
+            sculpt: Mutex::new(None),
             source_image_path: Mutex::new(None),
             app_settings: Mutex::new(settings::AppSettings::default()),
             undo_redo: Mutex::new(UndoRedoHistory::new()),
             last_point_cloud: Mutex::new(None),
+            point_cloud_source: Mutex::new(None),
+            depth_model_id: Mutex::new(None),
+            jobs: jobs::JobRegistry::default(),
         };
         assert_eq!(estimate_point_cloud_count_from_state(&state).unwrap(), None);
     }
@@ -1295,6 +2406,7 @@ mod tests {
                 step_x: 1,
                 step_y: 1,
                 depth_threshold: 0.0,
+                ..Default::default()
             }),
             ..Default::default()
         };
@@ -1302,15 +2414,378 @@ mod tests {
             depth: Mutex::new(Some(depth)),
             adjustment_params: Mutex::new(DepthAdjustmentParams::default()),
             mask: Mutex::new(None),
+            sculpt: Mutex::new(None),
             source_image_path: Mutex::new(None),
             app_settings: Mutex::new(app_settings),
             undo_redo: Mutex::new(UndoRedoHistory::new()),
             last_point_cloud: Mutex::new(None),
+            point_cloud_source: Mutex::new(None),
+            depth_model_id: Mutex::new(None),
+            jobs: jobs::JobRegistry::default(),
         };
-        let r = generate_point_cloud_from_state(&state).unwrap();
+        let job = jobs::JobContext::detached(jobs::JobKind::GeneratePointCloud);
+        let r = generate_point_cloud_from_state(&state, &job).unwrap();
         assert_eq!(r.point_count, 16);
     }
 
+    #[test]
+    fn depth_map_export_streams_without_cache() {
+        let depth = python_bridge::DepthMapOutput {
+            width: 5,
+            height: 3,
+            depth: vec![0.8f32; 15],
+        };
+        let state = AppState {
+            depth: Mutex::new(Some(depth)),
+            adjustment_params: Mutex::new(DepthAdjustmentParams::default()),
+            mask: Mutex::new(None),
+            sculpt: Mutex::new(None),
+            source_image_path: Mutex::new(None),
+            app_settings: Mutex::new(settings::AppSettings::default()),
+            undo_redo: Mutex::new(UndoRedoHistory::new()),
+            last_point_cloud: Mutex::new(None),
+            point_cloud_source: Mutex::new(None),
+            depth_model_id: Mutex::new(None),
+            jobs: jobs::JobRegistry::default(),
+        };
+        let path = std::env::temp_dir().join("sp3d_stream_export_test.pts");
+        let format: ExportFormat = "pts".parse().unwrap();
+        let options = ExportOptions::resolve(&format.options_schema(), None).unwrap();
+
+        let job = jobs::JobContext::detached(jobs::JobKind::Export);
+        let (_, receipt) = export_snapshot_to_file(
+            &ExportSnapshot::from_state(&state).unwrap(),
+            format,
+            &options,
+            &path,
+            &job,
+        )
+        .unwrap();
+        assert!(receipt.verified);
+        assert_eq!(receipt.point_count, 15);
+        let text = std::fs::read_to_string(&path).unwrap();
+        let _ = std::fs::remove_file(&path);
+        assert_eq!(text.lines().next(), Some("15"));
+        assert_eq!(text.lines().count(), 16);
+        assert!(state.last_point_cloud.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn cancelled_export_leaves_no_file() {
+        let state = AppState {
+            depth: Mutex::new(Some(python_bridge::DepthMapOutput {
+                width: 5,
+                height: 3,
+                depth: vec![0.8f32; 15],
+            })),
+            adjustment_params: Mutex::new(DepthAdjustmentParams::default()),
+            mask: Mutex::new(None),
+            sculpt: Mutex::new(None),
+            source_image_path: Mutex::new(None),
+            app_settings: Mutex::new(settings::AppSettings::default()),
+            undo_redo: Mutex::new(UndoRedoHistory::new()),
+            last_point_cloud: Mutex::new(None),
+            point_cloud_source: Mutex::new(None),
+            depth_model_id: Mutex::new(None),
+            jobs: jobs::JobRegistry::default(),
+        };
+        let job = state
+            .jobs
+            .start(jobs::JobKind::Export, Arc::new(|_: &jobs::JobProgress| {}));
+        assert!(state.jobs.cancel(job.id));
+        let path = std::env::temp_dir().join("sp3d_cancelled_export_test.ply");
+        let format: ExportFormat = "ply".parse().unwrap();
+        let options = ExportOptions::resolve(&format.options_schema(), None).unwrap();
+
+        let err = export_snapshot_to_file(
+            &ExportSnapshot::from_state(&state).unwrap(),
+            format,
+            &options,
+            &path,
+            &job,
+        )
+        .unwrap_err();
+        assert!(err.contains(jobs::JOB_CANCELLED));
+        assert!(!path.exists());
+        let dir_entries = std::fs::read_dir(std::env::temp_dir())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                e.file_name()
+                    .to_string_lossy()
+                    .contains("sp3d_cancelled_export_test")
+            })
+            .count();
+        assert_eq!(dir_entries, 0);
+    }
+
+    #[test]
+    fn export_recipe_replays_to_same_fit() {
+        let image = std::env::temp_dir().join("sp3d_recipe_replay_test.png");
+        std::fs::write(&image, b"not really a png").unwrap();
+        let image_str = image.to_string_lossy().to_string();
+        let depth = python_bridge::DepthMapOutput {
+            width: 4,
+            height: 3,
+            depth: (0..12).map(|i| i as f32 / 11.0).collect(),
+        };
+        let state = AppState {
+            depth: Mutex::new(None),
+            adjustment_params: Mutex::new(DepthAdjustmentParams::default()),
+            mask: Mutex::new(None),
+            sculpt: Mutex::new(None),
+            source_image_path: Mutex::new(None),
+            app_settings: Mutex::new(settings::AppSettings::default()),
+            undo_redo: Mutex::new(UndoRedoHistory::new()),
+            last_point_cloud: Mutex::new(None),
+            point_cloud_source: Mutex::new(None),
+            depth_model_id: Mutex::new(None),
+            jobs: jobs::JobRegistry::default(),
+        };
+        store_depth_map(&state, &depth, &image_str, &["MODEL stub".to_string()]).unwrap();
+        state.adjustment_params.lock().unwrap().operations = vec![depth_adjust::AdjustmentOp::new(
+            depth_adjust::AdjustmentKind::Invert,
+        )];
+        let mut mask = mask::MaskBitmap::all_false(4, 3);
+        mask.set_region(0, 0, 2, 2, true);
+        *state.mask.lock().unwrap() = Some(mask);
+
+        let job = jobs::JobContext::detached(jobs::JobKind::GeneratePointCloud);
+        let original = generate_point_cloud_from_state(&state, &job).unwrap();
+        let format: ExportFormat = "xyz".parse().unwrap();
+        let options = ExportOptions::resolve(&format.options_schema(), None).unwrap();
+        let snapshot = ExportSnapshot::from_state(&state).unwrap();
+        let recipe = build_export_recipe(
+            &snapshot,
+            recipe_source(&snapshot).unwrap(),
+            original.fit_result.clone(),
+            format,
+            &options,
+        );
+        let json = serde_json::to_string(&recipe).unwrap();
+        let recipe = recipe::ExportRecipe::from_json(&json).unwrap();
+
+        let fresh = AppState {
+            depth: Mutex::new(None),
+            adjustment_params: Mutex::new(DepthAdjustmentParams::default()),
+            mask: Mutex::new(None),
+            sculpt: Mutex::new(None),
+            source_image_path: Mutex::new(None),
+            app_settings: Mutex::new(settings::AppSettings::default()),
+            undo_redo: Mutex::new(UndoRedoHistory::new()),
+            last_point_cloud: Mutex::new(None),
+            point_cloud_source: Mutex::new(None),
+            depth_model_id: Mutex::new(None),
+            jobs: jobs::JobRegistry::default(),
+        };
+        let job = jobs::JobContext::detached(jobs::JobKind::ReplayRecipe);
+        // A replay that fails leaves the session untouched.
+        assert!(replay_recipe_into_state(&fresh, &recipe, None, None, false, &job).is_err());
+        assert!(fresh.app_settings.lock().unwrap().blank_envelope.is_none());
+        assert!(fresh.depth.lock().unwrap().is_none());
+
+        // A different model or depth map is refused unless accepted, and then reported.
+        let mut drifted = depth.clone();
+        drifted.depth[0] = 0.5;
+        let other_model = vec!["MODEL other".to_string()];
+        let err = replay_recipe_into_state(
+            &fresh,
+            &recipe,
+            None,
+            Some(((drifted.clone(), other_model.clone()), image_str.clone())),
+            false,
+            &job,
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("depth model is other") && err.contains("differs"),
+            "{}",
+            err
+        );
+        assert!(fresh.depth.lock().unwrap().is_none());
+        let smaller = python_bridge::DepthMapOutput {
+            width: 2,
+            height: 2,
+            depth: vec![0.5; 4],
+        };
+        let accepted = replay_recipe_into_state(
+            &fresh,
+            &recipe,
+            None,
+            Some(((smaller, vec!["MODEL stub".to_string()]), image_str.clone())),
+            true,
+            &job,
+        )
+        .unwrap();
+        assert_eq!(accepted.mismatches.len(), 2, "{:?}", accepted.mismatches);
+        assert!(fresh.mask.lock().unwrap().is_none());
+
+        let replayed = replay_recipe_into_state(
+            &fresh,
+            &recipe,
+            None,
+            Some(((depth, vec!["MODEL stub".to_string()]), image_str)),
+            false,
+            &job,
+        );
+        let _ = std::fs::remove_file(&image);
+        let replayed = replayed.unwrap();
+        assert!(replayed.fit_matches);
+        assert_eq!(replayed.depth_matches, Some(true));
+        assert_eq!(replayed.depth_model_id.as_deref(), Some("stub"));
+        assert!(replayed.mismatches.is_empty());
+        assert_eq!(replayed.point_count, original.point_count);
+        assert_eq!(
+            fresh.adjustment_params.lock().unwrap().operations[0].kind,
+            depth_adjust::AdjustmentKind::Invert
+        );
+        assert!(fresh.mask.lock().unwrap().as_ref().unwrap().get(1, 1));
+        assert!(fresh.last_point_cloud.lock().unwrap().is_some());
+    }
+
+    /// Sculpting feeds the adjustment stack and is undone like any other action.
+    #[test]
+    fn sculpt_stroke_is_composited_before_adjustments_and_undoable() {
+        let state = AppState {
+            depth: Mutex::new(Some(python_bridge::DepthMapOutput {
+                width: 5,
+                height: 5,
+                depth: vec![0.2f32; 25],
+            })),
+            adjustment_params: Mutex::new(DepthAdjustmentParams::with_operations([
+                depth_adjust::AdjustmentKind::Invert,
+            ])),
+            mask: Mutex::new(None),
+            sculpt: Mutex::new(None),
+            source_image_path: Mutex::new(None),
+            app_settings: Mutex::new(settings::AppSettings::default()),
+            undo_redo: Mutex::new(UndoRedoHistory::new()),
+            last_point_cloud: Mutex::new(None),
+            point_cloud_source: Mutex::new(None),
+            depth_model_id: Mutex::new(None),
+            jobs: jobs::JobRegistry::default(),
+        };
+        let stroke = sculpt::SculptStroke {
+            points: vec![sculpt::StrokePoint { x: 2.5, y: 2.5 }],
+            radius_px: 1.0,
+            falloff: sculpt::BrushFalloff::Constant,
+            op: sculpt::SculptOp::Raise,
+            strength: 0.3,
+        };
+        apply_sculpt_stroke_to_state(&state, &stroke).unwrap();
+        let (adjusted, _, _) = current_adjusted_depth(&state).unwrap().unwrap();
+        assert!((adjusted[12] - 0.5).abs() < 1e-6, "raised then inverted");
+        assert!((adjusted[0] - 0.8).abs() < 1e-6);
+
+        let mut cmd = state.undo_redo.lock().unwrap().pop_undo().unwrap();
+        cmd.apply_previous(&mut UndoTarget {
+            params: &mut state.adjustment_params.lock().unwrap(),
+            mask: &mut state.mask.lock().unwrap(),
+            sculpt: &mut state.sculpt.lock().unwrap(),
+        });
+        let (adjusted, _, _) = current_adjusted_depth(&state).unwrap().unwrap();
+        assert!((adjusted[12] - 0.8).abs() < 1e-6);
+
+        let off_map = sculpt::SculptStroke {
+            points: vec![sculpt::StrokePoint { x: 50.0, y: 50.0 }],
+            ..stroke
+        };
+        apply_sculpt_stroke_to_state(&state, &off_map).unwrap();
+        assert!(!state.undo_redo.lock().unwrap().can_undo());
+    }
+
+    #[test]
+    fn imported_point_cloud_is_fitted_kept_and_refitted() {
+        let path = std::env::temp_dir().join("sp3d_import_state_test.xyz");
+        std::fs::write(&path, "0 0 0\n1000 500 200\n250 100 50\n").unwrap();
+        let state = AppState {
+            depth: Mutex::new(None),
+            adjustment_params: Mutex::new(DepthAdjustmentParams::default()),
+            mask: Mutex::new(None),
+            sculpt: Mutex::new(None),
+            source_image_path: Mutex::new(Some("/tmp/photo.png".to_string())),
+            app_settings: Mutex::new(settings::AppSettings::default()),
+            undo_redo: Mutex::new(UndoRedoHistory::new()),
+            last_point_cloud: Mutex::new(None),
+            point_cloud_source: Mutex::new(None),
+            depth_model_id: Mutex::new(None),
+            jobs: jobs::JobRegistry::default(),
+        };
+
+        let result = import_point_cloud_into_state(&state, &path.to_string_lossy());
+        let _ = std::fs::remove_file(&path);
+        let result = result.unwrap();
+        assert_eq!(result.point_count, 3);
+        assert_eq!(result.fit_result.outliers, 0);
+
+        // Depth/sampling invalidation keeps the imported cloud.
+        invalidate_point_cloud_cache(&state).unwrap();
+        assert!(state.last_point_cloud.lock().unwrap().is_some());
+        let snapshot = ExportSnapshot::from_state(&state).unwrap();
+        assert_eq!(
+            snapshot.metadata().source_image.as_deref(),
+            Some("sp3d_import_state_test.xyz")
+        );
+
+        let small = BlankEnvelope::cube(20.0, 1.0);
+        refit_imported_point_cloud(&state, &small).unwrap();
+        let guard = state.last_point_cloud.lock().unwrap();
+        let cached = guard.as_ref().unwrap();
+        assert!(cached.fit_result.scale < result.fit_result.scale);
+        assert_eq!(cached.fit_result.outliers, 0);
+        assert!(cached
+            .points
+            .iter()
+            .flatten()
+            .all(|&v| (0.999..=19.001).contains(&v)));
+        drop(guard);
+
+        // An export snapshot taken before the refit still writes the cloud it saw.
+        let out = std::env::temp_dir().join("sp3d_import_snapshot_test.xyz");
+        let format: ExportFormat = "xyz".parse().unwrap();
+        let options = ExportOptions::resolve(&format.options_schema(), None).unwrap();
+        let job = jobs::JobContext::detached(jobs::JobKind::Export);
+        let (fit, receipt) =
+            export_snapshot_to_file(&snapshot, format, &options, &out, &job).unwrap();
+        let _ = std::fs::remove_file(&out);
+        assert_eq!(receipt.point_count, 3);
+        assert_eq!(fit.scale, result.fit_result.scale);
+    }
+
+    #[test]
+    fn imported_mesh_is_sampled_and_cached() {
+        let path = std::env::temp_dir().join("sp3d_import_mesh_test.obj");
+        std::fs::write(&path, "v 0 0 0\nv 10 0 0\nv 0 10 0\nf 1 2 3\n").unwrap();
+        let state = AppState {
+            depth: Mutex::new(None),
+            adjustment_params: Mutex::new(DepthAdjustmentParams::default()),
+            mask: Mutex::new(None),
+            sculpt: Mutex::new(None),
+            source_image_path: Mutex::new(None),
+            app_settings: Mutex::new(settings::AppSettings::default()),
+            undo_redo: Mutex::new(UndoRedoHistory::new()),
+            last_point_cloud: Mutex::new(None),
+            point_cloud_source: Mutex::new(None),
+            depth_model_id: Mutex::new(None),
+            jobs: jobs::JobRegistry::default(),
+        };
+        let sampling = MeshSampling::Surface {
+            density_per_mm2: 1.0,
+        };
+
+        let result = import_mesh_into_state(&state, &path.to_string_lossy(), &sampling);
+        let _ = std::fs::remove_file(&path);
+        let result = result.unwrap();
+        assert!(result.point_count > 0);
+        assert_eq!(result.fit_result.point_count, result.point_count);
+        assert!(state
+            .point_cloud_source
+            .lock()
+            .unwrap()
+            .as_deref()
+            .is_some_and(|p| p.ends_with("sp3d_import_mesh_test.obj")));
+    }
+
     #[test]
     fn load_image_rejects_empty_path() {
         let err = load_image("".to_string()).unwrap_err();
@@ -1381,13 +2856,14 @@ mod tests {
     #[test]
     fn apply_adjustments_with_mask_none_equals_full() {
         let depth = vec![0.0, 0.25, 0.5, 0.75, 1.0];
-        let params = DepthAdjustmentParams {
+        let params: DepthAdjustmentParams = depth_adjust::FlatAdjustmentParams {
             brightness: 0.1,
             gamma: 1.2,
             ..Default::default()
-        };
-        let out = apply_adjustments_with_mask(&depth, 5, 1, &params, None);
-        let expected = apply_adjustments(&depth, &params);
+        }
+        .into();
+        let out = apply_adjustments_with_mask(&depth, 5, 1, &params, None, None);
+        let expected = apply_adjustments(&depth, 5, 1, &params, None, None);
         for (a, b) in out.iter().zip(expected.iter()) {
             assert!((a - b).abs() < 1e-6, "no mask should match full adjustment");
         }
@@ -1397,14 +2873,15 @@ mod tests {
     #[test]
     fn apply_adjustments_with_mask_isolates_region() {
         let depth = vec![0.5, 0.5, 0.5, 0.5]; // 4x1
-        let params = DepthAdjustmentParams {
+        let params: DepthAdjustmentParams = depth_adjust::FlatAdjustmentParams {
             brightness: 0.5, // would make 0.5 -> 1.0
             ..Default::default()
-        };
+        }
+        .into();
         let mut mask = mask::MaskBitmap::all_false(4, 1);
         mask.set(1, 0, true);
         mask.set(2, 0, true);
-        let out = apply_adjustments_with_mask(&depth, 4, 1, &params, Some(&mask));
+        let out = apply_adjustments_with_mask(&depth, 4, 1, &params, Some(&mask), None);
         assert!((out[0] - 0.5).abs() < 1e-6, "unmasked stays original");
         assert!((out[3] - 0.5).abs() < 1e-6, "unmasked stays original");
         assert!((out[1] - 1.0).abs() < 1e-5, "masked gets adjusted");
@@ -1415,14 +2892,15 @@ mod tests {
     #[test]
     fn apply_adjustments_with_mask_feather_blend() {
         let depth = vec![0.5, 0.5, 0.5]; // 3x1
-        let params = DepthAdjustmentParams {
+        let params: DepthAdjustmentParams = depth_adjust::FlatAdjustmentParams {
             brightness: 0.5,
             feather_radius_px: 1.0,
             ..Default::default()
-        };
+        }
+        .into();
         let mut mask = mask::MaskBitmap::all_false(3, 1);
         mask.set(1, 0, true); // only center masked
-        let out = apply_adjustments_with_mask(&depth, 3, 1, &params, Some(&mask));
+        let out = apply_adjustments_with_mask(&depth, 3, 1, &params, Some(&mask), None);
         // With feather, center is blended (between original 0.5 and adjusted 1.0)
         assert!((0.5..=1.0).contains(&out[1]), "center should be blended");
         assert!(
@@ -1431,6 +2909,92 @@ mod tests {
         );
     }
 
+    /// Spatial filters only change the masked region.
+    #[test]
+    fn apply_adjustments_with_mask_filters_only_masked_region() {
+        let mut depth = vec![0.5; 25]; // 5x5, speckle in both halves
+        depth[6] = 1.0;
+        depth[8] = 1.0;
+        let params: DepthAdjustmentParams = depth_adjust::FlatAdjustmentParams {
+            spatial_filters: vec![depth_filter::SpatialFilter::Median { radius_px: 1 }],
+            ..Default::default()
+        }
+        .into();
+        let mut mask = mask::MaskBitmap::all_false(5, 5);
+        mask.set(1, 1, true);
+        let out = apply_adjustments_with_mask(&depth, 5, 5, &params, Some(&mask), None);
+        assert!((out[6] - 0.5).abs() < 1e-6, "masked speckle removed");
+        assert!((out[8] - 1.0).abs() < 1e-6, "unmasked speckle kept");
+    }
+
+    /// Detail transfer needs the source image and is masked like the other stages.
+    #[test]
+    fn apply_adjustments_with_mask_detail_transfer_uses_source() {
+        let depth = vec![0.5; 9]; // 3x3, bright source pixel in the centre
+        let source = image::RgbImage::from_fn(3, 3, |x, y| {
+            let v = if (x, y) == (1, 1) { 255 } else { 0 };
+            image::Rgb([v, v, v])
+        });
+        let params: DepthAdjustmentParams = depth_adjust::FlatAdjustmentParams {
+            detail_transfer: Some(depth_filter::DetailTransfer {
+                strength: 0.5,
+                cutoff_px: 1.0,
+            }),
+            ..Default::default()
+        }
+        .into();
+        let without_source = apply_adjustments_with_mask(&depth, 3, 3, &params, None, None);
+        assert_eq!(without_source, depth);
+        let out = apply_adjustments_with_mask(&depth, 3, 3, &params, None, Some(&source));
+        assert!(out[4] > 0.6);
+        let mask = mask::MaskBitmap::all_false(3, 3);
+        let masked = apply_adjustments_with_mask(&depth, 3, 3, &params, Some(&mask), Some(&source));
+        assert_eq!(masked, depth);
+    }
+
+    /// Mask-rule inpainting fills the selected pixels from the unselected ones around them.
+    #[test]
+    fn apply_adjustments_with_mask_inpaints_selected_pixels() {
+        let mut depth = vec![0.4; 9]; // 3x3, glass reflection in the centre
+        depth[4] = 0.0;
+        let params =
+            DepthAdjustmentParams::with_operations([depth_adjust::AdjustmentKind::Inpaint(
+                depth_inpaint::Inpaint {
+                    holes: depth_inpaint::HoleRule::Mask,
+                    method: depth_inpaint::InpaintMethod::Diffusion,
+                    radius_px: 5,
+                },
+            )]);
+        let mut mask = mask::MaskBitmap::all_false(3, 3);
+        mask.set(1, 1, true);
+        let out = apply_adjustments_with_mask(&depth, 3, 3, &params, Some(&mask), None);
+        assert!((out[4] - 0.4).abs() < 1e-4);
+        assert_eq!(
+            apply_adjustments_with_mask(&depth, 3, 3, &params, None, None),
+            depth
+        );
+    }
+
+    /// Terracing only snaps the masked region.
+    #[test]
+    fn apply_adjustments_with_mask_terraces_only_masked_region() {
+        let depth = vec![0.3, 0.3, 0.7, 0.7]; // 4x1
+        let params: DepthAdjustmentParams = depth_adjust::FlatAdjustmentParams {
+            terracing: Some(depth_terrace::Terracing {
+                levels: 2,
+                spacing: depth_terrace::TerraceSpacing::Even,
+                transition: 0.0,
+            }),
+            ..Default::default()
+        }
+        .into();
+        let mut mask = mask::MaskBitmap::all_false(4, 1);
+        mask.set(0, 0, true);
+        mask.set(2, 0, true);
+        let out = apply_adjustments_with_mask(&depth, 4, 1, &params, Some(&mask), None);
+        assert_eq!(out, vec![0.0, 0.3, 1.0, 0.7]);
+    }
+
     /// JR2-202: When Python exits non-zero (e.g. invalid image), Rust returns Err without panic.
     #[test]
     fn subprocess_python_nonzero_exit_returns_err() {
@@ -1618,12 +3182,13 @@ mod tests {
     #[test]
     fn apply_adjustments_with_mask_no_mask_matches_full() {
         let depth = vec![0.2, 0.5, 0.8];
-        let params = DepthAdjustmentParams {
+        let params: DepthAdjustmentParams = depth_adjust::FlatAdjustmentParams {
             brightness: 0.1,
             ..Default::default()
-        };
-        let out = apply_adjustments_with_mask(&depth, 3, 1, &params, None);
-        let expected = apply_adjustments(&depth, &params);
+        }
+        .into();
+        let out = apply_adjustments_with_mask(&depth, 3, 1, &params, None, None);
+        let expected = apply_adjustments(&depth, 3, 1, &params, None, None);
         assert_eq!(out.len(), expected.len());
         for (a, b) in out.iter().zip(expected.iter()) {
             assert!((a - b).abs() < 1e-6, "no mask should match full apply");
@@ -1634,12 +3199,13 @@ mod tests {
     #[test]
     fn apply_adjustments_with_mask_all_false_unchanged() {
         let depth = vec![0.2, 0.5, 0.8];
-        let params = DepthAdjustmentParams {
+        let params: DepthAdjustmentParams = depth_adjust::FlatAdjustmentParams {
             brightness: 0.2,
             ..Default::default()
-        };
+        }
+        .into();
         let mask = mask::MaskBitmap::all_false(3, 1);
-        let out = apply_adjustments_with_mask(&depth, 3, 1, &params, Some(&mask));
+        let out = apply_adjustments_with_mask(&depth, 3, 1, &params, Some(&mask), None);
         assert_eq!(out.len(), depth.len());
         for (a, o) in out.iter().zip(depth.iter()) {
             assert!((a - o).abs() < 1e-6, "mask all false => original");
@@ -1650,13 +3216,14 @@ mod tests {
     #[test]
     fn apply_adjustments_with_mask_single_pixel_adjusted() {
         let depth = vec![0.0, 0.5, 1.0];
-        let params = DepthAdjustmentParams {
+        let params: DepthAdjustmentParams = depth_adjust::FlatAdjustmentParams {
             brightness: 0.2,
             ..Default::default()
-        };
+        }
+        .into();
         let mut mask = mask::MaskBitmap::all_false(3, 1);
         mask.set(1, 0, true); // center pixel only
-        let out = apply_adjustments_with_mask(&depth, 3, 1, &params, Some(&mask));
+        let out = apply_adjustments_with_mask(&depth, 3, 1, &params, Some(&mask), None);
         assert!((out[0] - 0.0).abs() < 1e-6, "unmasked stays 0");
         assert!((out[1] - 0.7).abs() < 1e-5, "masked 0.5+0.2=0.7");
         assert!((out[2] - 1.0).abs() < 1e-6, "unmasked stays 1");
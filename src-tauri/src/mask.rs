@@ -4,6 +4,9 @@
 //! Mask bitmap for regional depth adjustments (BACK-1201, ARCH-502).
 //! Row-major packed bits; dimensions must match the current depth map.
 
+/// Largest mask side (px): the largest depth map, as images are downsampled to this on load.
+pub const MAX_MASK_DIMENSION: u32 = crate::image_loading::MAX_DIMENSION;
+
 /// 2D boolean mask matching depth map dimensions. Stored as packed bits (1 bit per pixel).
 #[derive(Debug, Clone)]
 pub struct MaskBitmap {
@@ -88,6 +91,57 @@ impl MaskBitmap {
         (self.data[byte_idx] >> bit_idx) & 1 != 0
     }
 
+    /// Row-major run lengths, alternating unset/set and starting with an unset run (which
+    /// may be 0). Compact for the large uniform regions masks usually have.
+    pub fn to_rle(&self) -> Vec<u32> {
+        let n = (self.width as usize).saturating_mul(self.height as usize);
+        let mut runs = Vec::new();
+        let mut current = false;
+        let mut len = 0u32;
+        for i in 0..n {
+            let v = self.get_at_index(i);
+            if v != current {
+                runs.push(len);
+                current = v;
+                len = 0;
+            }
+            len += 1;
+        }
+        runs.push(len);
+        runs
+    }
+
+    /// Inverse of [`Self::to_rle`]. Fails if a side exceeds [`MAX_MASK_DIMENSION`] or the
+    /// runs don't cover exactly width × height.
+    pub fn from_rle(width: u32, height: u32, runs: &[u32]) -> Result<Self, String> {
+        if width > MAX_MASK_DIMENSION || height > MAX_MASK_DIMENSION {
+            return Err(format!(
+                "Mask is {}x{}, larger than the {}x{} maximum",
+                width, height, MAX_MASK_DIMENSION, MAX_MASK_DIMENSION
+            ));
+        }
+        let expected = width as u64 * height as u64;
+        let total: u64 = runs.iter().map(|&r| r as u64).sum();
+        if total != expected {
+            return Err(format!(
+                "Mask runs cover {} pixels, expected {}x{}",
+                total, width, height
+            ));
+        }
+        let mut m = Self::all_false(width, height);
+        let mut i = 0usize;
+        for (k, &run) in runs.iter().enumerate() {
+            let value = k % 2 == 1;
+            for j in i..i + run as usize {
+                if value {
+                    m.data[j / 8] |= 1u8 << (j % 8);
+                }
+            }
+            i += run as usize;
+        }
+        Ok(m)
+    }
+
     /// Check that (width, height) match the mask dimensions.
     pub fn dimensions_match(&self, width: u32, height: u32) -> bool {
         self.width == width && self.height == height
@@ -194,6 +248,24 @@ mod tests {
         assert!(!m.dimensions_match(99, 50));
     }
 
+    #[test]
+    fn rle_roundtrip() {
+        let mut m = MaskBitmap::all_false(7, 5);
+        m.set_region(2, 1, 3, 2, true);
+        m.set(6, 4, true);
+        let runs = m.to_rle();
+        assert_eq!(runs[0], 9);
+        assert_eq!(runs.iter().sum::<u32>(), 35);
+        let back = MaskBitmap::from_rle(7, 5, &runs).unwrap();
+        assert_eq!(back.to_bool_vec(), m.to_bool_vec());
+
+        assert_eq!(MaskBitmap::all_false(4, 2).to_rle(), vec![8]);
+        assert!(MaskBitmap::from_rle(4, 2, &[3, 4]).is_err());
+        // Oversized dimensions are rejected before the bitmap is allocated.
+        let side = MAX_MASK_DIMENSION + 1;
+        assert!(MaskBitmap::from_rle(side, side, &[side * side]).is_err());
+    }
+
     #[test]
     fn from_bool_vec_roundtrip() {
         let w = 4u32;
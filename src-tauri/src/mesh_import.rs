@@ -0,0 +1,578 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Mesh import (STL / OBJ) as a point cloud input source.
+//!
+//! Reads ASCII and binary STL and Wavefront OBJ triangle meshes, fits the mesh into the
+//! blank with [`fit_to_blank`] (on its vertices), then samples points in blank millimetres:
+//!
+//! - [`MeshSampling::Surface`]: points spread over each triangle in proportion to its area
+//!   (`density_per_mm2` points per mm²). Placement uses a low-discrepancy (R2) sequence so
+//!   the result is deterministic and evenly spread without a random generator.
+//! - [`MeshSampling::Volume`]: voxel centres on a cubic grid of `pitch_mm` that lie inside
+//!   the mesh. Inside-ness is decided per (x, y) column by pairing the sorted Z crossings of
+//!   the mesh surface, so the mesh should be closed (watertight).
+//!
+//! Sampling after fitting keeps densities and pitches in real engraving units regardless of
+//! the units the model was authored in.
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::blank_envelope::{fit_to_blank, BlankEnvelope, FitResult};
+use crate::import::MAX_IMPORT_POINTS;
+
+/// Default surface sampling density (points per mm²).
+pub const DEFAULT_SURFACE_DENSITY_PER_MM2: f32 = 4.0;
+
+/// Default interior voxel pitch (mm).
+pub const DEFAULT_VOLUME_PITCH_MM: f32 = 0.5;
+
+/// How to turn a mesh into points (densities and pitches are in blank millimetres).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(
+    tag = "mode",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum MeshSampling {
+    /// Sample the surface at `density_per_mm2` points per mm² of fitted area.
+    Surface { density_per_mm2: f32 },
+    /// Fill the interior with voxel centres spaced `pitch_mm` apart.
+    Volume { pitch_mm: f32 },
+}
+
+impl Default for MeshSampling {
+    fn default() -> Self {
+        MeshSampling::Surface {
+            density_per_mm2: DEFAULT_SURFACE_DENSITY_PER_MM2,
+        }
+    }
+}
+
+impl MeshSampling {
+    /// Validate sampling parameters (e.g. Tauri `import_mesh`).
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            MeshSampling::Surface { density_per_mm2 } => {
+                if !density_per_mm2.is_finite() || density_per_mm2 <= 0.0 {
+                    return Err("Surface density must be positive".to_string());
+                }
+            }
+            MeshSampling::Volume { pitch_mm } => {
+                if !pitch_mm.is_finite() || pitch_mm <= 0.0 {
+                    return Err("Voxel pitch must be positive".to_string());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Supported mesh file formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshFormat {
+    /// STL, ASCII or binary.
+    Stl,
+    /// Wavefront OBJ (`v` and `f` records; polygons are fan-triangulated).
+    Obj,
+}
+
+impl MeshFormat {
+    /// Detect the format from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "stl" => Some(MeshFormat::Stl),
+            "obj" => Some(MeshFormat::Obj),
+            _ => None,
+        }
+    }
+}
+
+/// Indexed triangle mesh.
+#[derive(Debug, Clone, Default)]
+pub struct Mesh {
+    pub vertices: Vec<[f32; 3]>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl Mesh {
+    fn triangle(&self, t: &[u32; 3]) -> [[f32; 3]; 3] {
+        [
+            self.vertices[t[0] as usize],
+            self.vertices[t[1] as usize],
+            self.vertices[t[2] as usize],
+        ]
+    }
+}
+
+/// Validate a mesh import path (shared input checks plus `.stl` / `.obj`).
+pub fn validate_mesh_path(path: &str) -> Result<std::path::PathBuf> {
+    let canonical = crate::import::validate_input_file(path)?;
+    ensure!(
+        MeshFormat::from_path(&canonical).is_some(),
+        "unsupported mesh format (expected stl or obj)"
+    );
+    Ok(canonical)
+}
+
+/// Read a mesh file, detecting the format from its extension.
+pub fn read_mesh_file(path: &Path) -> Result<Mesh> {
+    let format = MeshFormat::from_path(path)
+        .ok_or_else(|| anyhow!("unsupported mesh format (expected stl or obj)"))?;
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {:?}", path))?;
+    let mesh = match format {
+        MeshFormat::Stl => read_stl(&bytes)?,
+        MeshFormat::Obj => {
+            read_obj(std::str::from_utf8(&bytes).context("OBJ is not valid UTF-8")?)?
+        }
+    };
+    ensure!(!mesh.triangles.is_empty(), "mesh contains no triangles");
+    Ok(mesh)
+}
+
+// ============================================================================
+// STL
+// ============================================================================
+
+/// Read an STL mesh (binary when the size matches the triangle count, else ASCII).
+///
+/// STL stores unshared vertices; each facet contributes three vertices.
+pub fn read_stl(bytes: &[u8]) -> Result<Mesh> {
+    if bytes.len() >= 84 {
+        let count = u32::from_le_bytes(bytes[80..84].try_into().unwrap()) as usize;
+        if bytes.len() == 84 + count * 50 {
+            return read_stl_binary(&bytes[84..], count);
+        }
+    }
+    ensure!(
+        bytes.starts_with(b"solid"),
+        "not an STL file (binary size mismatch and no 'solid' header)"
+    );
+    read_stl_ascii(std::str::from_utf8(bytes).context("ASCII STL is not valid UTF-8")?)
+}
+
+fn read_stl_binary(body: &[u8], count: usize) -> Result<Mesh> {
+    let mut mesh = Mesh {
+        vertices: Vec::with_capacity(count * 3),
+        triangles: Vec::with_capacity(count),
+    };
+    for facet in body.chunks_exact(50) {
+        // 12 bytes normal, 3 × 12 bytes vertices, 2 bytes attribute count.
+        let base = mesh.vertices.len() as u32;
+        for v in 0..3 {
+            let at = 12 + v * 12;
+            let f = |i: usize| {
+                f32::from_le_bytes(facet[at + i * 4..at + i * 4 + 4].try_into().unwrap())
+            };
+            mesh.vertices.push([f(0), f(1), f(2)]);
+        }
+        mesh.triangles.push([base, base + 1, base + 2]);
+    }
+    Ok(mesh)
+}
+
+fn read_stl_ascii(text: &str) -> Result<Mesh> {
+    let mut mesh = Mesh::default();
+    let mut facet: Vec<[f32; 3]> = Vec::with_capacity(3);
+    for (i, line) in text.lines().enumerate() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("vertex") => {
+                let mut coord = || -> Result<f32> {
+                    let t = tokens
+                        .next()
+                        .ok_or_else(|| anyhow!("line {}: vertex needs 3 coordinates", i + 1))?;
+                    t.parse()
+                        .with_context(|| format!("line {}: invalid number '{}'", i + 1, t))
+                };
+                facet.push([coord()?, coord()?, coord()?]);
+            }
+            Some("endloop") => {
+                ensure!(
+                    facet.len() == 3,
+                    "line {}: facet has {} vertices (expected 3)",
+                    i + 1,
+                    facet.len()
+                );
+                let base = mesh.vertices.len() as u32;
+                mesh.vertices.append(&mut facet);
+                mesh.triangles.push([base, base + 1, base + 2]);
+            }
+            _ => {}
+        }
+    }
+    Ok(mesh)
+}
+
+// ============================================================================
+// OBJ
+// ============================================================================
+
+/// Read a Wavefront OBJ mesh: `v` vertices and `f` faces (`i`, `i/t`, `i//n`, `i/t/n`,
+/// negative indices relative to the end). Faces with more than three corners are fanned.
+pub fn read_obj(text: &str) -> Result<Mesh> {
+    let mut mesh = Mesh::default();
+    for (i, line) in text.lines().enumerate() {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("v") => {
+                let coords: Vec<f32> = tokens
+                    .take(3)
+                    .map(|t| {
+                        t.parse()
+                            .with_context(|| format!("line {}: invalid number '{}'", i + 1, t))
+                    })
+                    .collect::<Result<_>>()?;
+                ensure!(
+                    coords.len() == 3,
+                    "line {}: vertex needs 3 coordinates",
+                    i + 1
+                );
+                mesh.vertices.push([coords[0], coords[1], coords[2]]);
+            }
+            Some("f") => {
+                let corners: Vec<u32> = tokens
+                    .map(|t| obj_index(t, mesh.vertices.len(), i + 1))
+                    .collect::<Result<_>>()?;
+                ensure!(
+                    corners.len() >= 3,
+                    "line {}: face needs at least 3 vertices",
+                    i + 1
+                );
+                for k in 1..corners.len() - 1 {
+                    mesh.triangles
+                        .push([corners[0], corners[k], corners[k + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(mesh)
+}
+
+fn obj_index(token: &str, vertex_count: usize, line_no: usize) -> Result<u32> {
+    let first = token.split('/').next().unwrap_or("");
+    let idx: i64 = first
+        .parse()
+        .with_context(|| format!("line {}: invalid face index '{}'", line_no, token))?;
+    let resolved = match idx {
+        0 => bail!("line {}: face index 0 is invalid", line_no),
+        n if n > 0 => n - 1,
+        n => vertex_count as i64 + n,
+    };
+    ensure!(
+        (0..vertex_count as i64).contains(&resolved),
+        "line {}: face index {} out of range",
+        line_no,
+        idx
+    );
+    Ok(resolved as u32)
+}
+
+// ============================================================================
+// Sampling
+// ============================================================================
+
+/// Fit `mesh` into `envelope` and sample it. Returns the points (blank mm) and the fit.
+///
+/// `FitResult::point_count` is the number of sampled points.
+pub fn sample_mesh(
+    mesh: &Mesh,
+    envelope: &BlankEnvelope,
+    sampling: &MeshSampling,
+) -> Result<(Vec<[f32; 3]>, FitResult), String> {
+    sampling.validate()?;
+    let mut fitted = mesh.clone();
+    let mut fit = fit_to_blank(&mut fitted.vertices, envelope)?;
+    let points = match *sampling {
+        MeshSampling::Surface { density_per_mm2 } => sample_surface(&fitted, density_per_mm2),
+        MeshSampling::Volume { pitch_mm } => sample_volume(&fitted, pitch_mm),
+    }
+    .map_err(|e| e.to_string())?;
+    if points.is_empty() {
+        return Err("Mesh sampling produced no points (check density/pitch)".to_string());
+    }
+    fit.point_count = points.len();
+    Ok((points, fit))
+}
+
+fn triangle_area(t: &[[f32; 3]; 3]) -> f32 {
+    let a = sub(t[1], t[0]);
+    let b = sub(t[2], t[0]);
+    let c = [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ];
+    0.5 * (c[0] * c[0] + c[1] * c[1] + c[2] * c[2]).sqrt()
+}
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+/// Area-proportional surface samples; fractional counts carry over between triangles so
+/// the total matches `area * density` even for meshes of many tiny triangles.
+fn sample_surface(mesh: &Mesh, density: f32) -> Result<Vec<[f32; 3]>> {
+    let total_area: f64 = mesh
+        .triangles
+        .iter()
+        .map(|t| triangle_area(&mesh.triangle(t)) as f64)
+        .sum();
+    let expected = total_area * density as f64;
+    ensure!(
+        expected <= MAX_IMPORT_POINTS as f64,
+        "surface sampling would produce about {:.0} points (limit {}); lower the density",
+        expected,
+        MAX_IMPORT_POINTS
+    );
+
+    // R2 low-discrepancy sequence (plastic-number based).
+    const A1: f64 = 0.754_877_666_246_692_8;
+    const A2: f64 = 0.569_840_290_998_053_3;
+
+    let mut points = Vec::with_capacity(expected.ceil() as usize);
+    let mut carry = 0.0f64;
+    let mut k: u64 = 0;
+    for t in &mesh.triangles {
+        let tri = mesh.triangle(t);
+        carry += triangle_area(&tri) as f64 * density as f64;
+        let n = carry.floor();
+        carry -= n;
+        for _ in 0..n as u64 {
+            k += 1;
+            let mut u = (0.5 + A1 * k as f64).fract() as f32;
+            let mut v = (0.5 + A2 * k as f64).fract() as f32;
+            if u + v > 1.0 {
+                u = 1.0 - u;
+                v = 1.0 - v;
+            }
+            let e1 = sub(tri[1], tri[0]);
+            let e2 = sub(tri[2], tri[0]);
+            points.push(std::array::from_fn(|i| tri[0][i] + u * e1[i] + v * e2[i]));
+        }
+    }
+    Ok(points)
+}
+
+/// Sub-voxel offset (fraction of the pitch) applied to each inside/outside test ray.
+const RAY_NUDGE: [f32; 2] = [1.37e-4, 2.71e-4];
+
+/// Voxel centres inside the mesh, found by Z-ray crossing parity per (x, y) column.
+fn sample_volume(mesh: &Mesh, pitch: f32) -> Result<Vec<[f32; 3]>> {
+    let (min, max) = crate::blank_envelope::compute_bbox(&mesh.vertices)
+        .ok_or_else(|| anyhow!("mesh has no vertices"))?;
+    let cells = |axis: usize| ((max[axis] - min[axis]) / pitch).floor() as usize + 1;
+    let (nx, ny, nz) = (cells(0), cells(1), cells(2));
+    ensure!(
+        (nx as f64) * (ny as f64) * (nz as f64) <= MAX_IMPORT_POINTS as f64,
+        "voxel grid {}x{}x{} exceeds {} points; increase the pitch",
+        nx,
+        ny,
+        nz,
+        MAX_IMPORT_POINTS
+    );
+    let centre = |axis: usize, i: usize| min[axis] + (i as f32 + 0.5) * pitch;
+
+    // Z crossings of the surface for each column (x index, y index).
+    let mut columns: Vec<Vec<f32>> = vec![Vec::new(); nx * ny];
+    for t in &mesh.triangles {
+        let [a, b, c] = mesh.triangle(t);
+        let denom = (b[1] - c[1]) * (a[0] - c[0]) + (c[0] - b[0]) * (a[1] - c[1]);
+        if denom.abs() < f32::EPSILON {
+            continue; // Parallel to the ray (vertical in XY projection).
+        }
+        let lo_x = a[0].min(b[0]).min(c[0]);
+        let hi_x = a[0].max(b[0]).max(c[0]);
+        let lo_y = a[1].min(b[1]).min(c[1]);
+        let hi_y = a[1].max(b[1]).max(c[1]);
+        let ix0 = (((lo_x - min[0]) / pitch - 0.5).ceil().max(0.0)) as usize;
+        let iy0 = (((lo_y - min[1]) / pitch - 0.5).ceil().max(0.0)) as usize;
+        for iy in iy0..ny {
+            let y = centre(1, iy);
+            if y > hi_y {
+                break;
+            }
+            for ix in ix0..nx {
+                let x = centre(0, ix);
+                if x > hi_x {
+                    break;
+                }
+                // Barycentric coordinates in the XY projection. The ray is nudged off the
+                // grid so it does not pass exactly through shared edges of grid-aligned meshes
+                // (which would count one surface crossing twice).
+                let (x, y) = (x + RAY_NUDGE[0] * pitch, y + RAY_NUDGE[1] * pitch);
+                let w0 = ((b[1] - c[1]) * (x - c[0]) + (c[0] - b[0]) * (y - c[1])) / denom;
+                let w1 = ((c[1] - a[1]) * (x - c[0]) + (a[0] - c[0]) * (y - c[1])) / denom;
+                let w2 = 1.0 - w0 - w1;
+                if w0 >= 0.0 && w1 >= 0.0 && w2 >= 0.0 {
+                    columns[iy * nx + ix].push(w0 * a[2] + w1 * b[2] + w2 * c[2]);
+                }
+            }
+        }
+    }
+
+    let mut points = Vec::new();
+    for (idx, zs) in columns.iter_mut().enumerate() {
+        if zs.len() < 2 {
+            continue;
+        }
+        zs.sort_by(|p, q| p.total_cmp(q));
+        let (x, y) = (centre(0, idx % nx), centre(1, idx / nx));
+        for pair in zs.chunks_exact(2) {
+            for iz in 0..nz {
+                let z = centre(2, iz);
+                if z >= pair[0] && z <= pair[1] {
+                    points.push([x, y, z]);
+                }
+            }
+        }
+    }
+    Ok(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Axis-aligned cube [0, size]^3 as 12 outward-facing triangles.
+    fn cube_mesh(size: f32) -> Mesh {
+        let s = size;
+        let vertices = vec![
+            [0.0, 0.0, 0.0],
+            [s, 0.0, 0.0],
+            [s, s, 0.0],
+            [0.0, s, 0.0],
+            [0.0, 0.0, s],
+            [s, 0.0, s],
+            [s, s, s],
+            [0.0, s, s],
+        ];
+        let triangles = vec![
+            [0, 2, 1],
+            [0, 3, 2],
+            [4, 5, 6],
+            [4, 6, 7],
+            [0, 1, 5],
+            [0, 5, 4],
+            [1, 2, 6],
+            [1, 6, 5],
+            [2, 3, 7],
+            [2, 7, 6],
+            [3, 0, 4],
+            [3, 4, 7],
+        ];
+        Mesh {
+            vertices,
+            triangles,
+        }
+    }
+
+    fn cube_obj() -> String {
+        let mesh = cube_mesh(1.0);
+        let mut obj = String::from("# cube\n");
+        for v in &mesh.vertices {
+            obj.push_str(&format!("v {} {} {}\n", v[0], v[1], v[2]));
+        }
+        for t in &mesh.triangles {
+            obj.push_str(&format!(
+                "f {}/1/1 {}//2 {}\n",
+                t[0] + 1,
+                t[1] + 1,
+                t[2] + 1
+            ));
+        }
+        obj
+    }
+
+    #[test]
+    fn read_obj_faces_and_negative_indices() {
+        let mesh = read_obj(&cube_obj()).unwrap();
+        assert_eq!(mesh.vertices.len(), 8);
+        assert_eq!(mesh.triangles.len(), 12);
+
+        let quad = read_obj("v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nf -4 -3 -2 -1\n").unwrap();
+        assert_eq!(quad.triangles, vec![[0, 1, 2], [0, 2, 3]]);
+    }
+
+    #[test]
+    fn read_obj_rejects_out_of_range_index() {
+        assert!(read_obj("v 0 0 0\nf 1 2 3\n").is_err());
+    }
+
+    #[test]
+    fn read_stl_ascii_and_binary() {
+        let ascii = "solid t\nfacet normal 0 0 1\nouter loop\nvertex 0 0 0\nvertex 1 0 0\n\
+vertex 0 1 0\nendloop\nendfacet\nendsolid t\n";
+        let mesh = read_stl(ascii.as_bytes()).unwrap();
+        assert_eq!(mesh.triangles.len(), 1);
+        assert_eq!(mesh.vertices[1], [1.0, 0.0, 0.0]);
+
+        // Binary header may itself start with "solid"; size decides.
+        let mut binary = b"solid but binary".to_vec();
+        binary.resize(80, 0);
+        binary.extend_from_slice(&1u32.to_le_bytes());
+        for v in [
+            0.0f32, 0.0, 1.0, 0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 2.0, 0.0,
+        ] {
+            binary.extend_from_slice(&v.to_le_bytes());
+        }
+        binary.extend_from_slice(&[0, 0]);
+        let mesh = read_stl(&binary).unwrap();
+        assert_eq!(mesh.triangles.len(), 1);
+        assert_eq!(mesh.vertices[2], [0.0, 2.0, 0.0]);
+    }
+
+    #[test]
+    fn surface_sampling_matches_area_density() {
+        let envelope = BlankEnvelope::cube(22.0, 1.0);
+        let sampling = MeshSampling::Surface {
+            density_per_mm2: 0.5,
+        };
+        // Fitted cube is 20 mm per side: 6 × 400 mm² × 0.5 = 1200 points.
+        let (points, fit) = sample_mesh(&cube_mesh(3.0), &envelope, &sampling).unwrap();
+        assert!((points.len() as i64 - 1200).abs() <= 1, "{}", points.len());
+        assert_eq!(fit.point_count, points.len());
+        assert!(points
+            .iter()
+            .flatten()
+            .all(|&v| (0.999..=21.001).contains(&v)));
+    }
+
+    #[test]
+    fn volume_sampling_fills_interior() {
+        let envelope = BlankEnvelope::cube(12.0, 1.0);
+        let sampling = MeshSampling::Volume { pitch_mm: 1.0 };
+        // Fitted cube is 10 mm per side: 10^3 voxel centres inside.
+        let (points, _) = sample_mesh(&cube_mesh(1.0), &envelope, &sampling).unwrap();
+        assert_eq!(points.len(), 1000);
+        assert!(points.contains(&[5.5, 5.5, 5.5]));
+    }
+
+    #[test]
+    fn sampling_rejects_bad_params() {
+        let envelope = BlankEnvelope::default();
+        let bad = MeshSampling::Volume { pitch_mm: 0.0 };
+        assert!(sample_mesh(&cube_mesh(1.0), &envelope, &bad).is_err());
+        let too_dense = MeshSampling::Surface {
+            density_per_mm2: 1.0e9,
+        };
+        assert!(sample_mesh(&cube_mesh(1.0), &envelope, &too_dense).is_err());
+    }
+
+    #[test]
+    fn sampling_serde_is_tagged() {
+        let json = serde_json::to_string(&MeshSampling::Volume { pitch_mm: 0.25 }).unwrap();
+        assert_eq!(json, r#"{"mode":"volume","pitchMm":0.25}"#);
+        let back: MeshSampling =
+            serde_json::from_str(r#"{"mode":"surface","densityPerMm2":2.0}"#).unwrap();
+        assert_eq!(
+            back,
+            MeshSampling::Surface {
+                density_per_mm2: 2.0
+            }
+        );
+    }
+}
@@ -3,15 +3,22 @@
 
 //! Preset schema and serialization (BACK-1301, Sprint 2.3).
 //!
-//! Presets store depth adjustment params, curve control points, and mesh params as JSON.
+//! Presets store depth adjustment params, curve control points, mesh params and, when the
+//! depth map has been sculpted, the sculpt offset layer as JSON.
 //! Schema is documented in RESEARCH/architecture.md § Preset schema.
 
 use serde::{Deserialize, Serialize};
 
-use crate::depth_adjust::{preset_s_curve, CurvePoint};
+use crate::depth_adjust::{
+    preset_s_curve, AdjustmentOp, CurveInterpolation, CurvePoint, DepthAdjustmentParams,
+    FlatAdjustmentParams,
+};
+use crate::sculpt::OffsetLayer;
 
 /// Current preset schema version for forward compatibility (BACK-1301, JR2-1303).
-pub const PRESET_SCHEMA_VERSION: u32 = 1;
+/// Version 2 stores the adjustment operation stack; version 0/1 files hold the flat
+/// brightness / contrast / gamma / invert / curve fields and are migrated on load.
+pub const PRESET_SCHEMA_VERSION: u32 = 2;
 
 /// Built-in preset identifiers (BACK-1303, prd.md F2.3).
 pub const BUILTIN_PORTRAIT: &str = "Portrait";
@@ -31,123 +38,147 @@ pub fn builtin_preset_ids() -> &'static [&'static str] {
 
 /// Returns a built-in preset by id, or None if unknown (BACK-1303).
 pub fn get_builtin_preset(id: &str) -> Option<Preset> {
-    Some(match id {
-        BUILTIN_PORTRAIT => Preset {
-            schema_version: PRESET_SCHEMA_VERSION,
+    let flat = match id {
+        BUILTIN_PORTRAIT => FlatAdjustmentParams {
             brightness: 0.05,
             contrast: 1.1,
             gamma: 1.15,
-            invert: false,
-            depth_min_mm: 2.0,
             depth_max_mm: 10.0,
             curve_control_points: Some(preset_s_curve()),
-            step_x: 1,
-            step_y: 1,
-            target_width_mm: None,
-            target_height_mm: None,
+            ..Default::default()
         },
-        BUILTIN_LANDSCAPE => Preset {
-            schema_version: PRESET_SCHEMA_VERSION,
-            brightness: 0.0,
+        BUILTIN_LANDSCAPE => FlatAdjustmentParams {
             contrast: 1.05,
-            gamma: 1.0,
-            invert: false,
-            depth_min_mm: 2.0,
             depth_max_mm: 12.0,
-            curve_control_points: None,
-            step_x: 1,
-            step_y: 1,
-            target_width_mm: None,
-            target_height_mm: None,
+            ..Default::default()
         },
-        BUILTIN_HIGH_DETAIL => Preset {
-            schema_version: PRESET_SCHEMA_VERSION,
-            brightness: 0.0,
+        BUILTIN_HIGH_DETAIL => FlatAdjustmentParams {
             contrast: 1.35,
             gamma: 1.25,
-            invert: false,
-            depth_min_mm: 2.0,
             depth_max_mm: 10.0,
-            curve_control_points: None,
-            step_x: 1,
-            step_y: 1,
-            target_width_mm: None,
-            target_height_mm: None,
+            ..Default::default()
         },
-        BUILTIN_LOW_RELIEF => Preset {
-            schema_version: PRESET_SCHEMA_VERSION,
+        BUILTIN_LOW_RELIEF => FlatAdjustmentParams {
             brightness: 0.1,
             contrast: 0.9,
             gamma: 0.9,
-            invert: false,
-            depth_min_mm: 2.0,
             depth_max_mm: 6.0,
-            curve_control_points: None,
-            step_x: 1,
-            step_y: 1,
-            target_width_mm: None,
-            target_height_mm: None,
+            ..Default::default()
         },
         _ => return None,
-    })
+    };
+    Some(Preset::from_depth_params(&flat.into(), 1, 1, None, None))
 }
 
-/// Preset JSON structure: depth params, curve, mesh params (BACK-1301).
+/// Preset JSON structure: depth range, adjustment operations, mesh params (BACK-1301).
 /// Serialized with camelCase for JSON; stored under ~/.simplepicture3d/presets/ or user path.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", try_from = "PresetRepr")]
 pub struct Preset {
-    /// Schema version for migration (current = 1).
+    /// Schema version for migration (current = 2).
     pub schema_version: u32,
 
     // --- Depth adjustment (matches DepthAdjustmentParams) ---
-    pub brightness: f32,
-    pub contrast: f32,
-    pub gamma: f32,
-    pub invert: bool,
     pub depth_min_mm: f32,
     pub depth_max_mm: f32,
-
-    /// Optional curve control points; None or len < 2 means no curve.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub curve_control_points: Option<Vec<CurvePoint>>,
+    /// Adjustment operation stack, in order.
+    pub operations: Vec<AdjustmentOp>,
 
     // --- Mesh params ---
     /// Grid step X (1 = full resolution).
-    #[serde(default = "default_step")]
     pub step_x: u32,
     /// Grid step Y (1 = full resolution).
-    #[serde(default = "default_step")]
     pub step_y: u32,
 
     /// Optional target output width in mm (ADR-009).
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub target_width_mm: Option<f32>,
     /// Optional target output height in mm (ADR-009).
-    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub target_height_mm: Option<f32>,
+
+    /// Sculpt offset layer; only applied to a depth map of the same dimensions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sculpt: Option<OffsetLayer>,
+}
+
+/// On-disk layout of every schema version. `operations` (v2) takes precedence; without it the
+/// v0/v1 flat fields are required and migrated with [`FlatAdjustmentParams::operations`].
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PresetRepr {
+    schema_version: u32,
+    depth_min_mm: f32,
+    depth_max_mm: f32,
+    #[serde(default)]
+    operations: Option<Vec<AdjustmentOp>>,
+    #[serde(default)]
+    brightness: Option<f32>,
+    #[serde(default)]
+    contrast: Option<f32>,
+    #[serde(default)]
+    gamma: Option<f32>,
+    #[serde(default)]
+    invert: Option<bool>,
+    /// Optional curve control points; None or len < 2 means no curve.
+    #[serde(default)]
+    curve_control_points: Option<Vec<CurvePoint>>,
+    /// Curve interpolation; presets saved before it existed load as linear.
+    #[serde(default)]
+    curve_interpolation: CurveInterpolation,
+    #[serde(default = "default_step")]
+    step_x: u32,
+    #[serde(default = "default_step")]
+    step_y: u32,
+    #[serde(default)]
+    target_width_mm: Option<f32>,
+    #[serde(default)]
+    target_height_mm: Option<f32>,
+    #[serde(default)]
+    sculpt: Option<OffsetLayer>,
 }
 
 fn default_step() -> u32 {
     1
 }
 
+impl TryFrom<PresetRepr> for Preset {
+    type Error = String;
+
+    fn try_from(repr: PresetRepr) -> Result<Self, String> {
+        let operations = match repr.operations {
+            Some(operations) => operations,
+            None => {
+                let missing = |field: &str| format!("missing field `{}`", field);
+                FlatAdjustmentParams {
+                    brightness: repr.brightness.ok_or_else(|| missing("brightness"))?,
+                    contrast: repr.contrast.ok_or_else(|| missing("contrast"))?,
+                    gamma: repr.gamma.ok_or_else(|| missing("gamma"))?,
+                    invert: repr.invert.ok_or_else(|| missing("invert"))?,
+                    curve_control_points: repr.curve_control_points,
+                    curve_interpolation: repr.curve_interpolation,
+                    ..Default::default()
+                }
+                .operations()
+            }
+        };
+        Ok(Self {
+            schema_version: repr.schema_version,
+            depth_min_mm: repr.depth_min_mm,
+            depth_max_mm: repr.depth_max_mm,
+            operations,
+            step_x: repr.step_x,
+            step_y: repr.step_y,
+            target_width_mm: repr.target_width_mm,
+            target_height_mm: repr.target_height_mm,
+            sculpt: repr.sculpt,
+        })
+    }
+}
+
 impl Default for Preset {
     fn default() -> Self {
-        Self {
-            schema_version: PRESET_SCHEMA_VERSION,
-            brightness: 0.0,
-            contrast: 1.0,
-            gamma: 1.0,
-            invert: false,
-            depth_min_mm: 2.0,
-            depth_max_mm: 10.0,
-            curve_control_points: None,
-            step_x: 1,
-            step_y: 1,
-            target_width_mm: None,
-            target_height_mm: None,
-        }
+        Self::from_depth_params(&DepthAdjustmentParams::default(), 1, 1, None, None)
     }
 }
 
@@ -181,17 +212,10 @@ pub fn sanitize_preset_name(name: &str) -> Result<String, String> {
 }
 
 impl Preset {
-    /// Build a preset from depth params and optional curve/mesh overrides.
+    /// Build a preset from depth params and mesh settings.
     /// mesh_step_x/y and target dimensions can be passed from AppSettings or defaults.
-    #[allow(clippy::too_many_arguments)]
-    pub fn from_depth_and_mesh(
-        brightness: f32,
-        contrast: f32,
-        gamma: f32,
-        invert: bool,
-        depth_min_mm: f32,
-        depth_max_mm: f32,
-        curve_control_points: Option<Vec<CurvePoint>>,
+    pub fn from_depth_params(
+        params: &DepthAdjustmentParams,
         step_x: u32,
         step_y: u32,
         target_width_mm: Option<f32>,
@@ -199,30 +223,23 @@ impl Preset {
     ) -> Self {
         Self {
             schema_version: PRESET_SCHEMA_VERSION,
-            brightness,
-            contrast,
-            gamma,
-            invert,
-            depth_min_mm,
-            depth_max_mm,
-            curve_control_points,
+            depth_min_mm: params.depth_min_mm,
+            depth_max_mm: params.depth_max_mm,
+            operations: params.operations.clone(),
             step_x: step_x.max(1),
             step_y: step_y.max(1),
             target_width_mm,
             target_height_mm,
+            sculpt: None,
         }
     }
 
     /// Depth params for applying to DepthAdjustmentParams (and undo stack).
-    pub fn to_depth_params(&self) -> crate::depth_adjust::DepthAdjustmentParams {
-        crate::depth_adjust::DepthAdjustmentParams {
-            brightness: self.brightness,
-            contrast: self.contrast,
-            gamma: self.gamma,
-            invert: self.invert,
+    pub fn to_depth_params(&self) -> DepthAdjustmentParams {
+        DepthAdjustmentParams {
             depth_min_mm: self.depth_min_mm,
             depth_max_mm: self.depth_max_mm,
-            curve_control_points: self.curve_control_points.clone(),
+            operations: self.operations.clone(),
             feather_radius_px: 0.0, // BACK-1203: presets don't persist feather; use 0
         }
     }
@@ -245,74 +262,70 @@ impl Preset {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::depth_adjust::AdjustmentKind;
+
+    fn sample_params() -> DepthAdjustmentParams {
+        DepthAdjustmentParams {
+            depth_min_mm: 3.0,
+            depth_max_mm: 8.0,
+            operations: vec![
+                AdjustmentOp::new(AdjustmentKind::Invert),
+                AdjustmentOp {
+                    enabled: false,
+                    kind: AdjustmentKind::Gamma { gamma: 2.2 },
+                },
+                AdjustmentOp::new(AdjustmentKind::Curve {
+                    points: vec![
+                        CurvePoint { x: 0.0, y: 0.0 },
+                        CurvePoint { x: 0.5, y: 0.4 },
+                        CurvePoint { x: 1.0, y: 1.0 },
+                    ],
+                    interpolation: CurveInterpolation::MonotoneCubic,
+                }),
+                AdjustmentOp::new(AdjustmentKind::Brightness { brightness: 0.2 }),
+            ],
+            feather_radius_px: 4.0,
+        }
+    }
 
     #[test]
-    fn preset_default_has_version_1() {
+    fn preset_default_has_current_version() {
         let p = Preset::default();
-        assert_eq!(p.schema_version, 1);
+        assert_eq!(p.schema_version, PRESET_SCHEMA_VERSION);
         assert_eq!(p.step_x, 1);
         assert_eq!(p.step_y, 1);
+        assert!(p.operations.is_empty());
     }
 
     #[test]
     fn preset_roundtrip_json() {
-        let p = Preset {
-            schema_version: 1,
-            brightness: 0.2,
-            contrast: 1.1,
-            gamma: 2.2,
-            invert: true,
-            depth_min_mm: 3.0,
-            depth_max_mm: 8.0,
-            curve_control_points: Some(vec![
-                CurvePoint { x: 0.0, y: 0.0 },
-                CurvePoint { x: 1.0, y: 1.0 },
-            ]),
-            step_x: 2,
-            step_y: 2,
-            target_width_mm: Some(50.0),
-            target_height_mm: Some(70.0),
-        };
+        let p = Preset::from_depth_params(&sample_params(), 2, 2, Some(50.0), Some(70.0));
         let json = serde_json::to_string(&p).unwrap();
+        assert!(json.contains(r#""operations":[{"enabled":true,"type":"invert"}"#));
         let loaded: Preset = serde_json::from_str(&json).unwrap();
-        assert_eq!(loaded.schema_version, p.schema_version);
-        assert!((loaded.brightness - p.brightness).abs() < 1e-6);
-        assert_eq!(loaded.invert, p.invert);
-        assert_eq!(loaded.curve_control_points.as_ref().unwrap().len(), 2);
-        assert_eq!(loaded.step_x, 2);
-        assert_eq!(loaded.target_width_mm, Some(50.0));
+        assert_eq!(loaded, p);
+        let dp = loaded.to_depth_params();
+        assert_eq!(dp.operations, sample_params().operations);
+        assert_eq!((dp.depth_min_mm, dp.depth_max_mm), (3.0, 8.0));
+        // BACK-1203: feather is not part of a preset.
+        assert_eq!(dp.feather_radius_px, 0.0);
     }
 
-    /// JR2-1301: None curve round-trips cleanly; no curveControlPoints key in JSON.
+    /// JR2-1301: None target dimensions round-trip cleanly; no keys in JSON.
     #[test]
-    fn preset_roundtrip_no_curve_omits_key() {
-        let p = Preset {
-            schema_version: 1,
-            brightness: 0.0,
-            contrast: 1.0,
-            gamma: 1.0,
-            invert: false,
-            depth_min_mm: 2.0,
-            depth_max_mm: 10.0,
-            curve_control_points: None,
-            step_x: 1,
-            step_y: 1,
-            target_width_mm: None,
-            target_height_mm: None,
-        };
+    fn preset_roundtrip_no_target_omits_keys() {
+        let p = Preset::default();
         let json = serde_json::to_string(&p).unwrap();
-        assert!(
-            !json.contains("curveControlPoints"),
-            "None curve should not serialize curveControlPoints key"
-        );
+        assert!(!json.contains("targetWidthMm"));
+        assert!(!json.contains("curveControlPoints"));
         let loaded: Preset = serde_json::from_str(&json).unwrap();
-        assert_eq!(loaded.curve_control_points, None);
+        assert_eq!(loaded.target_width_mm, None);
     }
 
     /// JR2-1301: step_x/step_y default to 1 when absent from JSON (backwards compatibility).
     #[test]
     fn preset_deserialize_default_step_when_absent() {
-        let json = r#"{"schemaVersion":1,"brightness":0,"contrast":1,"gamma":1,"invert":false,"depthMinMm":2.0,"depthMaxMm":10.0}"#;
+        let json = r#"{"schemaVersion":2,"depthMinMm":2.0,"depthMaxMm":10.0,"operations":[]}"#;
         let loaded: Preset = serde_json::from_str(json).unwrap();
         assert_eq!(loaded.step_x, 1);
         assert_eq!(loaded.step_y, 1);
@@ -328,6 +341,17 @@ mod tests {
         assert_eq!(loaded.schema_version, p.schema_version);
     }
 
+    /// JR2-1301: step_x/step_y default to 1 in flat (version 1) presets too.
+    #[test]
+    fn preset_deserialize_defaults_step_when_absent_v1() {
+        let json = r#"{"schemaVersion":1,"brightness":0,"contrast":1,"gamma":1,"invert":false,"depthMinMm":2,"depthMaxMm":10}"#;
+        let loaded: Preset = serde_json::from_str(json).unwrap();
+        assert_eq!(loaded.step_x, 1);
+        assert_eq!(loaded.step_y, 1);
+        // All identity values: nothing to migrate.
+        assert!(loaded.operations.is_empty());
+    }
+
     /// JR2-1301: Built-in presets return Some and to_depth_params() has valid ranges.
     #[test]
     fn builtin_presets_valid() {
@@ -339,86 +363,62 @@ mod tests {
                 "builtin {}: depth_min_mm < depth_max_mm",
                 id
             );
-            assert!(dp.gamma > 0.0, "builtin {}: gamma > 0", id);
-            assert!(dp.contrast > 0.0, "builtin {}: contrast > 0", id);
+            assert!(!dp.operations.is_empty(), "builtin {}: has operations", id);
+            for op in &dp.operations {
+                match op.kind {
+                    AdjustmentKind::Gamma { gamma } => assert!(gamma > 0.0, "builtin {}", id),
+                    AdjustmentKind::Contrast { contrast } => {
+                        assert!(contrast > 0.0, "builtin {}", id)
+                    }
+                    _ => {}
+                }
+            }
         }
+        let portrait = get_builtin_preset(BUILTIN_PORTRAIT).unwrap();
+        assert!(portrait
+            .operations
+            .iter()
+            .any(|op| matches!(op.kind, AdjustmentKind::Curve { .. })));
+    }
+
+    #[test]
+    fn preset_roundtrip_with_sculpt_layer() {
+        let mut layer = OffsetLayer::zeros(8, 6);
+        layer.apply_stroke(
+            &[0.5; 48],
+            &crate::sculpt::SculptStroke {
+                points: vec![crate::sculpt::StrokePoint { x: 4.0, y: 3.0 }],
+                radius_px: 2.0,
+                falloff: Default::default(),
+                op: crate::sculpt::SculptOp::Raise,
+                strength: 0.1,
+            },
+        );
+        let mut p = Preset::default();
+        assert!(!serde_json::to_string(&p).unwrap().contains("sculpt"));
+        p.sculpt = Some(layer);
+        let json = serde_json::to_string(&p).unwrap();
+        assert_eq!(Preset::parse_and_validate_json(&json).unwrap(), p);
     }
 
     #[test]
     fn preset_to_depth_params() {
         let p = Preset::default();
         let dp = p.to_depth_params();
-        assert!((dp.brightness - 0.0).abs() < 1e-6);
-        assert!((dp.contrast - 1.0).abs() < 1e-6);
+        assert_eq!(dp, DepthAdjustmentParams::default());
         assert_eq!(dp.depth_min_mm, 2.0);
         assert_eq!(dp.depth_max_mm, 10.0);
     }
 
-    /// JR2-1301: Round-trip default config (from_depth_and_mesh → JSON → deserialize → state matches).
+    /// JR2-1301: Round-trip default config (from_depth_params → JSON → deserialize → state matches).
     #[test]
-    fn preset_roundtrip_from_depth_and_mesh_default() {
-        let p =
-            Preset::from_depth_and_mesh(0.0, 1.0, 1.0, false, 2.0, 10.0, None, 1, 1, None, None);
+    fn preset_roundtrip_from_depth_params_default() {
+        let p = Preset::from_depth_params(&DepthAdjustmentParams::default(), 0, 1, None, None);
+        assert_eq!(p.step_x, 1, "step clamped to at least 1");
         let json = serde_json::to_string(&p).unwrap();
         let loaded: Preset = serde_json::from_str(&json).unwrap();
-        assert_eq!(loaded.schema_version, p.schema_version);
-        assert!((loaded.brightness - p.brightness).abs() < 1e-6);
-        assert!((loaded.contrast - p.contrast).abs() < 1e-6);
-        assert!((loaded.gamma - p.gamma).abs() < 1e-6);
-        assert_eq!(loaded.invert, p.invert);
-        assert!((loaded.depth_min_mm - p.depth_min_mm).abs() < 1e-6);
-        assert!((loaded.depth_max_mm - p.depth_max_mm).abs() < 1e-6);
-        assert_eq!(
-            loaded.curve_control_points.is_none(),
-            p.curve_control_points.is_none()
-        );
-        assert_eq!(loaded.step_x, p.step_x);
-        assert_eq!(loaded.step_y, p.step_y);
-        assert_eq!(loaded.target_width_mm, p.target_width_mm);
-        assert_eq!(loaded.target_height_mm, p.target_height_mm);
-        let orig_dp = p.to_depth_params();
-        let loaded_dp = loaded.to_depth_params();
-        assert!((orig_dp.brightness - loaded_dp.brightness).abs() < 1e-6);
-        assert!((orig_dp.contrast - loaded_dp.contrast).abs() < 1e-6);
-        assert_eq!(orig_dp.invert, loaded_dp.invert);
-    }
-
-    /// JR2-1301: Round-trip custom config with curve and target dimensions.
-    #[test]
-    fn preset_roundtrip_from_depth_and_mesh_custom_curve() {
-        let curve = vec![
-            CurvePoint { x: 0.0, y: 0.0 },
-            CurvePoint { x: 0.5, y: 0.4 },
-            CurvePoint { x: 1.0, y: 1.0 },
-        ];
-        let p = Preset::from_depth_and_mesh(
-            0.1,
-            1.2,
-            1.3,
-            true,
-            3.0,
-            9.0,
-            Some(curve.clone()),
-            2,
-            3,
-            Some(50.0),
-            Some(70.0),
-        );
-        let json = serde_json::to_string(&p).unwrap();
-        let loaded: Preset = serde_json::from_str(&json).unwrap();
-        assert_eq!(loaded.schema_version, p.schema_version);
-        assert!((loaded.brightness - p.brightness).abs() < 1e-6);
-        assert_eq!(loaded.invert, p.invert);
-        assert_eq!(loaded.step_x, 2);
-        assert_eq!(loaded.step_y, 3);
-        assert_eq!(loaded.target_width_mm, Some(50.0));
-        assert_eq!(loaded.target_height_mm, Some(70.0));
-        let loaded_curve = loaded.curve_control_points.as_ref().unwrap();
-        assert_eq!(loaded_curve.len(), 3);
-        assert!((loaded_curve[1].x - 0.5).abs() < 1e-6);
-        assert!((loaded_curve[1].y - 0.4).abs() < 1e-6);
-        let loaded_dp = loaded.to_depth_params();
-        assert_eq!(loaded_dp.curve_control_points.as_ref().unwrap().len(), 3);
+        assert_eq!(loaded, p);
+        assert_eq!(loaded.to_depth_params(), p.to_depth_params());
     }
 
     // --- JR2-1302: Invalid JSON / wrong schema ---
@@ -437,10 +437,10 @@ mod tests {
 
     #[test]
     fn preset_deserialize_rejects_missing_required_field() {
-        // Valid shape but missing "brightness" (required).
+        // Flat (v1) shape without operations but missing "brightness" (required).
         let json = r#"{"schemaVersion":1,"contrast":1.0,"gamma":1.0,"invert":false,"depthMinMm":2.0,"depthMaxMm":10.0}"#;
         let err = serde_json::from_str::<Preset>(json).unwrap_err();
-        assert!(!err.to_string().is_empty());
+        assert!(err.to_string().contains("brightness"));
     }
 
     #[test]
@@ -449,6 +449,9 @@ mod tests {
         let json = r#"{"schemaVersion":1,"brightness":"high","contrast":1.0,"gamma":1.0,"invert":false,"depthMinMm":2.0,"depthMaxMm":10.0}"#;
         let err = serde_json::from_str::<Preset>(json).unwrap_err();
         assert!(!err.to_string().is_empty());
+        let json =
+            r#"{"schemaVersion":2,"depthMinMm":2,"depthMaxMm":10,"operations":[{"type":"warp"}]}"#;
+        assert!(serde_json::from_str::<Preset>(json).is_err());
     }
 
     #[test]
@@ -460,28 +463,53 @@ mod tests {
     // --- JR2-1303: Versioned schema ---
 
     #[test]
-    fn preset_schema_version_1_accepted() {
+    fn preset_schema_version_current_accepted() {
         let p = Preset::default();
         assert_eq!(p.schema_version, PRESET_SCHEMA_VERSION);
         let json = serde_json::to_string(&p).unwrap();
-        let loaded: Preset = serde_json::from_str(&json).unwrap();
+        let loaded = Preset::parse_and_validate_json(&json).unwrap();
+        assert_eq!(loaded.schema_version, 2);
+    }
+
+    /// Version 1 presets (flat fields) migrate to the operation stack in the former fixed order.
+    #[test]
+    fn preset_schema_version_1_migrates_to_operations() {
+        let json = r#"{"schemaVersion":1,"brightness":0.2,"contrast":1.1,"gamma":1.0,"invert":true,
+            "depthMinMm":3.0,"depthMaxMm":8.0,"curveControlPoints":[{"x":0,"y":0},{"x":1,"y":1}],
+            "curveInterpolation":"monotoneCubic","stepX":2,"stepY":2,"targetWidthMm":50.0}"#;
+        let loaded = Preset::parse_and_validate_json(json).unwrap();
         assert_eq!(loaded.schema_version, 1);
+        let kinds: Vec<AdjustmentKind> = loaded.operations.into_iter().map(|op| op.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                AdjustmentKind::Invert,
+                AdjustmentKind::Contrast { contrast: 1.1 },
+                AdjustmentKind::Brightness { brightness: 0.2 },
+                AdjustmentKind::Curve {
+                    points: vec![CurvePoint { x: 0.0, y: 0.0 }, CurvePoint { x: 1.0, y: 1.0 }],
+                    interpolation: CurveInterpolation::MonotoneCubic,
+                },
+            ]
+        );
+        assert_eq!(loaded.step_x, 2);
+        assert_eq!(loaded.target_width_mm, Some(50.0));
     }
 
-    /// Older schema version 0 (same field shape) deserializes; no data loss for supported fields (JR2-1303).
+    /// Older schema version 0 (same field shape as 1) deserializes; no data loss for supported fields (JR2-1303).
     #[test]
     fn preset_schema_version_0_deserializes() {
         let json = r#"{"schemaVersion":0,"brightness":0.05,"contrast":1.1,"gamma":1.0,"invert":false,"depthMinMm":2.0,"depthMaxMm":10.0,"stepX":1,"stepY":1}"#;
         let loaded: Preset = serde_json::from_str(json).unwrap();
         assert_eq!(loaded.schema_version, 0);
-        assert!((loaded.brightness - 0.05).abs() < 1e-6);
-        assert!((loaded.contrast - 1.1).abs() < 1e-6);
-        assert!(loaded.curve_control_points.is_none());
-        assert_eq!(loaded.step_x, 1);
-        assert_eq!(loaded.step_y, 1);
-        // to_depth_params still works; loader can treat v0 as valid and apply (migration = accept).
         let dp = loaded.to_depth_params();
-        assert!((dp.brightness - 0.05).abs() < 1e-6);
+        assert_eq!(
+            dp,
+            DepthAdjustmentParams::with_operations([
+                AdjustmentKind::Contrast { contrast: 1.1 },
+                AdjustmentKind::Brightness { brightness: 0.05 },
+            ])
+        );
     }
 
     #[test]
@@ -502,59 +530,6 @@ mod tests {
         assert!(sanitize_preset_name("a/b").is_err());
     }
 
-    // --- JR2-1301: Round-trip None curve, step defaults, built-in validity, sanitize edge cases ---
-
-    /// JR2-1301: None curve control points round-trips; no curveControlPoints key in JSON.
-    #[test]
-    fn preset_roundtrip_none_curve_no_key_in_json() {
-        let p = Preset {
-            schema_version: 1,
-            brightness: 0.0,
-            contrast: 1.0,
-            gamma: 1.0,
-            invert: false,
-            depth_min_mm: 2.0,
-            depth_max_mm: 10.0,
-            curve_control_points: None,
-            step_x: 1,
-            step_y: 1,
-            target_width_mm: None,
-            target_height_mm: None,
-        };
-        let json = serde_json::to_string(&p).unwrap();
-        assert!(
-            !json.contains("curveControlPoints"),
-            "None should not serialize curveControlPoints key"
-        );
-        let loaded: Preset = serde_json::from_str(&json).unwrap();
-        assert_eq!(loaded.curve_control_points, None);
-    }
-
-    /// JR2-1301: step_x/step_y default to 1 when absent from JSON (backwards compatibility).
-    #[test]
-    fn preset_deserialize_defaults_step_when_absent() {
-        let json = r#"{"schemaVersion":1,"brightness":0,"contrast":1,"gamma":1,"invert":false,"depthMinMm":2,"depthMaxMm":10}"#;
-        let loaded: Preset = serde_json::from_str(json).unwrap();
-        assert_eq!(loaded.step_x, 1);
-        assert_eq!(loaded.step_y, 1);
-    }
-
-    /// JR2-1301: Each built-in preset returns Some and to_depth_params() has valid ranges.
-    #[test]
-    fn builtin_presets_valid_depth_params() {
-        for id in builtin_preset_ids() {
-            let p = get_builtin_preset(id).unwrap_or_else(|| panic!("builtin {id} should exist"));
-            let dp = p.to_depth_params();
-            assert!(
-                dp.depth_min_mm < dp.depth_max_mm,
-                "{}: depth_min_mm < depth_max_mm",
-                id
-            );
-            assert!(dp.gamma > 0.0, "{}: gamma > 0", id);
-            assert!(dp.contrast > 0.0, "{}: contrast > 0", id);
-        }
-    }
-
     /// JR2-1301: get_builtin_preset returns Some for each built-in id.
     #[test]
     fn builtin_preset_each_returns_some() {
@@ -610,10 +585,10 @@ mod tests {
         assert!(!err.is_empty());
     }
 
-    /// JR2-1303: schemaVersion > 1 returns error (newer than supported).
+    /// JR2-1303: schemaVersion above the current one returns error (newer than supported).
     #[test]
-    fn parse_and_validate_json_schema_version_2_rejected() {
-        let json = r#"{"schemaVersion":2,"brightness":0,"contrast":1,"gamma":1,"invert":false,"depthMinMm":2,"depthMaxMm":10}"#;
+    fn parse_and_validate_json_schema_version_3_rejected() {
+        let json = r#"{"schemaVersion":3,"depthMinMm":2,"depthMaxMm":10,"operations":[]}"#;
         let err = Preset::parse_and_validate_json(json).unwrap_err();
         assert!(err.contains("newer than supported") || err.contains("schema version"));
     }
@@ -621,7 +596,7 @@ mod tests {
     /// JR2-1303: depthMinMm >= depthMaxMm — backend does not reject; deserializes and to_depth_params returns as-is (no clamp).
     #[test]
     fn preset_depth_min_ge_max_deserializes_no_reject() {
-        let json = r#"{"schemaVersion":1,"brightness":0,"contrast":1,"gamma":1,"invert":false,"depthMinMm":10,"depthMaxMm":2}"#;
+        let json = r#"{"schemaVersion":2,"depthMinMm":10,"depthMaxMm":2,"operations":[]}"#;
         let loaded: Preset = serde_json::from_str(json).unwrap();
         let dp = loaded.to_depth_params();
         assert!((dp.depth_min_mm - 10.0).abs() < 1e-6);
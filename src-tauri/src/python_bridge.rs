@@ -6,7 +6,8 @@
 //! Spawns `python -m python.depth_estimator --input <path>` with image bytes in a temp file;
 //! captures stdout (JSON depth map), stderr (progress/errors); enforces timeout; no user input in argv.
 //!
-//! Progress protocol (BACK-205, AI-203): Python writes to stderr only. Lines: `PROGRESS <0-100>`, `STAGE <name>`.
+//! Progress protocol (BACK-205, AI-203): Python writes to stderr only. Lines: `PROGRESS <0-100>`, `STAGE <name>`,
+//! `MODEL <id>`.
 
 #![allow(dead_code)] // Some helpers used only by integration tests or for progress logging; generate_depth_map is integrated (Sprint 1.4).
 
@@ -64,6 +65,16 @@ pub fn stages_from_stderr(lines: &[String]) -> Vec<String> {
         .collect()
 }
 
+/// Model id from the `MODEL <id>` stderr line (`"stub"` in stub mode); the last one wins.
+pub fn model_from_stderr(lines: &[String]) -> Option<String> {
+    lines
+        .iter()
+        .rev()
+        .filter_map(|line| line.trim().strip_prefix("MODEL "))
+        .map(|s| s.trim().to_string())
+        .find(|s| !s.is_empty())
+}
+
 /// Ensures path is canonical and under system temp dir (SEC-201).
 fn validate_input_path(path: &std::path::Path) -> Result<std::path::PathBuf> {
     let canonical = path
@@ -281,6 +292,20 @@ mod tests {
         assert_eq!(stages, ["loading_model", "inference", "post"]);
     }
 
+    #[test]
+    fn model_from_stderr_reads_model_line() {
+        let lines = vec![
+            "STAGE inference".to_string(),
+            "MODEL depth-anything/Depth-Anything-V2-Small-hf".to_string(),
+            "PROGRESS 90".to_string(),
+        ];
+        assert_eq!(
+            model_from_stderr(&lines).as_deref(),
+            Some("depth-anything/Depth-Anything-V2-Small-hf")
+        );
+        assert_eq!(model_from_stderr(&lines[..1]), None);
+    }
+
     #[test]
     fn stages_from_stderr_ignores_empty_stage() {
         let lines = vec!["STAGE ".to_string(), "STAGE  ".to_string()];
@@ -0,0 +1,316 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Reproducibility sidecar ("recipe") written next to every point cloud export.
+//!
+//! The recipe records everything that determines the exported points: the source
+//! (image hash and depth model, or the imported file), the depth adjustments, the mask
+//! (run-length encoded), the sculpt offset layer, the sampling parameters and point
+//! channels, the blank envelope and the resulting fit, plus the app version.
+//! `replay_recipe` feeds it back through the same pipeline to regenerate the cloud; the
+//! recorded depth model and depth map hash show whether the original depth was reproduced
+//! exactly. Sidecars are untrusted input: mask and sculpt dimensions are bounded before
+//! anything is allocated.
+//!
+//! The sidecar for `piece.ply` is `piece.ply.recipe.json`.
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::blank_envelope::{BlankEnvelope, FitResult};
+use crate::depth_adjust::DepthAdjustmentParams;
+use crate::mask::MaskBitmap;
+use crate::mesh_import::MeshSampling;
+use crate::sculpt::OffsetLayer;
+use crate::volumetric::{PointAttributeOptions, VolumetricParams};
+
+/// Current recipe schema version. Bump on incompatible changes.
+/// Version 2 stores the depth adjustments as an operation stack; version 1 flat params are
+/// migrated when read (see [`DepthAdjustmentParams`]).
+pub const RECIPE_VERSION: u32 = 2;
+
+/// Suffix appended to the export file name for its sidecar.
+pub const SIDECAR_SUFFIX: &str = ".recipe.json";
+
+/// A file identified by name, last known path and content hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileRecord {
+    pub file_name: String,
+    /// Absolute path at export time (replay looks here when no path is given).
+    pub path: String,
+    /// Lowercase hex SHA-256 of the file contents.
+    pub sha256: String,
+}
+
+impl FileRecord {
+    /// Hash `path` and record it.
+    pub fn from_path(path: &Path) -> Result<Self> {
+        Ok(Self {
+            file_name: path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            path: path.to_string_lossy().to_string(),
+            sha256: sha256_file(path)?,
+        })
+    }
+
+    /// Error unless the file at `path` has the recorded hash.
+    pub fn verify(&self, path: &Path) -> Result<()> {
+        let actual = sha256_file(path)?;
+        if actual != self.sha256 {
+            bail!(
+                "{:?} does not match the recorded {} (SHA-256 {} ≠ {})",
+                path,
+                self.file_name,
+                actual,
+                self.sha256
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Mask as row-major run lengths (see [`MaskBitmap::to_rle`]).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MaskRle {
+    pub width: u32,
+    pub height: u32,
+    pub runs: Vec<u32>,
+}
+
+impl MaskRle {
+    pub fn from_mask(mask: &MaskBitmap) -> Self {
+        let (width, height) = mask.dimensions();
+        Self {
+            width,
+            height,
+            runs: mask.to_rle(),
+        }
+    }
+
+    pub fn to_mask(&self) -> Result<MaskBitmap, String> {
+        MaskBitmap::from_rle(self.width, self.height, &self.runs)
+    }
+}
+
+/// Where the exported points came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum RecipeSource {
+    /// Surface map from a depth map estimated from `image`.
+    DepthMap {
+        image: FileRecord,
+        /// Depth model id reported by the estimator (`"stub"` in stub mode); `None` if unknown.
+        depth_model_id: Option<String>,
+        depth_width: u32,
+        depth_height: u32,
+        /// SHA-256 of the raw (unadjusted) depth map as little-endian `f32`s.
+        depth_sha256: String,
+        adjustment_params: Box<DepthAdjustmentParams>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mask: Option<MaskRle>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sculpt: Option<OffsetLayer>,
+        volumetric_params: VolumetricParams,
+        point_attributes: PointAttributeOptions,
+    },
+    /// Imported point cloud, or a mesh sampled with `mesh_sampling`.
+    Imported {
+        file: FileRecord,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        mesh_sampling: Option<MeshSampling>,
+    },
+}
+
+/// Reproducibility sidecar for one export.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportRecipe {
+    pub recipe_version: u32,
+    pub app_version: String,
+    pub generator: String,
+    pub source: RecipeSource,
+    pub envelope: BlankEnvelope,
+    pub fit_result: FitResult,
+    /// Export format id and the resolved options it was written with.
+    pub format: String,
+    #[serde(default)]
+    pub format_options: serde_json::Value,
+}
+
+impl ExportRecipe {
+    /// Parse a sidecar, rejecting newer schema versions.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let recipe: Self = serde_json::from_str(json).context("Invalid recipe JSON")?;
+        if recipe.recipe_version > RECIPE_VERSION {
+            bail!(
+                "Recipe version {} is newer than supported version {}",
+                recipe.recipe_version,
+                RECIPE_VERSION
+            );
+        }
+        Ok(recipe)
+    }
+}
+
+/// Sidecar path for an export: `<export file name>.recipe.json` in the same directory.
+pub fn sidecar_path(export_path: &Path) -> PathBuf {
+    let mut name = export_path.as_os_str().to_os_string();
+    name.push(SIDECAR_SUFFIX);
+    PathBuf::from(name)
+}
+
+/// Write `recipe` as pretty JSON next to `export_path` (atomically: a crash leaves the old
+/// sidecar or the new one, never a truncated file); returns the sidecar path.
+pub fn write_sidecar(export_path: &Path, recipe: &ExportRecipe) -> Result<PathBuf> {
+    let path = sidecar_path(export_path);
+    let json = serde_json::to_string_pretty(recipe)?;
+    crate::export::write_file_atomic(&path, json.as_bytes())
+        .with_context(|| format!("Failed to write recipe {:?}", path))?;
+    Ok(path)
+}
+
+/// Read and parse a sidecar.
+pub fn read_recipe(path: &Path) -> Result<ExportRecipe> {
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read recipe {:?}", path))?;
+    ExportRecipe::from_json(&json)
+}
+
+fn hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Lowercase hex SHA-256 of a file, read in chunks.
+pub fn sha256_file(path: &Path) -> Result<String> {
+    let mut file =
+        std::fs::File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    let mut hasher = Sha256::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex(&hasher.finalize()))
+}
+
+/// Lowercase hex SHA-256 of a depth map's little-endian `f32` bytes.
+pub fn sha256_depth(depth: &[f32]) -> String {
+    let mut hasher = Sha256::new();
+    for v in depth {
+        hasher.update(v.to_le_bytes());
+    }
+    hex(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_recipe() -> ExportRecipe {
+        let mut mask = MaskBitmap::all_false(4, 3);
+        mask.set_region(1, 1, 2, 1, true);
+        ExportRecipe {
+            recipe_version: RECIPE_VERSION,
+            app_version: "0.1.0".to_string(),
+            generator: "SimplePicture3D".to_string(),
+            source: RecipeSource::DepthMap {
+                image: FileRecord {
+                    file_name: "photo.png".to_string(),
+                    path: "/tmp/photo.png".to_string(),
+                    sha256: "ab".repeat(32),
+                },
+                depth_model_id: Some("stub".to_string()),
+                depth_width: 4,
+                depth_height: 3,
+                depth_sha256: sha256_depth(&[0.5; 12]),
+                adjustment_params: Box::default(),
+                mask: Some(MaskRle::from_mask(&mask)),
+                sculpt: None,
+                volumetric_params: VolumetricParams::default(),
+                point_attributes: PointAttributeOptions::default(),
+            },
+            envelope: BlankEnvelope::default(),
+            fit_result: FitResult {
+                scale: 1.5,
+                translation: [1.0, 2.0, 3.0],
+                point_count: 12,
+                outliers: 0,
+            },
+            format: "ply".to_string(),
+            format_options: serde_json::json!({ "binary": false }),
+        }
+    }
+
+    #[test]
+    fn recipe_json_roundtrip() {
+        let recipe = sample_recipe();
+        let json = serde_json::to_string(&recipe).unwrap();
+        assert!(json.contains("\"kind\":\"depthMap\""));
+        assert!(json.contains("\"depthModelId\":\"stub\""));
+        let back = ExportRecipe::from_json(&json).unwrap();
+        let RecipeSource::DepthMap { mask, .. } = back.source else {
+            panic!("expected depth map source");
+        };
+        let mask = mask.unwrap().to_mask().unwrap();
+        assert!(mask.get(1, 1) && mask.get(2, 1) && !mask.get(3, 1));
+        assert_eq!(back.fit_result.translation, [1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn newer_recipe_version_is_rejected() {
+        let mut recipe = sample_recipe();
+        recipe.recipe_version = RECIPE_VERSION + 1;
+        let json = serde_json::to_string(&recipe).unwrap();
+        assert!(ExportRecipe::from_json(&json).is_err());
+    }
+
+    #[test]
+    fn sidecar_sits_next_to_export_and_hash_verifies() {
+        assert_eq!(
+            sidecar_path(Path::new("/out/piece.ply")),
+            PathBuf::from("/out/piece.ply.recipe.json")
+        );
+
+        let path = std::env::temp_dir().join("sp3d_recipe_hash_test.bin");
+        std::fs::write(&path, b"abc").unwrap();
+        let record = FileRecord::from_path(&path).unwrap();
+        assert_eq!(
+            record.sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert!(record.verify(&path).is_ok());
+        std::fs::write(&path, b"abd").unwrap();
+        assert!(record.verify(&path).is_err());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn sidecar_is_replaced_whole_without_leftover_temporaries() {
+        let dir = std::env::temp_dir().join(format!("sp3d_sidecar_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let export = dir.join("piece.ply");
+        let mut recipe = sample_recipe();
+        write_sidecar(&export, &recipe).unwrap();
+        recipe.format = "xyz".to_string();
+        let path = write_sidecar(&export, &recipe).unwrap();
+        let back = read_recipe(&path).unwrap();
+        let entries = std::fs::read_dir(&dir).unwrap().count();
+        let _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(back.format, "xyz");
+        assert_eq!(entries, 1);
+    }
+}
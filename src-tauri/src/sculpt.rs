@@ -0,0 +1,599 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+//! Depth sculpting: brush strokes on a per-pixel offset layer.
+//!
+//! The layer holds a normalized depth offset per pixel and is added to the original depth
+//! before the adjustment stack runs, so sculpted fixes survive any change to the adjustments.
+//! A stroke is a polyline with a radius, falloff and operation; each pixel is affected once
+//! per stroke by its distance to the polyline (no build-up where dabs overlap):
+//!
+//! - **raise / lower**: add or subtract `strength` (depth units) at the centre line.
+//! - **smooth**: move towards the local mean (box of a quarter radius) by `strength` (0–1).
+//! - **flatten**: move towards the weighted mean depth under the brush by `strength` (0–1).
+//!
+//! Offsets are kept so that original + offset stays in [0, 1]. Applying a stroke returns a
+//! [`SculptPatch`] of the pixels it changed for undo (ARCH-403); clearing or loading a layer
+//! keeps one copy of the layer it replaced. In presets and recipes the layer is stored as the
+//! bounding box of its non-zero offsets, base64 little-endian `f32`, at most
+//! [`MAX_LAYER_DIMENSION`] pixels on a side.
+
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Largest brush radius (px).
+pub const MAX_RADIUS_PX: f32 = 512.0;
+
+/// Largest layer side (px): the largest depth map, as images are downsampled to this on load.
+pub const MAX_LAYER_DIMENSION: u32 = crate::image_loading::MAX_DIMENSION;
+
+/// What a stroke does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SculptOp {
+    Raise,
+    Lower,
+    Smooth,
+    Flatten,
+}
+
+/// Brush weight from the centre line (1) to the radius (0).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BrushFalloff {
+    /// Full weight up to the radius.
+    Constant,
+    Linear,
+    /// Smoothstep; no visible edge.
+    #[default]
+    Smooth,
+}
+
+impl BrushFalloff {
+    /// Weight at `t` = distance / radius (0 at the centre line).
+    fn weight(self, t: f32) -> f32 {
+        if t >= 1.0 {
+            return 0.0;
+        }
+        match self {
+            BrushFalloff::Constant => 1.0,
+            BrushFalloff::Linear => 1.0 - t,
+            BrushFalloff::Smooth => 1.0 - t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// Stroke point in depth-map pixels; (0, 0) is the top-left corner of the first pixel.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct StrokePoint {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// One brush stroke (IPC payload of `apply_sculpt_stroke`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SculptStroke {
+    pub points: Vec<StrokePoint>,
+    pub radius_px: f32,
+    #[serde(default)]
+    pub falloff: BrushFalloff,
+    pub op: SculptOp,
+    /// Raise / lower: depth change (0–1 of the range) at the centre line. Smooth / flatten:
+    /// fraction of the way to the target (0–1).
+    pub strength: f32,
+}
+
+impl SculptStroke {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.points.is_empty() {
+            return Err("Stroke has no points".to_string());
+        }
+        if self
+            .points
+            .iter()
+            .any(|p| !p.x.is_finite() || !p.y.is_finite())
+        {
+            return Err("Stroke points must be finite".to_string());
+        }
+        if !(self.radius_px > 0.0 && self.radius_px <= MAX_RADIUS_PX) {
+            return Err(format!("Brush radius must be in (0, {}] px", MAX_RADIUS_PX));
+        }
+        if !(0.0..=1.0).contains(&self.strength) {
+            return Err("Brush strength must be in [0, 1]".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Per-pixel depth offsets, row-major, same size as the depth map.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(into = "OffsetLayerRepr", try_from = "OffsetLayerRepr")]
+pub struct OffsetLayer {
+    width: u32,
+    height: u32,
+    offsets: Vec<f32>,
+}
+
+impl OffsetLayer {
+    pub fn zeros(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            offsets: vec![0.0; width as usize * height as usize],
+        }
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        (self.width, self.height)
+    }
+
+    pub fn dimensions_match(&self, width: u32, height: u32) -> bool {
+        self.width == width && self.height == height
+    }
+
+    pub fn offsets(&self) -> &[f32] {
+        &self.offsets
+    }
+
+    /// True when no pixel is offset.
+    pub fn is_empty(&self) -> bool {
+        self.offsets.iter().all(|&v| v == 0.0)
+    }
+
+    /// `depth` + offsets, clamped to [0, 1]. `depth` is returned unchanged if its length
+    /// does not match the layer.
+    pub fn composite(&self, depth: &[f32]) -> Vec<f32> {
+        if depth.len() != self.offsets.len() {
+            return depth.to_vec();
+        }
+        depth
+            .iter()
+            .zip(&self.offsets)
+            .map(|(&d, &o)| (d + o).clamp(0.0, 1.0))
+            .collect()
+    }
+
+    /// Apply `stroke` over the original `depth`. Returns the changed pixels, or `None` when
+    /// the stroke misses the layer or `depth` does not match it.
+    pub fn apply_stroke(&mut self, depth: &[f32], stroke: &SculptStroke) -> Option<SculptPatch> {
+        if depth.len() != self.offsets.len() || stroke.points.is_empty() {
+            return None;
+        }
+        let (w, h) = (self.width as usize, self.height as usize);
+        let r = stroke.radius_px;
+        let (min_x, max_x, min_y, max_y) = stroke.points.iter().fold(
+            (
+                f32::INFINITY,
+                f32::NEG_INFINITY,
+                f32::INFINITY,
+                f32::NEG_INFINITY,
+            ),
+            |(a, b, c, d), p| (a.min(p.x), b.max(p.x), c.min(p.y), d.max(p.y)),
+        );
+        let x0 = (min_x - r).floor().max(0.0) as usize;
+        let y0 = (min_y - r).floor().max(0.0) as usize;
+        let x1 = ((max_x + r).ceil().max(0.0) as usize).min(w);
+        let y1 = ((max_y + r).ceil().max(0.0) as usize).min(h);
+        if x0 >= x1 || y0 >= y1 {
+            return None;
+        }
+        let (rw, rh) = (x1 - x0, y1 - y0);
+
+        // Distance from each pixel centre to the polyline, one segment's bounding box at a time.
+        let mut distance = vec![f32::INFINITY; rw * rh];
+        let segments = stroke
+            .points
+            .windows(2)
+            .map(|s| (s[0], s[1]))
+            .chain((stroke.points.len() == 1).then(|| (stroke.points[0], stroke.points[0])));
+        for (a, b) in segments {
+            let sx0 = ((a.x.min(b.x) - r).floor().max(x0 as f32) as usize).max(x0);
+            let sy0 = ((a.y.min(b.y) - r).floor().max(y0 as f32) as usize).max(y0);
+            let sx1 = ((a.x.max(b.x) + r).ceil().max(0.0) as usize).min(x1);
+            let sy1 = ((a.y.max(b.y) + r).ceil().max(0.0) as usize).min(y1);
+            for y in sy0..sy1 {
+                for x in sx0..sx1 {
+                    let d = segment_distance(x as f32 + 0.5, y as f32 + 0.5, a, b);
+                    let slot = &mut distance[(y - y0) * rw + (x - x0)];
+                    *slot = slot.min(d);
+                }
+            }
+        }
+        let weights: Vec<f32> = distance
+            .iter()
+            .map(|&d| stroke.falloff.weight(d / r))
+            .collect();
+
+        let current: Vec<f32> = (0..rw * rh)
+            .map(|i| {
+                let j = (y0 + i / rw) * w + x0 + i % rw;
+                (depth[j] + self.offsets[j]).clamp(0.0, 1.0)
+            })
+            .collect();
+        let target: Vec<f32> = match stroke.op {
+            SculptOp::Raise => current.iter().map(|&v| v + stroke.strength).collect(),
+            SculptOp::Lower => current.iter().map(|&v| v - stroke.strength).collect(),
+            SculptOp::Smooth => {
+                let k = ((r / 4.0).round() as usize).max(1);
+                box_mean(depth, &self.offsets, w, h, (x0, y0, x1, y1), k)
+            }
+            SculptOp::Flatten => {
+                let (sum, total) = current
+                    .iter()
+                    .zip(&weights)
+                    .fold((0.0f64, 0.0f64), |(s, t), (&v, &wt)| {
+                        (s + (v * wt) as f64, t + wt as f64)
+                    });
+                let plane = if total > 0.0 {
+                    (sum / total) as f32
+                } else {
+                    0.0
+                };
+                vec![plane; rw * rh]
+            }
+        };
+        let amount = match stroke.op {
+            SculptOp::Raise | SculptOp::Lower => 1.0,
+            SculptOp::Smooth | SculptOp::Flatten => stroke.strength,
+        };
+        let (mut indices, mut previous, mut new) = (Vec::new(), Vec::new(), Vec::new());
+        for i in 0..rw * rh {
+            if weights[i] <= 0.0 {
+                continue;
+            }
+            let j = (y0 + i / rw) * w + x0 + i % rw;
+            let v = current[i] + weights[i] * amount * (target[i] - current[i]);
+            indices.push(j as u32);
+            previous.push(self.offsets[j]);
+            self.offsets[j] = v.clamp(0.0, 1.0) - depth[j];
+            new.push(self.offsets[j]);
+        }
+        if indices.is_empty() {
+            return None;
+        }
+        Some(SculptPatch::Pixels {
+            layer_width: self.width,
+            layer_height: self.height,
+            indices,
+            previous,
+            new,
+        })
+    }
+
+    fn write_region(&mut self, x: u32, y: u32, width: u32, values: &[f32]) {
+        let (w, x, y, rw) = (self.width as usize, x as usize, y as usize, width as usize);
+        for (row, chunk) in values.chunks(rw.max(1)).enumerate() {
+            let start = (y + row) * w + x;
+            if let Some(dst) = self.offsets.get_mut(start..start + chunk.len()) {
+                dst.copy_from_slice(chunk);
+            }
+        }
+    }
+}
+
+/// Distance from (px, py) to the segment a–b.
+fn segment_distance(px: f32, py: f32, a: StrokePoint, b: StrokePoint) -> f32 {
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 > 0.0 {
+        (((px - a.x) * dx + (py - a.y) * dy) / len2).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    ((px - a.x - t * dx).powi(2) + (py - a.y - t * dy).powi(2)).sqrt()
+}
+
+/// Mean of the composited depth in a (2k+1)² box around each pixel of the rectangle
+/// (x0, y0)–(x1, y1), clipped to the image, via a summed-area table.
+fn box_mean(
+    depth: &[f32],
+    offsets: &[f32],
+    w: usize,
+    h: usize,
+    (x0, y0, x1, y1): (usize, usize, usize, usize),
+    k: usize,
+) -> Vec<f32> {
+    let (ex0, ey0) = (x0.saturating_sub(k), y0.saturating_sub(k));
+    let (ex1, ey1) = ((x1 + k).min(w), (y1 + k).min(h));
+    let ew = ex1 - ex0;
+    let mut sat = vec![0.0f64; (ew + 1) * (ey1 - ey0 + 1)];
+    for y in ey0..ey1 {
+        let mut row = 0.0f64;
+        for x in ex0..ex1 {
+            let j = y * w + x;
+            row += (depth[j] + offsets[j]).clamp(0.0, 1.0) as f64;
+            let (sy, sx) = (y - ey0 + 1, x - ex0 + 1);
+            sat[sy * (ew + 1) + sx] = sat[(sy - 1) * (ew + 1) + sx] + row;
+        }
+    }
+    let mut out = Vec::with_capacity((x1 - x0) * (y1 - y0));
+    for y in y0..y1 {
+        for x in x0..x1 {
+            let (bx0, by0) = (x.saturating_sub(k) - ex0, y.saturating_sub(k) - ey0);
+            let (bx1, by1) = ((x + k + 1).min(w) - ex0, (y + k + 1).min(h) - ey0);
+            let at = |sy: usize, sx: usize| sat[sy * (ew + 1) + sx];
+            let sum = at(by1, bx1) - at(by0, bx1) - at(by1, bx0) + at(by0, bx0);
+            out.push((sum / ((bx1 - bx0) * (by1 - by0)) as f64) as f32);
+        }
+    }
+    out
+}
+
+/// Undoable change to the offset layer (ARCH-403).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SculptPatch {
+    /// A stroke: the offsets of the pixels it touched (non-zero brush weight) before and after.
+    Pixels {
+        layer_width: u32,
+        layer_height: u32,
+        /// Row-major pixel indices into the layer.
+        indices: Vec<u32>,
+        previous: Vec<f32>,
+        new: Vec<f32>,
+    },
+    /// Clear or load: the layer on the other side of the change. Undo and redo swap it with
+    /// the current layer, so only one copy is kept.
+    Swap(Option<OffsetLayer>),
+}
+
+impl SculptPatch {
+    /// Apply the "undo" state (previous offsets).
+    pub fn apply_previous(&mut self, layer: &mut Option<OffsetLayer>) {
+        match self {
+            SculptPatch::Pixels {
+                layer_width,
+                layer_height,
+                indices,
+                previous,
+                ..
+            } => write_pixels(layer, *layer_width, *layer_height, indices, previous),
+            SculptPatch::Swap(other) => std::mem::swap(layer, other),
+        }
+    }
+
+    /// Apply the "do" state (new offsets). Used for redo.
+    pub fn apply_new(&mut self, layer: &mut Option<OffsetLayer>) {
+        match self {
+            SculptPatch::Pixels {
+                layer_width,
+                layer_height,
+                indices,
+                new,
+                ..
+            } => write_pixels(layer, *layer_width, *layer_height, indices, new),
+            SculptPatch::Swap(other) => std::mem::swap(layer, other),
+        }
+    }
+}
+
+/// Set the offsets at `indices` to `values`, first replacing a missing or differently sized
+/// layer with a zero layer of `width`×`height`.
+fn write_pixels(
+    layer: &mut Option<OffsetLayer>,
+    width: u32,
+    height: u32,
+    indices: &[u32],
+    values: &[f32],
+) {
+    let layer = match layer {
+        Some(l) if l.dimensions_match(width, height) => l,
+        _ => layer.insert(OffsetLayer::zeros(width, height)),
+    };
+    for (&i, &v) in indices.iter().zip(values) {
+        if let Some(slot) = layer.offsets.get_mut(i as usize) {
+            *slot = v;
+        }
+    }
+}
+
+/// Serialized layer: only the bounding box of the non-zero offsets.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OffsetLayerRepr {
+    width: u32,
+    height: u32,
+    x: u32,
+    y: u32,
+    region_width: u32,
+    region_height: u32,
+    /// Base64 of the region's offsets as little-endian `f32`, row-major.
+    offsets: String,
+}
+
+impl From<OffsetLayer> for OffsetLayerRepr {
+    fn from(layer: OffsetLayer) -> Self {
+        let w = layer.width as usize;
+        let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+        for (i, _) in layer.offsets.iter().enumerate().filter(|(_, &v)| v != 0.0) {
+            let (x, y) = (i % w, i / w);
+            x0 = x0.min(x);
+            y0 = y0.min(y);
+            x1 = x1.max(x + 1);
+            y1 = y1.max(y + 1);
+        }
+        let (x0, y0) = (x0.min(x1), y0.min(y1));
+        let bytes: Vec<u8> = (y0..y1)
+            .flat_map(|y| &layer.offsets[y * w + x0..y * w + x1])
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        Self {
+            width: layer.width,
+            height: layer.height,
+            x: x0 as u32,
+            y: y0 as u32,
+            region_width: (x1 - x0) as u32,
+            region_height: (y1 - y0) as u32,
+            offsets: base64::engine::general_purpose::STANDARD.encode(bytes),
+        }
+    }
+}
+
+impl TryFrom<OffsetLayerRepr> for OffsetLayer {
+    type Error = String;
+
+    fn try_from(repr: OffsetLayerRepr) -> Result<Self, String> {
+        if repr.x.saturating_add(repr.region_width) > repr.width
+            || repr.y.saturating_add(repr.region_height) > repr.height
+        {
+            return Err("Sculpt region lies outside the layer".to_string());
+        }
+        if repr.width > MAX_LAYER_DIMENSION || repr.height > MAX_LAYER_DIMENSION {
+            return Err(format!(
+                "Sculpt layer {}x{} exceeds the maximum of {}x{}",
+                repr.width, repr.height, MAX_LAYER_DIMENSION, MAX_LAYER_DIMENSION
+            ));
+        }
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(repr.offsets.as_bytes())
+            .map_err(|e| format!("Invalid sculpt offsets: {}", e))?;
+        let expected = repr.region_width as usize * repr.region_height as usize * 4;
+        if bytes.len() != expected {
+            return Err(format!(
+                "Sculpt offsets hold {} bytes, expected {}",
+                bytes.len(),
+                expected
+            ));
+        }
+        let values: Vec<f32> = bytes
+            .chunks_exact(4)
+            .map(|c| f32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+            .collect();
+        if values.iter().any(|v| !v.is_finite()) {
+            return Err("Sculpt offsets must be finite".to_string());
+        }
+        let mut layer = OffsetLayer::zeros(repr.width, repr.height);
+        layer.write_region(repr.x, repr.y, repr.region_width, &values);
+        Ok(layer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stroke(op: SculptOp, points: &[(f32, f32)], radius_px: f32, strength: f32) -> SculptStroke {
+        SculptStroke {
+            points: points.iter().map(|&(x, y)| StrokePoint { x, y }).collect(),
+            radius_px,
+            falloff: BrushFalloff::Linear,
+            op,
+            strength,
+        }
+    }
+
+    #[test]
+    fn raise_peaks_on_the_stroke_and_fades_to_the_radius() {
+        let depth = vec![0.5; 21 * 11];
+        let mut layer = OffsetLayer::zeros(21, 11);
+        let patch = layer
+            .apply_stroke(
+                &depth,
+                &stroke(SculptOp::Raise, &[(5.5, 5.5), (15.5, 5.5)], 4.0, 0.2),
+            )
+            .unwrap();
+        let out = layer.composite(&depth);
+        assert!((out[5 * 21 + 10] - 0.7).abs() < 1e-5, "on the line");
+        assert!((out[3 * 21 + 10] - 0.6).abs() < 1e-5, "half way out");
+        assert_eq!(out[5 * 21], 0.5, "outside the radius");
+        // One pass per stroke: overlapping segments do not add up.
+        assert!(out.iter().all(|&v| v <= 0.7 + 1e-5));
+        // Undo keeps only the pixels under the brush, not its bounding rectangle.
+        let SculptPatch::Pixels { indices, .. } = patch else {
+            panic!("stroke patch");
+        };
+        assert!(indices.len() < 19 * 9);
+        assert!(indices.contains(&(5 * 21 + 10)));
+        assert!(!indices.contains(&(21 + 1)), "corner of the rectangle");
+    }
+
+    #[test]
+    fn lower_keeps_depth_in_range_and_undo_restores() {
+        let depth = vec![0.1; 9 * 9];
+        let mut layer = Some(OffsetLayer::zeros(9, 9));
+        let mut patch = layer
+            .as_mut()
+            .unwrap()
+            .apply_stroke(&depth, &stroke(SculptOp::Lower, &[(4.5, 4.5)], 3.0, 0.5))
+            .unwrap();
+        let sculpted = layer.as_ref().unwrap().composite(&depth);
+        assert_eq!(sculpted[4 * 9 + 4], 0.0);
+        assert!(layer
+            .as_ref()
+            .unwrap()
+            .offsets()
+            .iter()
+            .all(|&o| o >= -0.1 - 1e-6));
+        patch.apply_previous(&mut layer);
+        assert!(layer.as_ref().unwrap().is_empty());
+        patch.apply_new(&mut layer);
+        assert_eq!(layer.as_ref().unwrap().composite(&depth), sculpted);
+
+        // Clearing keeps the one replaced layer and swaps it back and forth.
+        let mut clear = SculptPatch::Swap(layer.replace(OffsetLayer::zeros(9, 9)));
+        clear.apply_previous(&mut layer);
+        assert_eq!(layer.as_ref().unwrap().composite(&depth), sculpted);
+        clear.apply_new(&mut layer);
+        assert!(layer.as_ref().unwrap().is_empty());
+    }
+
+    #[test]
+    fn smooth_and_flatten_pull_towards_the_neighbourhood() {
+        let mut depth = vec![0.4; 15 * 15];
+        depth[7 * 15 + 7] = 1.0;
+        let mut layer = OffsetLayer::zeros(15, 15);
+        layer.apply_stroke(&depth, &stroke(SculptOp::Smooth, &[(7.5, 7.5)], 8.0, 1.0));
+        let smoothed = layer.composite(&depth)[7 * 15 + 7];
+        assert!(smoothed < 0.5, "spike smoothed: {}", smoothed);
+
+        let ramp: Vec<f32> = (0..15 * 15).map(|i| (i % 15) as f32 / 14.0).collect();
+        let mut layer = OffsetLayer::zeros(15, 15);
+        let mut flatten = stroke(SculptOp::Flatten, &[(7.5, 7.5)], 20.0, 1.0);
+        flatten.falloff = BrushFalloff::Constant;
+        layer.apply_stroke(&ramp, &flatten);
+        let out = layer.composite(&ramp);
+        assert!(out.iter().all(|&v| (v - 0.5).abs() < 1e-4));
+    }
+
+    #[test]
+    fn layer_serializes_only_the_touched_region() {
+        let depth = vec![0.5; 40 * 30];
+        let mut layer = OffsetLayer::zeros(40, 30);
+        layer.apply_stroke(&depth, &stroke(SculptOp::Raise, &[(10.0, 10.0)], 2.0, 0.1));
+        let json = serde_json::to_value(&layer).unwrap();
+        assert_eq!(json["regionWidth"], 4);
+        assert_eq!(json["regionHeight"], 4);
+        let back: OffsetLayer = serde_json::from_value(json).unwrap();
+        assert_eq!(back, layer);
+
+        let empty = serde_json::to_string(&OffsetLayer::zeros(3, 2)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<OffsetLayer>(&empty).unwrap(),
+            OffsetLayer::zeros(3, 2)
+        );
+        let bad =
+            r#"{"width":2,"height":2,"x":1,"y":0,"regionWidth":2,"regionHeight":1,"offsets":""}"#;
+        assert!(serde_json::from_str::<OffsetLayer>(bad).is_err());
+        // Rejected before the layer is allocated.
+        let huge = r#"{"width":4000000000,"height":4000000000,"x":0,"y":0,"regionWidth":0,"regionHeight":0,"offsets":""}"#;
+        let err = serde_json::from_str::<OffsetLayer>(huge).unwrap_err();
+        assert!(err.to_string().contains("exceeds the maximum"));
+    }
+
+    #[test]
+    fn stroke_validation() {
+        assert!(stroke(SculptOp::Raise, &[(1.0, 1.0)], 5.0, 0.1)
+            .validate()
+            .is_ok());
+        assert!(stroke(SculptOp::Raise, &[], 5.0, 0.1).validate().is_err());
+        assert!(stroke(SculptOp::Raise, &[(1.0, 1.0)], 0.0, 0.1)
+            .validate()
+            .is_err());
+        assert!(stroke(SculptOp::Smooth, &[(1.0, 1.0)], 5.0, 1.5)
+            .validate()
+            .is_err());
+        assert!(stroke(SculptOp::Raise, &[(f32::NAN, 1.0)], 5.0, 0.1)
+            .validate()
+            .is_err());
+    }
+}
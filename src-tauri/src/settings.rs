@@ -7,11 +7,14 @@
 //! Loaded on startup; saved after each export to remember last export directory.
 
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 use crate::blank_envelope::BlankEnvelope;
-use crate::depth_adjust::CurvePoint;
-use crate::volumetric::VolumetricParams;
+use crate::depth_adjust::{AdjustmentKind, AdjustmentOp, CurveInterpolation, CurvePoint};
+use crate::export::dialect::TextDialect;
+use crate::mesh_import::MeshSampling;
+use crate::volumetric::{PointAttributeOptions, VolumetricParams};
 
 /// Application settings persisted between sessions (BACK-706, BACK-804, BACK-805).
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -52,23 +55,44 @@ pub struct AppSettings {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub target_height_mm: Option<f32>,
 
-    /// Curve control points for depth remapping (CURVE-001, Consultant §2.6). Persisted so curve
-    /// survives restart; applied to depth adjustment on load.
+    /// Depth adjustment operation stack, persisted so it survives restart and applied to the
+    /// depth adjustment on load (see [`AppSettings::restored_adjustment_operations`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjustment_operations: Option<Vec<AdjustmentOp>>,
+    /// Curve control points for depth remapping (CURVE-001, Consultant §2.6), written before the
+    /// operation stack existed. Read once as a curve operation; cleared when the stack is saved.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub curve_control_points: Option<Vec<CurvePoint>>,
+    /// Interpolation of the legacy persisted curve (BACK-1103); `None` = linear.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub curve_interpolation: Option<CurveInterpolation>,
 
     /// Crystal blank envelope for volumetric point cloud fitting (ADR-011).
     /// Defines the 3D bounds and margin for the target crystal blank.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub blank_envelope: Option<BlankEnvelope>,
 
-    /// Preferred point cloud export format: "ply", "xyz", or "csv" (ADR-011).
+    /// Preferred point cloud export format: "ply", "xyz", "csv", "las", "obj", "pts", or "glb"
+    /// (ADR-011).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub point_cloud_format: Option<String>,
 
     /// Last-used volumetric sampling parameters (ADR-012); applied on `generate_point_cloud`.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub volumetric_params: Option<VolumetricParams>,
+
+    /// Optional per-point channels recorded on `generate_point_cloud` and written to PLY.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub point_attributes: Option<PointAttributeOptions>,
+
+    /// Last-used mesh import sampling (surface density or interior voxel pitch).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mesh_sampling: Option<MeshSampling>,
+
+    /// Last-used text dialect per plain-text export format id ("xyz", "csv"); becomes that
+    /// format's option defaults in `list_export_formats`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub text_dialects: BTreeMap<String, TextDialect>,
 }
 
 /// App data directory: `~/.simplepicture3d/` (Sprint 2.3 presets, models, logs).
@@ -100,6 +124,28 @@ impl AppSettings {
         }
     }
 
+    /// Operation stack to restore on startup: the saved stack, or a single curve operation
+    /// migrated from the legacy curve fields.
+    pub fn restored_adjustment_operations(&self) -> Vec<AdjustmentOp> {
+        if let Some(operations) = &self.adjustment_operations {
+            return operations.clone();
+        }
+        match &self.curve_control_points {
+            Some(points) if points.len() >= 2 => vec![AdjustmentOp::new(AdjustmentKind::Curve {
+                points: points.clone(),
+                interpolation: self.curve_interpolation.unwrap_or_default(),
+            })],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Record the operation stack, replacing the legacy curve fields.
+    pub fn set_adjustment_operations(&mut self, operations: &[AdjustmentOp]) {
+        self.adjustment_operations = Some(operations.to_vec());
+        self.curve_control_points = None;
+        self.curve_interpolation = None;
+    }
+
     /// Save settings to disk. Creates directory if needed.
     pub fn save(&self) -> Result<(), anyhow::Error> {
         let path = Self::settings_path()
@@ -217,9 +263,12 @@ mod tests {
         assert!(s.target_width_mm.is_none());
         assert!(s.target_height_mm.is_none());
         assert!(s.curve_control_points.is_none());
+        assert!(s.adjustment_operations.is_none());
         assert!(s.blank_envelope.is_none());
         assert!(s.point_cloud_format.is_none());
         assert!(s.volumetric_params.is_none());
+        assert!(s.point_attributes.is_none());
+        assert!(s.mesh_sampling.is_none());
     }
 
     #[test]
@@ -276,13 +325,14 @@ mod tests {
 
     #[test]
     fn settings_curve_control_points_roundtrip() {
-        use crate::depth_adjust::CurvePoint;
+        use crate::depth_adjust::{CurveInterpolation, CurvePoint};
         let s = AppSettings {
             curve_control_points: Some(vec![
                 CurvePoint { x: 0.0, y: 0.0 },
                 CurvePoint { x: 0.5, y: 0.25 },
                 CurvePoint { x: 1.0, y: 1.0 },
             ]),
+            curve_interpolation: Some(CurveInterpolation::CatmullRom),
             ..AppSettings::default()
         };
         let json = serde_json::to_string(&s).unwrap();
@@ -291,6 +341,36 @@ mod tests {
         assert_eq!(pts.len(), 3);
         assert!((pts[0].x - 0.0).abs() < 1e-6);
         assert!((pts[1].y - 0.25).abs() < 1e-6);
+        assert_eq!(
+            loaded.curve_interpolation,
+            Some(CurveInterpolation::CatmullRom)
+        );
+    }
+
+    #[test]
+    fn settings_legacy_curve_migrates_to_operations() {
+        let json = r#"{"curveControlPoints":[{"x":0,"y":0},{"x":1,"y":0.5}],"curveInterpolation":"catmullRom"}"#;
+        let mut loaded: AppSettings = serde_json::from_str(json).unwrap();
+        let ops = loaded.restored_adjustment_operations();
+        assert_eq!(ops.len(), 1);
+        assert!(matches!(
+            &ops[0].kind,
+            AdjustmentKind::Curve { points, interpolation: CurveInterpolation::CatmullRom }
+                if points.len() == 2
+        ));
+
+        let stack = vec![
+            AdjustmentOp::new(AdjustmentKind::Invert),
+            AdjustmentOp::new(AdjustmentKind::Gamma { gamma: 1.4 }),
+        ];
+        loaded.set_adjustment_operations(&stack);
+        let json = serde_json::to_string(&loaded).unwrap();
+        assert!(!json.contains("curveControlPoints"));
+        let reloaded: AppSettings = serde_json::from_str(&json).unwrap();
+        assert_eq!(reloaded.restored_adjustment_operations(), stack);
+        assert!(AppSettings::default()
+            .restored_adjustment_operations()
+            .is_empty());
     }
 
     #[test]
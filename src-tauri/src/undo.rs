@@ -1,14 +1,18 @@
 // Copyright (c) 2026 SimplePicture3D Contributors
 // SPDX-License-Identifier: MIT
 
-//! Undo/redo for depth adjustment and mask state (BACK-1401, BACK-1402, ARCH-403, ARCH-502).
+//! Undo/redo for depth adjustment, mask and sculpt state (BACK-1401, BACK-1402, ARCH-403,
+//! ARCH-502).
 //!
 //! Command pattern: each command stores previous and new state; execute applies new,
-//! undo restores previous. History limited to last 20 actions (drop oldest when full).
-//! Single stack with heterogeneous commands (Depth | Mask) per ARCH-502.
+//! undo restores previous. Sculpt clears and loads keep only the replaced layer and swap it
+//! with the current one on undo/redo. History limited to last 20 actions (drop oldest when full).
+//! Single stack with heterogeneous commands (Depth | Mask | Sculpt) per ARCH-502; a Group
+//! undoes several changes made by one action together.
 
 use crate::depth_adjust::DepthAdjustmentParams;
 use crate::mask::MaskBitmap;
+use crate::sculpt::{OffsetLayer, SculptPatch};
 use std::collections::VecDeque;
 
 /// Maximum number of undo steps (ARCH-403).
@@ -53,31 +57,51 @@ impl SetMaskCommand {
     }
 }
 
-/// Heterogeneous undoable command (ARCH-502). One stack for both depth and mask.
+/// Heterogeneous undoable command (ARCH-502). One stack for depth, mask and sculpt.
 #[derive(Debug, Clone)]
 pub enum UndoableCommand {
-    Depth(SetDepthParamsCommand),
+    Depth(Box<SetDepthParamsCommand>),
     Mask(SetMaskCommand),
+    /// Changed pixels of the sculpt offset layer, or the whole layer a clear or load replaced.
+    Sculpt(SculptPatch),
+    /// Commands from one action; undone last to first.
+    Group(Vec<UndoableCommand>),
+}
+
+/// State the undoable commands act on.
+pub struct UndoTarget<'a> {
+    pub params: &'a mut DepthAdjustmentParams,
+    pub mask: &'a mut Option<MaskBitmap>,
+    pub sculpt: &'a mut Option<OffsetLayer>,
 }
 
 impl UndoableCommand {
-    /// Apply undo (restore previous state) to the relevant state.
-    pub fn apply_previous(
-        &self,
-        params: &mut DepthAdjustmentParams,
-        mask: &mut Option<MaskBitmap>,
-    ) {
+    /// Apply undo (restore previous state) to the relevant state. Takes `&mut self` because
+    /// whole-layer sculpt patches swap their stored layer with the current one.
+    pub fn apply_previous(&mut self, target: &mut UndoTarget) {
         match self {
-            UndoableCommand::Depth(cmd) => cmd.apply_previous(params),
-            UndoableCommand::Mask(cmd) => cmd.apply_previous(mask),
+            UndoableCommand::Depth(cmd) => cmd.apply_previous(target.params),
+            UndoableCommand::Mask(cmd) => cmd.apply_previous(target.mask),
+            UndoableCommand::Sculpt(patch) => patch.apply_previous(target.sculpt),
+            UndoableCommand::Group(cmds) => {
+                for cmd in cmds.iter_mut().rev() {
+                    cmd.apply_previous(target);
+                }
+            }
         }
     }
 
     /// Apply redo (restore new state) to the relevant state.
-    pub fn apply_new(&self, params: &mut DepthAdjustmentParams, mask: &mut Option<MaskBitmap>) {
+    pub fn apply_new(&mut self, target: &mut UndoTarget) {
         match self {
-            UndoableCommand::Depth(cmd) => cmd.apply_new(params),
-            UndoableCommand::Mask(cmd) => cmd.apply_new(mask),
+            UndoableCommand::Depth(cmd) => cmd.apply_new(target.params),
+            UndoableCommand::Mask(cmd) => cmd.apply_new(target.mask),
+            UndoableCommand::Sculpt(patch) => patch.apply_new(target.sculpt),
+            UndoableCommand::Group(cmds) => {
+                for cmd in cmds.iter_mut() {
+                    cmd.apply_new(target);
+                }
+            }
         }
     }
 }
@@ -160,16 +184,20 @@ impl Default for UndoRedoHistory {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::depth_adjust::AdjustmentKind;
+
+    fn brightened(brightness: f32) -> DepthAdjustmentParams {
+        DepthAdjustmentParams::with_operations([AdjustmentKind::Brightness { brightness }])
+    }
 
     /// JR2-1401: Execute command → state changes; undo → state restored.
     #[test]
     fn command_execute_undo_restores_state() {
         let initial = DepthAdjustmentParams::default();
-        let modified = DepthAdjustmentParams {
-            brightness: 0.25,
-            gamma: 1.5,
-            ..DepthAdjustmentParams::default()
-        };
+        let modified = DepthAdjustmentParams::with_operations([
+            AdjustmentKind::Brightness { brightness: 0.25 },
+            AdjustmentKind::Gamma { gamma: 1.5 },
+        ]);
 
         let cmd = SetDepthParamsCommand {
             previous: initial.clone(),
@@ -178,24 +206,10 @@ mod tests {
 
         let mut state = initial.clone();
         cmd.apply_new(&mut state);
-        assert!(
-            (state.brightness - 0.25).abs() < 1e-6,
-            "execute should apply new params"
-        );
-        assert!(
-            (state.gamma - 1.5).abs() < 1e-6,
-            "execute should apply new params"
-        );
+        assert_eq!(state, modified, "execute should apply new params");
 
         cmd.apply_previous(&mut state);
-        assert!(
-            (state.brightness - initial.brightness).abs() < 1e-6,
-            "undo should restore previous state"
-        );
-        assert!(
-            (state.gamma - initial.gamma).abs() < 1e-6,
-            "undo should restore previous state"
-        );
+        assert_eq!(state, initial, "undo should restore previous state");
     }
 
     /// JR2-1402: After 21+ actions, history must not grow beyond 20 (oldest dropped).
@@ -207,12 +221,9 @@ mod tests {
         for i in 0..MAX_HISTORY_LEN + 3 {
             let cmd = SetDepthParamsCommand {
                 previous: default.clone(),
-                new: DepthAdjustmentParams {
-                    brightness: i as f32 * 0.01,
-                    ..default.clone()
-                },
+                new: brightened(i as f32 * 0.01),
             };
-            hist.push(UndoableCommand::Depth(cmd));
+            hist.push(UndoableCommand::Depth(Box::new(cmd)));
         }
         assert_eq!(
             hist.len_undo(),
@@ -225,27 +236,21 @@ mod tests {
     fn undo_redo_roundtrip() {
         let mut hist = UndoRedoHistory::new();
         let a = DepthAdjustmentParams::default();
-        let b = DepthAdjustmentParams {
-            brightness: 0.2,
-            ..DepthAdjustmentParams::default()
-        };
-        let c = DepthAdjustmentParams {
-            brightness: 0.4,
-            ..DepthAdjustmentParams::default()
-        };
+        let b = brightened(0.2);
+        let c = brightened(0.4);
 
-        hist.push(UndoableCommand::Depth(SetDepthParamsCommand {
+        hist.push(UndoableCommand::Depth(Box::new(SetDepthParamsCommand {
             previous: a.clone(),
             new: b.clone(),
-        }));
-        hist.push(UndoableCommand::Depth(SetDepthParamsCommand {
+        })));
+        hist.push(UndoableCommand::Depth(Box::new(SetDepthParamsCommand {
             previous: b.clone(),
             new: c.clone(),
-        }));
+        })));
 
         let cmd = hist.pop_undo().unwrap();
         if let UndoableCommand::Depth(d) = &cmd {
-            assert!(d.new.brightness - 0.4 < 1e-6);
+            assert_eq!(d.new, c);
         } else {
             panic!("expected Depth command");
         }
@@ -255,7 +260,7 @@ mod tests {
 
         let cmd = hist.pop_redo().unwrap();
         if let UndoableCommand::Depth(d) = &cmd {
-            assert!(d.new.brightness - 0.4 < 1e-6);
+            assert_eq!(d.new, c);
         } else {
             panic!("expected Depth command");
         }
@@ -268,25 +273,52 @@ mod tests {
     fn push_clears_redo() {
         let mut hist = UndoRedoHistory::new();
         let a = DepthAdjustmentParams::default();
-        let b = DepthAdjustmentParams {
-            brightness: 0.1,
-            ..DepthAdjustmentParams::default()
-        };
-        hist.push(UndoableCommand::Depth(SetDepthParamsCommand {
+        let b = brightened(0.1);
+        hist.push(UndoableCommand::Depth(Box::new(SetDepthParamsCommand {
             previous: a.clone(),
             new: b.clone(),
-        }));
+        })));
         let cmd = hist.pop_undo().unwrap();
         hist.push_redo(cmd);
         assert!(hist.can_redo());
-        let c = DepthAdjustmentParams {
-            brightness: 0.2,
-            ..DepthAdjustmentParams::default()
-        };
-        hist.push(UndoableCommand::Depth(SetDepthParamsCommand {
+        let c = brightened(0.2);
+        hist.push(UndoableCommand::Depth(Box::new(SetDepthParamsCommand {
             previous: b.clone(),
             new: c.clone(),
-        }));
+        })));
         assert!(!hist.can_redo());
     }
+
+    #[test]
+    fn group_undoes_params_and_sculpt_together() {
+        let depth = vec![0.5; 16];
+        let mut layer = OffsetLayer::zeros(4, 4);
+        let stroke = crate::sculpt::SculptStroke {
+            points: vec![crate::sculpt::StrokePoint { x: 2.0, y: 2.0 }],
+            radius_px: 2.0,
+            falloff: Default::default(),
+            op: crate::sculpt::SculptOp::Raise,
+            strength: 0.2,
+        };
+        let patch = layer.apply_stroke(&depth, &stroke).unwrap();
+        let mut cmd = UndoableCommand::Group(vec![
+            UndoableCommand::Depth(Box::new(SetDepthParamsCommand {
+                previous: DepthAdjustmentParams::default(),
+                new: brightened(0.1),
+            })),
+            UndoableCommand::Sculpt(patch),
+        ]);
+        let (mut params, mut mask, mut sculpt) = (brightened(0.1), None, Some(layer.clone()));
+        let mut target = UndoTarget {
+            params: &mut params,
+            mask: &mut mask,
+            sculpt: &mut sculpt,
+        };
+        cmd.apply_previous(&mut target);
+        assert_eq!(*target.params, DepthAdjustmentParams::default());
+        assert!(target.sculpt.as_ref().unwrap().is_empty());
+        cmd.apply_new(&mut target);
+        assert_eq!(*target.params, brightened(0.1));
+        assert_eq!(target.sculpt.as_ref(), Some(&layer));
+    }
 }
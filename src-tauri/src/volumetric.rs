@@ -24,13 +24,40 @@
 //! - Points with `depth < depth_threshold` are skipped so background noise does
 //!   not get engraved.
 //!
-//! After point generation, [`fit_to_blank`] is applied to scale and centre the
-//! cloud inside the [`BlankEnvelope`] (unchanged from previous behaviour).
+//! That is the default [`Projection::Orthographic`] height field. Depth-Anything's
+//! output is really relative inverse depth (disparity) seen through a perspective
+//! camera, so [`Projection::Perspective`] instead converts each sample to depth and
+//! unprojects it through a pinhole camera with the given field of view. The depth map is
+//! normalised to [0, 1], which discards the estimator's disparity scale and shift, so the
+//! user supplies them as the distances of the nearest and farthest content:
+//!
+//! ```text
+//! Z = 1 / (d / near + (1 - d) / far)                // depth 1 at `near`, 0 at `far`
+//! f = (max(width, height) / 2) / tan(fov / 2)       // focal length in pixels
+//! X = (px + 0.5 - width / 2)  * Z / f
+//! Y = (py + 0.5 - height / 2) * Z / f
+//! emit [X, Y, Z]
+//! ```
+//!
+//! Far content spreads out and near content draws in, giving the scene's real 3D
+//! arrangement instead of a sheared relief; the fit below then scales it into the blank.
+//!
+//! The cloud is scaled and centred inside the [`BlankEnvelope`] with the same
+//! transform as [`fit_to_blank`](crate::blank_envelope::fit_to_blank). Generation
+//! is streaming: a first pass over the depth map finds the point count and bounds,
+//! [`fit_transform`] turns those into the fit, and a second pass hands each fitted
+//! point to a [`PointSink`]. Exporters can therefore write very large clouds without
+//! materialising them; [`generate_volumetric_points`] uses the [`PointCollector`] sink.
+//!
+//! Optional per-point channels ([`PointAttributes`]) carry the depth value, the raw
+//! estimator depth before sculpting and adjustments, source pixel, source
+//! colour/luminance and Z-layer index alongside each point, in the same order as
+//! `points`. They are enabled with [`PointAttributeOptions`].
 //!
 //! This module supersedes the ADR-011 column-sweep fill that previously lived
 //! here. See `RESEARCH/architecture.md` § ADR-012 for the rationale.
 
-use crate::blank_envelope::{fit_to_blank, BlankEnvelope, FitResult};
+use crate::blank_envelope::{fit_transform, is_outside_envelope, BlankEnvelope, FitResult};
 use serde::{Deserialize, Serialize};
 
 /// Default minimum depth required to emit a point. Pixels below this value are
@@ -39,11 +66,70 @@ use serde::{Deserialize, Serialize};
 /// realistic foreground/midground content is still captured.
 pub const DEFAULT_DEPTH_THRESHOLD: f32 = 0.05;
 
+/// Default field of view (degrees, across the longer image side) for
+/// [`Projection::Perspective`]; roughly a phone main camera.
+pub const DEFAULT_FOV_DEG: f32 = 70.0;
+
+/// Accepted field-of-view range (degrees) for [`Projection::Perspective`].
+pub const MIN_FOV_DEG: f32 = 1.0;
+pub const MAX_FOV_DEG: f32 = 170.0;
+
+/// Default distances of the nearest (depth 1) and farthest (depth 0) content for
+/// [`Projection::Perspective`]. Any unit; only their ratio survives the fit to the blank.
+pub const DEFAULT_NEAR_DISTANCE: f32 = 1.0;
+pub const DEFAULT_FAR_DISTANCE: f32 = 10.0;
+
+/// Largest accepted far / near distance ratio for [`Projection::Perspective`].
+pub const MAX_DEPTH_RATIO: f32 = 1000.0;
+
+/// How depth-map pixels are placed in 3D before fitting to the blank.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(
+    tag = "mode",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Projection {
+    /// Height field: pixel grid maps straight to X/Y, depth to Z (ADR-012).
+    #[default]
+    Orthographic,
+    /// Treat depth as disparity between `far_distance` (depth 0) and `near_distance`
+    /// (depth 1) and unproject through a pinhole camera with `fov_deg` across the longer
+    /// image side. The distances are absent in settings saved by older versions.
+    Perspective {
+        fov_deg: f32,
+        #[serde(default = "default_near_distance")]
+        near_distance: f32,
+        #[serde(default = "default_far_distance")]
+        far_distance: f32,
+    },
+}
+
+fn default_near_distance() -> f32 {
+    DEFAULT_NEAR_DISTANCE
+}
+
+fn default_far_distance() -> f32 {
+    DEFAULT_FAR_DISTANCE
+}
+
+impl Projection {
+    /// Perspective with [`DEFAULT_FOV_DEG`] and the default near/far distances.
+    pub fn perspective() -> Self {
+        Projection::Perspective {
+            fov_deg: DEFAULT_FOV_DEG,
+            near_distance: DEFAULT_NEAR_DISTANCE,
+            far_distance: DEFAULT_FAR_DISTANCE,
+        }
+    }
+}
+
 /// Parameters for 3D surface-map point cloud generation (ADR-012).
 ///
 /// Field semantics:
 /// - `step_x` / `step_y` — pixel stride for XY sampling (1 = every pixel).
 /// - `depth_threshold` — pixels with `depth < depth_threshold` are skipped.
+/// - `projection` — orthographic height field (default) or perspective unprojection.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VolumetricParams {
@@ -54,6 +140,9 @@ pub struct VolumetricParams {
     /// Minimum depth value [0.0, 1.0] required to emit a point. Pixels with a
     /// depth value below this threshold are treated as background and skipped.
     pub depth_threshold: f32,
+    /// How samples are placed in 3D. Absent in settings saved by older versions.
+    #[serde(default)]
+    pub projection: Projection,
 }
 
 impl Default for VolumetricParams {
@@ -62,8 +151,294 @@ impl Default for VolumetricParams {
             step_x: 1,
             step_y: 1,
             depth_threshold: DEFAULT_DEPTH_THRESHOLD,
+            projection: Projection::Orthographic,
+        }
+    }
+}
+
+/// Default Z-layer thickness in mm used for [`PointAttributes::layer_index`].
+pub const DEFAULT_LAYER_THICKNESS_MM: f32 = 0.1;
+
+/// Which optional per-point channels to record during generation.
+///
+/// All channels are off by default so the plain x/y/z cloud is unchanged.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PointAttributeOptions {
+    /// Record the (sculpted, adjusted, clamped) depth value that produced each point.
+    pub depth: bool,
+    /// Record the raw estimator depth of the source pixel, before sculpting and adjustments
+    /// (requires the original depth map).
+    pub raw_depth: bool,
+    /// Record the source pixel (u, v) of each point.
+    pub source_pixel: bool,
+    /// Record the source image RGB colour of each point (requires the source image).
+    pub rgb: bool,
+    /// Record the source image luminance in [0, 1] as intensity (requires the source image).
+    pub intensity: bool,
+    /// Record the Z-layer index: `floor((z - margin) / layer_thickness_mm)` after fitting.
+    pub layer_index: bool,
+    /// Layer thickness in mm for `layer_index`. Must be > 0 when `layer_index` is enabled.
+    pub layer_thickness_mm: f32,
+}
+
+impl Default for PointAttributeOptions {
+    fn default() -> Self {
+        Self {
+            depth: false,
+            raw_depth: false,
+            source_pixel: false,
+            rgb: false,
+            intensity: false,
+            layer_index: false,
+            layer_thickness_mm: DEFAULT_LAYER_THICKNESS_MM,
+        }
+    }
+}
+
+impl PointAttributeOptions {
+    /// True when any channel reads the source image (RGB or intensity).
+    pub fn needs_source_image(&self) -> bool {
+        self.rgb || self.intensity
+    }
+
+    /// Validate options (e.g. Tauri `set_point_attributes`).
+    pub fn validate(&self) -> Result<(), String> {
+        if self.layer_index
+            && (!self.layer_thickness_mm.is_finite() || self.layer_thickness_mm <= 0.0)
+        {
+            return Err("Layer thickness must be positive".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Optional per-point channels. Each enabled channel has one entry per point, in point order.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PointAttributes {
+    /// Depth value [0, 1] of the source pixel, as used for generation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<Vec<f32>>,
+    /// Estimator depth of the source pixel before sculpting and adjustments.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_depth: Option<Vec<f32>>,
+    /// Source pixel coordinates (u = column, v = row).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_pixel: Option<Vec<[u32; 2]>>,
+    /// Source image colour.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rgb: Option<Vec<[u8; 3]>>,
+    /// Source image luminance (Rec. 709) in [0, 1].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intensity: Option<Vec<f32>>,
+    /// Z-layer index of the fitted point.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer_index: Option<Vec<u32>>,
+    /// Layer thickness in mm that `layer_index` was computed with (set with that channel),
+    /// so points moved after generation can be re-layered.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer_thickness_mm: Option<f32>,
+}
+
+impl PointAttributes {
+    /// True when no channel is present.
+    pub fn is_empty(&self) -> bool {
+        self.depth.is_none()
+            && self.raw_depth.is_none()
+            && self.source_pixel.is_none()
+            && self.rgb.is_none()
+            && self.intensity.is_none()
+            && self.layer_index.is_none()
+    }
+
+    /// Check every present channel has exactly `point_count` entries.
+    pub fn validate_len(&self, point_count: usize) -> Result<(), String> {
+        let lens = [
+            ("depth", self.depth.as_ref().map(Vec::len)),
+            ("raw depth", self.raw_depth.as_ref().map(Vec::len)),
+            ("source pixel", self.source_pixel.as_ref().map(Vec::len)),
+            ("rgb", self.rgb.as_ref().map(Vec::len)),
+            ("intensity", self.intensity.as_ref().map(Vec::len)),
+            ("layer index", self.layer_index.as_ref().map(Vec::len)),
+        ];
+        for (name, len) in lens {
+            if let Some(len) = len {
+                if len != point_count {
+                    return Err(format!(
+                        "Point attribute '{}' has {} entries for {} points",
+                        name, len, point_count
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Channels present, as options.
+    pub fn channels(&self) -> PointAttributeOptions {
+        PointAttributeOptions {
+            depth: self.depth.is_some(),
+            raw_depth: self.raw_depth.is_some(),
+            source_pixel: self.source_pixel.is_some(),
+            rgb: self.rgb.is_some(),
+            intensity: self.intensity.is_some(),
+            layer_index: self.layer_index.is_some(),
+            layer_thickness_mm: self
+                .layer_thickness_mm
+                .unwrap_or(DEFAULT_LAYER_THICKNESS_MM),
+        }
+    }
+
+    /// Empty vectors for each channel enabled in `channels`.
+    pub fn with_channels(channels: &PointAttributeOptions, capacity: usize) -> Self {
+        Self {
+            depth: channels.depth.then(|| Vec::with_capacity(capacity)),
+            raw_depth: channels.raw_depth.then(|| Vec::with_capacity(capacity)),
+            source_pixel: channels.source_pixel.then(|| Vec::with_capacity(capacity)),
+            rgb: channels.rgb.then(|| Vec::with_capacity(capacity)),
+            intensity: channels.intensity.then(|| Vec::with_capacity(capacity)),
+            layer_index: channels.layer_index.then(|| Vec::with_capacity(capacity)),
+            layer_thickness_mm: channels.layer_index.then_some(channels.layer_thickness_mm),
+        }
+    }
+
+    /// The record of point `index` (at `position`). Call [`Self::validate_len`] first.
+    pub fn record(&self, index: usize, position: [f32; 3]) -> PointRecord {
+        PointRecord {
+            position,
+            depth: self.depth.as_ref().map(|v| v[index]),
+            raw_depth: self.raw_depth.as_ref().map(|v| v[index]),
+            source_pixel: self.source_pixel.as_ref().map(|v| v[index]),
+            rgb: self.rgb.as_ref().map(|v| v[index]),
+            intensity: self.intensity.as_ref().map(|v| v[index]),
+            layer_index: self.layer_index.as_ref().map(|v| v[index]),
+        }
+    }
+
+    /// Append the channels of `record` that are present in `self`.
+    fn push(&mut self, record: &PointRecord) {
+        if let (Some(v), Some(d)) = (self.depth.as_mut(), record.depth) {
+            v.push(d);
+        }
+        if let (Some(v), Some(d)) = (self.raw_depth.as_mut(), record.raw_depth) {
+            v.push(d);
         }
+        if let (Some(v), Some(px)) = (self.source_pixel.as_mut(), record.source_pixel) {
+            v.push(px);
+        }
+        if let (Some(v), Some(c)) = (self.rgb.as_mut(), record.rgb) {
+            v.push(c);
+        }
+        if let (Some(v), Some(i)) = (self.intensity.as_mut(), record.intensity) {
+            v.push(i);
+        }
+        if let (Some(v), Some(l)) = (self.layer_index.as_mut(), record.layer_index) {
+            v.push(l);
+        }
+    }
+
+    /// Keep only the entries whose `keep` flag is set (one flag per point).
+    pub fn retain(&mut self, keep: &[bool]) {
+        fn filter<T>(channel: &mut Option<Vec<T>>, keep: &[bool]) {
+            if let Some(values) = channel {
+                let mut flags = keep.iter();
+                values.retain(|_| *flags.next().unwrap_or(&true));
+            }
+        }
+        filter(&mut self.depth, keep);
+        filter(&mut self.raw_depth, keep);
+        filter(&mut self.source_pixel, keep);
+        filter(&mut self.rgb, keep);
+        filter(&mut self.intensity, keep);
+        filter(&mut self.layer_index, keep);
     }
+
+    /// Approximate heap size of all present channels in bytes.
+    pub fn memory_bytes(&self) -> usize {
+        self.depth.as_ref().map_or(0, |v| v.len() * 4)
+            + self.raw_depth.as_ref().map_or(0, |v| v.len() * 4)
+            + self.source_pixel.as_ref().map_or(0, |v| v.len() * 8)
+            + self.rgb.as_ref().map_or(0, |v| v.len() * 3)
+            + self.intensity.as_ref().map_or(0, |v| v.len() * 4)
+            + self.layer_index.as_ref().map_or(0, |v| v.len() * 4)
+    }
+}
+
+/// One fitted point with the optional channels enabled in [`PointAttributeOptions`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PointRecord {
+    pub position: [f32; 3],
+    pub depth: Option<f32>,
+    pub raw_depth: Option<f32>,
+    pub source_pixel: Option<[u32; 2]>,
+    pub rgb: Option<[u8; 3]>,
+    pub intensity: Option<f32>,
+    pub layer_index: Option<u32>,
+}
+
+/// What a [`PointSink`] is told before the first point of a streamed cloud.
+#[derive(Debug, Clone)]
+pub struct StreamPlan {
+    /// Exact number of points that will be pushed.
+    pub point_count: usize,
+    /// Fit scale (see [`FitResult::scale`]).
+    pub scale: f32,
+    /// Fit translation (see [`FitResult::translation`]).
+    pub translation: [f32; 3],
+    /// Bounds (min, max) of the fitted points.
+    pub bounds: ([f32; 3], [f32; 3]),
+    /// Channels present on every pushed [`PointRecord`].
+    pub channels: PointAttributeOptions,
+}
+
+/// Receives a streamed point cloud: [`PointSink::begin`] once, then one
+/// [`PointSink::push`] per point in generation order.
+pub trait PointSink {
+    /// Progress of the pass that plans the stream, before [`PointSink::begin`]: `done` of
+    /// `total` depth map rows scanned. An error stops generation.
+    fn scan_progress(&mut self, _done: usize, _total: usize) -> Result<(), String> {
+        Ok(())
+    }
+    fn begin(&mut self, plan: &StreamPlan) -> Result<(), String>;
+    fn push(&mut self, record: &PointRecord) -> Result<(), String>;
+    /// Called after the last point was pushed.
+    fn end(&mut self) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Sink that materialises the cloud (what [`generate_volumetric_points`] returns).
+#[derive(Debug, Default)]
+pub struct PointCollector {
+    pub points: Vec<[f32; 3]>,
+    pub attributes: PointAttributes,
+}
+
+impl PointSink for PointCollector {
+    fn begin(&mut self, plan: &StreamPlan) -> Result<(), String> {
+        self.points = Vec::with_capacity(plan.point_count);
+        self.attributes = PointAttributes::with_channels(&plan.channels, plan.point_count);
+        Ok(())
+    }
+
+    fn push(&mut self, record: &PointRecord) -> Result<(), String> {
+        self.points.push(record.position);
+        self.attributes.push(record);
+        Ok(())
+    }
+}
+
+/// Z-layer index of a fitted point at height `z`: `floor((z - margin) / layer_thickness_mm)`.
+#[inline]
+pub(crate) fn layer_of(z: f32, margin: f32, layer_thickness_mm: f32) -> u32 {
+    ((z - margin).max(0.0) / layer_thickness_mm).floor() as u32
+}
+
+/// Rec. 709 luminance of an 8-bit RGB pixel, in [0, 1].
+#[inline]
+pub(crate) fn luminance(rgb: [u8; 3]) -> f32 {
+    (0.2126 * rgb[0] as f32 + 0.7152 * rgb[1] as f32 + 0.0722 * rgb[2] as f32) / 255.0
 }
 
 /// Result of surface-map point cloud generation.
@@ -78,6 +453,57 @@ pub struct VolumetricResult {
     pub fit_result: FitResult,
     /// Estimated memory usage in bytes.
     pub memory_bytes: usize,
+    /// Optional per-point channels (empty unless enabled via [`PointAttributeOptions`]).
+    #[serde(default)]
+    pub attributes: PointAttributes,
+}
+
+impl VolumetricResult {
+    /// Result of a cloud streamed into a [`PointCollector`].
+    pub fn from_collector(collector: PointCollector, fit_result: FitResult) -> Self {
+        let PointCollector { points, attributes } = collector;
+        let point_count = points.len();
+        let memory_bytes =
+            point_count * std::mem::size_of::<[f32; 3]>() + attributes.memory_bytes();
+        Self {
+            points,
+            point_count,
+            fit_result,
+            memory_bytes,
+            attributes,
+        }
+    }
+
+    /// Wrap an already-fitted cloud from another input source (e.g. an imported file).
+    pub fn from_fitted_points(points: Vec<[f32; 3]>, fit_result: FitResult) -> Self {
+        let point_count = points.len();
+        Self {
+            memory_bytes: point_count * std::mem::size_of::<[f32; 3]>(),
+            points,
+            point_count,
+            fit_result,
+            attributes: PointAttributes::default(),
+        }
+    }
+
+    /// Replay the cloud into `sink` as if it were being generated; returns its fit.
+    pub fn stream_into(&self, sink: &mut dyn PointSink) -> Result<FitResult, String> {
+        self.attributes.validate_len(self.points.len())?;
+        let bounds =
+            crate::blank_envelope::compute_bbox(&self.points).ok_or("Point cloud is empty")?;
+        sink.begin(&StreamPlan {
+            point_count: self.points.len(),
+            scale: self.fit_result.scale,
+            translation: self.fit_result.translation,
+            bounds,
+            channels: self.attributes.channels(),
+        })?;
+        for (i, &p) in self.points.iter().enumerate() {
+            sink.push(&self.attributes.record(i, p))?;
+        }
+        sink.end()?;
+        Ok(self.fit_result.clone())
+    }
 }
 
 /// Generate a 3D surface-map point cloud from a depth map (ADR-012).
@@ -87,7 +513,7 @@ pub struct VolumetricResult {
 /// no column sweep / Z fill — exactly one point per accepted (x, y) sample.
 ///
 /// After generation, points are scaled and centred inside the blank envelope
-/// via [`fit_to_blank`].
+/// via [`fit_to_blank`](crate::blank_envelope::fit_to_blank).
 ///
 /// # Arguments
 /// * `depth` - Row-major depth map, normalized 0.0-1.0 (0=far, 1=near).
@@ -107,89 +533,298 @@ pub fn generate_volumetric_points(
     params: &VolumetricParams,
     envelope: &BlankEnvelope,
 ) -> Result<VolumetricResult, String> {
-    let expected_len = (width as usize)
-        .checked_mul(height as usize)
-        .ok_or("Depth map dimensions overflow")?;
-
-    if depth.len() != expected_len {
-        return Err(format!(
-            "Depth map length {} doesn't match {}x{}={}",
-            depth.len(),
-            width,
-            height,
-            expected_len
-        ));
-    }
+    generate_volumetric_points_with_attributes(
+        depth,
+        width,
+        height,
+        params,
+        envelope,
+        &PointAttributeOptions::default(),
+        None,
+        None,
+    )
+}
 
-    if width == 0 || height == 0 {
-        return Err("Depth map dimensions must be positive".to_string());
-    }
+/// [`generate_volumetric_points`] that also records the optional per-point channels
+/// enabled in `options`.
+///
+/// `source_rgb` must be provided (with the depth map's dimensions) when RGB or
+/// intensity is requested, and `raw_depth` (the estimator's depth before sculpting and
+/// adjustments, same length as `depth`) when raw depth is requested.
+#[allow(clippy::too_many_arguments)]
+pub fn generate_volumetric_points_with_attributes(
+    depth: &[f32],
+    width: u32,
+    height: u32,
+    params: &VolumetricParams,
+    envelope: &BlankEnvelope,
+    options: &PointAttributeOptions,
+    source_rgb: Option<&image::RgbImage>,
+    raw_depth: Option<&[f32]>,
+) -> Result<VolumetricResult, String> {
+    let mut collector = PointCollector::default();
+    let fit_result = stream_volumetric_points(
+        depth,
+        width,
+        height,
+        params,
+        envelope,
+        options,
+        source_rgb,
+        raw_depth,
+        &mut collector,
+    )?;
+    Ok(VolumetricResult::from_collector(collector, fit_result))
+}
 
-    if params.step_x == 0 || params.step_y == 0 {
-        return Err("Step size must be positive".to_string());
-    }
+/// Stream the fitted surface-map cloud into `sink` without holding it in memory.
+///
+/// Makes two passes over the depth map: the first counts accepted samples and their
+/// bounds, from which [`fit_transform`] gives the fit analytically; the second pushes
+/// each fitted point. Points, order and fit are identical to
+/// [`generate_volumetric_points_with_attributes`]. Memory use is independent of the
+/// point count.
+#[allow(clippy::too_many_arguments)]
+pub fn stream_volumetric_points(
+    depth: &[f32],
+    width: u32,
+    height: u32,
+    params: &VolumetricParams,
+    envelope: &BlankEnvelope,
+    options: &PointAttributeOptions,
+    source_rgb: Option<&image::RgbImage>,
+    raw_depth: Option<&[f32]>,
+    sink: &mut dyn PointSink,
+) -> Result<FitResult, String> {
+    let sampler = SurfaceSampler::new(
+        depth, width, height, params, envelope, options, source_rgb, raw_depth,
+    )?;
+
+    let mut point_count = 0usize;
+    let mut min = [f32::MAX; 3];
+    let mut max = [f32::MIN; 3];
+    let mut scanned_row = None;
+    sampler.for_each(|_, py, _, p| {
+        if scanned_row != Some(py) {
+            scanned_row = Some(py);
+            sink.scan_progress(py as usize, height as usize)?;
+        }
+        point_count += 1;
+        for i in 0..3 {
+            min[i] = min[i].min(p[i]);
+            max[i] = max[i].max(p[i]);
+        }
+        Ok(())
+    })?;
 
-    if !params.depth_threshold.is_finite()
-        || params.depth_threshold < 0.0
-        || params.depth_threshold > 1.0
-    {
-        return Err("Depth threshold must be in [0.0, 1.0]".to_string());
+    if point_count == 0 {
+        return Err("No points generated (all depth values are below depth_threshold)".to_string());
     }
 
-    envelope.validate()?;
+    let (scale, translation) = fit_transform(min, max, envelope);
+    let apply = |p: [f32; 3]| -> [f32; 3] {
+        [
+            p[0] * scale + translation[0],
+            p[1] * scale + translation[1],
+            p[2] * scale + translation[2],
+        ]
+    };
+    sink.begin(&StreamPlan {
+        point_count,
+        scale,
+        translation,
+        bounds: (apply(min), apply(max)),
+        channels: options.clone(),
+    })?;
 
-    let interior_length = envelope.interior_length();
-    let interior_width = envelope.interior_width();
-    let interior_height = envelope.interior_height();
     let margin = envelope.margin_mm;
+    let mut outliers = 0usize;
+    sampler.for_each(|px, py, d, p| {
+        let position = apply(p);
+        if is_outside_envelope(&position, envelope) {
+            outliers += 1;
+        }
+        let rgb = sampler.source_rgb.map(|img| img.get_pixel(px, py).0);
+        let idx = py as usize * width as usize + px as usize;
+        sink.push(&PointRecord {
+            position,
+            depth: options.depth.then_some(d),
+            raw_depth: sampler.raw_depth.map(|raw| raw[idx]),
+            source_pixel: options.source_pixel.then_some([px, py]),
+            rgb: rgb.filter(|_| options.rgb),
+            intensity: rgb.filter(|_| options.intensity).map(luminance),
+            layer_index: options
+                .layer_index
+                .then(|| layer_of(position[2], margin, options.layer_thickness_mm)),
+        })
+    })?;
+    sink.end()?;
+
+    Ok(FitResult {
+        scale,
+        translation,
+        point_count,
+        outliers,
+    })
+}
 
-    let width_f = width as f32;
-    let height_f = height as f32;
-
-    let num_cols = width.div_ceil(params.step_x);
-    let num_rows = height.div_ceil(params.step_y);
+/// Validated inputs of one surface-map generation; yields accepted samples in order.
+struct SurfaceSampler<'a> {
+    depth: &'a [f32],
+    width: u32,
+    height: u32,
+    params: &'a VolumetricParams,
+    envelope: &'a BlankEnvelope,
+    source_rgb: Option<&'a image::RgbImage>,
+    /// Present only when the raw depth channel is recorded.
+    raw_depth: Option<&'a [f32]>,
+}
 
-    let capacity = (num_cols as usize).saturating_mul(num_rows as usize);
-    let mut points: Vec<[f32; 3]> = Vec::with_capacity(capacity);
+impl<'a> SurfaceSampler<'a> {
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        depth: &'a [f32],
+        width: u32,
+        height: u32,
+        params: &'a VolumetricParams,
+        envelope: &'a BlankEnvelope,
+        options: &PointAttributeOptions,
+        source_rgb: Option<&'a image::RgbImage>,
+        raw_depth: Option<&'a [f32]>,
+    ) -> Result<Self, String> {
+        let expected_len = (width as usize)
+            .checked_mul(height as usize)
+            .ok_or("Depth map dimensions overflow")?;
+
+        if depth.len() != expected_len {
+            return Err(format!(
+                "Depth map length {} doesn't match {}x{}={}",
+                depth.len(),
+                width,
+                height,
+                expected_len
+            ));
+        }
 
-    let threshold = params.depth_threshold;
+        if width == 0 || height == 0 {
+            return Err("Depth map dimensions must be positive".to_string());
+        }
 
-    for row in 0..num_rows {
-        let py = (row * params.step_y).min(height - 1);
-        let y_mm = (py as f32 / height_f) * interior_width + margin;
+        validate_volumetric_params(params)?;
+        envelope.validate()?;
+        options.validate()?;
+
+        let source_rgb = if options.needs_source_image() {
+            let img = source_rgb.ok_or("Source image is required for RGB/intensity attributes")?;
+            if img.dimensions() != (width, height) {
+                return Err(format!(
+                    "Source image {}x{} doesn't match depth map {}x{}",
+                    img.width(),
+                    img.height(),
+                    width,
+                    height
+                ));
+            }
+            Some(img)
+        } else {
+            None
+        };
 
-        for col in 0..num_cols {
-            let px = (col * params.step_x).min(width - 1);
+        let raw_depth = if options.raw_depth {
+            let raw =
+                raw_depth.ok_or("Original depth map is required for the raw depth attribute")?;
+            if raw.len() != expected_len {
+                return Err(format!(
+                    "Raw depth length {} doesn't match {}x{}={}",
+                    raw.len(),
+                    width,
+                    height,
+                    expected_len
+                ));
+            }
+            Some(raw)
+        } else {
+            None
+        };
 
-            let idx = (py as usize) * (width as usize) + (px as usize);
-            let d = depth[idx].clamp(0.0, 1.0);
+        Ok(Self {
+            depth,
+            width,
+            height,
+            params,
+            envelope,
+            source_rgb,
+            raw_depth,
+        })
+    }
 
-            if d < threshold {
-                continue;
+    /// Call `f(px, py, depth, unfitted_point)` for every sample at or above the threshold.
+    fn for_each(
+        &self,
+        mut f: impl FnMut(u32, u32, f32, [f32; 3]) -> Result<(), String>,
+    ) -> Result<(), String> {
+        let interior_length = self.envelope.interior_length();
+        let interior_width = self.envelope.interior_width();
+        let interior_height = self.envelope.interior_height();
+        let margin = self.envelope.margin_mm;
+
+        let width_f = self.width as f32;
+        let height_f = self.height as f32;
+
+        let num_cols = self.width.div_ceil(self.params.step_x);
+        let num_rows = self.height.div_ceil(self.params.step_y);
+        let threshold = self.params.depth_threshold;
+
+        if let Projection::Perspective {
+            fov_deg,
+            near_distance,
+            far_distance,
+        } = self.params.projection
+        {
+            let (near_disparity, far_disparity) = (1.0 / near_distance, 1.0 / far_distance);
+            let half_fov = (fov_deg.to_radians() * 0.5).tan();
+            let focal = 0.5 * width_f.max(height_f) / half_fov;
+            let (cx, cy) = (0.5 * width_f, 0.5 * height_f);
+            for row in 0..num_rows {
+                let py = (row * self.params.step_y).min(self.height - 1);
+                let v = (py as f32 + 0.5 - cy) / focal;
+                for col in 0..num_cols {
+                    let px = (col * self.params.step_x).min(self.width - 1);
+                    let idx = (py as usize) * (self.width as usize) + (px as usize);
+                    let d = self.depth[idx].clamp(0.0, 1.0);
+                    if d < threshold {
+                        continue;
+                    }
+                    let z = 1.0 / (far_disparity + d * (near_disparity - far_disparity));
+                    let u = (px as f32 + 0.5 - cx) / focal;
+                    f(px, py, d, [u * z, v * z, z])?;
+                }
             }
+            return Ok(());
+        }
 
-            let x_mm = (px as f32 / width_f) * interior_length + margin;
-            let z_mm = margin + (1.0 - d) * interior_height;
+        for row in 0..num_rows {
+            let py = (row * self.params.step_y).min(self.height - 1);
+            let y_mm = (py as f32 / height_f) * interior_width + margin;
 
-            points.push([x_mm, y_mm, z_mm]);
-        }
-    }
+            for col in 0..num_cols {
+                let px = (col * self.params.step_x).min(self.width - 1);
 
-    if points.is_empty() {
-        return Err("No points generated (all depth values are below depth_threshold)".to_string());
-    }
+                let idx = (py as usize) * (self.width as usize) + (px as usize);
+                let d = self.depth[idx].clamp(0.0, 1.0);
 
-    let fit_result = fit_to_blank(&mut points, envelope)?;
+                if d < threshold {
+                    continue;
+                }
 
-    let point_count = points.len();
-    let memory_bytes = point_count * std::mem::size_of::<[f32; 3]>();
+                let x_mm = (px as f32 / width_f) * interior_length + margin;
+                let z_mm = margin + (1.0 - d) * interior_height;
 
-    Ok(VolumetricResult {
-        points,
-        point_count,
-        fit_result,
-        memory_bytes,
-    })
+                f(px, py, d, [x_mm, y_mm, z_mm])?;
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Estimate the number of points that will be generated without performing the
@@ -233,6 +868,25 @@ pub fn validate_volumetric_params(params: &VolumetricParams) -> Result<(), Strin
     {
         return Err("Depth threshold must be in [0.0, 1.0]".to_string());
     }
+    if let Projection::Perspective {
+        fov_deg,
+        near_distance,
+        far_distance,
+    } = params.projection
+    {
+        if !fov_deg.is_finite() || !(MIN_FOV_DEG..=MAX_FOV_DEG).contains(&fov_deg) {
+            return Err(format!(
+                "Field of view must be in [{MIN_FOV_DEG}, {MAX_FOV_DEG}] degrees"
+            ));
+        }
+        let ratio = far_distance / near_distance;
+        if !(near_distance > 0.0 && ratio > 1.0 && ratio <= MAX_DEPTH_RATIO) {
+            return Err(format!(
+                "Far distance must be greater than the near distance (> 0) and at most \
+                 {MAX_DEPTH_RATIO}× as far"
+            ));
+        }
+    }
     Ok(())
 }
 
@@ -271,6 +925,7 @@ mod tests {
             step_x: 2,
             step_y: 2,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -290,6 +945,7 @@ mod tests {
             step_x: 1,
             step_y: 1,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -304,6 +960,7 @@ mod tests {
             step_x: 1,
             step_y: 1,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -334,6 +991,7 @@ mod tests {
             step_x: 1,
             step_y: 1,
             depth_threshold: 0.5,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -349,6 +1007,7 @@ mod tests {
             step_x: 1,
             step_y: 1,
             depth_threshold: 1.0,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -381,6 +1040,7 @@ mod tests {
             step_x: 1,
             step_y: 1,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let r_full = generate_volumetric_points(&depth, 8, 8, &p_full, &envelope).unwrap();
         assert_eq!(r_full.point_count, 8 * 8);
@@ -389,6 +1049,7 @@ mod tests {
             step_x: 2,
             step_y: 2,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let r_step2 = generate_volumetric_points(&depth, 8, 8, &p_step2, &envelope).unwrap();
         assert_eq!(r_step2.point_count, 4 * 4);
@@ -397,6 +1058,7 @@ mod tests {
             step_x: 4,
             step_y: 4,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let r_step4 = generate_volumetric_points(&depth, 8, 8, &p_step4, &envelope).unwrap();
         assert_eq!(r_step4.point_count, 2 * 2);
@@ -410,6 +1072,7 @@ mod tests {
             step_x: 3,
             step_y: 2,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -445,6 +1108,7 @@ mod tests {
             step_x: 0,
             step_y: 1,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -459,6 +1123,7 @@ mod tests {
             step_x: 1,
             step_y: 1,
             depth_threshold: -0.1,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -473,6 +1138,7 @@ mod tests {
             step_x: 1,
             step_y: 1,
             depth_threshold: 1.5,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -502,6 +1168,7 @@ mod tests {
             step_x: 1,
             step_y: 1,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -511,6 +1178,187 @@ mod tests {
         assert!(result.points[0][2] < result.points[1][2]);
     }
 
+    #[test]
+    fn attributes_absent_by_default() {
+        let depth = make_flat_depth(4, 4, 0.5);
+        let result = generate_volumetric_points(
+            &depth,
+            4,
+            4,
+            &VolumetricParams::default(),
+            &BlankEnvelope::default(),
+        )
+        .unwrap();
+        assert!(result.attributes.is_empty());
+    }
+
+    #[test]
+    fn attributes_track_each_point() {
+        // 3x2 map; first row below threshold so only the second row is emitted.
+        let depth = vec![0.0, 0.0, 0.0, 0.2, 0.6, 1.0];
+        let img = image::RgbImage::from_fn(3, 2, |x, y| image::Rgb([x as u8 * 100, y as u8, 255]));
+        let params = VolumetricParams {
+            step_x: 1,
+            step_y: 1,
+            depth_threshold: 0.1,
+            ..Default::default()
+        };
+        let options = PointAttributeOptions {
+            depth: true,
+            raw_depth: true,
+            source_pixel: true,
+            rgb: true,
+            intensity: true,
+            layer_index: true,
+            layer_thickness_mm: 1.0,
+        };
+        let envelope = BlankEnvelope::default();
+        // What the estimator produced before sculpting/adjustments turned it into `depth`.
+        let raw: Vec<f32> = depth.iter().map(|d| d * 0.5).collect();
+
+        let result = generate_volumetric_points_with_attributes(
+            &depth,
+            3,
+            2,
+            &params,
+            &envelope,
+            &options,
+            Some(&img),
+            Some(&raw),
+        )
+        .unwrap();
+
+        let attrs = &result.attributes;
+        attrs.validate_len(result.point_count).unwrap();
+        assert_eq!(attrs.depth.as_ref().unwrap(), &vec![0.2, 0.6, 1.0]);
+        assert_eq!(attrs.raw_depth.as_ref().unwrap(), &vec![0.1, 0.3, 0.5]);
+        assert_eq!(attrs.source_pixel.as_ref().unwrap()[2], [2, 1]);
+        assert_eq!(attrs.rgb.as_ref().unwrap()[1], [100, 1, 255]);
+        let intensity = attrs.intensity.as_ref().unwrap();
+        assert!(intensity.iter().all(|v| (0.0..=1.0).contains(v)));
+        // Nearer points (higher depth) sit in lower Z layers.
+        let layers = attrs.layer_index.as_ref().unwrap();
+        assert!(layers[0] > layers[2]);
+        assert!(result.memory_bytes > result.point_count * 12);
+    }
+
+    #[test]
+    fn colour_attributes_require_source_image() {
+        let depth = make_flat_depth(2, 2, 0.5);
+        let options = PointAttributeOptions {
+            rgb: true,
+            ..Default::default()
+        };
+        let result = generate_volumetric_points_with_attributes(
+            &depth,
+            2,
+            2,
+            &VolumetricParams::default(),
+            &BlankEnvelope::default(),
+            &options,
+            None,
+            None,
+        );
+        assert!(result.is_err());
+
+        let raw_only = PointAttributeOptions {
+            raw_depth: true,
+            ..Default::default()
+        };
+        let result = generate_volumetric_points_with_attributes(
+            &depth,
+            2,
+            2,
+            &VolumetricParams::default(),
+            &BlankEnvelope::default(),
+            &raw_only,
+            None,
+            None,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn streamed_fit_matches_fit_to_blank() {
+        let (w, h) = (20u32, 14u32);
+        let depth = make_gradient_depth(w, h);
+        let params = VolumetricParams::default();
+        let envelope = BlankEnvelope::new(60.0, 40.0, 30.0, 1.5);
+
+        // Reference: materialise unfitted points, then fit them.
+        let mut raw = Vec::new();
+        for py in 0..h {
+            for px in 0..w {
+                let d = depth[(py * w + px) as usize];
+                if d >= params.depth_threshold {
+                    raw.push([
+                        (px as f32 / w as f32) * envelope.interior_length() + envelope.margin_mm,
+                        (py as f32 / h as f32) * envelope.interior_width() + envelope.margin_mm,
+                        envelope.margin_mm + (1.0 - d) * envelope.interior_height(),
+                    ]);
+                }
+            }
+        }
+        let reference_fit = crate::blank_envelope::fit_to_blank(&mut raw, &envelope).unwrap();
+
+        let result = generate_volumetric_points(&depth, w, h, &params, &envelope).unwrap();
+        assert_eq!(result.points, raw);
+        assert_eq!(result.fit_result.scale, reference_fit.scale);
+        assert_eq!(result.fit_result.translation, reference_fit.translation);
+        assert_eq!(result.fit_result.outliers, reference_fit.outliers);
+    }
+
+    #[test]
+    fn stream_announces_count_and_bounds_before_points() {
+        #[derive(Default)]
+        struct Recorder {
+            plan: Option<StreamPlan>,
+            pushed: usize,
+            min_z: f32,
+            max_z: f32,
+        }
+        impl PointSink for Recorder {
+            fn begin(&mut self, plan: &StreamPlan) -> Result<(), String> {
+                assert!(self.plan.is_none());
+                self.plan = Some(plan.clone());
+                self.min_z = f32::MAX;
+                self.max_z = f32::MIN;
+                Ok(())
+            }
+            fn push(&mut self, record: &PointRecord) -> Result<(), String> {
+                assert!(self.plan.is_some());
+                assert_eq!(record.depth, None);
+                self.pushed += 1;
+                self.min_z = self.min_z.min(record.position[2]);
+                self.max_z = self.max_z.max(record.position[2]);
+                Ok(())
+            }
+        }
+
+        let depth = make_gradient_depth(9, 7);
+        let params = VolumetricParams::default();
+        let envelope = BlankEnvelope::default();
+        let mut sink = Recorder::default();
+        let fit = stream_volumetric_points(
+            &depth,
+            9,
+            7,
+            &params,
+            &envelope,
+            &PointAttributeOptions::default(),
+            None,
+            None,
+            &mut sink,
+        )
+        .unwrap();
+
+        let plan = sink.plan.unwrap();
+        assert_eq!(plan.point_count, sink.pushed);
+        assert_eq!(fit.point_count, sink.pushed);
+        assert_eq!(plan.bounds.0[2], sink.min_z);
+        assert_eq!(plan.bounds.1[2], sink.max_z);
+    }
+
     #[test]
     fn estimate_matches_actual_for_threshold_zero() {
         // With threshold = 0.0, every sampled pixel is emitted, so the estimator
@@ -519,6 +1367,7 @@ mod tests {
             step_x: 2,
             step_y: 2,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
 
@@ -538,6 +1387,7 @@ mod tests {
                 step_x: 1,
                 step_y: 1,
                 depth_threshold: 0.1,
+                ..Default::default()
             },
             &envelope,
         );
@@ -548,6 +1398,7 @@ mod tests {
                 step_x: 1,
                 step_y: 1,
                 depth_threshold: 0.9,
+                ..Default::default()
             },
             &envelope,
         );
@@ -572,6 +1423,7 @@ mod tests {
             step_x: 0,
             step_y: 1,
             depth_threshold: 0.0,
+            ..Default::default()
         };
         let envelope = BlankEnvelope::default();
         assert_eq!(estimate_point_count(100, 100, &params, &envelope), 0);
@@ -583,6 +1435,7 @@ mod tests {
             step_x: 2,
             step_y: 3,
             depth_threshold: 0.25,
+            ..Default::default()
         };
         let json = serde_json::to_string(&params).unwrap();
         // camelCase rename should expose depthThreshold in the JSON payload.
@@ -592,4 +1445,139 @@ mod tests {
         assert_eq!(loaded.step_y, 3);
         assert!((loaded.depth_threshold - 0.25).abs() < f32::EPSILON);
     }
+
+    fn row_x_span(points: &[[f32; 3]]) -> f32 {
+        let (lo, hi) = points.iter().fold((f32::MAX, f32::MIN), |(lo, hi), p| {
+            (lo.min(p[0]), hi.max(p[0]))
+        });
+        hi - lo
+    }
+
+    #[test]
+    fn perspective_spreads_far_content_and_keeps_flat_proportions() {
+        // Row 0 at the near distance (depth 1), row 1 at the far one, four times as far.
+        let mut depth = vec![1.0f32; 8];
+        depth.extend(vec![0.0f32; 8]);
+        let envelope = BlankEnvelope::default();
+        let ortho = VolumetricParams {
+            depth_threshold: 0.0,
+            ..Default::default()
+        };
+        let persp = VolumetricParams {
+            projection: Projection::Perspective {
+                fov_deg: DEFAULT_FOV_DEG,
+                near_distance: 1.0,
+                far_distance: 4.0,
+            },
+            ..ortho.clone()
+        };
+
+        let o = generate_volumetric_points(&depth, 8, 2, &ortho, &envelope).unwrap();
+        assert!((row_x_span(&o.points[..8]) - row_x_span(&o.points[8..])).abs() < 1e-4);
+
+        let p = generate_volumetric_points(&depth, 8, 2, &persp, &envelope).unwrap();
+        let ratio = row_x_span(&p.points[8..]) / row_x_span(&p.points[..8]);
+        assert!((ratio - 4.0).abs() < 1e-3, "far/near span ratio {ratio}");
+        // Far row sits deeper (toward the back face) than the near row.
+        assert!(p.points[8][2] > p.points[0][2]);
+
+        // A fronto-parallel plane keeps the image's aspect ratio (pixel centres 19 x 9 apart).
+        let flat = make_flat_depth(20, 10, 0.5);
+        let r = generate_volumetric_points(&flat, 20, 10, &persp, &envelope).unwrap();
+        let (min, max) = r
+            .points
+            .iter()
+            .fold(([f32::MAX; 3], [f32::MIN; 3]), |(lo, hi), q| {
+                (
+                    [lo[0].min(q[0]), lo[1].min(q[1]), lo[2].min(q[2])],
+                    [hi[0].max(q[0]), hi[1].max(q[1]), hi[2].max(q[2])],
+                )
+            });
+        assert!(((max[0] - min[0]) / (max[1] - min[1]) - 19.0 / 9.0).abs() < 1e-3);
+        assert!((max[2] - min[2]).abs() < 1e-4);
+    }
+
+    #[test]
+    fn perspective_reproduces_known_scene_proportions() {
+        // Three fronto-parallel strips at 2, 3 and 5 m seen through a 90° lens (f = 4 px
+        // for an 8 px wide image). Depth is the disparity normalised between near = 2 and
+        // far = 5, so the 3 m strip has d = (1/3 - 1/5) / (1/2 - 1/5) = 4/9.
+        let (w, h) = (8u32, 3u32);
+        let mut depth = vec![1.0f32; 8];
+        depth.extend(vec![4.0 / 9.0; 8]);
+        depth.extend(vec![0.0f32; 8]);
+        let params = VolumetricParams {
+            depth_threshold: 0.0,
+            projection: Projection::Perspective {
+                fov_deg: 90.0,
+                near_distance: 2.0,
+                far_distance: 5.0,
+            },
+            ..Default::default()
+        };
+        let r =
+            generate_volumetric_points(&depth, w, h, &params, &BlankEnvelope::default()).unwrap();
+        let rows: Vec<&[[f32; 3]]> = r.points.chunks(8).collect();
+        let z: Vec<f32> = rows.iter().map(|row| row[0][2]).collect();
+
+        // Depth gaps 2 → 3 → 5 m are 1 : 2.
+        assert!(((z[2] - z[1]) / (z[1] - z[0]) - 2.0).abs() < 1e-3);
+        // Widths grow with distance: 2 : 3 : 5.
+        let near_span = row_x_span(rows[0]);
+        assert!((row_x_span(rows[1]) / near_span - 1.5).abs() < 1e-3);
+        assert!((row_x_span(rows[2]) / near_span - 2.5).abs() < 1e-3);
+        // Width against depth: pixel centres 7 px apart at 2 m span 7 * 2 / 4 = 3.5 m, and
+        // the scene is 3 m deep.
+        assert!((near_span / (z[2] - z[0]) - 3.5 / 3.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn projection_validation_and_legacy_json() {
+        let legacy: VolumetricParams =
+            serde_json::from_str(r#"{"stepX":1,"stepY":1,"depthThreshold":0.05}"#).unwrap();
+        assert_eq!(legacy.projection, Projection::Orthographic);
+
+        let json = serde_json::to_string(&VolumetricParams {
+            projection: Projection::Perspective {
+                fov_deg: 60.0,
+                near_distance: 2.0,
+                far_distance: 8.0,
+            },
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(json.contains(
+            r#""projection":{"mode":"perspective","fovDeg":60.0,"nearDistance":2.0,"farDistance":8.0}"#
+        ));
+        let fov_only: VolumetricParams = serde_json::from_str(
+            r#"{"stepX":1,"stepY":1,"depthThreshold":0.05,"projection":{"mode":"perspective","fovDeg":70.0}}"#,
+        )
+        .unwrap();
+        assert_eq!(fov_only.projection, Projection::perspective());
+
+        for (fov_deg, near_distance, far_distance) in [
+            (0.0, 1.0, 10.0),
+            (180.0, 1.0, 10.0),
+            (f32::NAN, 1.0, 10.0),
+            (70.0, 0.0, 10.0),
+            (70.0, 2.0, 2.0),
+            (70.0, 1.0, f32::INFINITY),
+            (70.0, 0.001, 10.0),
+        ] {
+            let params = VolumetricParams {
+                projection: Projection::Perspective {
+                    fov_deg,
+                    near_distance,
+                    far_distance,
+                },
+                ..Default::default()
+            };
+            assert!(validate_volumetric_params(&params).is_err());
+        }
+        assert!(validate_volumetric_params(&VolumetricParams {
+            projection: Projection::perspective(),
+            ..Default::default()
+        })
+        .is_ok());
+    }
 }
@@ -9,14 +9,12 @@ import { describe, it, expect, vi } from "vitest";
 import { render, screen, fireEvent } from "@testing-library/svelte";
 import DepthControls from "../DepthControls.svelte";
 import type { DepthAdjustmentParams } from "$lib/tauri";
+import { adjustmentView } from "$lib/adjustments";
 
 const defaultParams: DepthAdjustmentParams = {
-  brightness: 0,
-  contrast: 1,
-  gamma: 1,
-  invert: false,
   depthMinMm: 2,
   depthMaxMm: 10,
+  operations: [],
 };
 
 describe("DepthControls", () => {
@@ -65,7 +63,7 @@ describe("DepthControls", () => {
     await fireEvent.input(slider, { target: { value: "0.2" } });
     expect(onParamsChange).toHaveBeenCalled();
     const lastCall = onParamsChange.mock.calls[onParamsChange.mock.calls.length - 1][0];
-    expect(lastCall.brightness).toBe(0.2);
+    expect(adjustmentView(lastCall).brightness).toBe(0.2);
   });
 
   it("toggling invert checkbox fires onParamsChange with invert true", async () => {
@@ -73,7 +71,7 @@ describe("DepthControls", () => {
     render(DepthControls, {
       props: {
         hasDepth: true,
-        params: { ...defaultParams, invert: false },
+        params: defaultParams,
         onParamsChange,
       },
     });
@@ -81,7 +79,7 @@ describe("DepthControls", () => {
     await fireEvent.click(checkbox);
     expect(onParamsChange).toHaveBeenCalled();
     const lastCall = onParamsChange.mock.calls[onParamsChange.mock.calls.length - 1][0];
-    expect(lastCall.invert).toBe(true);
+    expect(lastCall.operations).toEqual([{ type: "invert", enabled: true }]);
   });
 
   it("clicking Reset calls onReset", async () => {
@@ -129,6 +127,6 @@ describe("DepthControls", () => {
     await fireEvent.keyDown(brightnessSlider, { key: "ArrowRight" });
     expect(onParamsChange).toHaveBeenCalled();
     const lastCall = onParamsChange.mock.calls[onParamsChange.mock.calls.length - 1][0];
-    expect(lastCall.brightness).toBeCloseTo(0.01, 2);
+    expect(adjustmentView(lastCall).brightness).toBeCloseTo(0.01, 2);
   });
 });
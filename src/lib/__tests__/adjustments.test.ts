@@ -0,0 +1,87 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+import { describe, it, expect } from "vitest";
+import {
+  adjustmentView,
+  moveOperation,
+  removeOperation,
+  setOperationEnabled,
+  withAdjustments,
+  DEFAULT_ADJUSTMENT_PARAMS,
+} from "../adjustments";
+import type { DepthAdjustmentParams } from "../tauri";
+
+const types = (p: DepthAdjustmentParams) => p.operations.map((op) => op.type);
+
+describe("adjustment operation stack", () => {
+  it("empty stack reads as identity controls", () => {
+    const view = adjustmentView(DEFAULT_ADJUSTMENT_PARAMS);
+    expect(view).toMatchObject({ brightness: 0, contrast: 1, gamma: 1, invert: false, curveControlPoints: null });
+    expect(view.spatialFilters).toEqual([]);
+  });
+
+  it("new operations are inserted in canonical order", () => {
+    let p = withAdjustments(DEFAULT_ADJUSTMENT_PARAMS, { brightness: 0.1 });
+    p = withAdjustments(p, { terracing: { levels: 4, spacing: "even", transition: 0 } });
+    p = withAdjustments(p, { invert: true, gamma: 1.2 });
+    p = withAdjustments(p, { spatialFilters: [{ type: "median", radiusPx: 1 }] });
+    expect(types(p)).toEqual(["filter", "invert", "gamma", "brightness", "terracing"]);
+  });
+
+  it("hole filling goes first in the stack", () => {
+    let p = withAdjustments(DEFAULT_ADJUSTMENT_PARAMS, { gamma: 1.2 });
+    p = withAdjustments(p, {
+      inpaint: { holes: { rule: "depthRange", min: 0.02, max: 1 }, method: "diffusion", radiusPx: 5 },
+    });
+    expect(types(p)).toEqual(["inpaint", "gamma"]);
+    expect(adjustmentView(p).inpaint?.holes).toEqual({ rule: "depthRange", min: 0.02, max: 1 });
+  });
+
+  it("editing keeps the operation's place and enabled flag", () => {
+    let p = withAdjustments(DEFAULT_ADJUSTMENT_PARAMS, { brightness: 0.1, invert: true });
+    p = moveOperation(p, 1, -1); // brightness before invert
+    p = setOperationEnabled(p, 0, false);
+    p = withAdjustments(p, { brightness: 0.3 });
+    expect(p.operations[0]).toEqual({ type: "brightness", brightness: 0.3, enabled: false });
+    expect(types(p)).toEqual(["brightness", "invert"]);
+  });
+
+  it("null or false removes the operation", () => {
+    let p = withAdjustments(DEFAULT_ADJUSTMENT_PARAMS, { invert: true, basRelief: { strength: 0.5, targetRatio: 1 } });
+    p = withAdjustments(p, { invert: false, basRelief: null });
+    expect(p.operations).toEqual([]);
+  });
+
+  it("curve points and interpolation edit the same operation", () => {
+    const points = [
+      { x: 0, y: 0 },
+      { x: 1, y: 0.5 },
+    ];
+    let p = withAdjustments(DEFAULT_ADJUSTMENT_PARAMS, { curveControlPoints: points });
+    p = withAdjustments(p, { curveInterpolation: "monotoneCubic" });
+    expect(p.operations).toEqual([{ type: "curve", points, interpolation: "monotoneCubic", enabled: true }]);
+  });
+
+  it("filter list edits filter operations in place", () => {
+    let p = withAdjustments(DEFAULT_ADJUSTMENT_PARAMS, {
+      spatialFilters: [
+        { type: "median", radiusPx: 1 },
+        { type: "gaussian", sigmaPx: 2 },
+      ],
+      gamma: 1.5,
+    });
+    p = moveOperation(p, 2, -1); // gamma between the two filters
+    p = withAdjustments(p, { spatialFilters: [{ type: "median", radiusPx: 2 }, { type: "gaussian", sigmaPx: 2 }] });
+    expect(types(p)).toEqual(["filter", "gamma", "filter"]);
+    p = withAdjustments(p, { spatialFilters: [{ type: "median", radiusPx: 2 }] });
+    expect(types(p)).toEqual(["filter", "gamma"]);
+    expect(adjustmentView(removeOperation(p, 0)).spatialFilters).toEqual([]);
+  });
+
+  it("move ignores out-of-range targets", () => {
+    const p = withAdjustments(DEFAULT_ADJUSTMENT_PARAMS, { invert: true });
+    expect(moveOperation(p, 0, -1)).toBe(p);
+    expect(moveOperation(p, 0, 1)).toBe(p);
+  });
+});
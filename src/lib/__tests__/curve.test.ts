@@ -0,0 +1,41 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+import { describe, it, expect } from "vitest";
+import { sampleCurve } from "../curve";
+
+const S_CURVE = [
+  { x: 0, y: 0 },
+  { x: 0.25, y: 0.12 },
+  { x: 0.5, y: 0.5 },
+  { x: 0.75, y: 0.88 },
+  { x: 1, y: 1 },
+];
+
+describe("sampleCurve (BACK-1103)", () => {
+  it("is the identity without a curve", () => {
+    const out = sampleCurve([], "monotoneCubic", 4);
+    expect(out.map((p) => p.y)).toEqual([0, 0.25, 0.5, 0.75, 1]);
+  });
+
+  it("passes through the control points in every mode", () => {
+    for (const mode of ["linear", "monotoneCubic", "catmullRom"] as const) {
+      const out = sampleCurve(S_CURVE, mode, 4);
+      out.forEach((p, i) => expect(p.y).toBeCloseTo(S_CURVE[i].y, 5));
+    }
+  });
+
+  it("monotone cubic never decreases on a rising curve", () => {
+    const step = [
+      { x: 0, y: 0 },
+      { x: 0.4, y: 0 },
+      { x: 0.6, y: 1 },
+      { x: 1, y: 1 },
+    ];
+    const out = sampleCurve(step, "monotoneCubic", 200);
+    for (let i = 1; i < out.length; i++) {
+      expect(out[i].y).toBeGreaterThanOrEqual(out[i - 1].y - 1e-9);
+    }
+    expect(out[20].y).toBe(0);
+  });
+});
@@ -18,6 +18,15 @@ import {
   loadPreset,
   deletePreset,
   renamePreset,
+  defaultSpatialFilter,
+  proposeDepthLevels,
+  applySculptStroke,
+  clearSculpt,
+  exportPointCloud,
+  exportSliceStack,
+  exportSvgLayers,
+  replayRecipe,
+  cancelJob,
   type DepthAdjustmentParams,
   type LoadImageResult,
   type DepthMapResult,
@@ -31,6 +40,37 @@ vi.mock("@tauri-apps/api/core", () => ({
   invoke: (...args: unknown[]) => mockInvoke(...args),
 }));
 
+type EventHandler = (event: { payload: unknown }) => void;
+const listeners = new Map<string, Set<EventHandler>>();
+
+vi.mock("@tauri-apps/api/event", () => ({
+  listen: async (name: string, handler: EventHandler) => {
+    if (!listeners.has(name)) listeners.set(name, new Set());
+    listeners.get(name)!.add(handler);
+    return () => listeners.get(name)!.delete(handler);
+  },
+}));
+
+function emit(name: string, payload: unknown) {
+  listeners.get(name)?.forEach((handler) => handler({ payload }));
+}
+
+/** Make job commands return `jobId` and finish it with `result` (before the id arrives). */
+function mockJob(jobId: number, result: unknown) {
+  mockInvoke.mockImplementation(async () => {
+    emit("job-progress", {
+      jobId,
+      kind: "export",
+      stage: "points",
+      percent: 50,
+      processed: 1,
+      total: 2,
+    });
+    emit("job-finished", { jobId, kind: "export", status: "completed", result });
+    return jobId;
+  });
+}
+
 describe("tauri IPC", () => {
   beforeEach(() => {
     mockInvoke.mockReset();
@@ -61,8 +101,115 @@ describe("tauri IPC", () => {
   });
 
   // Sprint A: `exportStl` / `exportObj` and the 2.5D STL/OBJ pipeline have been retired.
-  // Point cloud exporters (PLY/XYZ/CSV per ADR-012) ship in Sprint B (BACK-B-03) and
-  // will get their own coverage when they land.
+  // Point cloud exporters go through the generic registry command.
+
+  describe("exportPointCloud", () => {
+    it("calls invoke with export_point_cloud, format, options and path", async () => {
+      mockJob(3, { pointCount: 2, sha256: "ab", byteSize: 10, verified: true, mergedPoints: 0 });
+      const receipt = await exportPointCloud("ply", "/out/cloud.ply", { binary: true });
+      expect(receipt.byteSize).toBe(10);
+      expect(mockInvoke).toHaveBeenCalledWith("export_point_cloud", {
+        format: "ply",
+        options: { binary: true },
+        path: "/out/cloud.ply",
+      });
+    });
+
+    it("defaults options to an empty object", async () => {
+      mockJob(4, { pointCount: 2, sha256: "ab", byteSize: 10, verified: true, mergedPoints: 0 });
+      await exportPointCloud("xyz", "/out/cloud.xyz");
+      expect(mockInvoke).toHaveBeenCalledWith("export_point_cloud", {
+        format: "xyz",
+        options: {},
+        path: "/out/cloud.xyz",
+      });
+    });
+  });
+
+  describe("exportSliceStack", () => {
+    it("calls invoke with export_layer_folder, the slices format, path and options", async () => {
+      mockJob(6, { layers: [{ index: 0, zMm: 1.25, file: "layer_00000.png", pointCount: 3 }] });
+      const options = { layerThicknessMm: 0.5, dpi: 600, imageFormat: "png" as const };
+      const manifest = await exportSliceStack("/out/stack", options);
+      expect(manifest.layers[0].zMm).toBe(1.25);
+      expect(mockInvoke).toHaveBeenCalledWith("export_layer_folder", {
+        format: "slices",
+        options,
+        path: "/out/stack",
+      });
+    });
+  });
+
+  describe("exportSvgLayers", () => {
+    it("calls invoke with export_layer_folder, the svg format, path and options", async () => {
+      mockJob(7, { layers: [], pointCount: 0 });
+      const options = {
+        layerThicknessMm: 0.5,
+        style: "polylines" as const,
+        travelOrder: "serpentine" as const,
+        dotDiameterMm: 0.05,
+        joinDistanceMm: 1,
+      };
+      await exportSvgLayers("/out/svg", options);
+      expect(mockInvoke).toHaveBeenCalledWith("export_layer_folder", {
+        format: "svg",
+        options,
+        path: "/out/svg",
+      });
+    });
+  });
+
+  describe("runJob", () => {
+    it("reports progress and rejects a cancelled job", async () => {
+      mockInvoke.mockImplementation(async () => {
+        emit("job-progress", {
+          jobId: 9,
+          kind: "export",
+          stage: "points",
+          percent: 40,
+          processed: 4,
+          total: 10,
+        });
+        emit("job-finished", {
+          jobId: 9,
+          kind: "export",
+          status: "cancelled",
+          error: "Job cancelled",
+        });
+        return 9;
+      });
+      const progress: number[] = [];
+      const started: number[] = [];
+      await expect(
+        exportPointCloud("xyz", "/out/c.xyz", {}, {
+          onStart: (id) => started.push(id),
+          onProgress: (e) => progress.push(e.percent),
+        }),
+      ).rejects.toThrow("Job cancelled");
+      expect(started).toEqual([9]);
+      // Progress emitted before the id is known is not attributed to the job.
+      expect(progress).toEqual([]);
+    });
+
+    it("cancelJob invokes cancel_job with the id", async () => {
+      mockInvoke.mockResolvedValue(true);
+      await expect(cancelJob(5)).resolves.toBe(true);
+      expect(mockInvoke).toHaveBeenCalledWith("cancel_job", { jobId: 5 });
+    });
+  });
+
+  describe("replayRecipe", () => {
+    it("passes recipe path and a null source override by default", async () => {
+      mockJob(10, { pointCount: 3 });
+      const result = await replayRecipe("/out/cloud.ply.recipe.json");
+      expect(result.pointCount).toBe(3);
+      expect(mockInvoke).toHaveBeenCalledWith("replay_recipe", {
+        recipePath: "/out/cloud.ply.recipe.json",
+        sourcePath: null,
+        acceptMismatch: false,
+      });
+    });
+  });
 
   describe("generateDepthMap", () => {
     it("calls invoke with generate_depth_map and path", async () => {
@@ -107,24 +254,54 @@ describe("tauri IPC", () => {
     });
   });
 
+  describe("proposeDepthLevels", () => {
+    it("calls invoke with propose_depth_levels and returns the proposal", async () => {
+      const proposal = {
+        low: 0.1,
+        high: 0.9,
+        autoLevels: { lowPercentile: 0.5, highPercentile: 99.5 },
+        equalization: { mode: "clahe", tiles: 8, clipLimit: 2 },
+      };
+      mockInvoke.mockResolvedValue(proposal);
+      const out = await proposeDepthLevels();
+      expect(mockInvoke).toHaveBeenCalledWith("propose_depth_levels");
+      expect(out).toEqual(proposal);
+    });
+  });
+
+  describe("sculpt", () => {
+    it("sends the stroke to apply_sculpt_stroke and clears with clear_sculpt", async () => {
+      const state = { canUndo: true, canRedo: false, params: { depthMinMm: 2, depthMaxMm: 10, operations: [] } };
+      mockInvoke.mockResolvedValue(state);
+      const stroke = {
+        points: [
+          { x: 1, y: 2 },
+          { x: 5, y: 2 },
+        ],
+        radiusPx: 8,
+        falloff: "smooth" as const,
+        op: "raise" as const,
+        strength: 0.05,
+      };
+      expect(await applySculptStroke(stroke)).toEqual(state);
+      expect(mockInvoke).toHaveBeenCalledWith("apply_sculpt_stroke", { stroke });
+      await clearSculpt();
+      expect(mockInvoke).toHaveBeenCalledWith("clear_sculpt");
+    });
+  });
+
   describe("getDepthAdjustmentParams", () => {
     it("calls invoke with get_depth_adjustment_params", async () => {
       const result: DepthAdjustmentParams = {
-        brightness: 0,
-        contrast: 1,
-        gamma: 1.5,
-        invert: false,
         depthMinMm: 0,
         depthMaxMm: 100,
+        operations: [{ type: "gamma", gamma: 1.5, enabled: true }],
       };
       mockInvoke.mockResolvedValue(result);
       const out = await getDepthAdjustmentParams();
       expect(mockInvoke).toHaveBeenCalledWith("get_depth_adjustment_params");
       expect(out).toEqual(result);
-      expect(out).toHaveProperty("brightness");
-      expect(out).toHaveProperty("contrast");
-      expect(out).toHaveProperty("gamma");
-      expect(out).toHaveProperty("invert");
+      expect(out).toHaveProperty("operations");
       expect(out).toHaveProperty("depthMinMm");
       expect(out).toHaveProperty("depthMaxMm");
     });
@@ -139,12 +316,13 @@ describe("tauri IPC", () => {
     it("calls invoke with set_depth_adjustment_params and params", async () => {
       mockInvoke.mockResolvedValue(undefined);
       const params: DepthAdjustmentParams = {
-        brightness: 0.1,
-        contrast: 1.2,
-        gamma: 1.0,
-        invert: true,
         depthMinMm: 5,
         depthMaxMm: 95,
+        operations: [
+          { type: "invert", enabled: true },
+          { type: "contrast", contrast: 1.2, enabled: true },
+          { type: "brightness", brightness: 0.1, enabled: false },
+        ],
       };
       await setDepthAdjustmentParams(params);
       expect(mockInvoke).toHaveBeenCalledWith("set_depth_adjustment_params", { params });
@@ -154,12 +332,9 @@ describe("tauri IPC", () => {
       mockInvoke.mockRejectedValue(new Error("set failed"));
       await expect(
         setDepthAdjustmentParams({
-          brightness: 0,
-          contrast: 1,
-          gamma: 1,
-          invert: false,
           depthMinMm: 0,
           depthMaxMm: 100,
+          operations: [],
         })
       ).rejects.toThrow("set failed");
     });
@@ -250,26 +425,25 @@ describe("tauri IPC", () => {
 describe("DepthAdjustmentParams interface", () => {
   it("has all expected fields with correct types", () => {
     const params: DepthAdjustmentParams = {
-      brightness: 0,
-      contrast: 1,
-      gamma: 1,
-      invert: false,
       depthMinMm: 0,
       depthMaxMm: 100,
+      operations: [],
     };
-    expect(typeof params.brightness).toBe("number");
-    expect(typeof params.contrast).toBe("number");
-    expect(typeof params.gamma).toBe("number");
-    expect(typeof params.invert).toBe("boolean");
     expect(typeof params.depthMinMm).toBe("number");
     expect(typeof params.depthMaxMm).toBe("number");
-    expect(Object.keys(params).sort()).toEqual([
-      "brightness",
-      "contrast",
-      "depthMaxMm",
-      "depthMinMm",
-      "gamma",
-      "invert",
-    ]);
+    expect(Array.isArray(params.operations)).toBe(true);
+    expect(Object.keys(params).sort()).toEqual(["depthMaxMm", "depthMinMm", "operations"]);
+  });
+});
+
+describe("defaultSpatialFilter", () => {
+  it("returns a filter of the requested type with positive sizes", () => {
+    for (const type of ["gaussian", "median", "bilateral", "guided", "unsharpMask"] as const) {
+      const filter = defaultSpatialFilter(type);
+      expect(filter.type).toBe(type);
+      for (const [key, value] of Object.entries(filter)) {
+        if (key !== "type") expect(value).toBeGreaterThan(0);
+      }
+    }
   });
 });
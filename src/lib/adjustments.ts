@@ -0,0 +1,270 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+/**
+ * Helpers for the depth adjustment operation stack (Rust `depth_adjust::AdjustmentOp`).
+ * The individual controls (sliders, curve, filters, levels, …) edit the first operation of their
+ * type through a flat `AdjustmentView`; new operations go where the backend's canonical order
+ * puts them, so the controls behave like the former fixed pipeline until the user reorders.
+ */
+
+import type {
+  AdjustmentKind,
+  AdjustmentOp,
+  AdjustmentType,
+  AutoLevels,
+  BasRelief,
+  CurveInterpolation,
+  CurvePoint,
+  DepthAdjustmentParams,
+  DetailTransfer,
+  Equalization,
+  Inpaint,
+  SpatialFilter,
+  Terracing,
+} from "./tauri";
+
+/** Canonical order (Rust `AdjustmentKind::canonical_rank`). */
+export const CANONICAL_ORDER: AdjustmentType[] = [
+  "inpaint",
+  "filter",
+  "basRelief",
+  "autoLevels",
+  "equalization",
+  "invert",
+  "gamma",
+  "contrast",
+  "brightness",
+  "curve",
+  "terracing",
+  "detailTransfer",
+];
+
+export const OPERATION_LABELS: Record<AdjustmentType, string> = {
+  inpaint: "Fill holes",
+  filter: "Filter",
+  basRelief: "Bas-relief",
+  autoLevels: "Auto-levels",
+  equalization: "Equalisation",
+  invert: "Invert",
+  gamma: "Gamma",
+  contrast: "Contrast",
+  brightness: "Brightness",
+  curve: "Curve",
+  terracing: "Terraces",
+  detailTransfer: "Photo detail",
+};
+
+/** Values of the first operation of each type, in the shape the controls edit. */
+export interface AdjustmentView {
+  brightness: number;
+  contrast: number;
+  gamma: number;
+  invert: boolean;
+  curveControlPoints: CurvePoint[] | null;
+  curveInterpolation: CurveInterpolation;
+  inpaint: Inpaint | null;
+  spatialFilters: SpatialFilter[];
+  basRelief: BasRelief | null;
+  autoLevels: AutoLevels | null;
+  equalization: Equalization | null;
+  terracing: Terracing | null;
+  detailTransfer: DetailTransfer | null;
+}
+
+type OpOf<T extends AdjustmentType> = Extract<AdjustmentOp, { type: T }>;
+
+export const DEFAULT_ADJUSTMENT_PARAMS: DepthAdjustmentParams = {
+  depthMinMm: 2,
+  depthMaxMm: 10,
+  operations: [],
+};
+
+/** First operation of `type` (enabled or not). */
+export function firstOp<T extends AdjustmentType>(
+  params: DepthAdjustmentParams,
+  type: T
+): OpOf<T> | undefined {
+  return params.operations.find((op): op is OpOf<T> => op.type === type);
+}
+
+/** Strip `type` and `enabled`, leaving the operation's settings. */
+function settings<T extends object>(op: (T & { type: string; enabled: boolean }) | undefined): T | null {
+  if (!op) return null;
+  const { type: _type, enabled: _enabled, ...rest } = op;
+  return rest as unknown as T;
+}
+
+export function adjustmentView(params: DepthAdjustmentParams): AdjustmentView {
+  const curve = firstOp(params, "curve");
+  return {
+    brightness: firstOp(params, "brightness")?.brightness ?? 0,
+    contrast: firstOp(params, "contrast")?.contrast ?? 1,
+    gamma: firstOp(params, "gamma")?.gamma ?? 1,
+    invert: !!firstOp(params, "invert"),
+    curveControlPoints: curve?.points ?? null,
+    curveInterpolation: curve?.interpolation ?? "linear",
+    inpaint: settings<Inpaint>(firstOp(params, "inpaint")),
+    spatialFilters: params.operations.flatMap((op) => (op.type === "filter" ? [op.filter] : [])),
+    basRelief: settings<BasRelief>(firstOp(params, "basRelief")),
+    autoLevels: settings<AutoLevels>(firstOp(params, "autoLevels")),
+    equalization: firstOp(params, "equalization")?.equalization ?? null,
+    terracing: settings<Terracing>(firstOp(params, "terracing")),
+    detailTransfer: settings<DetailTransfer>(firstOp(params, "detailTransfer")),
+  };
+}
+
+/** Index where an operation of `type` goes: before the first operation ranked after it. */
+export function canonicalInsertIndex(operations: AdjustmentOp[], type: AdjustmentType): number {
+  const rank = CANONICAL_ORDER.indexOf(type);
+  const i = operations.findIndex((op) => CANONICAL_ORDER.indexOf(op.type) > rank);
+  return i < 0 ? operations.length : i;
+}
+
+/**
+ * Replace the first operation of `kind.type` (keeping its place and enabled flag), or insert
+ * it at the canonical position; `null` removes the first operation of `type`.
+ */
+export function setOperation(
+  params: DepthAdjustmentParams,
+  type: AdjustmentType,
+  kind: AdjustmentKind | null
+): DepthAdjustmentParams {
+  const operations = [...params.operations];
+  const i = operations.findIndex((op) => op.type === type);
+  if (kind === null) {
+    if (i >= 0) operations.splice(i, 1);
+  } else if (i >= 0) {
+    operations[i] = { ...kind, enabled: operations[i].enabled } as AdjustmentOp;
+  } else {
+    operations.splice(canonicalInsertIndex(operations, type), 0, { ...kind, enabled: true } as AdjustmentOp);
+  }
+  return { ...params, operations };
+}
+
+/** Replace the filter operations in place; extra filters go after the last existing one. */
+function setFilters(params: DepthAdjustmentParams, filters: SpatialFilter[]): DepthAdjustmentParams {
+  const operations: AdjustmentOp[] = [];
+  let next = 0;
+  let insertAt = -1;
+  for (const op of params.operations) {
+    if (op.type !== "filter") {
+      operations.push(op);
+      continue;
+    }
+    if (next < filters.length) operations.push({ ...op, filter: filters[next++] });
+    insertAt = operations.length;
+  }
+  if (insertAt < 0) insertAt = canonicalInsertIndex(operations, "filter");
+  const added = filters.slice(next).map((filter): AdjustmentOp => ({ type: "filter", filter, enabled: true }));
+  operations.splice(insertAt, 0, ...added);
+  return { ...params, operations };
+}
+
+/** Apply edits made through an `AdjustmentView` to the stack. */
+export function withAdjustments(
+  params: DepthAdjustmentParams,
+  partial: Partial<AdjustmentView>
+): DepthAdjustmentParams {
+  let next = params;
+  const view = adjustmentView(params);
+  for (const key of Object.keys(partial) as (keyof AdjustmentView)[]) {
+    switch (key) {
+      case "brightness":
+        next = setOperation(next, "brightness", { type: "brightness", brightness: partial.brightness! });
+        break;
+      case "contrast":
+        next = setOperation(next, "contrast", { type: "contrast", contrast: partial.contrast! });
+        break;
+      case "gamma":
+        next = setOperation(next, "gamma", { type: "gamma", gamma: partial.gamma! });
+        break;
+      case "invert":
+        next = setOperation(next, "invert", partial.invert ? { type: "invert" } : null);
+        break;
+      case "curveControlPoints":
+      case "curveInterpolation": {
+        const points = partial.curveControlPoints === undefined ? view.curveControlPoints : partial.curveControlPoints;
+        const interpolation = partial.curveInterpolation ?? view.curveInterpolation;
+        next = setOperation(next, "curve", points ? { type: "curve", points, interpolation } : null);
+        break;
+      }
+      case "inpaint":
+        next = setOperation(next, "inpaint", partial.inpaint ? { type: "inpaint", ...partial.inpaint } : null);
+        break;
+      case "spatialFilters":
+        next = setFilters(next, partial.spatialFilters ?? []);
+        break;
+      case "basRelief":
+        next = setOperation(next, "basRelief", partial.basRelief ? { type: "basRelief", ...partial.basRelief } : null);
+        break;
+      case "autoLevels":
+        next = setOperation(next, "autoLevels", partial.autoLevels ? { type: "autoLevels", ...partial.autoLevels } : null);
+        break;
+      case "equalization":
+        next = setOperation(
+          next,
+          "equalization",
+          partial.equalization ? { type: "equalization", equalization: partial.equalization } : null
+        );
+        break;
+      case "terracing":
+        next = setOperation(next, "terracing", partial.terracing ? { type: "terracing", ...partial.terracing } : null);
+        break;
+      case "detailTransfer":
+        next = setOperation(
+          next,
+          "detailTransfer",
+          partial.detailTransfer ? { type: "detailTransfer", ...partial.detailTransfer } : null
+        );
+        break;
+    }
+  }
+  return next;
+}
+
+/** Move the operation at `index` up (`delta` −1) or down (+1). */
+export function moveOperation(params: DepthAdjustmentParams, index: number, delta: number): DepthAdjustmentParams {
+  const to = index + delta;
+  if (index < 0 || index >= params.operations.length || to < 0 || to >= params.operations.length) return params;
+  const operations = [...params.operations];
+  [operations[index], operations[to]] = [operations[to], operations[index]];
+  return { ...params, operations };
+}
+
+export function setOperationEnabled(
+  params: DepthAdjustmentParams,
+  index: number,
+  enabled: boolean
+): DepthAdjustmentParams {
+  return {
+    ...params,
+    operations: params.operations.map((op, i) => (i === index ? { ...op, enabled } : op)),
+  };
+}
+
+export function removeOperation(params: DepthAdjustmentParams, index: number): DepthAdjustmentParams {
+  return { ...params, operations: params.operations.filter((_, i) => i !== index) };
+}
+
+/** Short description of an operation for the stack list. */
+export function describeOperation(op: AdjustmentOp): string {
+  switch (op.type) {
+    case "gamma":
+      return `Gamma ${op.gamma.toFixed(2)}`;
+    case "contrast":
+      return `Contrast ${op.contrast.toFixed(2)}`;
+    case "brightness":
+      return `Brightness ${op.brightness >= 0 ? "+" : ""}${op.brightness.toFixed(2)}`;
+    case "curve":
+      return `Curve (${op.points.length} points)`;
+    case "filter":
+      return `Filter: ${op.filter.type}`;
+    case "terracing":
+      return `Terraces ×${op.levels}`;
+    case "inpaint":
+      return op.holes.rule === "mask" ? "Fill holes (mask)" : "Fill holes (depth range)";
+    default:
+      return OPERATION_LABELS[op.type];
+  }
+}
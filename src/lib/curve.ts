@@ -0,0 +1,107 @@
+// Copyright (c) 2026 SimplePicture3D Contributors
+// SPDX-License-Identifier: MIT
+
+/**
+ * Depth curve evaluation for the curves graph (BACK-1103).
+ * Mirrors Rust `depth_adjust::curve_value` so the drawn curve is the one the backend applies.
+ */
+
+import type { CurveInterpolation, CurvePoint } from "./tauri";
+
+const clamp01 = (v: number) => Math.max(0, Math.min(1, v));
+
+/** Piecewise linear through sorted points; below the first point it extrapolates the first segment. */
+function linearValue(v: number, pts: CurvePoint[]): number {
+  let i = 0;
+  for (let j = 0; j < pts.length; j++) {
+    if (pts[j].x >= v) break;
+    i = j;
+  }
+  if (i >= pts.length - 1) return clamp01(pts[pts.length - 1].y);
+  const p0 = pts[i];
+  const p1 = pts[i + 1];
+  const dx = p1.x - p0.x;
+  const t = dx <= 0 ? 1 : (v - p0.x) / dx;
+  return clamp01(p0.y + t * (p1.y - p0.y));
+}
+
+/** Tangents at each sorted point: Catmull-Rom finite differences or Fritsch–Carlson. */
+function tangents(pts: CurvePoint[], mode: CurveInterpolation): number[] {
+  const n = pts.length;
+  const d: number[] = [];
+  for (let k = 0; k < n - 1; k++) {
+    const dx = pts[k + 1].x - pts[k].x;
+    d.push(dx <= 0 ? 0 : (pts[k + 1].y - pts[k].y) / dx);
+  }
+  const m = new Array<number>(n).fill(0);
+  m[0] = d[0];
+  m[n - 1] = d[n - 2];
+  if (mode === "catmullRom") {
+    for (let k = 1; k < n - 1; k++) {
+      const dx = pts[k + 1].x - pts[k - 1].x;
+      m[k] = dx <= 0 ? 0 : (pts[k + 1].y - pts[k - 1].y) / dx;
+    }
+    return m;
+  }
+  for (let k = 1; k < n - 1; k++) {
+    m[k] = d[k - 1] * d[k] <= 0 ? 0 : (d[k - 1] + d[k]) / 2;
+  }
+  for (let k = 0; k < n - 1; k++) {
+    if (d[k] === 0) {
+      m[k] = 0;
+      m[k + 1] = 0;
+      continue;
+    }
+    const a = m[k] / d[k];
+    const b = m[k + 1] / d[k];
+    const s = a * a + b * b;
+    if (s > 9) {
+      const t = 3 / Math.sqrt(s);
+      m[k] = t * a * d[k];
+      m[k + 1] = t * b * d[k];
+    }
+  }
+  return m;
+}
+
+function hermiteValue(v: number, pts: CurvePoint[], m: number[]): number {
+  const last = pts.length - 1;
+  if (v <= pts[0].x) return clamp01(pts[0].y);
+  if (v >= pts[last].x) return clamp01(pts[last].y);
+  let i = 0;
+  while (i < last - 1 && pts[i + 1].x <= v) i++;
+  const p0 = pts[i];
+  const p1 = pts[i + 1];
+  const h = p1.x - p0.x;
+  if (h <= 0) return clamp01(p1.y);
+  const t = (v - p0.x) / h;
+  const t2 = t * t;
+  const t3 = t2 * t;
+  return clamp01(
+    (2 * t3 - 3 * t2 + 1) * p0.y +
+      (t3 - 2 * t2 + t) * h * m[i] +
+      (-2 * t3 + 3 * t2) * p1.y +
+      (t3 - t2) * h * m[i + 1],
+  );
+}
+
+/**
+ * Sample the curve at `samples + 1` evenly spaced inputs over [0, 1].
+ * Fewer than two points give the identity.
+ */
+export function sampleCurve(
+  points: CurvePoint[],
+  mode: CurveInterpolation = "linear",
+  samples: number = 128,
+): CurvePoint[] {
+  const pts = [...points].sort((a, b) => a.x - b.x);
+  const m = pts.length >= 2 && mode !== "linear" ? tangents(pts, mode) : null;
+  const out: CurvePoint[] = [];
+  for (let i = 0; i <= samples; i++) {
+    const x = i / samples;
+    let y = x;
+    if (pts.length >= 2) y = m ? hermiteValue(x, pts, m) : linearValue(x, pts);
+    out.push({ x, y });
+  }
+  return out;
+}
@@ -4,10 +4,13 @@
 /**
  * Tauri IPC helpers (UI-004). Types match backend commands in `src-tauri/src/lib.rs`.
  *
- * ADR-012: `setBlankEnvelope`, `setVolumetricParams`, `generatePointCloud`,
- * `exportPly` / `exportXyz` / `exportCsv`.
+ * ADR-012: `setBlankEnvelope`, `setVolumetricParams`, `setPointAttributes`, `generatePointCloud`,
+ * `importPointCloud`, `importMesh`, `clearImportedPointCloud`, `listExportFormats`,
+ * `exportPointCloud`, `replayRecipe`. Generation, export and recipe replay run as backend jobs
+ * (`runJob`, `cancelJob`).
  */
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
 /** Arguments for load_image command. */
 export interface LoadImageArgs {
@@ -35,12 +38,53 @@ export interface BlankEnvelope {
   marginMm: number;
 }
 
+/**
+ * How depth pixels are placed in 3D; matches Rust `Projection`. Perspective treats depth as
+ * disparity between `farDistance` (depth 0) and `nearDistance` (depth 1), any unit, far at most
+ * 1000× near; and unprojects through a pinhole camera (`fovDeg` across the longer side, 1–170).
+ */
+export type Projection =
+  | { mode: "orthographic" }
+  | { mode: "perspective"; fovDeg: number; nearDistance: number; farDistance: number };
+
 /** Surface-map sampling (ADR-012); matches Rust `VolumetricParams`. */
 export interface VolumetricParams {
   stepX: number;
   stepY: number;
   depthThreshold: number;
-}
+  /** Defaults to orthographic when omitted. */
+  projection?: Projection;
+}
+
+/** Optional per-point channels (PLY export); matches Rust `PointAttributeOptions`. */
+export interface PointAttributeOptions {
+  /** Depth after sculpting and adjustments. */
+  depth: boolean;
+  /** Raw estimator depth, before sculpting and adjustments. Absent in older settings. */
+  rawDepth?: boolean;
+  sourcePixel: boolean;
+  rgb: boolean;
+  intensity: boolean;
+  layerIndex: boolean;
+  layerThicknessMm: number;
+}
+
+/** Per-point channels aligned with `points`; only enabled channels are present. */
+export interface PointAttributes {
+  depth?: number[];
+  rawDepth?: number[];
+  sourcePixel?: [number, number][];
+  rgb?: [number, number, number][];
+  intensity?: number[];
+  layerIndex?: number[];
+  /** Layer thickness (mm) `layerIndex` was computed with. */
+  layerThicknessMm?: number;
+}
+
+/** Mesh import sampling (blank millimetres); matches Rust `MeshSampling`. */
+export type MeshSampling =
+  | { mode: "surface"; densityPerMm2: number }
+  | { mode: "volume"; pitchMm: number };
 
 /** Fit statistics from blank scaling (`fit_to_blank`). */
 export interface FitResult {
@@ -56,6 +100,7 @@ export interface VolumetricResult {
   pointCount: number;
   fitResult: FitResult;
   memoryBytes: number;
+  attributes: PointAttributes;
 }
 
 /**
@@ -79,14 +124,35 @@ export interface AppSettings {
   targetHeightMm?: number | null;
   windowWidth?: number | null;
   windowHeight?: number | null;
-  /** Curve control points (CURVE-001). Persisted in settings; restored on load. */
+  /** Depth adjustment operation stack. Persisted in settings; restored on load. */
+  adjustmentOperations?: AdjustmentOp[] | null;
+  /** Legacy curve (CURVE-001), read once as a curve operation when no stack is saved. */
   curveControlPoints?: CurvePoint[] | null;
+  /** Interpolation of the legacy curve; null = linear. */
+  curveInterpolation?: CurveInterpolation | null;
   /** Crystal blank L×W×H (mm) + margin; persisted when set via `setBlankEnvelope`. */
   blankEnvelope?: BlankEnvelope | null;
   /** Preferred point cloud export format: `"ply"` | `"xyz"` | `"csv"`. */
   pointCloudFormat?: string | null;
   /** Sampling parameters for `generatePointCloud` (ADR-012). */
   volumetricParams?: VolumetricParams | null;
+  /** Per-point channels recorded by `generatePointCloud`. */
+  pointAttributes?: PointAttributeOptions | null;
+  /** Last-used mesh import sampling. */
+  meshSampling?: MeshSampling | null;
+  /** Last-used XYZ/CSV dialect by format id; becomes the format's option defaults. */
+  textDialects?: Record<string, TextDialect>;
+}
+
+/** Plain-text export dialect (XYZ/CSV); matches Rust `TextDialect`. */
+export interface TextDialect {
+  delimiter: "space" | "tab" | "comma" | "semicolon";
+  decimalSeparator: "point" | "comma";
+  precision: number;
+  units: "mm" | "um" | "cm" | "m" | "in";
+  columns: ("index" | "x" | "y" | "z")[];
+  header: boolean;
+  lineEnding: "lf" | "crlf";
 }
 
 /** Load and validate image at path; returns dimensions, file size, and base64 preview (BACK-101, BACK-105). */
@@ -134,16 +200,125 @@ export interface CurvePoint {
   y: number;
 }
 
-/** Depth adjustment params (BACK-401–405, BACK-1102). Matches Rust DepthAdjustmentParams (camelCase). */
+/** Curve interpolation between control points (BACK-1103); matches Rust `CurveInterpolation`. */
+export type CurveInterpolation = "linear" | "monotoneCubic" | "catmullRom";
+
+/**
+ * One adjustment operation (Rust `depth_adjust::AdjustmentKind`), tagged by `type` with its
+ * fields alongside.
+ */
+export type AdjustmentKind =
+  | { type: "invert" }
+  | { type: "gamma"; gamma: number }
+  | { type: "contrast"; contrast: number }
+  | { type: "brightness"; brightness: number }
+  | { type: "curve"; points: CurvePoint[]; interpolation?: CurveInterpolation }
+  | ({ type: "inpaint" } & Inpaint)
+  | { type: "filter"; filter: SpatialFilter }
+  | ({ type: "basRelief" } & BasRelief)
+  | ({ type: "autoLevels" } & AutoLevels)
+  | { type: "equalization"; equalization: Equalization }
+  | ({ type: "terracing" } & Terracing)
+  | ({ type: "detailTransfer" } & DetailTransfer);
+
+export type AdjustmentType = AdjustmentKind["type"];
+
+/** Operation in the stack; disabled operations keep their place but are skipped. */
+export type AdjustmentOp = AdjustmentKind & { enabled: boolean };
+
+/**
+ * Depth adjustment params (BACK-401–405, BACK-1102). Matches Rust DepthAdjustmentParams (camelCase).
+ * `operations` run top to bottom; see `$lib/adjustments` for reading and editing them.
+ */
 export interface DepthAdjustmentParams {
-  brightness: number;
-  contrast: number;
-  gamma: number;
-  invert: boolean;
   depthMinMm: number;
   depthMaxMm: number;
-  /** Optional curve control points (BACK-1102). When null/undefined or length < 2, no curve. */
-  curveControlPoints?: CurvePoint[] | null;
+  operations: AdjustmentOp[];
+}
+
+/** Auto-levels (Rust depth_levels::AutoLevels); percentiles in percent. */
+export interface AutoLevels {
+  lowPercentile: number;
+  highPercentile: number;
+}
+
+/** Histogram equalisation (Rust depth_levels::Equalization, tagged by `mode`). */
+export type Equalization =
+  | { mode: "global" }
+  | { mode: "clahe"; tiles: number; clipLimit: number };
+
+/** Suggested levels for the current depth map (propose_depth_levels); nothing is applied. */
+export interface LevelsProposal {
+  /** Depth values at the proposed percentiles. */
+  low: number;
+  high: number;
+  autoLevels: AutoLevels;
+  equalization: Equalization | null;
+}
+
+/** Terracing (Rust depth_terrace::Terracing). */
+export interface Terracing {
+  /** Number of planes, 2–64. */
+  levels: number;
+  /** Even = equal layer thickness; kMeans = planes at the main depth clusters. */
+  spacing: "even" | "kMeans";
+  /** Soft step width as a fraction of the plane gap, 0–1 (0 = hard). */
+  transition: number;
+}
+
+/** Hole selection for inpainting (Rust depth_inpaint::HoleRule, tagged by `rule`). */
+export type HoleRule = { rule: "mask" } | { rule: "depthRange"; min: number; max: number };
+
+/** Hole filling (Rust depth_inpaint::Inpaint). Non-finite depth is always a hole. */
+export interface Inpaint {
+  /** Selection mask pixels, or depth outside [min, max] (0–1). */
+  holes: HoleRule;
+  /** Diffusion = smooth membrane; fastMarching = nearest-first weighted fill. */
+  method: "diffusion" | "fastMarching";
+  /** Fast-marching neighbourhood radius (px), 1–32. */
+  radiusPx: number;
+}
+
+/** Bas-relief compression (Rust bas_relief::BasRelief). */
+export interface BasRelief {
+  /** 0 = off … 1 = large depth jumps nearly flattened. */
+  strength: number;
+  /** Output depth span as a fraction of the input span, (0, 1]. */
+  targetRatio: number;
+}
+
+/** Detail transfer (Rust depth_filter::DetailTransfer). */
+export interface DetailTransfer {
+  /** Depth change (0–1 of the range) per unit of luminance detail; negative engraves. */
+  strength: number;
+  /** Gaussian sigma (px); only features finer than this are transferred. */
+  cutoffPx: number;
+}
+
+/** One spatial filter (Rust depth_filter::SpatialFilter, tagged by `type`). */
+export type SpatialFilter =
+  | { type: "gaussian"; sigmaPx: number }
+  | { type: "median"; radiusPx: number }
+  | { type: "bilateral"; sigmaSpatialPx: number; sigmaRange: number }
+  | { type: "guided"; radiusPx: number; epsilon: number }
+  | { type: "unsharpMask"; sigmaPx: number; amount: number };
+
+export type SpatialFilterType = SpatialFilter["type"];
+
+/** Starting values when a filter is added in the UI. */
+export function defaultSpatialFilter(type: SpatialFilterType): SpatialFilter {
+  switch (type) {
+    case "gaussian":
+      return { type, sigmaPx: 1 };
+    case "median":
+      return { type, radiusPx: 1 };
+    case "bilateral":
+      return { type, sigmaSpatialPx: 2, sigmaRange: 0.1 };
+    case "guided":
+      return { type, radiusPx: 4, epsilon: 0.001 };
+    case "unsharpMask":
+      return { type, sigmaPx: 2, amount: 0.5 };
+  }
 }
 
 /**
@@ -164,6 +339,11 @@ export async function getDepthHistogram(): Promise<number[] | null> {
   return invoke<number[] | null>("get_depth_histogram");
 }
 
+/** Suggest auto-levels / equalisation from the current histogram without applying them. Null if no depth. */
+export async function proposeDepthLevels(): Promise<LevelsProposal | null> {
+  return invoke<LevelsProposal | null>("propose_depth_levels");
+}
+
 export async function getDepthAdjustmentParams(): Promise<DepthAdjustmentParams> {
   return invoke<DepthAdjustmentParams>("get_depth_adjustment_params");
 }
@@ -260,6 +440,32 @@ export async function loadMaskFromPath(path: string): Promise<UndoRedoState> {
   return invoke<UndoRedoState>("load_mask_from_path", { path });
 }
 
+// --- Depth sculpting (sculpt.rs) ---
+
+export type SculptOp = "raise" | "lower" | "smooth" | "flatten";
+export type BrushFalloff = "constant" | "linear" | "smooth";
+
+/** One brush stroke (Rust sculpt::SculptStroke). Points in depth-map pixels. */
+export interface SculptStroke {
+  points: { x: number; y: number }[];
+  /** Brush radius (px), up to 512. */
+  radiusPx: number;
+  falloff: BrushFalloff;
+  op: SculptOp;
+  /** Raise/lower: depth change (0–1) at the centre line. Smooth/flatten: amount (0–1). */
+  strength: number;
+}
+
+/** Apply a stroke to the sculpt layer (one undo step). Returns updated undo/redo state. */
+export async function applySculptStroke(stroke: SculptStroke): Promise<UndoRedoState> {
+  return invoke<UndoRedoState>("apply_sculpt_stroke", { stroke });
+}
+
+/** Remove all sculpting. Returns updated undo/redo state. */
+export async function clearSculpt(): Promise<UndoRedoState> {
+  return invoke<UndoRedoState>("clear_sculpt");
+}
+
 // --- ADR-012: Crystal surface point cloud ---
 
 export async function setBlankEnvelope(envelope: BlankEnvelope): Promise<void> {
@@ -270,34 +476,310 @@ export async function setVolumetricParams(params: VolumetricParams): Promise<voi
   return invoke("set_volumetric_params", { params });
 }
 
-/** Persist preferred export format (`ply`, `xyz`, or `csv`). */
+/** Persist preferred export format (`ply`, `xyz`, `csv`, `las`, `obj`, `pts`, or `glb`). */
 export async function setPointCloudFormat(format: string): Promise<void> {
   return invoke("set_point_cloud_format", { format });
 }
 
+/**
+ * Load a PLY / XYZ / CSV / PTS / PCD point cloud, fit it to the current blank and cache it for
+ * the export commands (replaces the generated cloud until `generatePointCloud` runs again).
+ */
+export async function importPointCloud(path: string): Promise<VolumetricResult> {
+  return invoke<VolumetricResult>("import_point_cloud", { path });
+}
+
+/**
+ * Load an STL / OBJ mesh, fit it to the current blank and sample its surface or interior
+ * (cached for export like `importPointCloud`; sampling is persisted).
+ */
+export async function importMesh(path: string, sampling: MeshSampling): Promise<VolumetricResult> {
+  return invoke<VolumetricResult>("import_mesh", { path, sampling });
+}
+
+/** Persist per-point attribute options (invalidates the cached point cloud). */
+export async function setPointAttributes(options: PointAttributeOptions): Promise<void> {
+  return invoke("set_point_attributes", { options });
+}
+
 /** Estimated point count from current depth + settings; `null` if no depth loaded. */
 export async function estimatePointCloudCount(): Promise<number | null> {
   return invoke<number | null>("estimate_point_cloud_count");
 }
 
-/** Generate surface-map point cloud from current adjusted depth (cached for export commands). */
-export async function generatePointCloud(): Promise<VolumetricResult> {
-  return invoke<VolumetricResult>("generate_point_cloud");
+/** What a backend job does; matches Rust `JobKind`. */
+export type JobKind = "generatePointCloud" | "export" | "layerFolder" | "replayRecipe";
+
+/** Part of a job a progress event covers; matches Rust `JobStage`. */
+export type JobStage = "preparing" | "points" | "writing";
+
+/** Payload for the "job-progress" Tauri event; matches Rust `JobProgress`. */
+export interface JobProgressEvent {
+  jobId: number;
+  kind: JobKind;
+  stage: JobStage;
+  /** Progress of `stage`; starts again from 0 at each stage. */
+  percent: number;
+  processed: number;
+  total: number;
+}
+
+/** Payload for the "job-finished" Tauri event; matches Rust `JobFinished`. */
+export interface JobFinishedEvent {
+  jobId: number;
+  kind: JobKind;
+  status: "completed" | "cancelled" | "failed";
+  result?: unknown;
+  error?: string;
+}
+
+/** Callbacks for a backend job started by `runJob`. */
+export interface JobCallbacks {
+  /** Called with the job id once the backend accepted the job (e.g. to offer Cancel). */
+  onStart?: (jobId: number) => void;
+  onProgress?: (event: JobProgressEvent) => void;
+}
+
+/**
+ * Start a job command (returns a job id) and resolve with its "job-finished" result.
+ * Rejects with the job error, or "Job cancelled" after `cancelJob`.
+ */
+export async function runJob<T>(
+  command: string,
+  args: Record<string, unknown> = {},
+  callbacks: JobCallbacks = {},
+): Promise<T> {
+  let jobId: number | null = null;
+  const finished = new Map<number, JobFinishedEvent>();
+  let settle: ((event: JobFinishedEvent) => void) | null = null;
+  const unlistenProgress = await listen<JobProgressEvent>("job-progress", (event) => {
+    if (event.payload.jobId === jobId) callbacks.onProgress?.(event.payload);
+  });
+  const unlistenFinished = await listen<JobFinishedEvent>("job-finished", (event) => {
+    // A fast job can finish before `invoke` returns its id; keep it until the id is known.
+    finished.set(event.payload.jobId, event.payload);
+    if (event.payload.jobId === jobId) settle?.(event.payload);
+  });
+  try {
+    const outcome = await new Promise<JobFinishedEvent>((resolve, reject) => {
+      settle = resolve;
+      invoke<number>(command, args)
+        .then((id) => {
+          jobId = id;
+          callbacks.onStart?.(id);
+          const early = finished.get(id);
+          if (early) resolve(early);
+        })
+        .catch(reject);
+    });
+    if (outcome.status !== "completed") {
+      throw new Error(outcome.error ?? `Job ${outcome.status}`);
+    }
+    return outcome.result as T;
+  } finally {
+    unlistenProgress();
+    unlistenFinished();
+  }
+}
+
+/** Request cancellation of a running job; `false` if it already finished. */
+export async function cancelJob(jobId: number): Promise<boolean> {
+  return invoke<boolean>("cancel_job", { jobId });
+}
+
+/**
+ * Generate surface-map point cloud from current adjusted depth (cached for export commands).
+ * Runs as a backend job; see `runJob` for progress and cancellation.
+ */
+export async function generatePointCloud(callbacks: JobCallbacks = {}): Promise<VolumetricResult> {
+  return runJob<VolumetricResult>("generate_point_cloud", {}, callbacks);
+}
+
+/** One exporter option from the backend registry (`ExportOptionSpec`). */
+export interface ExportOptionSpec {
+  key: string;
+  label: string;
+  type: "bool" | "number" | "string";
+  default: boolean | number | string;
+  /** Allowed values for string options. */
+  choices?: string[];
+}
+
+/** A registered point cloud export format (`list_export_formats`). */
+export interface ExportFormatInfo {
+  id: string;
+  label: string;
+  extensions: string[];
+  options: ExportOptionSpec[];
+}
+
+/** Forget an imported cloud so exports use the depth map again. */
+export async function clearImportedPointCloud(): Promise<void> {
+  return invoke("clear_imported_point_cloud");
+}
+
+/** Export formats known to the backend, in picker order. */
+export async function listExportFormats(): Promise<ExportFormatInfo[]> {
+  return invoke<ExportFormatInfo[]>("list_export_formats");
+}
+
+/** Result of `export_point_cloud`; matches Rust `ExportReceipt`. */
+export interface ExportReceipt {
+  pointCount: number;
+  /** Lowercase hex SHA-256 of the written file. */
+  sha256: string;
+  byteSize: number;
+  /** File was read back and its point count and bounds matched (every point cloud format). */
+  verified: boolean;
+  /** Points dropped because they snapped onto an already used machine grid node (`gridMm`). */
+  mergedPoints: number;
+}
+
+/**
+ * Write the point cloud in any registered format. Depth-map clouds are generated while
+ * writing (no `generatePointCloud` needed); an imported cloud is written as fitted.
+ * Omitted options take the format's defaults. A `<path>.recipe.json` sidecar is written alongside.
+ * The file is written to a temporary, verified and renamed into place. Runs as a backend job;
+ * a cancelled export leaves no file.
+ */
+export async function exportPointCloud(
+  format: string,
+  path: string,
+  options: Record<string, boolean | number | string> = {},
+  callbacks: JobCallbacks = {},
+): Promise<ExportReceipt> {
+  return runJob<ExportReceipt>("export_point_cloud", { format, options, path }, callbacks);
+}
+
+/** Z-slice stack settings; matches Rust `SliceStackOptions`. */
+export interface SliceStackOptions {
+  layerThicknessMm: number;
+  dpi: number;
+  imageFormat: "png" | "bmp";
+}
+
+/** One layer image of a slice stack; matches Rust `SliceLayer`. */
+export interface SliceLayer {
+  index: number;
+  /** Z of the layer centre, mm above the blank floor. */
+  zMm: number;
+  file: string;
+  pointCount: number;
+}
+
+/** `manifest.json` of a slice stack; matches Rust `SliceManifest`. */
+export interface SliceManifest {
+  imageFormat: "png" | "bmp";
+  layerThicknessMm: number;
+  dpi: number;
+  pixelPitchMm: number;
+  widthPx: number;
+  heightPx: number;
+  blankLengthMm: number;
+  blankWidthMm: number;
+  pointCount: number;
+  clippedPoints: number;
+  layers: SliceLayer[];
+}
+
+/** Folder exporter ids; matches Rust `LAYER_FOLDER_EXPORTERS`. */
+export type LayerFolderFormat = "slices" | "svg";
+
+/**
+ * Write the cached point cloud as a folder of layer files plus `manifest.json` with a
+ * registered folder exporter. `options` is that format's options object. Runs as a backend job;
+ * resolves to the format's manifest.
+ */
+export async function exportLayerFolder<T>(
+  format: LayerFolderFormat,
+  path: string,
+  options: object,
+  callbacks: JobCallbacks = {},
+): Promise<T> {
+  return runJob<T>("export_layer_folder", { format, options, path }, callbacks);
+}
+
+/**
+ * Slice the cached point cloud into a folder of numbered 1-bit PNG/BMP layer images plus
+ * `manifest.json` (layer-based engravers). `path` is the folder to write; its parent must exist.
+ * Requires a generated or imported cloud. Runs as a backend job.
+ */
+export async function exportSliceStack(
+  path: string,
+  options: SliceStackOptions,
+  callbacks: JobCallbacks = {},
+): Promise<SliceManifest> {
+  return exportLayerFolder<SliceManifest>("slices", path, options, callbacks);
+}
+
+/** Per-layer SVG settings; matches Rust `SvgLayerOptions`. */
+export interface SvgLayerOptions {
+  layerThicknessMm: number;
+  style: "circles" | "polylines";
+  travelOrder: "asGenerated" | "serpentine" | "nearestNeighbour";
+  /** Circle diameter, or stroke width for polylines (mm). */
+  dotDiameterMm: number;
+  /** Longest polyline segment before the line is broken (mm). */
+  joinDistanceMm: number;
+}
+
+/** `manifest.json` of an SVG layer export; matches Rust `SvgLayerManifest`. */
+export interface SvgLayerManifest {
+  style: SvgLayerOptions["style"];
+  travelOrder: SvgLayerOptions["travelOrder"];
+  layerThicknessMm: number;
+  dotDiameterMm: number;
+  blankLengthMm: number;
+  blankWidthMm: number;
+  marginMm: number;
+  pointCount: number;
+  clippedPoints: number;
+  layers: SliceLayer[];
 }
 
-/** Write cached point cloud as PLY. Run `generatePointCloud` first. */
-export async function exportPly(path: string, binary: boolean): Promise<void> {
-  return invoke("export_ply", { path, binary });
+/**
+ * Write the cached point cloud as one 1:1 millimetre SVG per Z slab plus `manifest.json`,
+ * with the blank outline and margin on a guide layer. `path` is the folder to write.
+ * Runs as a backend job.
+ */
+export async function exportSvgLayers(
+  path: string,
+  options: SvgLayerOptions,
+  callbacks: JobCallbacks = {},
+): Promise<SvgLayerManifest> {
+  return exportLayerFolder<SvgLayerManifest>("svg", path, options, callbacks);
 }
 
-/** Write cached point cloud as XYZ. */
-export async function exportXyz(path: string): Promise<void> {
-  return invoke("export_xyz", { path });
+/** Result of `replay_recipe`; matches Rust `ReplayResult`. */
+export interface ReplayResult {
+  pointCount: number;
+  fitResult: FitResult;
+  /** Same point count, scale and translation as recorded. */
+  fitMatches: boolean;
+  /** Re-estimated depth is bit-identical to the recorded one (null for imported clouds). */
+  depthMatches: boolean | null;
+  depthModelId: string | null;
+  /** How the replay differs from the export; only non-empty when mismatches were accepted. */
+  mismatches: string[];
 }
 
-/** Write cached point cloud as CSV. */
-export async function exportCsv(path: string): Promise<void> {
-  return invoke("export_csv", { path });
+/**
+ * Regenerate the cloud described by an export's `.recipe.json` sidecar. `sourcePath`
+ * overrides the recorded image/import path; its hash must match the recipe. A different
+ * depth model or depth map fails the replay unless `acceptMismatch` is set. Runs as a
+ * backend job; the session is only changed when the replay succeeds.
+ */
+export async function replayRecipe(
+  recipePath: string,
+  sourcePath?: string,
+  callbacks: JobCallbacks = {},
+  acceptMismatch = false,
+): Promise<ReplayResult> {
+  return runJob<ReplayResult>(
+    "replay_recipe",
+    { recipePath, sourcePath: sourcePath ?? null, acceptMismatch },
+    callbacks,
+  );
 }
 
 // --- Sprint 1.10: Model management ---